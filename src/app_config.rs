@@ -1,8 +1,4 @@
-use std::{
-    fs,
-    path::PathBuf,
-    sync::{Mutex, OnceLock},
-};
+use std::{fs, path::PathBuf};
 
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
@@ -16,8 +12,6 @@ pub struct AppConfig {
 }
 pub const APP_EXT: &str = "opw";
 
-static ASPECT_RATIO: OnceLock<Mutex<f32>> = OnceLock::new();
-
 impl AppConfig {
     pub fn init() {
         AppConfig::setup_config_dir();
@@ -57,24 +51,14 @@ impl AppConfig {
         db_path
     }
 
+    /// Design resolution of new presentations, each presentation keeps its
+    /// own in [crate::widgets::canvas::serialise::Resolution]
     pub fn aspect_size() -> (i32, i32) {
         (1920, 1080)
-        // (1440, 900)
     }
     pub fn aspect_ratio() -> f32 {
         let def = Self::aspect_size();
-        *ASPECT_RATIO
-            .get_or_init(|| Mutex::new(def.0 as f32 / def.1 as f32))
-            .lock()
-            .unwrap()
-    }
-    pub fn set_aspect_ratio(new_val: f32) -> Result<(), String> {
-        ASPECT_RATIO
-            .get()
-            .ok_or("OnceLock not initialized")?
-            .lock()
-            .map(|mut val| *val = new_val)
-            .map_err(|e| format!("Mutex error: {}", e))
+        def.0 as f32 / def.1 as f32
     }
 }
 
@@ -24,11 +24,14 @@ use crate::{
     widgets::{
         search::songs::edit_modal::{EditorType, SongEditWindow},
         settings_window::SettingsWindow,
+        song_usage_window::SongUsageWindow,
+        theme_window::ThemeWindow,
     },
 };
 
 mod signal {
     pub const SONG_IMPORTED: &str = "song-imported";
+    pub const SONG_USED: &str = "song-used";
 }
 
 mod imp {
@@ -36,24 +39,19 @@ mod imp {
 
     use adw::subclass::prelude::AdwApplicationImpl;
     use gtk::{
-        gdk::{
-            self,
-            prelude::{DisplayExt, MonitorExt},
-        },
         gio::{
-            prelude::{FileExtManual, ListModelExt},
+            prelude::FileExtManual,
             subclass::prelude::{ApplicationImpl, ApplicationImplExt},
         },
         glib::{
             Properties,
-            object::CastNone,
             subclass::{
                 Signal,
                 object::ObjectImpl,
                 types::{ObjectSubclass, ObjectSubclassExt},
             },
         },
-        prelude::{GtkWindowExt, ObjectExt, WidgetExt},
+        prelude::{GtkWindowExt, ObjectExt},
         subclass::prelude::{DerivedObjectProperties, GtkApplicationImpl},
     };
 
@@ -84,7 +82,10 @@ mod imp {
             static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
 
             SIGNALS.get_or_init(|| {
-                return vec![Signal::builder(signal::SONG_IMPORTED).build()];
+                return vec![
+                    Signal::builder(signal::SONG_IMPORTED).build(),
+                    Signal::builder(signal::SONG_USED).build(),
+                ];
             })
         }
     }
@@ -202,20 +203,6 @@ mod imp {
             self.add_app_menu(Some(&main_window.window_box()));
 
             main_window.show_all();
-
-            let monitors = WidgetExt::display(&extended_screen).monitors();
-            for i in 0..monitors.n_items() {
-                let Some(mon) = monitors.item(i).and_downcast::<gdk::Monitor>() else {
-                    continue;
-                };
-
-                let geometry = mon.geometry();
-
-                if geometry.x() != 0 || geometry.y() != 0 {
-                    extended_screen.fullscreen_on_monitor(&mon);
-                    break;
-                }
-            }
         }
     }
 }
@@ -273,6 +260,32 @@ impl OwApplication {
             })
             .build();
 
+        let song_usage_action = gio::ActionEntry::builder("song-usage")
+            .activate(|app: &OwApplication, _, _| {
+                let win = SongUsageWindow::new();
+                win.set_transient_for(Some(&app.main_window()));
+                win.present();
+            })
+            .build();
+
+        let themes_action = gio::ActionEntry::builder("themes")
+            .activate(|app: &OwApplication, _, _| {
+                let win = ThemeWindow::new();
+                win.set_transient_for(Some(&app.main_window()));
+                win.present();
+            })
+            .build();
+
+        let reset_service_timer = gio::ActionEntry::builder("reset-service-timer")
+            .activate(|app: &OwApplication, _, _| {
+                app.main_window().extended_screen().reset_service_timer();
+            })
+            .build();
+
+        let lower_third = gio::ActionEntry::builder("lower-third")
+            .activate(|app: &OwApplication, _, _| app.main_window().show_lower_third())
+            .build();
+
         let open = gio::ActionEntry::builder("open")
             .activate(|app: &OwApplication, _, _| {
                 let mut filters = glib::List::new();
@@ -437,6 +450,10 @@ impl OwApplication {
             // FILE
             open,
             add_song_action,
+            song_usage_action,
+            themes_action,
+            reset_service_timer,
+            lower_third,
             // HELP
             report_bug,
         ]);
@@ -517,4 +534,16 @@ impl OwApplication {
             }),
         );
     }
+
+    /// Tells listeners a song was shown live and its usage logged
+    pub fn emit_song_used(&self) {
+        self.emit_by_name::<()>(signal::SONG_USED, &[])
+    }
+    pub fn connect_song_used<F: Fn(&Self) + 'static>(&self, f: F) {
+        self.connect_closure(
+            signal::SONG_USED,
+            false,
+            glib::closure_local!(|obj: &Self| f(obj)),
+        );
+    }
 }
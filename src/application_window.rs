@@ -1,5 +1,6 @@
 use gtk::gio;
 use gtk::glib;
+use gtk::glib::subclass::types::ObjectSubclassIsExt;
 use gtk::prelude::GtkWindowExt;
 
 mod imp {
@@ -7,18 +8,21 @@ mod imp {
 
     use adw::subclass::prelude::AdwApplicationWindowImpl;
     use gtk::{
-        gdk::prelude::{DisplayExt, MonitorExt},
-        gio::prelude::{ApplicationExt, ListModelExt},
+        gdk,
+        gio::prelude::ApplicationExt,
         glib::{
             self, Properties,
-            object::{Cast, CastNone},
+            object::CastNone,
             subclass::{
                 object::{ObjectImpl, ObjectImplExt},
-                types::ObjectSubclass,
+                types::{ObjectSubclass, ObjectSubclassExt},
             },
             types::StaticTypeExt,
         },
-        prelude::{GtkWindowExt, ObjectExt, PopoverExt, ToggleButtonExt, WidgetExt},
+        prelude::{
+            BoxExt, EventControllerExt, GtkWindowExt, ObjectExt, PopoverExt, ToggleButtonExt,
+            WidgetExt,
+        },
         subclass::{
             prelude::{ApplicationWindowImpl, DerivedObjectProperties},
             widget::{
@@ -30,11 +34,30 @@ mod imp {
     };
 
     use crate::{
-        services::message_alert_manager::MessageAlertManager,
+        application::OwApplication,
+        db::query::Query,
+        services::{
+            audio_player::AudioPlayer,
+            auto_advancer::AutoAdvancer,
+            live_output::LiveOutput,
+            message_alert_manager::MessageAlertManager,
+            output_manager::OutputManager,
+            settings::ApplicationSettings,
+            shortcuts::{self, ShortcutAction},
+            song_usage::{SongUsageTracker, UsageChange},
+        },
         widgets::{
-            activity_viewer::ActivityViewer, canvas::serialise::SlideManagerData,
-            extended_screen::ExtendedScreen, message_alert_viewer::MessageAlertViewer,
-            schedule_activity_viewer::ScheduleActivityViewer, search::SearchActivityViewer,
+            activity_viewer::ActivityViewer,
+            audio_transport::AudioTransport,
+            auto_advance_controls::AutoAdvanceControls,
+            canvas::{
+                screen_cover::ScreenMode,
+                serialise::{SlideManagerData, TimerFinish},
+            },
+            extended_screen::ExtendedScreen,
+            message_alert_viewer::MessageAlertViewer,
+            schedule_activity_viewer::ScheduleActivityViewer,
+            search::SearchActivityViewer,
         },
     };
 
@@ -67,15 +90,28 @@ mod imp {
         alert_popover: gtk::TemplateChild<gtk::Popover>,
         #[template_child]
         alert_btn: gtk::TemplateChild<gtk::MenuButton>,
+        #[template_child]
+        clear_btn: gtk::TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        show_black_btn: gtk::TemplateChild<gtk::ToggleButton>,
+        #[template_child]
+        show_logo_btn: gtk::TemplateChild<gtk::ToggleButton>,
 
         //
         #[property(get)]
         extended_screen: RefCell<ExtendedScreen>,
 
-        //
-        slide_change_handler_id: RefCell<Option<glib::SignalHandlerId>>,
+        /// what the live viewer and outputs show
+        #[property(get)]
+        live_output: RefCell<LiveOutput>,
 
         alert_manager: RefCell<MessageAlertManager>,
+        pub(super) output_manager: RefCell<Option<OutputManager>>,
+        audio_player: RefCell<AudioPlayer>,
+        auto_advancer: RefCell<AutoAdvancer>,
+        song_usage: RefCell<SongUsageTracker>,
+        /// keys of the live shortcuts, from the `shortcuts` setting
+        shortcuts: RefCell<Vec<(gdk::Key, gdk::ModifierType, ShortcutAction)>>,
     }
 
     #[glib::object_subclass]
@@ -106,14 +142,74 @@ mod imp {
             self.extended_screen
                 .borrow()
                 .set_alert_manager(&self.alert_manager.borrow().clone());
-            let id = self.live_viewer.connect_slide_change(glib::clone!(
+            self.output_manager
+                .replace(Some(OutputManager::new(&self.extended_screen.borrow())));
+            let live = self.live_output.borrow().clone();
+            self.extended_screen.borrow().follow(&live);
+            self.live_viewer.follow(&live);
+            self.live_viewer.connect_slide_change(glib::clone!(
+                #[weak]
+                live,
+                move |_, position| live.show_slide(position)
+            ));
+            live.connect_item_changed(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |_, data| imp.item_live(data)
+            ));
+            live.connect_slide_changed(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |live, position| imp.slide_live(live, position)
+            ));
+            live.connect_screen_mode_notify(glib::clone!(
                 #[weak(rename_to=imp)]
                 self,
-                move |_, position| {
-                    imp.extended_screen.borrow().set_pos(position);
+                move |live| {
+                    let mode = live.screen_mode();
+                    imp.show_black_btn.set_active(mode == ScreenMode::Black);
+                    imp.show_logo_btn.set_active(mode == ScreenMode::Logo);
                 }
             ));
-            self.slide_change_handler_id.replace(Some(id));
+            self.alert_manager
+                .borrow()
+                .connect_showing_notify(glib::clone!(
+                    #[weak]
+                    live,
+                    move |manager| live.set_alert(manager.showing())
+                ));
+
+            self.live_viewer
+                .append(&AudioTransport::new(&self.audio_player.borrow()));
+            self.live_viewer
+                .append(&AutoAdvanceControls::new(&self.auto_advancer.borrow()));
+
+            let auto_advancer = self.auto_advancer.borrow();
+            auto_advancer.connect_advance(glib::clone!(
+                #[weak]
+                live,
+                move |_, position| live.show_slide(position)
+            ));
+            auto_advancer.connect_resume_item(glib::clone!(
+                #[weak]
+                live,
+                move |_, data| live.show_item(data)
+            ));
+
+            self.extended_screen
+                .borrow()
+                .slide_manager()
+                .connect_timer_finished(glib::clone!(
+                    #[weak]
+                    live,
+                    move |_, action| match action {
+                        TimerFinish::NextSlide => live.next_slide(),
+                        TimerFinish::ShowLogo => live.set_logo(true),
+                        TimerFinish::None => (),
+                    }
+                ));
+
+            self.register_shortcuts();
 
             // set popover viewer
             self.alert_popover
@@ -156,8 +252,7 @@ mod imp {
 
         #[template_callback]
         fn handle_clear_live(&self, btn: &gtk::ToggleButton) {
-            self.live_viewer.clear_display(btn.is_active());
-            self.extended_screen.borrow().clear_display(btn.is_active());
+            self.live_output.borrow().set_clear(btn.is_active());
         }
 
         #[template_callback]
@@ -166,13 +261,13 @@ mod imp {
         }
 
         #[template_callback]
-        fn handle_show_black(&self, _: &gtk::Button) {
-            glib::g_warning!("application_window", "TODO: Show black");
+        fn handle_show_black(&self, btn: &gtk::ToggleButton) {
+            self.live_output.borrow().set_black(btn.is_active());
         }
 
         #[template_callback]
-        fn handle_show_logo(&self, _: &gtk::Button) {
-            glib::g_warning!("application_window", "TODO: Show logo");
+        fn handle_show_logo(&self, btn: &gtk::ToggleButton) {
+            self.live_output.borrow().set_logo(btn.is_active());
         }
 
         #[template_callback]
@@ -197,22 +292,7 @@ mod imp {
 
         #[template_callback]
         fn handle_preview_activate_slide(&self, data: &SlideManagerData, _: &ActivityViewer) {
-            // block change
-            let id = self.slide_change_handler_id.take();
-            if let Some(id) = id {
-                self.live_viewer.block_signal(&id);
-                self.slide_change_handler_id.replace(Some(id));
-            }
-
-            self.live_viewer.load_data(data);
-            self.extended_screen.borrow().load_data(data);
-
-            // unblock change
-            let id = self.slide_change_handler_id.take();
-            if let Some(id) = id {
-                self.live_viewer.unblock_signal(&id);
-                self.slide_change_handler_id.replace(Some(id));
-            }
+            self.live_output.borrow().show_item(data);
         }
 
         // #[template_callback]
@@ -222,23 +302,153 @@ mod imp {
     }
 
     impl MainApplicationWindow {
-        fn get_display_geometry() -> Option<gtk::gdk::Rectangle> {
-            let display_backend = gtk::gdk::Display::default().expect("no display");
+        /// Starts the audio, song usage and auto advance of the item that
+        /// went live
+        fn item_live(&self, data: &SlideManagerData) {
+            let audio_player = self.audio_player.borrow();
+            audio_player.show_item(data.audio.as_ref());
+            let audio = data
+                .slides
+                .get(data.current_slide as usize)
+                .and_then(|s| s.audio.as_ref());
+            audio_player.show_slide(audio);
+
+            let change = self
+                .song_usage
+                .borrow_mut()
+                .item_live(data.song_id, data.current_slide);
+            self.log_song_usage(change);
+            self.auto_advancer.borrow().show_item(data);
+        }
+
+        fn slide_live(&self, live: &LiveOutput, position: u32) {
+            let audio = live.slide_data(position).and_then(|s| s.audio);
+            self.audio_player.borrow().show_slide(audio.as_ref());
+            self.auto_advancer.borrow().show_slide(position);
+            let change = self.song_usage.borrow_mut().slide_shown(position);
+            self.log_song_usage(change);
+        }
 
-            let x_mon = match display_backend.monitors().item(0) {
-                Some(val) => val.downcast::<gtk::gdk::Monitor>(),
-                None => return None,
+        /// Live shortcuts work whichever pane has focus. Text fields get the
+        /// keys first and only pass on those they do not use, like Page_Down
+        fn register_shortcuts(&self) {
+            self.load_shortcuts();
+            ApplicationSettings::get_instance().connect_shortcuts_changed(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |_| imp.load_shortcuts()
+            ));
+
+            for phase in [
+                gtk::PropagationPhase::Capture,
+                gtk::PropagationPhase::Bubble,
+            ] {
+                let controller = gtk::EventControllerKey::new();
+                controller.set_propagation_phase(phase);
+                controller.connect_key_pressed(glib::clone!(
+                    #[weak(rename_to=imp)]
+                    self,
+                    #[upgrade_or]
+                    glib::Propagation::Proceed,
+                    move |_, key, _, state| {
+                        let editing = imp
+                            .obj()
+                            .focus()
+                            .is_some_and(|w| w.is::<gtk::Editable>() || w.is::<gtk::TextView>());
+                        match editing == (phase == gtk::PropagationPhase::Bubble) {
+                            true => imp.run_shortcut(key, state),
+                            false => glib::Propagation::Proceed,
+                        }
+                    }
+                ));
+                self.obj().add_controller(controller);
+            }
+        }
+
+        fn load_shortcuts(&self) {
+            let entries = ApplicationSettings::get_instance().shortcuts();
+            let keys = shortcuts::parse_shortcuts(&entries)
+                .into_iter()
+                .flat_map(|shortcut| {
+                    let action = shortcut.action;
+                    shortcut
+                        .keys
+                        .into_iter()
+                        .filter_map(|k| gtk::accelerator_parse(k.as_str()))
+                        .map(move |(key, modifiers)| (key.to_lower(), modifiers, action.clone()))
+                })
+                .collect();
+            self.shortcuts.replace(keys);
+        }
+
+        fn run_shortcut(&self, key: gdk::Key, state: gdk::ModifierType) -> glib::Propagation {
+            let key = key.to_lower();
+            let state = state & gtk::accelerator_get_default_mod_mask();
+            let action = self
+                .shortcuts
+                .borrow()
+                .iter()
+                .find(|(k, m, _)| *k == key && *m == state)
+                .map(|(_, _, action)| action.clone());
+            let Some(action) = action else {
+                return glib::Propagation::Proceed;
             };
 
-            let geometry = match x_mon {
-                Ok(val) => val.geometry(),
-                Err(err) => {
-                    println!("Error {:?}", err);
-                    return None;
-                }
+            let live = self.live_output.borrow().clone();
+            let toggle = |btn: &gtk::ToggleButton| btn.set_active(!btn.is_active());
+            match action {
+                ShortcutAction::GoLive => self.preview_viewer.emit_activate_slide(),
+                ShortcutAction::NextSlide => live.next_slide(),
+                ShortcutAction::PreviousSlide => live.previous_slide(),
+                ShortcutAction::NextItem => self.item_live_next(true),
+                ShortcutAction::PreviousItem => self.item_live_next(false),
+                ShortcutAction::Verse(tag) => Self::show_verse(&live, &tag),
+                ShortcutAction::Clear => toggle(&self.clear_btn),
+                ShortcutAction::Black => toggle(&self.show_black_btn),
+                ShortcutAction::Logo => toggle(&self.show_logo_btn),
+            }
+            glib::Propagation::Stop
+        }
+
+        /// Previews the next schedule item and puts it live
+        fn item_live_next(&self, forward: bool) {
+            if self.schedule_viewer.activate_next_item(forward) {
+                self.preview_viewer.emit_activate_slide();
+            }
+        }
+
+        /// Shows the next slide of the live item with the verse `tag`
+        fn show_verse(live: &LiveOutput, tag: &str) {
+            let Some(item) = live.item() else {
+                return;
             };
+            let tags = item.slides.iter().map(|s| s.tag.as_deref());
+            if let Some(position) = shortcuts::find_tag(tags, live.slide(), tag) {
+                live.show_slide(position);
+            }
+        }
 
-            Some(geometry)
+        fn log_song_usage(&self, change: UsageChange) {
+            let res = match change {
+                UsageChange::Started {
+                    song_id,
+                    slide_count,
+                } => Query::log_song_usage(song_id, slide_count).map(|id| {
+                    self.song_usage.borrow_mut().set_usage_id(id);
+                    if let Some(app) = self.obj().application().and_downcast::<OwApplication>() {
+                        app.emit_song_used();
+                    }
+                }),
+                UsageChange::SlidesShown {
+                    usage_id,
+                    slide_count,
+                } => Query::update_song_usage(usage_id, slide_count),
+                UsageChange::None => Ok(()),
+            };
+
+            if let Err(e) = res {
+                eprintln!("SQL ERROR: {:?}", e);
+            }
         }
     }
 }
@@ -263,5 +473,15 @@ impl MainApplicationWindow {
     pub fn show_all(&self) {
         self.present();
         self.extended_screen().present();
+        if let Some(output_manager) = self.imp().output_manager.borrow().as_ref() {
+            output_manager.apply();
+        }
+    }
+
+    /// Opens the livestream lower third in a window
+    pub fn show_lower_third(&self) {
+        if let Some(output_manager) = self.imp().output_manager.borrow().as_ref() {
+            output_manager.show_lower_third();
+        }
     }
 }
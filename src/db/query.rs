@@ -1,10 +1,16 @@
+use std::collections::{BTreeMap, HashMap};
+
 use gtk::glib::{self};
-use rusqlite::params;
+use rusqlite::{OptionalExtension, params};
 
 use crate::{
     db::connection::BibleVerse,
-    dto::{SongData, SongVerse},
-    services::{alert::Alert, settings::ApplicationSettings},
+    dto::{SongData, SongDetails, SongRevision, SongTag, SongUsageReport, SongVerse},
+    services::{
+        alert::Alert,
+        settings::ApplicationSettings,
+        theme::{SlideTheme, ThemeContent},
+    },
     widgets::canvas::serialise::{CanvasItemType, SlideData, SlideManagerData},
 };
 
@@ -107,7 +113,8 @@ impl Query {
         Ok(rows)
     }
 
-    pub fn insert_song(song: &SongData) -> Result<(), DBError> {
+    /// Inserts a new song and returns its id
+    pub fn insert_song(song: &SongData) -> Result<u32, DBError> {
         let song_sql = r#"
             INSERT INTO songs(title) VALUES(?1) RETURNING id
         "#;
@@ -133,8 +140,11 @@ impl Query {
                     ),
                 )?;
             }
+            save_verse_translations(&tx, song_id, &song.verses)?;
+            save_song_details(&tx, song_id, &song.details)?;
 
-            tx.commit()
+            tx.commit()?;
+            Ok(song_id)
         })?;
 
         Ok(r)
@@ -157,6 +167,8 @@ impl Query {
                 let mut song_verse_stmt = tx.prepare(song_verse_sql)?;
                 for song in songs {
                     let song_id = song_stmt.query_row([&song.title], |r| r.get::<_, u32>(0))?;
+                    save_song_details(&tx, song_id, &song.details)?;
+                    save_verse_translations(&tx, song_id, &song.verses)?;
 
                     for (i, verse) in song.verses.iter().enumerate() {
                         song_verse_stmt.execute((
@@ -186,8 +198,15 @@ impl Query {
             INSERT INTO song_verses(song_id,verse,text,tag,slide) VALUES(?1,?2,?3,?4,jsonb(?5))
         "#;
 
+        let settings = ApplicationSettings::get_instance();
+        let revision_limit = settings.song_revision_limit();
+        let revision_max_age = settings.song_revision_max_age();
+
         let r = DatabaseConnection::with_mut_db(|conn| {
             let tx = conn.transaction()?;
+            // keep what is about to be overwritten
+            save_song_revision(&tx, song)?;
+
             tx.execute(song_sql, (&song.title, &song.song_id))?;
             tx.execute(clear_song_verses_sql, [&song.song_id])?;
 
@@ -203,6 +222,9 @@ impl Query {
                     ),
                 )?;
             }
+            save_verse_translations(&tx, song.song_id, &song.verses)?;
+
+            prune_song_revisions(&tx, song.song_id, revision_limit, revision_max_age)?;
 
             tx.commit()
         })?;
@@ -213,9 +235,23 @@ impl Query {
     pub fn delete_song(song: SongData) -> Result<(), DBError> {
         let song_sql = "DELETE FROM songs WHERE id = ?1";
         let song_verses_sql = "DELETE FROM song_verses WHERE song_id = ?1";
+        let song_revisions_sql = "DELETE FROM song_revisions WHERE song_id = ?1";
+        let song_aliases_sql = "DELETE FROM song_aliases WHERE song_id = ?1";
+        let song_details_sql = "DELETE FROM song_details WHERE song_id = ?1";
+        let song_tags_sql = "DELETE FROM song_tag_links WHERE song_id = ?1";
+        let song_favourite_sql = "DELETE FROM song_favourites WHERE song_id = ?1";
+        let song_translations_sql = "DELETE FROM song_verse_translations WHERE song_id = ?1";
+        let song_theme_sql = "DELETE FROM song_themes WHERE song_id = ?1";
 
         let r = DatabaseConnection::with_mut_db(|conn| {
             let tx = conn.transaction()?;
+            tx.execute(song_translations_sql, [&song.song_id])?;
+            tx.execute(song_revisions_sql, [&song.song_id])?;
+            tx.execute(song_aliases_sql, [&song.song_id])?;
+            tx.execute(song_details_sql, [&song.song_id])?;
+            tx.execute(song_tags_sql, [&song.song_id])?;
+            tx.execute(song_favourite_sql, [&song.song_id])?;
+            tx.execute(song_theme_sql, [&song.song_id])?;
             tx.execute(song_verses_sql, [&song.song_id])?;
             tx.execute(song_sql, [&song.song_id])?;
 
@@ -225,6 +261,418 @@ impl Query {
         Ok(r)
     }
 
+    pub fn get_song(song_id: u32) -> Result<Option<SongData>, DBError> {
+        let r = DatabaseConnection::with_db(|conn| {
+            let title = conn
+                .query_row("SELECT title FROM songs WHERE id = ?1", [song_id], |r| {
+                    r.get::<_, String>(0)
+                })
+                .optional()?;
+            let Some(title) = title else {
+                return Ok(None);
+            };
+
+            let mut translations = get_verse_translations(conn, song_id)?;
+            let mut stmt = conn.prepare(
+                "SELECT verse, text, tag, json(slide) FROM song_verses WHERE song_id = ?1 ORDER BY verse ASC",
+            )?;
+            let verses = stmt
+                .query_map([song_id], |r| {
+                    let verse = r.get::<_, u32>(0)?;
+                    let text = r.get::<_, String>(1)?;
+                    let tag = r.get::<_, Option<String>>(2)?;
+                    let slide = r.get::<_, Option<String>>(3)?;
+
+                    let slide = slide_str_to_slide_data_str(text.clone(), slide);
+                    let mut song_verse = SongVerse::new(text, tag, slide);
+                    song_verse.translations = translations.remove(&verse).unwrap_or_default();
+                    Ok(song_verse)
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            Ok(Some(SongData::new(song_id, title, verses)))
+        })?;
+
+        Ok(r)
+    }
+
+    /// Replaces `song` with the merged result and removes the songs in
+    /// `merged_ids`. Their revisions move to `song` and their ids are kept
+    /// as aliases so items referencing them resolve to `song`
+    pub fn merge_songs(song: &SongData, merged_ids: &[u32]) -> Result<(), DBError> {
+        let song_sql = "UPDATE songs SET title=?1 WHERE id = ?2";
+        let clear_song_verses_sql = "DELETE FROM song_verses WHERE song_id = ?1";
+        let song_verse_sql = r#"
+            INSERT INTO song_verses(song_id,verse,text,tag,slide) VALUES(?1,?2,?3,?4,jsonb(?5))
+        "#;
+        let move_revisions_sql = "UPDATE song_revisions SET song_id = ?1 WHERE song_id = ?2";
+        let move_aliases_sql = "UPDATE song_aliases SET song_id = ?1 WHERE song_id = ?2";
+        let alias_sql = "INSERT OR REPLACE INTO song_aliases(alias_id, song_id) VALUES(?1, ?2)";
+        let move_usage_sql = "UPDATE song_usage SET song_id = ?1 WHERE song_id = ?2";
+        // details of the kept song win, missing ones are taken from merged songs
+        let move_details_sql = r#"
+            INSERT OR IGNORE INTO song_details(song_id, ccli_number, author, copyright)
+            SELECT ?1, ccli_number, author, copyright FROM song_details WHERE song_id = ?2
+        "#;
+        let delete_details_sql = "DELETE FROM song_details WHERE song_id = ?1";
+        let move_tags_sql = r#"
+            INSERT OR IGNORE INTO song_tag_links(song_id, tag_id)
+            SELECT ?1, tag_id FROM song_tag_links WHERE song_id = ?2
+        "#;
+        let delete_tags_sql = "DELETE FROM song_tag_links WHERE song_id = ?1";
+        let move_favourite_sql = r#"
+            INSERT OR IGNORE INTO song_favourites(song_id, created_at)
+            SELECT ?1, created_at FROM song_favourites WHERE song_id = ?2
+        "#;
+        let delete_favourite_sql = "DELETE FROM song_favourites WHERE song_id = ?1";
+        let move_theme_sql = r#"
+            INSERT OR IGNORE INTO song_themes(song_id, theme_id)
+            SELECT ?1, theme_id FROM song_themes WHERE song_id = ?2
+        "#;
+        let delete_theme_sql = "DELETE FROM song_themes WHERE song_id = ?1";
+        let clear_translations_sql = "DELETE FROM song_verse_translations WHERE song_id = ?1";
+        let delete_song_sql = "DELETE FROM songs WHERE id = ?1";
+
+        let settings = ApplicationSettings::get_instance();
+        let revision_limit = settings.song_revision_limit();
+        let revision_max_age = settings.song_revision_max_age();
+
+        let r = DatabaseConnection::with_mut_db(|conn| {
+            let tx = conn.transaction()?;
+            save_song_revision(&tx, song)?;
+
+            tx.execute(song_sql, (&song.title, &song.song_id))?;
+            tx.execute(clear_song_verses_sql, [&song.song_id])?;
+            for (i, verse) in song.verses.iter().enumerate() {
+                tx.execute(
+                    song_verse_sql,
+                    (
+                        &song.song_id,
+                        &i.saturating_add(1),
+                        &verse.text,
+                        &verse.tag,
+                        &verse.slide,
+                    ),
+                )?;
+            }
+            save_verse_translations(&tx, song.song_id, &song.verses)?;
+
+            for id in merged_ids.iter().filter(|id| **id != song.song_id) {
+                tx.execute(move_revisions_sql, (&song.song_id, id))?;
+                tx.execute(move_aliases_sql, (&song.song_id, id))?;
+                tx.execute(alias_sql, (id, &song.song_id))?;
+                tx.execute(move_usage_sql, (&song.song_id, id))?;
+                tx.execute(move_details_sql, (&song.song_id, id))?;
+                tx.execute(delete_details_sql, [id])?;
+                tx.execute(move_tags_sql, (&song.song_id, id))?;
+                tx.execute(delete_tags_sql, [id])?;
+                tx.execute(move_favourite_sql, (&song.song_id, id))?;
+                tx.execute(delete_favourite_sql, [id])?;
+                tx.execute(move_theme_sql, (&song.song_id, id))?;
+                tx.execute(delete_theme_sql, [id])?;
+                tx.execute(clear_translations_sql, [id])?;
+                tx.execute(clear_song_verses_sql, [id])?;
+                tx.execute(delete_song_sql, [id])?;
+            }
+
+            prune_song_revisions(&tx, song.song_id, revision_limit, revision_max_age)?;
+
+            tx.commit()
+        })?;
+
+        Ok(r)
+    }
+
+    pub fn get_song_details(song_id: u32) -> Result<SongDetails, DBError> {
+        let sql = "SELECT ccli_number, author, copyright FROM song_details WHERE song_id = ?1";
+
+        let r = DatabaseConnection::with_db(|conn| {
+            conn.query_row(sql, [song_id], |r| {
+                Ok(SongDetails {
+                    ccli_number: r.get::<_, Option<String>>(0)?,
+                    author: r.get::<_, Option<String>>(1)?,
+                    copyright: r.get::<_, Option<String>>(2)?,
+                })
+            })
+            .optional()
+        })?;
+
+        Ok(r.unwrap_or_default())
+    }
+
+    pub fn update_song_details(song_id: u32, details: &SongDetails) -> Result<(), DBError> {
+        let r = DatabaseConnection::with_mut_db(|conn| {
+            let tx = conn.transaction()?;
+            save_song_details(&tx, song_id, details)?;
+            tx.commit()
+        })?;
+
+        Ok(r)
+    }
+
+    /// Records a song going live and returns the usage id,
+    /// `None` when the song is no longer in the library
+    pub fn log_song_usage(song_id: u32, slide_count: u32) -> Result<Option<u32>, DBError> {
+        let Some(song_id) = Self::resolve_song_id(song_id)? else {
+            return Ok(None);
+        };
+
+        let sql = r#"
+            INSERT INTO song_usage(song_id, title, ccli_number, author, copyright, slide_count)
+            SELECT songs.id, songs.title, d.ccli_number, d.author, d.copyright, ?2
+            FROM songs LEFT JOIN song_details AS d ON d.song_id = songs.id
+            WHERE songs.id = ?1
+            RETURNING id
+        "#;
+
+        let r = DatabaseConnection::with_db(|conn| {
+            conn.query_row(sql, (song_id, slide_count), |r| r.get::<_, u32>(0))
+                .optional()
+        })?;
+
+        Ok(r)
+    }
+
+    pub fn update_song_usage(usage_id: u32, slide_count: u32) -> Result<(), DBError> {
+        let sql = "UPDATE song_usage SET slide_count = ?2 WHERE id = ?1";
+        DatabaseConnection::with_db(|conn| conn.execute(sql, (usage_id, slide_count)))?;
+
+        Ok(())
+    }
+
+    /// Songs shown live between the unix timestamps `from` and `to`,
+    /// most used first
+    pub fn song_usage_report(from: i64, to: i64) -> Result<Vec<SongUsageReport>, DBError> {
+        // with a single MAX() sqlite takes the bare columns from the
+        // latest usage row, so the latest copied details are reported
+        let sql = r#"
+            SELECT song_id, title, ccli_number, author, copyright,
+                COUNT(*), SUM(slide_count), MAX(shown_at)
+            FROM song_usage
+            WHERE shown_at >= ?1 AND shown_at < ?2
+            GROUP BY song_id
+            ORDER BY COUNT(*) DESC, title ASC
+        "#;
+
+        let r = DatabaseConnection::with_db(|conn| {
+            let mut stmt = conn.prepare(sql)?;
+            let rows = stmt
+                .query_map((from, to), |r| {
+                    Ok(SongUsageReport {
+                        song_id: r.get::<_, u32>(0)?,
+                        title: r.get::<_, String>(1)?,
+                        details: SongDetails {
+                            ccli_number: r.get::<_, Option<String>>(2)?,
+                            author: r.get::<_, Option<String>>(3)?,
+                            copyright: r.get::<_, Option<String>>(4)?,
+                        },
+                        times_used: r.get::<_, u32>(5)?,
+                        slides_shown: r.get::<_, u32>(6)?,
+                        last_used: r.get::<_, i64>(7)?,
+                    })
+                })?
+                .filter_map(|v| match v {
+                    Ok(v) => Some(v),
+                    Err(e) => {
+                        eprintln!("SQL ERROR: {:?}", e);
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            Ok(rows)
+        })?;
+
+        Ok(r)
+    }
+
+    pub fn get_song_tags() -> Result<Vec<SongTag>, DBError> {
+        let sql = "SELECT id, name FROM song_tags ORDER BY name ASC";
+
+        let r = DatabaseConnection::with_db(|conn| {
+            let mut stmt = conn.prepare(sql)?;
+            stmt.query_map([], |r| {
+                Ok(SongTag {
+                    tag_id: r.get::<_, u32>(0)?,
+                    name: r.get::<_, String>(1)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()
+        })?;
+
+        Ok(r)
+    }
+
+    /// Creates the tag `name`, returns the id of the existing tag
+    /// when one with the same name is present
+    pub fn insert_song_tag(name: &str) -> Result<u32, DBError> {
+        let insert_sql = "INSERT OR IGNORE INTO song_tags(name) VALUES(?1)";
+        let select_sql = "SELECT id FROM song_tags WHERE name = ?1";
+
+        let r = DatabaseConnection::with_mut_db(|conn| {
+            let tx = conn.transaction()?;
+            tx.execute(insert_sql, [name])?;
+            let id = tx.query_row(select_sql, [name], |r| r.get::<_, u32>(0))?;
+            tx.commit()?;
+            Ok(id)
+        })?;
+
+        Ok(r)
+    }
+
+    pub fn delete_song_tag(tag_id: u32) -> Result<(), DBError> {
+        let r = DatabaseConnection::with_mut_db(|conn| {
+            let tx = conn.transaction()?;
+            tx.execute("DELETE FROM song_tag_links WHERE tag_id = ?1", [tag_id])?;
+            tx.execute("DELETE FROM song_tags WHERE id = ?1", [tag_id])?;
+            tx.commit()
+        })?;
+
+        Ok(r)
+    }
+
+    /// Ids of the tags `song_id` belongs to
+    pub fn get_tags_of_song(song_id: u32) -> Result<Vec<u32>, DBError> {
+        let sql = "SELECT tag_id FROM song_tag_links WHERE song_id = ?1";
+
+        let r = DatabaseConnection::with_db(|conn| {
+            let mut stmt = conn.prepare(sql)?;
+            stmt.query_map([song_id], |r| r.get::<_, u32>(0))?
+                .collect::<rusqlite::Result<Vec<_>>>()
+        })?;
+
+        Ok(r)
+    }
+
+    pub fn set_song_tagged(song_id: u32, tag_id: u32, tagged: bool) -> Result<(), DBError> {
+        let sql = match tagged {
+            true => "INSERT OR IGNORE INTO song_tag_links(song_id, tag_id) VALUES(?1, ?2)",
+            false => "DELETE FROM song_tag_links WHERE song_id = ?1 AND tag_id = ?2",
+        };
+
+        DatabaseConnection::with_db(|conn| conn.execute(sql, (song_id, tag_id)))?;
+        Ok(())
+    }
+
+    /// Ids of the songs in the tag `tag_id`
+    pub fn get_tagged_song_ids(tag_id: u32) -> Result<Vec<u32>, DBError> {
+        let sql = "SELECT song_id FROM song_tag_links WHERE tag_id = ?1";
+
+        let r = DatabaseConnection::with_db(|conn| {
+            let mut stmt = conn.prepare(sql)?;
+            stmt.query_map([tag_id], |r| r.get::<_, u32>(0))?
+                .collect::<rusqlite::Result<Vec<_>>>()
+        })?;
+
+        Ok(r)
+    }
+
+    pub fn set_song_favourite(song_id: u32, favourite: bool) -> Result<(), DBError> {
+        let sql = match favourite {
+            true => "INSERT OR IGNORE INTO song_favourites(song_id) VALUES(?1)",
+            false => "DELETE FROM song_favourites WHERE song_id = ?1",
+        };
+
+        DatabaseConnection::with_db(|conn| conn.execute(sql, [song_id]))?;
+        Ok(())
+    }
+
+    pub fn get_favourite_song_ids() -> Result<Vec<u32>, DBError> {
+        let sql = "SELECT song_id FROM song_favourites";
+
+        let r = DatabaseConnection::with_db(|conn| {
+            let mut stmt = conn.prepare(sql)?;
+            stmt.query_map([], |r| r.get::<_, u32>(0))?
+                .collect::<rusqlite::Result<Vec<_>>>()
+        })?;
+
+        Ok(r)
+    }
+
+    /// Ids of the last `limit` songs shown live, most recent first
+    pub fn get_recent_song_ids(limit: u32) -> Result<Vec<u32>, DBError> {
+        let sql = r#"
+            SELECT song_id FROM song_usage
+            GROUP BY song_id
+            ORDER BY MAX(shown_at) DESC
+            LIMIT ?1
+        "#;
+
+        let r = DatabaseConnection::with_db(|conn| {
+            let mut stmt = conn.prepare(sql)?;
+            stmt.query_map([limit], |r| r.get::<_, u32>(0))?
+                .collect::<rusqlite::Result<Vec<_>>>()
+        })?;
+
+        Ok(r)
+    }
+
+    /// Id of the library song `song_id` currently refers to,
+    /// following merges. `None` when the song was deleted
+    pub fn resolve_song_id(song_id: u32) -> Result<Option<u32>, DBError> {
+        let sql = r#"
+            SELECT id FROM songs WHERE id = ?1
+            UNION ALL
+            SELECT song_id FROM song_aliases WHERE alias_id = ?1
+            LIMIT 1
+        "#;
+
+        let r = DatabaseConnection::with_db(|conn| {
+            conn.query_row(sql, [song_id], |r| r.get::<_, u32>(0))
+                .optional()
+        })?;
+
+        Ok(r)
+    }
+
+    /// Revisions of a song, newest first
+    pub fn get_song_revisions(song_id: u32) -> Result<Vec<SongRevision>, DBError> {
+        let sql = r#"
+            SELECT id, title, json(verses), created_at FROM song_revisions
+            WHERE song_id = ?1 ORDER BY created_at DESC, id DESC
+        "#;
+
+        let r = DatabaseConnection::with_db(|conn| {
+            let mut stmt = conn.prepare(sql)?;
+            let revisions = stmt
+                .query_map([song_id], |r| {
+                    let revision_id = r.get::<_, u32>(0)?;
+                    let title = r.get::<_, String>(1)?;
+                    let verses = r.get::<_, String>(2)?;
+                    let created_at = r.get::<_, i64>(3)?;
+
+                    let verses = serde_json::from_str::<Vec<SongVerse>>(&verses)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|v| {
+                            let slide = slide_str_to_slide_data_str(v.text.clone(), v.slide);
+                            let mut verse = SongVerse::new(v.text, v.tag, slide);
+                            verse.translations = v.translations;
+                            verse
+                        })
+                        .collect::<Vec<_>>();
+
+                    Ok(SongRevision {
+                        revision_id,
+                        created_at,
+                        song: SongData::new(song_id, title, verses),
+                    })
+                })?
+                .filter_map(|v| match v {
+                    Ok(v) => Some(v),
+                    Err(e) => {
+                        eprintln!("SQL ERROR: {:?}", e);
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            Ok(revisions)
+        })?;
+
+        Ok(r)
+    }
+
     pub fn search_songs(search_text: &str, title_mode: bool) -> Result<Vec<SongData>, DBError> {
         let r = DatabaseConnection::with_mut_db(|conn| {
             let song_sql = match title_mode {
@@ -250,7 +698,9 @@ impl Query {
 
             let mut songs = Vec::new();
             for song in db_songs {
+                let mut translations = get_verse_translations(conn, song.0)?;
                 let verses_query = songs_verses_sql.query_map([&song.0], |r| {
+                    let verse = r.get::<_, u32>(0)?;
                     let text = r.get::<_, Option<String>>(1)?;
                     let tag = r.get::<_, Option<String>>(2)?;
                     let slide = r.get::<_, Option<String>>(3)?;
@@ -258,7 +708,9 @@ impl Query {
                     let slide =
                         slide_str_to_slide_data_str(text.clone().unwrap_or_default(), slide);
 
-                    Ok(SongVerse::new(text.unwrap_or_default(), tag, slide))
+                    let mut song_verse = SongVerse::new(text.unwrap_or_default(), tag, slide);
+                    song_verse.translations = translations.remove(&verse).unwrap_or_default();
+                    Ok(song_verse)
                 })?;
 
                 let verses = verses_query.map(|v| v.unwrap()).collect::<Vec<SongVerse>>();
@@ -494,6 +946,294 @@ impl Query {
 
         Ok(())
     }
+
+    pub fn get_themes() -> Result<Vec<SlideTheme>, DBError> {
+        let sql = "SELECT id, name, json(theme) FROM themes ORDER BY name ASC";
+
+        let r = DatabaseConnection::with_db(|conn| {
+            let mut stmt = conn.prepare(sql)?;
+            stmt.query_map([], row_to_theme)?
+                .collect::<rusqlite::Result<Vec<_>>>()
+        })?;
+
+        Ok(r)
+    }
+
+    pub fn get_theme(theme_id: u32) -> Result<Option<SlideTheme>, DBError> {
+        let sql = "SELECT id, name, json(theme) FROM themes WHERE id = ?1";
+
+        let r = DatabaseConnection::with_db(|conn| {
+            conn.query_row(sql, [theme_id], row_to_theme).optional()
+        })?;
+
+        Ok(r)
+    }
+
+    /// Saves `theme` under its name, returns the new theme id
+    pub fn insert_theme(theme: &SlideTheme) -> Result<u32, DBError> {
+        let sql = "INSERT INTO themes(name, theme) VALUES(?1, jsonb(?2))";
+        let theme_str =
+            serde_json::to_string(theme).map_err(|e| DBError::CustomError(format!("{:?}", e)))?;
+
+        let r = DatabaseConnection::with_db(|conn| {
+            conn.execute(sql, (&theme.name, &theme_str))?;
+            Ok(conn.last_insert_rowid() as u32)
+        })?;
+
+        Ok(r)
+    }
+
+    pub fn update_theme(theme: &SlideTheme) -> Result<(), DBError> {
+        let sql = "UPDATE themes SET name = ?2, theme = jsonb(?3) WHERE id = ?1";
+        let theme_str =
+            serde_json::to_string(theme).map_err(|e| DBError::CustomError(format!("{:?}", e)))?;
+
+        DatabaseConnection::with_db(|conn| conn.execute(sql, (theme.id, &theme.name, &theme_str)))?;
+        Ok(())
+    }
+
+    /// Deletes the theme along with the defaults and songs using it
+    pub fn delete_theme(theme_id: u32) -> Result<(), DBError> {
+        let r = DatabaseConnection::with_mut_db(|conn| {
+            let tx = conn.transaction()?;
+            tx.execute("DELETE FROM theme_defaults WHERE theme_id = ?1", [theme_id])?;
+            tx.execute("DELETE FROM song_themes WHERE theme_id = ?1", [theme_id])?;
+            tx.execute("DELETE FROM themes WHERE id = ?1", [theme_id])?;
+            tx.commit()
+        })?;
+
+        Ok(r)
+    }
+
+    pub fn get_default_theme(content: ThemeContent) -> Result<Option<SlideTheme>, DBError> {
+        let sql = r#"
+            SELECT t.id, t.name, json(t.theme) FROM themes t
+            JOIN theme_defaults d ON d.theme_id = t.id
+            WHERE d.content = ?1
+        "#;
+
+        let r = DatabaseConnection::with_db(|conn| {
+            conn.query_row(sql, [content.key()], row_to_theme)
+                .optional()
+        })?;
+
+        Ok(r)
+    }
+
+    /// Makes `theme_id` the default theme for `content`, `None` goes back to the built in look
+    pub fn set_default_theme(content: ThemeContent, theme_id: Option<u32>) -> Result<(), DBError> {
+        DatabaseConnection::with_db(|conn| match theme_id {
+            Some(id) => conn.execute(
+                "INSERT OR REPLACE INTO theme_defaults(content, theme_id) VALUES(?1, ?2)",
+                (content.key(), id),
+            ),
+            None => conn.execute(
+                "DELETE FROM theme_defaults WHERE content = ?1",
+                [content.key()],
+            ),
+        })?;
+
+        Ok(())
+    }
+
+    pub fn get_song_theme_id(song_id: u32) -> Result<Option<u32>, DBError> {
+        let sql = "SELECT theme_id FROM song_themes WHERE song_id = ?1";
+
+        let r = DatabaseConnection::with_db(|conn| {
+            conn.query_row(sql, [song_id], |r| r.get::<_, u32>(0))
+                .optional()
+        })?;
+
+        Ok(r)
+    }
+
+    /// Shows the song `song_id` with `theme_id`, `None` uses the default theme for songs
+    pub fn set_song_theme(song_id: u32, theme_id: Option<u32>) -> Result<(), DBError> {
+        DatabaseConnection::with_db(|conn| match theme_id {
+            Some(id) => conn.execute(
+                "INSERT OR REPLACE INTO song_themes(song_id, theme_id) VALUES(?1, ?2)",
+                (song_id, id),
+            ),
+            None => conn.execute("DELETE FROM song_themes WHERE song_id = ?1", [song_id]),
+        })?;
+
+        Ok(())
+    }
+}
+
+fn row_to_theme(r: &rusqlite::Row) -> rusqlite::Result<SlideTheme> {
+    let theme = r.get::<_, String>(2)?;
+    let theme = serde_json::from_str::<SlideTheme>(&theme).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+
+    Ok(SlideTheme {
+        id: r.get::<_, u32>(0)?,
+        name: r.get::<_, String>(1)?,
+        ..theme
+    })
+}
+
+/// Stores the current state of `song` as a revision, unless `song`
+/// would leave it unchanged
+fn save_song_revision(tx: &rusqlite::Transaction, song: &SongData) -> rusqlite::Result<()> {
+    let title = tx
+        .query_row(
+            "SELECT title FROM songs WHERE id = ?1",
+            [&song.song_id],
+            |r| r.get::<_, String>(0),
+        )
+        .optional()?;
+    let Some(title) = title else {
+        return Ok(());
+    };
+
+    let verses = {
+        let mut translations = get_verse_translations(tx, song.song_id)?;
+        let mut stmt = tx.prepare(
+            "SELECT verse, text, tag, json(slide) FROM song_verses WHERE song_id = ?1 ORDER BY verse ASC",
+        )?;
+        stmt.query_map([&song.song_id], |r| {
+            let mut verse = SongVerse::new(
+                r.get::<_, String>(1)?,
+                r.get::<_, Option<String>>(2)?,
+                r.get::<_, Option<String>>(3)?,
+            );
+            verse.translations = translations
+                .remove(&r.get::<_, u32>(0)?)
+                .unwrap_or_default();
+            Ok(verse)
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?
+    };
+
+    let slide_value = |slide: &Option<String>| {
+        slide
+            .as_ref()
+            .and_then(|s| serde_json::from_str::<serde_json::Value>(s).ok())
+    };
+    let unchanged = title == song.title
+        && verses.len() == song.verses.len()
+        && verses.iter().zip(song.verses.iter()).all(|(old, new)| {
+            old.text == new.text
+                && old.tag == new.tag
+                && old.translations == new.translations
+                && slide_value(&old.slide) == slide_value(&new.slide)
+        });
+    if unchanged {
+        return Ok(());
+    }
+
+    let verses = serde_json::to_string(&verses)
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    tx.execute(
+        "INSERT INTO song_revisions(song_id, title, verses) VALUES(?1, ?2, jsonb(?3))",
+        (&song.song_id, &title, &verses),
+    )?;
+
+    Ok(())
+}
+
+fn save_song_details(
+    tx: &rusqlite::Transaction,
+    song_id: u32,
+    details: &SongDetails,
+) -> rusqlite::Result<()> {
+    if details.is_empty() {
+        tx.execute("DELETE FROM song_details WHERE song_id = ?1", [song_id])?;
+        return Ok(());
+    }
+
+    tx.execute(
+        r#"
+        INSERT OR REPLACE INTO song_details(song_id, ccli_number, author, copyright)
+        VALUES(?1, ?2, ?3, ?4)
+        "#,
+        (
+            song_id,
+            &details.ccli_number,
+            &details.author,
+            &details.copyright,
+        ),
+    )?;
+
+    Ok(())
+}
+
+/// Keeps the newest `limit` revisions of a song and drops revisions
+/// older than `max_age` days. A `max_age` of 0 keeps them forever
+fn prune_song_revisions(
+    tx: &rusqlite::Transaction,
+    song_id: u32,
+    limit: u32,
+    max_age: u32,
+) -> rusqlite::Result<()> {
+    tx.execute(
+        r#"
+        DELETE FROM song_revisions WHERE song_id = ?1 AND id NOT IN (
+            SELECT id FROM song_revisions WHERE song_id = ?1
+            ORDER BY created_at DESC, id DESC LIMIT ?2
+        )
+        "#,
+        (song_id, limit),
+    )?;
+
+    if max_age > 0 {
+        tx.execute(
+            "DELETE FROM song_revisions WHERE song_id = ?1 AND created_at < unixepoch() - ?2 * 86400",
+            (song_id, max_age),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Translations of the verses of a song keyed by verse number
+fn get_verse_translations(
+    conn: &rusqlite::Connection,
+    song_id: u32,
+) -> rusqlite::Result<HashMap<u32, BTreeMap<String, String>>> {
+    let mut stmt =
+        conn.prepare("SELECT verse, lang, text FROM song_verse_translations WHERE song_id = ?1")?;
+    let rows = stmt.query_map([song_id], |r| {
+        Ok((
+            r.get::<_, u32>(0)?,
+            r.get::<_, String>(1)?,
+            r.get::<_, String>(2)?,
+        ))
+    })?;
+
+    let mut translations: HashMap<u32, BTreeMap<String, String>> = HashMap::new();
+    for row in rows {
+        let (verse, lang, text) = row?;
+        translations.entry(verse).or_default().insert(lang, text);
+    }
+
+    Ok(translations)
+}
+
+/// Replaces the stored translations of a song, verse numbers
+/// follow the position of the verse like in `song_verses`
+fn save_verse_translations(
+    tx: &rusqlite::Transaction,
+    song_id: u32,
+    verses: &[SongVerse],
+) -> rusqlite::Result<()> {
+    tx.execute(
+        "DELETE FROM song_verse_translations WHERE song_id = ?1",
+        [song_id],
+    )?;
+
+    let mut stmt = tx.prepare(
+        "INSERT INTO song_verse_translations(song_id, verse, lang, text) VALUES(?1, ?2, ?3, ?4)",
+    )?;
+    for (i, verse) in verses.iter().enumerate() {
+        for (lang, text) in &verse.translations {
+            stmt.execute((song_id, i.saturating_add(1), lang, text))?;
+        }
+    }
+
+    Ok(())
 }
 
 fn slide_str_to_slide_data_str(text: String, slide: Option<String>) -> Option<String> {
@@ -15,6 +15,71 @@ CREATE TABLE IF NOT EXISTS song_verses (
   FOREIGN KEY (song_id) REFERENCES songs(id) ON DELETE CASCADE
 );
 
+CREATE TABLE IF NOT EXISTS song_revisions (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  song_id INTEGER NOT NULL,
+  title TEXT NOT NULL,
+  verses BLOB NOT NULL,
+  created_at INTEGER NOT NULL DEFAULT (unixepoch()),
+  FOREIGN KEY (song_id) REFERENCES songs(id) ON DELETE CASCADE
+);
+
+-- songs merged away by the duplicate tool, pointing at the song kept
+CREATE TABLE IF NOT EXISTS song_aliases (
+  alias_id INTEGER PRIMARY KEY,
+  song_id INTEGER NOT NULL,
+  FOREIGN KEY (song_id) REFERENCES songs(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS song_details (
+  song_id INTEGER PRIMARY KEY,
+  ccli_number TEXT,
+  author TEXT,
+  copyright TEXT,
+  FOREIGN KEY (song_id) REFERENCES songs(id) ON DELETE CASCADE
+);
+
+-- songs shown live, title and licence details are copied so
+-- reports survive songs being edited or deleted
+CREATE TABLE IF NOT EXISTS song_usage (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  song_id INTEGER NOT NULL,
+  title TEXT NOT NULL,
+  ccli_number TEXT,
+  author TEXT,
+  copyright TEXT,
+  slide_count INTEGER NOT NULL DEFAULT 0,
+  shown_at INTEGER NOT NULL DEFAULT (unixepoch())
+);
+
+CREATE INDEX IF NOT EXISTS song_usage_shown_at ON song_usage(shown_at);
+
+-- verse text in other languages, `verse` matches song_verses.verse
+CREATE TABLE IF NOT EXISTS song_verse_translations (
+  song_id INTEGER NOT NULL,
+  verse INTEGER NOT NULL,
+  lang TEXT NOT NULL,
+  text TEXT NOT NULL,
+  PRIMARY KEY (song_id, verse, lang)
+);
+
+-- user defined song collections
+CREATE TABLE IF NOT EXISTS song_tags (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name TEXT NOT NULL UNIQUE COLLATE NOCASE
+);
+
+CREATE TABLE IF NOT EXISTS song_tag_links (
+  song_id INTEGER NOT NULL,
+  tag_id INTEGER NOT NULL,
+  PRIMARY KEY (song_id, tag_id)
+);
+
+CREATE TABLE IF NOT EXISTS song_favourites (
+  song_id INTEGER PRIMARY KEY,
+  created_at INTEGER NOT NULL DEFAULT (unixepoch())
+);
+
 CREATE TABLE IF NOT EXISTS bible_books (
   id INTEGER PRIMARY KEY AUTOINCREMENT,
   name TEXT NOT NULL
@@ -40,4 +105,23 @@ CREATE TABLE IF NOT EXISTS presentations (
   slide_data BLOB NOT NULL
 );
 
+-- named slide looks, `theme` holds a serialised SlideTheme
+CREATE TABLE IF NOT EXISTS themes (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+  theme BLOB NOT NULL
+);
+
+-- theme of new slides per content type: song, scripture or presentation
+CREATE TABLE IF NOT EXISTS theme_defaults (
+  content TEXT PRIMARY KEY,
+  theme_id INTEGER NOT NULL
+);
+
+-- songs shown with a theme other than the default for songs
+CREATE TABLE IF NOT EXISTS song_themes (
+  song_id INTEGER PRIMARY KEY,
+  theme_id INTEGER NOT NULL
+);
+
 COMMIT;
@@ -1,13 +1,21 @@
+use std::collections::BTreeMap;
+
 use gtk::glib;
 use gtk::glib::subclass::types::ObjectSubclassIsExt;
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    services::settings::ApplicationSettings,
+    db::query::Query,
+    services::{
+        settings::ApplicationSettings,
+        song_languages::{SecondaryLanguageStyle, bilingual_markup, find_translation},
+        theme::{SlideTheme, ThemeContent, song_footer},
+    },
     widgets::canvas::serialise::{CanvasItemType, SlideData, SlideManagerData},
 };
 
 // SONG VERSE
-#[derive(Debug, Clone, Default, PartialEq, Eq, glib::Boxed)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, glib::Boxed)]
 #[boxed_type(name = "SongVerse")]
 pub struct SongVerse {
     /// song tags are identifiers like
@@ -17,21 +25,44 @@ pub struct SongVerse {
     pub tag: Option<String>,
     pub text: String,
     pub slide: Option<String>,
+    /// the verse in other languages, keyed by language code
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub translations: BTreeMap<String, String>,
 }
 
 impl SongVerse {
     pub fn new(text: String, tag: Option<String>, slide: Option<String>) -> Self {
-        SongVerse { tag, text, slide }
+        SongVerse {
+            tag,
+            text,
+            slide,
+            translations: BTreeMap::new(),
+        }
     }
 }
 
 // SONG
 
+/// Licensing details of a song, used for CCLI reporting
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SongDetails {
+    pub ccli_number: Option<String>,
+    pub author: Option<String>,
+    pub copyright: Option<String>,
+}
+
+impl SongDetails {
+    pub fn is_empty(&self) -> bool {
+        self.ccli_number.is_none() && self.author.is_none() && self.copyright.is_none()
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct SongData {
     pub song_id: u32,
     pub title: String,
     pub verses: Vec<SongVerse>,
+    pub details: SongDetails,
 }
 
 impl SongData {
@@ -40,10 +71,58 @@ impl SongData {
             song_id: id,
             title,
             verses,
+            details: SongDetails::default(),
+        }
+    }
+
+    /// Plain text view of the song used when comparing revisions,
+    /// one line per lyric line with the verse tag as a header
+    pub fn plain_text(&self) -> String {
+        let mut lines = vec![self.title.clone(), String::new()];
+
+        for verse in &self.verses {
+            if let Some(tag) = &verse.tag {
+                lines.push(format!("[{tag}]"));
+            }
+            lines.extend(verse.text.lines().map(String::from));
+            lines.push(String::new());
         }
+
+        lines.join("\n")
     }
 }
 
+// SONG USAGE
+
+/// Usage of a song within a reporting period
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SongUsageReport {
+    pub song_id: u32,
+    pub title: String,
+    pub details: SongDetails,
+    pub times_used: u32,
+    pub slides_shown: u32,
+    /// unix timestamp
+    pub last_used: i64,
+}
+
+/// User defined collection songs can be added to
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SongTag {
+    pub tag_id: u32,
+    pub name: String,
+}
+
+// SONG REVISION
+
+#[derive(Debug, Clone, Default)]
+pub struct SongRevision {
+    pub revision_id: u32,
+    /// unix timestamp
+    pub created_at: i64,
+    pub song: SongData,
+}
+
 mod imp {
     use std::cell::{Cell, RefCell};
 
@@ -120,37 +199,44 @@ impl SongObject {
     pub fn song_data(&self) -> SongData {
         self.imp().data.borrow().clone()
     }
-}
-impl From<SongData> for SongObject {
-    fn from(data: SongData) -> Self {
-        let obj = SongObject::from_verses(data.title, data.verses, data.song_id);
-        obj
-    }
-}
-
-// Convert from SongObject to SongData
-impl From<SongObject> for SongData {
-    fn from(obj: SongObject) -> Self {
-        obj.imp().data.borrow().clone()
-    }
-}
 
-impl Into<SlideManagerData> for SongObject {
-    fn into(self) -> SlideManagerData {
+    /// Slides of the song. When `bilingual` is set the secondary language
+    /// chosen in the settings is shown under each verse that has it
+    pub fn slide_manager_data(&self, bilingual: bool) -> SlideManagerData {
         let settings = ApplicationSettings::get_instance();
+        let secondary = Some(settings.song_secondary_language())
+            .filter(|lang| bilingual && !lang.is_empty())
+            .map(|lang| SecondaryLanguageStyle {
+                lang,
+                font: settings.song_secondary_font(),
+                size_percent: settings.song_secondary_size(),
+                italic: settings.song_secondary_italic(),
+            });
+
         let slide_list = self
             .verses()
             .into_iter()
-            .map(|s| {
-                let mut s = s
+            .map(|verse| {
+                let mut s = verse
                     .slide
                     .as_ref()
                     .and_then(|val| serde_json::from_str(val).ok())
                     .unwrap_or_else(SlideData::from_default);
+                s.tag = verse.tag.clone();
+
+                let translation = secondary.as_ref().and_then(|style| {
+                    find_translation(&verse.translations, &style.lang)
+                        .map(|t| bilingual_markup(&verse.text, t, style))
+                });
+
                 for v in &mut s.items {
                     match &mut v.item_type {
                         CanvasItemType::Text(text_item_data) => {
                             text_item_data.font = settings.song_font();
+                            if let Some(text) = &translation {
+                                text_item_data.text_data =
+                                    glib::base64_encode(text.as_bytes()).into();
+                            }
                         }
                         _ => (),
                     };
@@ -161,9 +247,37 @@ impl Into<SlideManagerData> for SongObject {
 
         let mut sm_data = SlideManagerData::new(0, 0, slide_list);
         sm_data.title = self.title();
+        sm_data.song_id = (self.song_id() != 0).then_some(self.song_id());
         sm_data
     }
 }
+impl From<SongData> for SongObject {
+    fn from(data: SongData) -> Self {
+        let obj = SongObject::from_verses(data.title, data.verses, data.song_id);
+        obj
+    }
+}
+
+// Convert from SongObject to SongData
+impl From<SongObject> for SongData {
+    fn from(obj: SongObject) -> Self {
+        obj.imp().data.borrow().clone()
+    }
+}
+
+impl Into<SlideManagerData> for SongObject {
+    fn into(self) -> SlideManagerData {
+        let mut data = self.slide_manager_data(true);
+        if let Some(theme) = SlideTheme::for_song(self.song_id()) {
+            let footer = theme.show_footer.then(|| {
+                let details = Query::get_song_details(self.song_id()).unwrap_or_default();
+                song_footer(&self.title(), &details)
+            });
+            theme.apply_to_plain(&mut data, footer.as_deref());
+        }
+        data
+    }
+}
 
 impl From<SlideManagerData> for SongObject {
     /// Converts [SlideManagerData] into a [SongObject].
@@ -177,6 +291,8 @@ impl From<SlideManagerData> for SongObject {
             .slides
             .iter_mut()
             .filter_map(|slide| {
+                // the tag is kept on the verse, not in its slide
+                let tag = slide.tag.take();
                 for v in &mut slide.items.iter_mut() {
                     match &mut v.item_type {
                         CanvasItemType::Text(text_item) => {
@@ -186,7 +302,7 @@ impl From<SlideManagerData> for SongObject {
                                 .then_some(serde_json::to_string(&slide).ok().unwrap_or_default());
                             let song_verse = SongVerse::new(
                                 String::from_utf8(b64).unwrap_or_default(),
-                                None,
+                                tag,
                                 slide_data,
                             );
                             return Some(song_verse);
@@ -324,7 +440,10 @@ impl Into<SlideData> for ScriptureVerseRange {
             };
         }
 
-        slide_data
+        match SlideTheme::default_for(ThemeContent::Scripture) {
+            Some(theme) => theme.apply(&slide_data, Some(&self.translation)),
+            None => slide_data,
+        }
     }
 }
 
@@ -388,7 +507,10 @@ pub mod scripture {
                 };
             }
 
-            slide_data
+            match SlideTheme::default_for(ThemeContent::Scripture) {
+                Some(theme) => theme.apply(&slide_data, Some(&self.item().translation)),
+                None => slide_data,
+            }
         }
     }
 
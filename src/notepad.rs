@@ -11,7 +11,6 @@ use gtk::glib;
 use gtk::glib::collections::slist;
 use gtk::glib::object::IsA;
 use gtk::glib::prelude::*;
-use gtk::glib::subclass::types::ObjectSubclassIsExt;
 use gtk::prelude::{
     AccessibleExt, AdjustmentExt, BoxExt, ButtonExt, DialogExt, GtkApplicationExt, GtkWindowExt,
     OrientableExt, ScrollableExt, SnapshotExt, StyleContextExt, TextBufferExt, TextViewExt,
@@ -29,7 +28,7 @@ use crate::services::message_alert_manager::MessageAlertManager;
 use crate::services::slide::Slide;
 use crate::services::slide_manager::SlideManager;
 use crate::utils::{WidgetChildrenExt, WidgetExtrasExt};
-use crate::widgets::canvas::canvas_item::{CanvasItem, CanvasItemExt};
+use crate::widgets::canvas::canvas_item::CanvasItemExt;
 use crate::widgets::canvas::serialise::{SlideData, SlideManagerData};
 use crate::widgets::canvas::text_item::{self, TextItem};
 use crate::widgets::entry_combo::EntryCombo;
@@ -295,9 +294,9 @@ fn build_ui(app: &impl IsA<gtk::Application>) -> impl IsA<gtk::Window> {
                 let Some(ti) = canvas.widget().get_children::<TextItem>().next() else {
                     return;
                 };
-                let ci = ti.clone().upcast::<CanvasItem>();
-                ci.imp().rotation.set(btn.value());
-                ti.style();
+                let mut style = ti.item_style();
+                style.rotation = btn.value();
+                ti.set_item_style(style);
             }
         });
         t_box.append(&a);
@@ -1,7 +1,28 @@
+pub mod advance_state;
 pub mod alert;
+pub mod audio_cues;
+pub mod audio_player;
+pub mod auto_advancer;
+pub mod diff;
 pub mod file_manager;
+pub mod history_manager;
+pub mod live_output;
+pub mod live_state;
+pub mod lower_third;
 pub mod message_alert_manager;
 pub mod openlyrics;
+pub mod output_manager;
+pub mod outputs;
+pub mod renderer;
 pub mod settings;
+pub mod shortcuts;
 pub mod slide;
 pub mod slide_manager;
+pub mod song_duplicates;
+pub mod song_languages;
+pub mod song_usage;
+pub mod stage;
+pub mod text_fit;
+pub mod theme;
+pub mod timer;
+pub mod transition;
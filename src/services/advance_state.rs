@@ -0,0 +1,217 @@
+use std::time::Duration;
+
+use crate::widgets::canvas::serialise::SlideManagerData;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AdvanceAction {
+    /// stop the running countdown, if any
+    Cancel,
+    /// show the slide at `position` once `after` has passed
+    Schedule { position: u32, after: Duration },
+}
+
+/// Decides when the live item moves on to its next slide without an
+/// operator, and keeps a looping item that another item interrupted
+/// so it can be resumed
+#[derive(Debug, Default)]
+pub struct AdvanceState {
+    /// live item that advances on its own
+    live: Option<SlideManagerData>,
+    position: u32,
+    paused: bool,
+    /// looping item that was live before the current one, at the slide
+    /// it was on
+    interrupted: Option<SlideManagerData>,
+}
+
+impl AdvanceState {
+    /// The live item advances on its own
+    pub fn running(&self) -> bool {
+        self.live.is_some()
+    }
+
+    pub fn paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn interrupted(&self) -> Option<&SlideManagerData> {
+        self.interrupted.as_ref()
+    }
+
+    /// An item went live
+    pub fn show_item(&mut self, data: &SlideManagerData) -> AdvanceAction {
+        self.paused = false;
+
+        if data.auto_advance.is_some() {
+            self.live = Some(data.clone());
+            self.interrupted = None;
+            return self.show_slide(data.current_slide);
+        }
+
+        if let Some(mut live) = self.live.take()
+            && live.auto_advance.as_ref().is_some_and(|a| a.loop_slides)
+        {
+            live.current_slide = self.position;
+            self.interrupted = Some(live);
+        }
+        AdvanceAction::Cancel
+    }
+
+    /// The slide at `position` of the live item was shown
+    pub fn show_slide(&mut self, position: u32) -> AdvanceAction {
+        self.position = position;
+
+        let Some(live) = self.live.as_ref().filter(|_| !self.paused) else {
+            return AdvanceAction::Cancel;
+        };
+        let Some(auto_advance) = &live.auto_advance else {
+            return AdvanceAction::Cancel;
+        };
+
+        let count = live.slides.len() as u32;
+        let next = match position + 1 < count {
+            true => position + 1,
+            // a single slide has nowhere to loop to
+            false if auto_advance.loop_slides && count > 1 => 0,
+            false => return AdvanceAction::Cancel,
+        };
+
+        let seconds = live
+            .slides
+            .get(position as usize)
+            .and_then(|s| s.duration)
+            .unwrap_or(auto_advance.duration);
+        // slides without a duration wait for the operator
+        if seconds == 0 {
+            return AdvanceAction::Cancel;
+        }
+
+        AdvanceAction::Schedule {
+            position: next,
+            after: Duration::from_secs(seconds as u64),
+        }
+    }
+
+    pub fn pause(&mut self) -> AdvanceAction {
+        self.paused = true;
+        AdvanceAction::Cancel
+    }
+
+    /// Counts down the current slide again
+    pub fn resume(&mut self) -> AdvanceAction {
+        self.paused = false;
+        self.show_slide(self.position)
+    }
+
+    /// The interrupted loop, to put live again
+    pub fn take_interrupted(&mut self) -> Option<SlideManagerData> {
+        self.interrupted.take()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::canvas::serialise::{AutoAdvance, CanvasData, SlideData};
+
+    fn item(slides: usize, auto_advance: Option<AutoAdvance>) -> SlideManagerData {
+        let slides =
+            (0..slides).map(|_| SlideData::new(0, Vec::new(), Vec::new(), CanvasData::default()));
+        let mut data = SlideManagerData::new(0, 0, slides);
+        data.auto_advance = auto_advance;
+        data
+    }
+
+    fn looping(duration: u32) -> Option<AutoAdvance> {
+        Some(AutoAdvance {
+            duration,
+            loop_slides: true,
+        })
+    }
+
+    fn schedule(position: u32, secs: u64) -> AdvanceAction {
+        AdvanceAction::Schedule {
+            position,
+            after: Duration::from_secs(secs),
+        }
+    }
+
+    #[test]
+    fn test_advance_and_loop() {
+        let mut state = AdvanceState::default();
+        let mut data = item(3, looping(5));
+        data.slides[1].duration = Some(8);
+
+        assert_eq!(state.show_item(&data), schedule(1, 5));
+        assert!(state.running());
+        assert_eq!(state.show_slide(1), schedule(2, 8));
+        assert_eq!(state.show_slide(2), schedule(0, 5));
+
+        data.auto_advance.as_mut().unwrap().loop_slides = false;
+        assert_eq!(state.show_item(&data), schedule(1, 5));
+        assert_eq!(state.show_slide(2), AdvanceAction::Cancel);
+
+        // nothing to loop through
+        assert_eq!(state.show_item(&item(1, looping(5))), AdvanceAction::Cancel);
+    }
+
+    #[test]
+    fn test_manual_items_and_slides() {
+        let mut state = AdvanceState::default();
+        assert_eq!(state.show_item(&item(3, None)), AdvanceAction::Cancel);
+        assert!(!state.running());
+        assert_eq!(state.show_slide(1), AdvanceAction::Cancel);
+
+        let mut data = item(3, looping(5));
+        data.slides[0].duration = Some(0);
+        assert_eq!(state.show_item(&data), AdvanceAction::Cancel);
+        assert_eq!(state.show_slide(1), schedule(2, 5));
+    }
+
+    #[test]
+    fn test_pause_and_resume() {
+        let mut state = AdvanceState::default();
+        state.show_item(&item(3, looping(5)));
+
+        assert_eq!(state.pause(), AdvanceAction::Cancel);
+        assert!(state.paused());
+        // the operator can still move between slides while paused
+        assert_eq!(state.show_slide(2), AdvanceAction::Cancel);
+        assert_eq!(state.resume(), schedule(0, 5));
+        assert!(!state.paused());
+    }
+
+    #[test]
+    fn test_interrupt_loop() {
+        let mut state = AdvanceState::default();
+        let announcements = item(3, looping(5));
+        state.show_item(&announcements);
+        state.show_slide(2);
+
+        assert_eq!(state.show_item(&item(2, None)), AdvanceAction::Cancel);
+        assert!(!state.running());
+        assert_eq!(state.interrupted().map(|d| d.current_slide), Some(2));
+
+        // a second interruption keeps the loop
+        state.show_item(&item(2, None));
+        let resumed = state.take_interrupted().unwrap();
+        assert_eq!(resumed.current_slide, 2);
+        assert!(state.interrupted().is_none());
+        assert_eq!(state.show_item(&resumed), schedule(0, 5));
+
+        // items that do not loop are not resumed
+        let mut state = AdvanceState::default();
+        let mut once = item(3, looping(5));
+        once.auto_advance.as_mut().unwrap().loop_slides = false;
+        state.show_item(&once);
+        state.show_item(&item(2, None));
+        assert!(state.interrupted().is_none());
+
+        // another loop replaces the interrupted one
+        let mut state = AdvanceState::default();
+        state.show_item(&announcements);
+        state.show_item(&item(2, None));
+        state.show_item(&item(2, looping(3)));
+        assert!(state.interrupted().is_none());
+    }
+}
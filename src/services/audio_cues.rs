@@ -0,0 +1,157 @@
+use crate::widgets::canvas::serialise::AudioCue;
+
+/// Where a playing cue came from, a schedule item cue keeps
+/// playing while moving between the slides of that item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CueSource {
+    Item,
+    Slide,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CueAction {
+    /// leave the current audio as is
+    Keep,
+    Stop,
+    /// stop the current audio and play this cue
+    Play(AudioCue),
+}
+
+/// Decides which audio cue plays as items and slides go live
+#[derive(Debug, Default)]
+pub struct CueState {
+    current: Option<(CueSource, AudioCue)>,
+}
+
+impl CueState {
+    #[cfg(test)]
+    pub fn current(&self) -> Option<&AudioCue> {
+        self.current.as_ref().map(|(_, cue)| cue)
+    }
+
+    /// A schedule item went live, this leaves every slide of the previous item
+    pub fn show_item(&mut self, cue: Option<&AudioCue>) -> CueAction {
+        self.show(CueSource::Item, cue)
+    }
+
+    /// A slide of the live item was shown
+    pub fn show_slide(&mut self, cue: Option<&AudioCue>) -> CueAction {
+        self.show(CueSource::Slide, cue)
+    }
+
+    /// The audio was stopped from the transport
+    pub fn stopped(&mut self) {
+        self.current = None;
+    }
+
+    fn show(&mut self, source: CueSource, cue: Option<&AudioCue>) -> CueAction {
+        if let Some(cue) = cue.filter(|c| c.play_on_show && !c.url.is_empty()) {
+            self.current = Some((source, cue.clone()));
+            return CueAction::Play(cue.clone());
+        }
+
+        let leaving = match &self.current {
+            Some((current_source, current)) => {
+                current.stop_on_leave
+                    && (source == CueSource::Item || *current_source == CueSource::Slide)
+            }
+            None => false,
+        };
+
+        if leaving {
+            self.current = None;
+            return CueAction::Stop;
+        }
+
+        CueAction::Keep
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn cue(url: &str, play_on_show: bool, stop_on_leave: bool) -> AudioCue {
+        AudioCue {
+            url: url.to_string(),
+            play_on_show,
+            stop_on_leave,
+            ..AudioCue::default()
+        }
+    }
+
+    #[test]
+    fn test_play_on_show() {
+        let mut state = CueState::default();
+        let a = cue("a.mp3", true, true);
+
+        assert_eq!(state.show_slide(Some(&a)), CueAction::Play(a.clone()));
+        assert_eq!(state.current(), Some(&a));
+    }
+
+    #[test]
+    fn test_manual_cue_does_not_play() {
+        let mut state = CueState::default();
+
+        assert_eq!(
+            state.show_slide(Some(&cue("a.mp3", false, true))),
+            CueAction::Keep
+        );
+        assert_eq!(
+            state.show_slide(Some(&cue("", true, true))),
+            CueAction::Keep
+        );
+        assert_eq!(state.current(), None);
+    }
+
+    #[test]
+    fn test_stop_on_leave_slide() {
+        let mut state = CueState::default();
+        state.show_slide(Some(&cue("a.mp3", true, true)));
+
+        assert_eq!(state.show_slide(None), CueAction::Stop);
+        assert_eq!(state.show_slide(None), CueAction::Keep);
+    }
+
+    #[test]
+    fn test_keep_playing_without_stop_on_leave() {
+        let mut state = CueState::default();
+        let a = cue("a.mp3", true, false);
+        state.show_slide(Some(&a));
+
+        assert_eq!(state.show_slide(None), CueAction::Keep);
+        assert_eq!(state.show_item(None), CueAction::Keep);
+        assert_eq!(state.current(), Some(&a));
+    }
+
+    #[test]
+    fn test_item_cue_plays_across_slides() {
+        let mut state = CueState::default();
+        let a = cue("a.mp3", true, true);
+        state.show_item(Some(&a));
+
+        assert_eq!(state.show_slide(None), CueAction::Keep);
+        assert_eq!(state.show_slide(None), CueAction::Keep);
+        assert_eq!(state.show_item(None), CueAction::Stop);
+    }
+
+    #[test]
+    fn test_new_cue_replaces_current() {
+        let mut state = CueState::default();
+        let (a, b) = (cue("a.mp3", true, false), cue("b.mp3", true, true));
+        state.show_item(Some(&a));
+
+        assert_eq!(state.show_slide(Some(&b)), CueAction::Play(b.clone()));
+        assert_eq!(state.current(), Some(&b));
+    }
+
+    #[test]
+    fn test_stopped() {
+        let mut state = CueState::default();
+        state.show_slide(Some(&cue("a.mp3", true, true)));
+        state.stopped();
+
+        assert_eq!(state.current(), None);
+        assert_eq!(state.show_slide(None), CueAction::Keep);
+    }
+}
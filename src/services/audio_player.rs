@@ -0,0 +1,118 @@
+use gtk::glib::{self, subclass::types::ObjectSubclassIsExt};
+use gtk::prelude::MediaStreamExt;
+
+use crate::services::audio_cues::CueAction;
+use crate::widgets::canvas::serialise::AudioCue;
+
+mod imp {
+    use std::cell::RefCell;
+
+    use gtk::glib::{
+        self, Properties,
+        subclass::{object::ObjectImpl, types::ObjectSubclass},
+    };
+    use gtk::prelude::*;
+    use gtk::subclass::prelude::DerivedObjectProperties;
+
+    use crate::services::audio_cues::CueState;
+
+    #[derive(Default, Properties)]
+    #[properties(wrapper_type=super::AudioPlayer)]
+    pub struct AudioPlayer {
+        #[property(get, nullable)]
+        pub media: RefCell<Option<gtk::MediaFile>>,
+        /// file name of the playing cue
+        #[property(get)]
+        pub title: RefCell<String>,
+
+        pub(super) cues: RefCell<CueState>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for AudioPlayer {
+        const NAME: &'static str = "OwAudioPlayer";
+        type Type = super::AudioPlayer;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for AudioPlayer {
+        fn dispose(&self) {
+            if let Some(media) = self.media.take() {
+                media.pause();
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct AudioPlayer(ObjectSubclass<imp::AudioPlayer>);
+}
+
+impl Default for AudioPlayer {
+    fn default() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl AudioPlayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A schedule item went live
+    pub fn show_item(&self, cue: Option<&AudioCue>) {
+        let action = self.imp().cues.borrow_mut().show_item(cue);
+        self.apply(action);
+    }
+
+    /// A slide of the live item was shown
+    pub fn show_slide(&self, cue: Option<&AudioCue>) {
+        let action = self.imp().cues.borrow_mut().show_slide(cue);
+        self.apply(action);
+    }
+
+    pub fn stop(&self) {
+        self.imp().cues.borrow_mut().stopped();
+        self.stop_media();
+    }
+
+    fn apply(&self, action: CueAction) {
+        match action {
+            CueAction::Keep => (),
+            CueAction::Stop => self.stop_media(),
+            CueAction::Play(cue) => self.play(&cue),
+        }
+    }
+
+    fn play(&self, cue: &AudioCue) {
+        if let Some(media) = self.imp().media.take() {
+            media.pause();
+        }
+
+        let media = gtk::MediaFile::for_filename(&cue.url);
+        media.set_loop(cue.loop_audio);
+        media.set_volume(cue.volume.clamp(0.0, 1.0));
+        media.play();
+
+        let title = std::path::Path::new(&cue.url)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        self.imp().media.replace(Some(media));
+        self.imp().title.replace(title);
+        self.notify_media();
+        self.notify_title();
+    }
+
+    fn stop_media(&self) {
+        let Some(media) = self.imp().media.take() else {
+            return;
+        };
+        media.pause();
+
+        self.imp().title.replace(String::new());
+        self.notify_media();
+        self.notify_title();
+    }
+}
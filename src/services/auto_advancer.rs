@@ -0,0 +1,190 @@
+use gtk::glib::{self, object::ObjectExt, subclass::types::ObjectSubclassIsExt};
+
+use crate::services::advance_state::AdvanceAction;
+use crate::widgets::canvas::serialise::SlideManagerData;
+
+mod signals {
+    pub const ADVANCE: &str = "advance";
+    pub const RESUME_ITEM: &str = "resume-item";
+}
+
+mod imp {
+    use std::{
+        cell::{Cell, RefCell},
+        sync::OnceLock,
+    };
+
+    use gtk::glib::{
+        self, Properties,
+        object::ObjectExt,
+        subclass::{Signal, object::ObjectImpl, types::ObjectSubclass},
+        types::StaticType,
+    };
+    use gtk::subclass::prelude::DerivedObjectProperties;
+
+    use super::signals;
+    use crate::{
+        services::advance_state::AdvanceState, widgets::canvas::serialise::SlideManagerData,
+    };
+
+    #[derive(Default, Properties)]
+    #[properties(wrapper_type=super::AutoAdvancer)]
+    pub struct AutoAdvancer {
+        /// the live item advances on its own
+        #[property(get)]
+        pub running: Cell<bool>,
+        #[property(get)]
+        pub paused: Cell<bool>,
+        /// a looping item was interrupted and can be resumed
+        #[property(get)]
+        pub can_resume: Cell<bool>,
+
+        pub(super) state: RefCell<AdvanceState>,
+        pub(super) timeout: RefCell<Option<glib::SourceId>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for AutoAdvancer {
+        const NAME: &'static str = "OwAutoAdvancer";
+        type Type = super::AutoAdvancer;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for AutoAdvancer {
+        fn signals() -> &'static [glib::subclass::Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+
+            SIGNALS.get_or_init(|| {
+                vec![
+                    Signal::builder(signals::ADVANCE)
+                        .param_types([u32::static_type()])
+                        .build(),
+                    Signal::builder(signals::RESUME_ITEM)
+                        .param_types([SlideManagerData::static_type()])
+                        .build(),
+                ]
+            })
+        }
+
+        fn dispose(&self) {
+            if let Some(id) = self.timeout.take() {
+                id.remove();
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct AutoAdvancer(ObjectSubclass<imp::AutoAdvancer>);
+}
+
+impl Default for AutoAdvancer {
+    fn default() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl AutoAdvancer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An item went live
+    pub fn show_item(&self, data: &SlideManagerData) {
+        let action = self.imp().state.borrow_mut().show_item(data);
+        self.apply(action);
+    }
+
+    /// A slide of the live item was shown
+    pub fn show_slide(&self, position: u32) {
+        let action = self.imp().state.borrow_mut().show_slide(position);
+        self.apply(action);
+    }
+
+    pub fn pause(&self) {
+        let action = self.imp().state.borrow_mut().pause();
+        self.apply(action);
+    }
+
+    pub fn resume(&self) {
+        let action = self.imp().state.borrow_mut().resume();
+        self.apply(action);
+    }
+
+    /// Puts the interrupted loop live again through "resume-item"
+    pub fn resume_loop(&self) {
+        let data = self.imp().state.borrow_mut().take_interrupted();
+        self.update_props();
+        if let Some(data) = data {
+            self.emit_by_name::<()>(signals::RESUME_ITEM, &[&data]);
+        }
+    }
+
+    fn apply(&self, action: AdvanceAction) {
+        let imp = self.imp();
+        if let Some(id) = imp.timeout.take() {
+            id.remove();
+        }
+
+        if let AdvanceAction::Schedule { position, after } = action {
+            let id = glib::timeout_add_local_once(
+                after,
+                glib::clone!(
+                    #[weak(rename_to = obj)]
+                    self,
+                    move || {
+                        // the source is gone once it has run
+                        obj.imp().timeout.take();
+                        obj.emit_by_name::<()>(signals::ADVANCE, &[&position]);
+                    }
+                ),
+            );
+            imp.timeout.replace(Some(id));
+        }
+
+        self.update_props();
+    }
+
+    fn update_props(&self) {
+        let imp = self.imp();
+        let (running, paused, can_resume) = {
+            let state = imp.state.borrow();
+            (
+                state.running(),
+                state.paused(),
+                state.interrupted().is_some(),
+            )
+        };
+
+        if imp.running.replace(running) != running {
+            self.notify_running();
+        }
+        if imp.paused.replace(paused) != paused {
+            self.notify_paused();
+        }
+        if imp.can_resume.replace(can_resume) != can_resume {
+            self.notify_can_resume();
+        }
+    }
+
+    /// Time is up for the live slide, move to `position`
+    pub fn connect_advance<F: Fn(&Self, u32) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            signals::ADVANCE,
+            false,
+            glib::closure_local!(move |obj: &Self, position: u32| f(obj, position)),
+        )
+    }
+
+    /// The interrupted loop should go live again
+    pub fn connect_resume_item<F: Fn(&Self, &SlideManagerData) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_closure(
+            signals::RESUME_ITEM,
+            false,
+            glib::closure_local!(move |obj: &Self, data: &SlideManagerData| f(obj, data)),
+        )
+    }
+}
@@ -0,0 +1,97 @@
+/// A line of a line-level diff between two texts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Same(String),
+    Added(String),
+    Removed(String),
+}
+
+/// Computes a line-level diff turning `old` into `new`
+/// using the longest common subsequence of lines
+pub fn line_diff(old: &str, new: &str) -> Vec<DiffLine> {
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+
+    // lcs[i][j] is the lcs length of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = match old[i] == new[j] {
+                true => lcs[i + 1][j + 1] + 1,
+                false => lcs[i + 1][j].max(lcs[i][j + 1]),
+            };
+        }
+    }
+
+    let mut diff = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            diff.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+
+    diff.extend(old[i..].iter().map(|l| DiffLine::Removed(l.to_string())));
+    diff.extend(new[j..].iter().map(|l| DiffLine::Added(l.to_string())));
+
+    diff
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_identical_text() {
+        let diff = line_diff("a\nb", "a\nb");
+        assert_eq!(
+            diff,
+            vec![DiffLine::Same("a".into()), DiffLine::Same("b".into())]
+        );
+    }
+
+    #[test]
+    fn test_changed_line() {
+        let diff = line_diff(
+            "Amazing grace\nhow sweet",
+            "Amazing grace\nhow sweet the sound",
+        );
+        assert_eq!(
+            diff,
+            vec![
+                DiffLine::Same("Amazing grace".into()),
+                DiffLine::Removed("how sweet".into()),
+                DiffLine::Added("how sweet the sound".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_added_and_removed_lines() {
+        let diff = line_diff("a\nb\nc", "b\nc\nd");
+        assert_eq!(
+            diff,
+            vec![
+                DiffLine::Removed("a".into()),
+                DiffLine::Same("b".into()),
+                DiffLine::Same("c".into()),
+                DiffLine::Added("d".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_empty_texts() {
+        assert_eq!(line_diff("", ""), vec![]);
+        assert_eq!(line_diff("", "a"), vec![DiffLine::Added("a".into())]);
+        assert_eq!(line_diff("a", ""), vec![DiffLine::Removed("a".into())]);
+    }
+}
@@ -2,24 +2,31 @@ use std::{
     cell::RefCell,
     collections::{HashSet, VecDeque},
     fs,
+    rc::Rc,
 };
 
 use futures_util::TryFutureExt;
 use gtk::{
     FileFilter, gdk,
+    gdk::prelude::PaintableExt,
     gio::{
         self,
         prelude::{FileExt, FileExtManual, ListModelExtManual},
     },
-    glib,
+    glib::{self, object::ObjectExt},
+    gsk::prelude::GskRendererExt,
+    prelude::{MediaStreamExt, SnapshotExt},
 };
 use sha2::{Digest, Sha256};
 
 use crate::{
     app_config::{self, AppConfigDir},
-    widgets::canvas::serialise::SlideManagerData,
+    widgets::canvas::serialise::{CanvasItemType, SlideManagerData},
 };
 
+/// Largest media file embedded in a saved schedule, 16 MiB
+const MAX_PACKED_MEDIA_SIZE: u64 = 16 * 1024 * 1024;
+
 mod imp {
 
     use gtk::glib::{
@@ -172,6 +179,53 @@ impl FileManager {
             window,
         )
     }
+    pub fn open_video(window: Option<&gtk::Window>) -> Option<gio::File> {
+        let mut filters: glib::List<gtk::FileFilter> = glib::List::new();
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some("Videos"));
+        filter.add_mime_type("video/*");
+
+        filters.push_back(filter);
+
+        FileManager::get_file_from_user(
+            String::from("Open Video"),
+            String::from("Open"),
+            &mut filters,
+            window,
+        )
+    }
+    pub fn open_audio(window: Option<&gtk::Window>) -> Option<gio::File> {
+        let mut filters: glib::List<gtk::FileFilter> = glib::List::new();
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some("Audio"));
+        filter.add_mime_type("audio/*");
+
+        filters.push_back(filter);
+
+        FileManager::get_file_from_user(
+            String::from("Open Audio"),
+            String::from("Open"),
+            &mut filters,
+            window,
+        )
+    }
+    /// Images and videos, for slide backgrounds
+    pub fn open_media(window: Option<&gtk::Window>) -> Option<gio::File> {
+        let mut filters: glib::List<gtk::FileFilter> = glib::List::new();
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some("Images and Videos"));
+        filter.add_mime_type("image/*");
+        filter.add_mime_type("video/*");
+
+        filters.push_back(filter);
+
+        FileManager::get_file_from_user(
+            String::from("Open Background"),
+            String::from("Open"),
+            &mut filters,
+            window,
+        )
+    }
     pub fn open_files(
         title: &str,
         accept_button_label: &str,
@@ -186,6 +240,25 @@ impl FileManager {
         )
     }
 
+    pub fn open_folder(
+        title: &str,
+        accept_button_label: &str,
+        window: Option<&gtk::Window>,
+    ) -> Option<gio::File> {
+        let dialog = gtk::FileDialog::builder()
+            .modal(true)
+            .accept_label(accept_button_label)
+            .title(title)
+            .build();
+
+        let ctx = glib::MainContext::default();
+        ctx.block_on(async move {
+            let res = dialog.select_folder_future(window).await;
+            let res = res.inspect_err(|e| eprintln!("Error opening folder in dialog: {:?}", e));
+            res.ok()
+        })
+    }
+
     pub fn open_schedule_file(window: Option<&gtk::Window>) -> Vec<SlideManagerData> {
         let mut filters = glib::List::new();
         let filter = gtk::FileFilter::new();
@@ -217,40 +290,28 @@ impl FileManager {
         };
 
         //
-        let mut images = HashSet::new();
+        let mut media = HashSet::new();
         let mut decom = |v: &mut SlideManagerData| {
-            for slide in v.slides.iter_mut() {
-                let Some(bg) = slide.canvas_data.background_pattern.clone() else {
-                    slide.canvas_data.background_pattern = None;
-                    continue;
-                };
-
-                if bg.is_empty() {
-                    slide.canvas_data.background_pattern = None;
-                    continue;
-                };
-
-                let bg64 = bg.split(&[':', ';', ','][..]).collect::<Vec<_>>();
+            if let Some(audio) = &mut v.audio {
+                audio.url = Self::unpack_media(&audio.url, &mut media).unwrap_or_default();
+            }
 
-                let content_type = if let Some(content_type) = bg64.get(1)
-                    && content_type.contains("image")
-                {
-                    content_type.replace("image/", "")
-                } else {
-                    slide.canvas_data.background_pattern = None;
-                    continue;
-                };
-                let Some(content) = bg64.get(3).map(|v| v.to_string()) else {
-                    slide.canvas_data.background_pattern = None;
-                    continue;
-                };
+            for slide in v.slides.iter_mut() {
+                if let Some(audio) = &mut slide.audio {
+                    audio.url = Self::unpack_media(&audio.url, &mut media).unwrap_or_default();
+                }
 
-                let checksum = hex::encode(Sha256::digest(content.as_bytes()));
-                let mut path = AppConfigDir::dir_path(AppConfigDir::SlideMedia);
-                path.push(format!("{checksum}.{content_type}"));
-                slide.canvas_data.background_pattern = Some(path.display().to_string());
+                slide.canvas_data.background_pattern = slide
+                    .canvas_data
+                    .background_pattern
+                    .take()
+                    .and_then(|bg| Self::unpack_media(&bg, &mut media));
 
-                images.insert((path, content));
+                for item in slide.items.iter_mut() {
+                    if let CanvasItemType::Video(video) = &mut item.item_type {
+                        video.url = Self::unpack_media(&video.url, &mut media).unwrap_or_default();
+                    }
+                }
             }
         };
 
@@ -258,7 +319,7 @@ impl FileManager {
             decom(i);
         }
 
-        for (path, content) in images {
+        for (path, content) in media {
             let bytes = glib::base64_decode(&content);
             if std::path::Path::new(&path).exists() {
                 glib::g_warning!("FileManager", "file already exists");
@@ -266,7 +327,7 @@ impl FileManager {
             };
 
             let _ = fs::write(path, bytes).map_err(|e| {
-                glib::g_warning!("FileManager", "Error: writing slide image: {:?}", e)
+                glib::g_warning!("FileManager", "Error: writing slide media: {:?}", e)
             });
         }
         //
@@ -299,20 +360,37 @@ impl FileManager {
         // alllow for duplicate base64 images across slides
         let mut payload = payload;
         for item in &mut payload {
+            if let Some(audio) = &mut item.audio {
+                let Some(data) = Self::pack_media(&audio.url) else {
+                    return;
+                };
+                audio.url = data;
+            }
+
             for slide in &mut item.slides {
-                if let Some(bg) = &slide.canvas_data.background_pattern {
-                    let p = std::path::Path::new(bg);
-                    let file = gio::File::for_path(p);
-                    let Some(content_type) =
-                        gio::content_type_get_mime_type(&gio::content_type_guess(Some(p), &[]).0)
-                    else {
+                if let Some(audio) = &mut slide.audio {
+                    let Some(data) = Self::pack_media(&audio.url) else {
                         return;
                     };
-                    let Some(b64) = Self::file_to_base64(&file) else {
+                    audio.url = data;
+                }
+
+                if let Some(bg) = &slide.canvas_data.background_pattern {
+                    let Some(data) = Self::pack_media(bg) else {
                         return;
                     };
-                    let image_b64 = format!("data:{content_type};base64,{b64}");
-                    slide.canvas_data.background_pattern = Some(image_b64);
+                    slide.canvas_data.background_pattern = Some(data);
+                }
+
+                for canvas_item in &mut slide.items {
+                    if let CanvasItemType::Video(video) = &mut canvas_item.item_type
+                        && !video.url.is_empty()
+                    {
+                        let Some(data) = Self::pack_media(&video.url) else {
+                            return;
+                        };
+                        video.url = data;
+                    }
                 }
             }
         }
@@ -371,6 +449,57 @@ impl FileManager {
         // to file content
     }
 
+    /// Embeds the image, video or audio at `path` as a base64 data url.
+    /// Files over [MAX_PACKED_MEDIA_SIZE], usually videos, are too big to
+    /// carry in the schedule json, so the schedule links to them instead
+    /// and only opens where the file is at the same path
+    fn pack_media(path: &str) -> Option<String> {
+        let p = std::path::Path::new(path);
+        if fs::metadata(p).is_ok_and(|m| m.len() > MAX_PACKED_MEDIA_SIZE) {
+            return Some(path.to_string());
+        }
+        let file = gio::File::for_path(p);
+        let content_type =
+            gio::content_type_get_mime_type(&gio::content_type_guess(Some(p), &[]).0)?;
+        let b64 = Self::file_to_base64(&file)?;
+
+        Some(format!("data:{content_type};base64,{b64}"))
+    }
+
+    /// Resolves a data url made by `pack_media` to a path in the slide media
+    /// directory, the decoded content to write there is added to `media`
+    fn unpack_media(
+        data: &str,
+        media: &mut HashSet<(std::path::PathBuf, String)>,
+    ) -> Option<String> {
+        if data.is_empty() {
+            return None;
+        }
+        // linked by `pack_media`
+        if !data.starts_with("data:") {
+            return Some(data.to_string());
+        }
+
+        let data64 = data.split(&[':', ';', ','][..]).collect::<Vec<_>>();
+        let extension = data64
+            .get(1)
+            .filter(|t| {
+                ["image/", "video/", "audio/"]
+                    .iter()
+                    .any(|m| t.starts_with(m))
+            })
+            .and_then(|t| t.split('/').nth(1))?;
+        let content = data64.get(3).map(|v| v.to_string())?;
+
+        let checksum = hex::encode(Sha256::digest(content.as_bytes()));
+        let mut path = AppConfigDir::dir_path(AppConfigDir::SlideMedia);
+        path.push(format!("{checksum}.{extension}"));
+        let link = path.display().to_string();
+
+        media.insert((path, content));
+        Some(link)
+    }
+
     pub fn get_data(file: &gio::File) -> Option<Vec<u8>> {
         file.load_contents(None::<&gio::Cancellable>)
             .map(|(bytes, _)| bytes.to_vec())
@@ -495,6 +624,7 @@ impl FileManager {
             let guess = gio::content_type_guess(Some(file_entry.path()), &[]);
             if let Some(mime_type) = gio::content_type_get_mime_type(&guess.0)
                 && !mime_type.contains("image")
+                && !mime_type.contains("video")
             {
                 continue;
             };
@@ -510,6 +640,11 @@ impl FileManager {
         size: Option<(i32, i32)>,
         cb: F,
     ) {
+        if Self::is_video(path) {
+            Self::get_video_frame(path, 0, size, cb);
+            return;
+        }
+
         let path = path.display().to_string();
         let texture = LOADED_BACKGROUND_IMAGES.with_borrow_mut({
             let path = path.clone();
@@ -565,6 +700,130 @@ impl FileManager {
             }
         });
     }
+
+    pub fn is_video(path: &std::path::Path) -> bool {
+        let guess = gio::content_type_guess(Some(path), &[]);
+        gio::content_type_get_mime_type(&guess.0).is_some_and(|m| m.starts_with("video/"))
+    }
+
+    /// Still frame of the video at `path`, `offset` is in milliseconds
+    pub fn get_video_frame<F: FnOnce(Option<gdk::Texture>) + 'static>(
+        path: &std::path::Path,
+        offset: u32,
+        size: Option<(i32, i32)>,
+        cb: F,
+    ) {
+        let key = format!("{}@{offset}", path.display());
+        let texture = LOADED_VIDEO_FRAMES
+            .with_borrow(|v| v.iter().find(|(s, _)| *s == key).map(|(_, t)| t.clone()));
+        if texture.is_some() {
+            cb(texture);
+            return;
+        }
+
+        let media = gtk::MediaFile::for_filename(path);
+        media.set_muted(true);
+
+        let cb = Rc::new(RefCell::new(Some(cb)));
+        let handlers = Rc::new(RefCell::new(Vec::<glib::SignalHandlerId>::new()));
+        // `media` is kept alive until a frame or an error arrives
+        let keep_alive = Rc::new(RefCell::new(Some(media.clone())));
+        let finish = {
+            let cb = cb.clone();
+            let handlers = handlers.clone();
+            move |media: &gtk::MediaFile, texture: Option<gdk::Texture>| {
+                for id in handlers.take() {
+                    media.disconnect(id);
+                }
+                keep_alive.take();
+                if let Some(texture) = &texture {
+                    LOADED_VIDEO_FRAMES.with_borrow_mut(|v| {
+                        if v.len() >= 10 {
+                            v.pop_front();
+                        }
+                        v.push_back((key.clone(), texture.clone()));
+                    });
+                }
+                if let Some(cb) = cb.take() {
+                    cb(texture);
+                }
+            }
+        };
+        let finish = Rc::new(finish);
+
+        let capture = {
+            let finish = finish.clone();
+            move |media: &gtk::MediaFile| {
+                if !media.is_prepared() || media.is_seeking() {
+                    return;
+                }
+                if offset > 0 && media.timestamp() < offset as i64 * 1000 {
+                    return;
+                }
+
+                let frame = media.current_image();
+                let (w, h) = (frame.intrinsic_width(), frame.intrinsic_height());
+                if w <= 0 || h <= 0 {
+                    return;
+                }
+                let (w, h) = match size {
+                    Some((sw, sh)) => {
+                        let scale = (sw as f64 / w as f64).min(sh as f64 / h as f64);
+                        (w as f64 * scale, h as f64 * scale)
+                    }
+                    None => (w as f64, h as f64),
+                };
+                finish(media, Self::render_paintable(&frame, w, h));
+            }
+        };
+        let capture = Rc::new(capture);
+
+        let ids = [
+            media.connect_prepared_notify(move |media| {
+                if offset > 0 && media.is_seekable() {
+                    media.seek(offset as i64 * 1000);
+                }
+            }),
+            media.connect_invalidate_contents({
+                let capture = capture.clone();
+                move |media| capture(media)
+            }),
+            media.connect_seeking_notify({
+                let capture = capture.clone();
+                move |media| capture(media)
+            }),
+            media.connect_error_notify({
+                let finish = finish.clone();
+                move |media| {
+                    if let Some(e) = media.error() {
+                        glib::g_warning!("FileManager", "Failed to load video: {:?}", e);
+                    }
+                    finish(media, None);
+                }
+            }),
+        ];
+        handlers.borrow_mut().extend(ids);
+    }
+
+    fn render_paintable(paintable: &gdk::Paintable, w: f64, h: f64) -> Option<gdk::Texture> {
+        let snapshot = gtk::Snapshot::new();
+        paintable.snapshot(&snapshot, w, h);
+        let node = snapshot.to_node()?;
+
+        let renderer = gtk::gsk::CairoRenderer::new();
+        renderer.realize(None::<&gdk::Surface>).ok()?;
+        let texture = renderer.render_texture(
+            &node,
+            Some(&gtk::graphene::Rect::new(0.0, 0.0, w as f32, h as f32)),
+        );
+        renderer.unrealize();
+
+        Some(texture)
+    }
+}
+
+thread_local! {
+    static LOADED_VIDEO_FRAMES: RefCell<VecDeque<(String, gdk::Texture)>> = RefCell::new(VecDeque::with_capacity(10));
 }
 
 thread_local! {
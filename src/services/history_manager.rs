@@ -0,0 +1,371 @@
+use gtk::glib;
+
+/// Edits made within this many milliseconds of each other on the same
+/// target are merged into one undo step
+pub const COALESCE_WINDOW_MS: i64 = 1000;
+pub const HISTORY_LIMIT: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, glib::Boxed)]
+#[boxed_type(name = "OwHistoryActionKind")]
+pub enum HistoryActionKind {
+    ItemAdded,
+    ItemDeleted,
+    ItemMoved,
+    ItemResized,
+    TextEdited,
+    ItemReordered,
+    SlideAdded,
+    SlideDeleted,
+    SlideReordered,
+    StyleChanged,
+}
+
+impl HistoryActionKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::ItemAdded => "Add item",
+            Self::ItemDeleted => "Delete item",
+            Self::ItemMoved => "Move item",
+            Self::ItemResized => "Resize item",
+            Self::TextEdited => "Edit text",
+            Self::ItemReordered => "Reorder item",
+            Self::SlideAdded => "Add slide",
+            Self::SlideDeleted => "Delete slide",
+            Self::SlideReordered => "Reorder slide",
+            Self::StyleChanged => "Change style",
+        }
+    }
+
+    /// Continuous edits such as drags and typing arrive as many small
+    /// actions, only those are merged
+    fn coalesces(&self) -> bool {
+        matches!(
+            self,
+            Self::ItemMoved | Self::ItemResized | Self::TextEdited | Self::StyleChanged
+        )
+    }
+}
+
+/// One undoable change, `before` and `after` are full snapshots
+/// of the edited document
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryAction<S> {
+    pub kind: HistoryActionKind,
+    /// what was edited, e.g. a slide and item position,
+    /// only actions on the same target are merged
+    pub target: Option<String>,
+    pub before: S,
+    pub after: S,
+    /// milliseconds, only compared with other actions
+    pub time: i64,
+}
+
+impl<S> HistoryAction<S> {
+    pub fn new(
+        kind: HistoryActionKind,
+        target: Option<String>,
+        before: S,
+        after: S,
+        time: i64,
+    ) -> Self {
+        Self {
+            kind,
+            target,
+            before,
+            after,
+            time,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct HistoryManager<S> {
+    undo_stack: Vec<HistoryAction<S>>,
+    redo_stack: Vec<HistoryAction<S>>,
+    limit: usize,
+}
+
+impl<S: Clone + PartialEq> Default for HistoryManager<S> {
+    fn default() -> Self {
+        Self::new(HISTORY_LIMIT)
+    }
+}
+
+impl<S: Clone + PartialEq> HistoryManager<S> {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            limit: limit.max(1),
+        }
+    }
+
+    /// Records `action`, it is merged into the previous action when both
+    /// are continuous edits of the same target unless `force` is set.
+    ///
+    /// Returns false when the action changes nothing
+    pub fn add_undoable_action(&mut self, action: HistoryAction<S>, force: Option<bool>) -> bool {
+        if action.before == action.after {
+            return false;
+        }
+
+        self.redo_stack.clear();
+
+        if !force.unwrap_or(false)
+            && let Some(last) = self.undo_stack.last_mut()
+            && last.kind == action.kind
+            && last.target == action.target
+            && action.kind.coalesces()
+            && action.time - last.time <= COALESCE_WINDOW_MS
+            && last.after == action.before
+        {
+            last.after = action.after;
+            last.time = action.time;
+
+            if last.before == last.after {
+                self.undo_stack.pop();
+            }
+            return true;
+        }
+
+        self.undo_stack.push(action);
+        if self.undo_stack.len() > self.limit {
+            self.undo_stack.remove(0);
+        }
+
+        true
+    }
+
+    /// Snapshot to restore to undo the last action
+    pub fn undo(&mut self) -> Option<S> {
+        let action = self.undo_stack.pop()?;
+        let state = action.before.clone();
+        self.redo_stack.push(action);
+        Some(state)
+    }
+
+    /// Snapshot to restore to redo the last undone action
+    pub fn redo(&mut self) -> Option<S> {
+        let action = self.redo_stack.pop()?;
+        let state = action.after.clone();
+        self.undo_stack.push(action);
+        Some(state)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    #[cfg(test)]
+    pub fn next_undo(&self) -> Option<HistoryActionKind> {
+        self.undo_stack.last().map(|a| a.kind)
+    }
+
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::canvas::serialise::{CanvasData, SlideData};
+
+    fn slide(background: &str) -> SlideData {
+        let canvas = CanvasData {
+            background_color: background.to_string(),
+            ..CanvasData::default()
+        };
+        SlideData::new(0, [], Vec::new(), canvas)
+    }
+
+    fn action(
+        kind: HistoryActionKind,
+        target: &str,
+        before: &SlideData,
+        after: &SlideData,
+        time: i64,
+    ) -> HistoryAction<SlideData> {
+        HistoryAction::new(
+            kind,
+            Some(target.to_string()),
+            before.clone(),
+            after.clone(),
+            time,
+        )
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let (a, b, c) = (slide("#000"), slide("#111"), slide("#222"));
+        let mut history = HistoryManager::default();
+
+        assert!(history.add_undoable_action(
+            action(HistoryActionKind::StyleChanged, "0", &a, &b, 0),
+            None
+        ));
+        assert!(
+            history
+                .add_undoable_action(action(HistoryActionKind::ItemAdded, "0", &b, &c, 10), None)
+        );
+
+        assert_eq!(history.next_undo(), Some(HistoryActionKind::ItemAdded));
+        assert_eq!(history.undo(), Some(b.clone()));
+        assert_eq!(history.undo(), Some(a.clone()));
+        assert_eq!(history.undo(), None);
+        assert!(!history.can_undo());
+
+        assert_eq!(history.redo(), Some(b.clone()));
+        assert_eq!(history.redo(), Some(c.clone()));
+        assert_eq!(history.redo(), None);
+    }
+
+    #[test]
+    fn test_new_action_clears_redo() {
+        let (a, b, c) = (slide("#000"), slide("#111"), slide("#222"));
+        let mut history = HistoryManager::default();
+
+        history.add_undoable_action(action(HistoryActionKind::ItemAdded, "0", &a, &b, 0), None);
+        history.undo();
+        assert!(history.can_redo());
+
+        history.add_undoable_action(
+            action(HistoryActionKind::ItemDeleted, "0", &a, &c, 10),
+            None,
+        );
+        assert!(!history.can_redo());
+        assert_eq!(history.undo(), Some(a));
+    }
+
+    #[test]
+    fn test_unchanged_action_is_ignored() {
+        let a = slide("#000");
+        let mut history = HistoryManager::default();
+
+        assert!(
+            !history
+                .add_undoable_action(action(HistoryActionKind::ItemMoved, "0", &a, &a, 0), None)
+        );
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn test_coalesce_continuous_edits() {
+        let (a, b, c) = (slide("#000"), slide("#111"), slide("#222"));
+        let mut history = HistoryManager::default();
+
+        history.add_undoable_action(
+            action(HistoryActionKind::TextEdited, "0:0", &a, &b, 0),
+            None,
+        );
+        history.add_undoable_action(
+            action(HistoryActionKind::TextEdited, "0:0", &b, &c, 500),
+            None,
+        );
+
+        assert_eq!(history.undo(), Some(a));
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn test_coalesce_limits() {
+        let (a, b, c, d) = (slide("#000"), slide("#111"), slide("#222"), slide("#333"));
+        let mut history = HistoryManager::default();
+
+        // too far apart
+        history.add_undoable_action(action(HistoryActionKind::ItemMoved, "0:0", &a, &b, 0), None);
+        history.add_undoable_action(
+            action(
+                HistoryActionKind::ItemMoved,
+                "0:0",
+                &b,
+                &c,
+                COALESCE_WINDOW_MS + 1,
+            ),
+            None,
+        );
+        // other item
+        history.add_undoable_action(
+            action(
+                HistoryActionKind::ItemMoved,
+                "0:1",
+                &c,
+                &d,
+                COALESCE_WINDOW_MS + 2,
+            ),
+            None,
+        );
+        // forced
+        history.add_undoable_action(
+            action(
+                HistoryActionKind::ItemMoved,
+                "0:1",
+                &d,
+                &a,
+                COALESCE_WINDOW_MS + 3,
+            ),
+            Some(true),
+        );
+
+        assert_eq!(history.undo(), Some(d));
+        assert_eq!(history.undo(), Some(c));
+        assert_eq!(history.undo(), Some(b));
+        assert_eq!(history.undo(), Some(a));
+        assert_eq!(history.undo(), None);
+    }
+
+    #[test]
+    fn test_discrete_actions_do_not_coalesce() {
+        let (a, b, c) = (slide("#000"), slide("#111"), slide("#222"));
+        let mut history = HistoryManager::default();
+
+        history.add_undoable_action(action(HistoryActionKind::SlideAdded, "0", &a, &b, 0), None);
+        history.add_undoable_action(action(HistoryActionKind::SlideAdded, "0", &b, &c, 1), None);
+
+        assert_eq!(history.undo(), Some(b));
+        assert_eq!(history.undo(), Some(a));
+    }
+
+    #[test]
+    fn test_coalesce_back_to_start_drops_action() {
+        let (a, b) = (slide("#000"), slide("#111"));
+        let mut history = HistoryManager::default();
+
+        history.add_undoable_action(action(HistoryActionKind::ItemMoved, "0:0", &a, &b, 0), None);
+        history.add_undoable_action(
+            action(HistoryActionKind::ItemMoved, "0:0", &b, &a, 10),
+            None,
+        );
+
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn test_history_limit() {
+        let slides = (0..5).map(|i| slide(&format!("#{i}"))).collect::<Vec<_>>();
+        let mut history = HistoryManager::new(3);
+
+        for (i, pair) in slides.windows(2).enumerate() {
+            history.add_undoable_action(
+                action(
+                    HistoryActionKind::SlideAdded,
+                    "0",
+                    &pair[0],
+                    &pair[1],
+                    i as i64,
+                ),
+                None,
+            );
+        }
+
+        assert_eq!(history.undo(), Some(slides[3].clone()));
+        assert_eq!(history.undo(), Some(slides[2].clone()));
+        assert_eq!(history.undo(), Some(slides[1].clone()));
+        assert_eq!(history.undo(), None);
+    }
+}
@@ -0,0 +1,212 @@
+use gtk::glib::{self, object::ObjectExt, subclass::types::ObjectSubclassIsExt};
+
+use crate::services::live_state::LiveChange;
+use crate::widgets::canvas::serialise::{SlideData, SlideManagerData};
+
+mod signals {
+    pub const ITEM_CHANGED: &str = "item-changed";
+    pub const SLIDE_CHANGED: &str = "slide-changed";
+}
+
+mod imp {
+    use std::{
+        cell::{Cell, RefCell},
+        sync::OnceLock,
+    };
+
+    use gtk::glib::{
+        self, Properties,
+        object::ObjectExt,
+        subclass::{Signal, object::ObjectImpl, types::ObjectSubclass},
+        types::StaticType,
+    };
+    use gtk::subclass::prelude::DerivedObjectProperties;
+
+    use super::signals;
+    use crate::{
+        services::live_state::LiveState,
+        widgets::canvas::{screen_cover::ScreenMode, serialise::SlideManagerData},
+    };
+
+    /// The program side of the preview/program pair, see [LiveState].
+    /// Outputs follow its signals and properties instead of each other.
+    #[derive(Default, Properties)]
+    #[properties(wrapper_type=super::LiveOutput)]
+    pub struct LiveOutput {
+        /// position of the live slide in the live item
+        #[property(get)]
+        pub slide: Cell<u32>,
+        /// slides are shown without their items
+        #[property(get)]
+        pub clear: Cell<bool>,
+        #[property(get, builder(ScreenMode::default()))]
+        pub screen_mode: Cell<ScreenMode>,
+        /// an alert is scrolling over the slides
+        #[property(get)]
+        pub alert: Cell<bool>,
+
+        pub(super) state: RefCell<LiveState>,
+        /// set while the outputs load a new item
+        pub(super) loading: Cell<bool>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for LiveOutput {
+        const NAME: &'static str = "OwLiveOutput";
+        type Type = super::LiveOutput;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for LiveOutput {
+        fn signals() -> &'static [glib::subclass::Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+
+            SIGNALS.get_or_init(|| {
+                vec![
+                    Signal::builder(signals::ITEM_CHANGED)
+                        .param_types([SlideManagerData::static_type()])
+                        .build(),
+                    Signal::builder(signals::SLIDE_CHANGED)
+                        .param_types([u32::static_type()])
+                        .build(),
+                ]
+            })
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct LiveOutput(ObjectSubclass<imp::LiveOutput>);
+}
+
+impl Default for LiveOutput {
+    fn default() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl LiveOutput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The live item, as it went live
+    pub fn item(&self) -> Option<SlideManagerData> {
+        self.imp().state.borrow().item().cloned()
+    }
+
+    /// Data of the slide at `position` of the live item
+    pub fn slide_data(&self, position: u32) -> Option<SlideData> {
+        let state = self.imp().state.borrow();
+        state.item()?.slides.get(position as usize).cloned()
+    }
+
+    /// Puts `data` live on its current slide
+    pub fn show_item(&self, data: &SlideManagerData) {
+        let change = self.imp().state.borrow_mut().show_item(data);
+        self.apply(Some(change));
+    }
+
+    /// Shows the slide at `position` of the live item. Outputs that report
+    /// their selection while they load a new item are ignored.
+    pub fn show_slide(&self, position: u32) {
+        if self.imp().loading.get() {
+            return;
+        }
+        let change = self.imp().state.borrow_mut().show_slide(position);
+        self.apply(change);
+    }
+
+    pub fn next_slide(&self) {
+        let change = self.imp().state.borrow_mut().next_slide();
+        self.apply(change);
+    }
+
+    pub fn previous_slide(&self) {
+        let change = self.imp().state.borrow_mut().previous_slide();
+        self.apply(change);
+    }
+
+    /// Shows the slides without their items
+    pub fn set_clear(&self, clear: bool) {
+        let change = self.imp().state.borrow_mut().set_clear(clear);
+        self.apply(change);
+    }
+
+    /// Covers the slides with black, in place of the logo
+    pub fn set_black(&self, black: bool) {
+        let change = self.imp().state.borrow_mut().set_black(black);
+        self.apply(change);
+    }
+
+    /// Covers the slides with the logo, in place of black
+    pub fn set_logo(&self, logo: bool) {
+        let change = self.imp().state.borrow_mut().set_logo(logo);
+        self.apply(change);
+    }
+
+    pub fn set_alert(&self, alert: bool) {
+        let change = self.imp().state.borrow_mut().set_alert(alert);
+        self.apply(change);
+    }
+
+    fn apply(&self, change: Option<LiveChange>) {
+        let imp = self.imp();
+        let Some(change) = change else {
+            return;
+        };
+
+        match change {
+            LiveChange::Item => {
+                let data = imp.state.borrow().item().cloned().unwrap_or_default();
+                imp.slide.set(data.current_slide);
+
+                imp.loading.set(true);
+                self.emit_by_name::<()>(signals::ITEM_CHANGED, &[&data]);
+                imp.loading.set(false);
+                self.notify_slide();
+            }
+            LiveChange::Slide(position) => {
+                imp.slide.set(position);
+                self.notify_slide();
+                self.emit_by_name::<()>(signals::SLIDE_CHANGED, &[&position]);
+            }
+            LiveChange::Clear(clear) => {
+                imp.clear.set(clear);
+                self.notify_clear();
+            }
+            LiveChange::ScreenMode(mode) => {
+                imp.screen_mode.set(mode);
+                self.notify_screen_mode();
+            }
+            LiveChange::Alert(alert) => {
+                imp.alert.set(alert);
+                self.notify_alert();
+            }
+        }
+    }
+
+    /// Another item went live, with its current slide set to the live slide
+    pub fn connect_item_changed<F: Fn(&Self, &SlideManagerData) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_closure(
+            signals::ITEM_CHANGED,
+            false,
+            glib::closure_local!(move |obj: &Self, data: &SlideManagerData| f(obj, data)),
+        )
+    }
+
+    /// The live item moved to the slide at `position`
+    pub fn connect_slide_changed<F: Fn(&Self, u32) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_closure(
+            signals::SLIDE_CHANGED,
+            false,
+            glib::closure_local!(move |obj: &Self, position: u32| f(obj, position)),
+        )
+    }
+}
@@ -0,0 +1,199 @@
+use crate::widgets::canvas::{screen_cover::ScreenMode, serialise::SlideManagerData};
+
+/// A change of the live state that the outputs follow
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiveChange {
+    /// another item went live, on its current slide
+    Item,
+    Slide(u32),
+    Clear(bool),
+    ScreenMode(ScreenMode),
+    Alert(bool),
+}
+
+/// What is on the live outputs: the item and its slide, whether the
+/// slides are cleared of their items, covered by black or the logo, and
+/// whether an alert is showing
+#[derive(Debug, Default)]
+pub struct LiveState {
+    item: Option<SlideManagerData>,
+    slide: u32,
+    clear: bool,
+    black: bool,
+    logo: bool,
+    alert: bool,
+}
+
+impl LiveState {
+    pub fn item(&self) -> Option<&SlideManagerData> {
+        self.item.as_ref()
+    }
+
+    #[cfg(test)]
+    pub fn slide(&self) -> u32 {
+        self.slide
+    }
+
+    pub fn slide_count(&self) -> u32 {
+        self.item.as_ref().map_or(0, |d| d.slides.len() as u32)
+    }
+
+    #[cfg(test)]
+    pub fn clear(&self) -> bool {
+        self.clear
+    }
+
+    /// Black covers the logo when both are on
+    pub fn screen_mode(&self) -> ScreenMode {
+        match (self.black, self.logo) {
+            (true, _) => ScreenMode::Black,
+            (_, true) => ScreenMode::Logo,
+            _ => ScreenMode::Live,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn alert(&self) -> bool {
+        self.alert
+    }
+
+    /// Puts `data` live on its current slide, the display flags are kept
+    pub fn show_item(&mut self, data: &SlideManagerData) -> LiveChange {
+        let count = data.slides.len() as u32;
+        self.slide = data.current_slide.min(count.saturating_sub(1));
+
+        let mut data = data.clone();
+        data.current_slide = self.slide;
+        self.item = Some(data);
+        LiveChange::Item
+    }
+
+    /// Shows the slide at `position` of the live item, `None` when it is
+    /// already shown or there is no such slide
+    pub fn show_slide(&mut self, position: u32) -> Option<LiveChange> {
+        if position >= self.slide_count() || position == self.slide {
+            return None;
+        }
+        self.slide = position;
+        Some(LiveChange::Slide(position))
+    }
+
+    pub fn next_slide(&mut self) -> Option<LiveChange> {
+        self.show_slide(self.slide + 1)
+    }
+
+    pub fn previous_slide(&mut self) -> Option<LiveChange> {
+        self.show_slide(self.slide.checked_sub(1)?)
+    }
+
+    pub fn set_clear(&mut self, clear: bool) -> Option<LiveChange> {
+        if self.clear == clear {
+            return None;
+        }
+        self.clear = clear;
+        Some(LiveChange::Clear(clear))
+    }
+
+    /// Black and the logo replace each other
+    pub fn set_black(&mut self, black: bool) -> Option<LiveChange> {
+        self.set_screen(|s| {
+            s.black = black;
+            s.logo &= !black;
+        })
+    }
+
+    pub fn set_logo(&mut self, logo: bool) -> Option<LiveChange> {
+        self.set_screen(|s| {
+            s.logo = logo;
+            s.black &= !logo;
+        })
+    }
+
+    pub fn set_alert(&mut self, alert: bool) -> Option<LiveChange> {
+        if self.alert == alert {
+            return None;
+        }
+        self.alert = alert;
+        Some(LiveChange::Alert(alert))
+    }
+
+    fn set_screen<F: FnOnce(&mut Self)>(&mut self, f: F) -> Option<LiveChange> {
+        let mode = self.screen_mode();
+        f(self);
+        let new_mode = self.screen_mode();
+        (new_mode != mode).then_some(LiveChange::ScreenMode(new_mode))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::canvas::serialise::{CanvasData, SlideData};
+
+    fn item(slides: usize, current_slide: u32) -> SlideManagerData {
+        let slides =
+            (0..slides).map(|_| SlideData::new(0, Vec::new(), Vec::new(), CanvasData::default()));
+        SlideManagerData::new(current_slide, 0, slides)
+    }
+
+    #[test]
+    fn test_show_item_and_slides() {
+        let mut state = LiveState::default();
+        assert_eq!(state.show_slide(0), None);
+        assert_eq!(state.next_slide(), None);
+
+        assert_eq!(state.show_item(&item(3, 1)), LiveChange::Item);
+        assert_eq!(state.slide(), 1);
+        assert_eq!(state.show_slide(1), None);
+        assert_eq!(state.next_slide(), Some(LiveChange::Slide(2)));
+        assert_eq!(state.next_slide(), None);
+        assert_eq!(state.show_slide(3), None);
+        assert_eq!(state.show_slide(0), Some(LiveChange::Slide(0)));
+        assert_eq!(state.previous_slide(), None);
+        assert_eq!(state.slide(), 0);
+
+        // a stale position is kept on the item
+        state.show_item(&item(2, 5));
+        assert_eq!(state.slide(), 1);
+        assert_eq!(state.item().map(|d| d.current_slide), Some(1));
+        state.show_item(&item(0, 0));
+        assert_eq!(state.slide(), 0);
+        assert_eq!(state.slide_count(), 0);
+    }
+
+    #[test]
+    fn test_black_and_logo() {
+        let mut state = LiveState::default();
+        assert_eq!(state.screen_mode(), ScreenMode::Live);
+        assert_eq!(state.set_black(false), None);
+
+        assert_eq!(
+            state.set_logo(true),
+            Some(LiveChange::ScreenMode(ScreenMode::Logo))
+        );
+        assert_eq!(
+            state.set_black(true),
+            Some(LiveChange::ScreenMode(ScreenMode::Black))
+        );
+        // black took the place of the logo
+        assert_eq!(
+            state.set_black(false),
+            Some(LiveChange::ScreenMode(ScreenMode::Live))
+        );
+        assert_eq!(state.set_logo(false), None);
+    }
+
+    #[test]
+    fn test_flags_survive_items() {
+        let mut state = LiveState::default();
+        assert_eq!(state.set_clear(true), Some(LiveChange::Clear(true)));
+        assert_eq!(state.set_clear(true), None);
+        assert_eq!(state.set_alert(true), Some(LiveChange::Alert(true)));
+        state.set_logo(true);
+
+        state.show_item(&item(2, 0));
+        assert!(state.clear());
+        assert!(state.alert());
+        assert_eq!(state.screen_mode(), ScreenMode::Logo);
+    }
+}
@@ -0,0 +1,70 @@
+/// Smallest text size the lower third shrinks to
+pub const MIN_TEXT_SIZE: f64 = 8.0;
+
+/// Where the lower third is drawn on an output of `width` × `height`,
+/// as `(x, y, width, height)`.
+///
+/// `height_fraction` is the share of the output height it takes and
+/// `margin_fraction` the share kept free at the sides and bottom.
+pub fn region(
+    width: f64,
+    height: f64,
+    height_fraction: f64,
+    margin_fraction: f64,
+) -> (f64, f64, f64, f64) {
+    let margin = height * margin_fraction.max(0.0);
+    let h = (height * height_fraction.clamp(0.0, 1.0)).min((height - margin).max(0.0));
+    let w = (width - margin * 2.0).max(0.0);
+    (width / 2.0 - w / 2.0, (height - margin - h).max(0.0), w, h)
+}
+
+/// Slide text rearranged into at most `max_lines` lines, for a region that
+/// is much wider than it is high.
+///
+/// Blank lines are dropped and, when there are too many lines, neighbouring
+/// ones are joined so each line holds about the same number of them.
+pub fn reflow(text: &str, max_lines: usize) -> String {
+    let lines = text
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>();
+    let max_lines = max_lines.max(1);
+    if lines.len() <= max_lines {
+        return lines.join("\n");
+    }
+
+    let per_line = lines.len().div_ceil(max_lines);
+    lines
+        .chunks(per_line)
+        .map(|chunk| chunk.join(" "))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_region() {
+        assert_eq!(
+            region(1920.0, 1080.0, 0.25, 0.05),
+            (54.0, 756.0, 1812.0, 270.0)
+        );
+        assert_eq!(region(1000.0, 500.0, 0.2, 0.0), (0.0, 400.0, 1000.0, 100.0));
+        // never taller than the output
+        assert_eq!(region(100.0, 100.0, 2.0, 0.1), (10.0, 0.0, 80.0, 90.0));
+    }
+
+    #[test]
+    fn test_reflow() {
+        assert_eq!(reflow("one\ntwo", 2), "one\ntwo");
+        assert_eq!(reflow("one\n\n  two  \n", 2), "one\ntwo");
+        assert_eq!(reflow("a\nb\nc\nd", 2), "a b\nc d");
+        assert_eq!(reflow("a\nb\nc", 2), "a b\nc");
+        assert_eq!(reflow("a\nb\nc", 1), "a b c");
+        assert_eq!(reflow("a\nb", 0), "a b");
+        assert_eq!(reflow("", 2), "");
+    }
+}
@@ -12,7 +12,7 @@ use crate::{
 };
 
 mod imp {
-    use std::cell::RefCell;
+    use std::cell::{Cell, RefCell};
 
     use gtk::prelude::ObjectExt;
     use gtk::{
@@ -42,6 +42,9 @@ mod imp {
         viewer: RefCell<MessageAlertViewer>,
         #[property(get)]
         editor: RefCell<MessageAlertEditorWindow>,
+        /// an alert is scrolling on the marquee
+        #[property(get, set)]
+        showing: Cell<bool>,
     }
 
     #[glib::object_subclass]
@@ -212,6 +215,7 @@ impl MessageAlertManager {
         alert.set_active(true);
         self.marquee().add_message(&alert.message());
         self.marquee().start_marquee();
+        self.set_showing(true);
 
         alert.set_count(alert.count().saturating_sub(1));
         // if alert.count() == 0 {
@@ -220,6 +224,7 @@ impl MessageAlertManager {
     }
     pub fn hide(&self) {
         self.marquee().stop_marquee();
+        self.set_showing(false);
     }
 
     /// handles [STOP_MARQUEE_REQUEST](crate::widgets::message_alert::signals)
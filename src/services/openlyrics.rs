@@ -15,34 +15,111 @@ pub fn openlyrics_to_song_data(content: &str) -> Option<SongData> {
     };
 
     let title = &song_lyrics.properties.titles.titles[0].title;
-    let _authors = &song_lyrics.properties.authors.authors[0].name;
-    let lyrics = &song_lyrics
-        .lyrics
-        .lyrics
+    let authors = song_lyrics
+        .properties
+        .authors
+        .authors
         .iter()
-        .filter_map(|v| match v {
-            openlyrics::types::LyricEntry::Verse {
-                name,
-                lang: _,
-                translit: _,
-                lines,
-            } => {
-                let line = lines
-                    .iter()
-                    .map(|v| openlyrics::simplify_contents(&v.contents).join("\n"))
-                    .collect::<Vec<_>>()
-                    .join("\n");
-                return Some((name.clone(), line));
+        .map(|a| a.name.clone())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    // verses in the language of the first verse are the song,
+    // the same verse in another language is kept as a translation
+    let mut primary_lang: Option<String> = None;
+    let mut song_verses: Vec<SongVerse> = Vec::new();
+    for entry in &song_lyrics.lyrics.lyrics {
+        let openlyrics::types::LyricEntry::Verse {
+            name,
+            lang,
+            translit: _,
+            lines,
+        } = entry
+        else {
+            continue;
+        };
+
+        let text = lines
+            .iter()
+            .map(|v| openlyrics::simplify_contents(&v.contents).join("\n"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if primary_lang.is_none() {
+            primary_lang = lang.clone();
+        }
+
+        let translated = lang.as_ref().filter(|l| Some(*l) != primary_lang.as_ref());
+        let original = song_verses
+            .iter()
+            .rposition(|v| v.tag.as_ref() == Some(name));
+
+        match (translated, original) {
+            (Some(lang), Some(i)) => {
+                song_verses[i].translations.insert(lang.clone(), text);
             }
-            _ => return None,
-        })
-        .collect::<Vec<_>>();
+            _ => song_verses.push(SongVerse::new(text, Some(name.clone()), None)),
+        }
+    }
 
-    let song_verses = lyrics
-        .iter()
-        .map(|(t, l)| SongVerse::new(l.clone(), Some(t.clone()), None))
-        .collect::<Vec<_>>();
-    let data = SongData::new(0, title.clone(), song_verses);
+    let properties = &song_lyrics.properties;
+    let mut data = SongData::new(0, title.clone(), song_verses);
+    data.details.author = (!authors.is_empty()).then_some(authors);
+    data.details.copyright = properties.copyright.clone().filter(|c| !c.is_empty());
+    data.details.ccli_number = properties.ccli_no.map(|n| n.to_string());
 
     return Some(data);
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_import_details() {
+        let xml = r#"<song>
+            <properties>
+                <titles><title>Amazing Grace</title></titles>
+                <authors><author>John Newton</author></authors>
+                <copyright>Public Domain</copyright>
+                <ccliNo>22025</ccliNo>
+            </properties>
+            <lyrics><verse name="v1"><lines>Amazing grace</lines></verse></lyrics>
+        </song>"#;
+
+        let data = openlyrics_to_song_data(xml).unwrap();
+        assert_eq!(data.title, "Amazing Grace");
+        assert_eq!(data.details.author.as_deref(), Some("John Newton"));
+        assert_eq!(data.details.copyright.as_deref(), Some("Public Domain"));
+        assert_eq!(data.details.ccli_number.as_deref(), Some("22025"));
+
+        let xml = r#"<song>
+            <properties><titles><title>Untitled</title></titles></properties>
+            <lyrics><verse name="v1"><lines>Words</lines></verse></lyrics>
+        </song>"#;
+        let data = openlyrics_to_song_data(xml).unwrap();
+        assert!(data.details.is_empty());
+    }
+
+    #[test]
+    fn test_import_translations() {
+        let xml = r#"<song>
+            <properties><titles><title>Amazing Grace</title></titles></properties>
+            <lyrics>
+                <verse name="v1" lang="en"><lines>Amazing grace</lines></verse>
+                <verse name="v1" lang="fr"><lines>Grâce étonnante</lines></verse>
+                <verse name="c" lang="en"><lines>How sweet the sound</lines></verse>
+            </lyrics>
+        </song>"#;
+
+        let data = openlyrics_to_song_data(xml).unwrap();
+        assert_eq!(data.verses.len(), 2);
+        assert_eq!(data.verses[0].tag.as_deref(), Some("v1"));
+        assert_eq!(
+            data.verses[0].translations.get("fr").map(String::as_str),
+            Some("Grâce étonnante")
+        );
+        assert_eq!(data.verses[1].text, "How sweet the sound");
+        assert!(data.verses[1].translations.is_empty());
+    }
+}
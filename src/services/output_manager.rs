@@ -0,0 +1,199 @@
+use gtk::gdk;
+use gtk::glib::{self, subclass::types::ObjectSubclassIsExt};
+use gtk::prelude::*;
+
+use crate::services::{
+    outputs::{self, MonitorInfo, OutputRole},
+    settings::ApplicationSettings,
+};
+use crate::widgets::{
+    extended_screen::ExtendedScreen, lower_third_screen::LowerThirdScreen,
+    stage_screen::StageScreen,
+};
+
+mod imp {
+    use std::cell::RefCell;
+
+    use gtk::glib::{
+        self, Properties,
+        subclass::{
+            object::{ObjectImpl, ObjectImplExt},
+            types::{ObjectSubclass, ObjectSubclassExt},
+        },
+    };
+    use gtk::prelude::*;
+    use gtk::subclass::prelude::DerivedObjectProperties;
+
+    use crate::services::settings::ApplicationSettings;
+    use crate::widgets::{extended_screen::ExtendedScreen, lower_third_screen::LowerThirdScreen};
+
+    /// Puts the live screen, its mirrors and the stage displays on the
+    /// monitors chosen in the `outputs` setting, again whenever monitors are
+    /// plugged in or out
+    #[derive(Default, Properties)]
+    #[properties(wrapper_type=super::OutputManager)]
+    pub struct OutputManager {
+        #[property(get, construct_only)]
+        pub(super) extended_screen: glib::WeakRef<ExtendedScreen>,
+        /// mirror, stage display and lower third windows
+        pub(super) windows: RefCell<Vec<gtk::Window>>,
+        /// lower third in a window of its own, for capturing into a livestream
+        pub(super) lower_third: glib::WeakRef<LowerThirdScreen>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for OutputManager {
+        const NAME: &'static str = "OwOutputManager";
+        type Type = super::OutputManager;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for OutputManager {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            ApplicationSettings::get_instance().connect_outputs_changed(glib::clone!(
+                #[weak]
+                obj,
+                move |_| obj.apply()
+            ));
+
+            if let Some(display) = gtk::gdk::Display::default() {
+                display.monitors().connect_items_changed(glib::clone!(
+                    #[weak]
+                    obj,
+                    move |_, _, _, _| {
+                        // a new monitor may not have its connector and model yet
+                        glib::idle_add_local_once(move || obj.apply());
+                    }
+                ));
+            }
+        }
+
+        fn dispose(&self) {
+            for window in self.windows.take() {
+                window.destroy();
+            }
+            if let Some(window) = self.lower_third.upgrade() {
+                window.destroy();
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct OutputManager(ObjectSubclass<imp::OutputManager>);
+}
+
+impl OutputManager {
+    pub fn new(extended_screen: &ExtendedScreen) -> Self {
+        glib::Object::builder()
+            .property("extended_screen", extended_screen)
+            .build()
+    }
+
+    /// Connected monitors, in the order of the display
+    pub fn monitors() -> Vec<(gdk::Monitor, MonitorInfo)> {
+        let Some(display) = gdk::Display::default() else {
+            return vec![];
+        };
+
+        display
+            .monitors()
+            .iter::<gdk::Monitor>()
+            .filter_map(|m| m.ok())
+            .map(|m| {
+                let geometry = m.geometry();
+                let info = MonitorInfo {
+                    connector: m.connector().map(String::from),
+                    model: m.model().map(String::from),
+                    origin: (geometry.x(), geometry.y()),
+                };
+                (m, info)
+            })
+            .collect()
+    }
+
+    /// The role of each connected monitor, from the `outputs` setting
+    pub fn roles() -> Vec<(gdk::Monitor, MonitorInfo, OutputRole)> {
+        let monitors = Self::monitors();
+        let configs = outputs::parse_outputs(&ApplicationSettings::get_instance().outputs());
+        let infos = monitors.iter().map(|(_, m)| m.clone()).collect::<Vec<_>>();
+        let roles = outputs::assign_roles(&configs, &infos);
+
+        monitors
+            .into_iter()
+            .zip(roles)
+            .map(|((monitor, info), role)| (monitor, info, role))
+            .collect()
+    }
+
+    /// Moves the outputs to their monitors
+    pub fn apply(&self) {
+        let Some(screen) = self.extended_screen() else {
+            return;
+        };
+        let imp = self.imp();
+
+        for window in imp.windows.take() {
+            window.destroy();
+        }
+
+        let roles = Self::roles();
+        match roles.iter().find(|(_, _, role)| *role == OutputRole::Live) {
+            Some((monitor, _, _)) => screen.fullscreen_on_monitor(monitor),
+            None => screen.unfullscreen(),
+        }
+
+        let windows = roles
+            .iter()
+            .filter_map(|(monitor, _, role)| {
+                let window = match role {
+                    OutputRole::Mirror => Self::mirror(&screen),
+                    OutputRole::Stage => StageScreen::new(&screen).upcast(),
+                    OutputRole::LowerThird => LowerThirdScreen::new(&screen).upcast(),
+                    OutputRole::None | OutputRole::Live => return None,
+                };
+                window.set_application(screen.application().as_ref());
+                window.present();
+                window.fullscreen_on_monitor(monitor);
+                Some(window)
+            })
+            .collect::<Vec<_>>();
+        imp.windows.replace(windows);
+    }
+
+    /// Opens the lower third in a window that is not on any monitor,
+    /// or brings back the one already open
+    pub fn show_lower_third(&self) {
+        let Some(screen) = self.extended_screen() else {
+            return;
+        };
+        let imp = self.imp();
+
+        let window = imp.lower_third.upgrade().unwrap_or_else(|| {
+            let window = LowerThirdScreen::new(&screen);
+            window.set_application(screen.application().as_ref());
+            imp.lower_third.set(Some(&window));
+            window
+        });
+        window.present();
+    }
+
+    /// A window showing what `screen` shows
+    fn mirror(screen: &ExtendedScreen) -> gtk::Window {
+        let picture = gtk::Picture::builder()
+            .paintable(&gtk::WidgetPaintable::new(screen.child().as_ref()))
+            .content_fit(gtk::ContentFit::Contain)
+            .can_shrink(true)
+            .build();
+        picture.add_css_class("black_bg_box");
+
+        gtk::Window::builder()
+            .title("Mirror")
+            .child(&picture)
+            .decorated(false)
+            .build()
+    }
+}
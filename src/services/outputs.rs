@@ -0,0 +1,331 @@
+/// Separates the fields of an `outputs` setting entry
+const FIELD_SEPARATOR: char = '|';
+
+/// What a monitor is used for
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OutputRole {
+    /// not used for output
+    #[default]
+    None,
+    /// the live screen the congregation sees
+    Live,
+    /// a copy of the live screen, e.g. a second projector
+    Mirror,
+    /// the stage display for the people up front
+    Stage,
+    /// the slide text over a transparent background, for a livestream
+    LowerThird,
+}
+
+impl OutputRole {
+    pub const ALL: [OutputRole; 5] = [
+        OutputRole::None,
+        OutputRole::Live,
+        OutputRole::Mirror,
+        OutputRole::Stage,
+        OutputRole::LowerThird,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OutputRole::None => "Not used",
+            OutputRole::Live => "Live output",
+            OutputRole::Mirror => "Mirror of live output",
+            OutputRole::Stage => "Stage display",
+            OutputRole::LowerThird => "Livestream lower third",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            OutputRole::None => "none",
+            OutputRole::Live => "live",
+            OutputRole::Mirror => "mirror",
+            OutputRole::Stage => "stage",
+            OutputRole::LowerThird => "lower-third",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|r| r.name() == name)
+    }
+}
+
+/// A connected monitor, as far as choosing outputs goes
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MonitorInfo {
+    pub connector: Option<String>,
+    pub model: Option<String>,
+    /// position on the desktop, the monitor at `(0, 0)` is taken as the
+    /// one the operator uses
+    pub origin: (i32, i32),
+}
+
+impl MonitorInfo {
+    pub fn label(&self) -> String {
+        match (&self.model, &self.connector) {
+            (Some(model), Some(connector)) => format!("{model} ({connector})"),
+            (Some(name), None) | (None, Some(name)) => name.clone(),
+            (None, None) => String::from("Unknown monitor"),
+        }
+    }
+}
+
+/// Role remembered for a monitor, stored in the `outputs` setting as
+/// `role|connector|model`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct OutputConfig {
+    pub role: OutputRole,
+    pub connector: String,
+    pub model: String,
+}
+
+impl OutputConfig {
+    pub fn new(role: OutputRole, monitor: &MonitorInfo) -> Self {
+        Self {
+            role,
+            connector: monitor.connector.clone().unwrap_or_default(),
+            model: monitor.model.clone().unwrap_or_default(),
+        }
+    }
+
+    pub fn parse(entry: &str) -> Option<Self> {
+        let mut fields = entry.splitn(3, FIELD_SEPARATOR);
+        let role = OutputRole::from_name(fields.next()?)?;
+        let connector = fields.next()?.to_string();
+        let model = fields.next().unwrap_or_default().to_string();
+
+        if connector.is_empty() && model.is_empty() {
+            return None;
+        }
+        Some(Self {
+            role,
+            connector,
+            model,
+        })
+    }
+
+    pub fn to_entry(&self) -> String {
+        format!(
+            "{}{FIELD_SEPARATOR}{}{FIELD_SEPARATOR}{}",
+            self.role.name(),
+            self.connector,
+            self.model
+        )
+    }
+
+    /// How well this config describes `monitor`, `0` when it does not.
+    /// Both fields matching beats the connector, which beats the model, so a
+    /// monitor moved to another port keeps its role.
+    fn score(&self, monitor: &MonitorInfo) -> u8 {
+        let same = |a: &str, b: &Option<String>| !a.is_empty() && b.as_deref() == Some(a);
+        match (
+            same(&self.connector, &monitor.connector),
+            same(&self.model, &monitor.model),
+        ) {
+            (true, true) => 3,
+            (true, false) if self.model.is_empty() || monitor.model.is_none() => 2,
+            (false, true) => 1,
+            _ => 0,
+        }
+    }
+}
+
+/// Parses the `outputs` setting, skipping invalid entries
+pub fn parse_outputs(entries: &[String]) -> Vec<OutputConfig> {
+    entries
+        .iter()
+        .filter_map(|e| OutputConfig::parse(e))
+        .collect()
+}
+
+pub fn format_outputs(configs: &[OutputConfig]) -> Vec<String> {
+    configs.iter().map(OutputConfig::to_entry).collect()
+}
+
+/// The role of each of `monitors`.
+///
+/// Each config is used for the monitor it matches best. Only one monitor
+/// is live, and when none is configured the first one away from `(0, 0)`
+/// is used.
+pub fn assign_roles(configs: &[OutputConfig], monitors: &[MonitorInfo]) -> Vec<OutputRole> {
+    let mut roles = vec![OutputRole::None; monitors.len()];
+    let mut scores = vec![0; monitors.len()];
+
+    for config in configs {
+        let best = monitors
+            .iter()
+            .enumerate()
+            .map(|(i, m)| (i, config.score(m)))
+            .filter(|(i, score)| *score > scores[*i])
+            .max_by_key(|(_, score)| *score);
+
+        if let Some((i, score)) = best {
+            roles[i] = config.role;
+            scores[i] = score;
+        }
+    }
+
+    let live = (0..roles.len())
+        .filter(|i| roles[*i] == OutputRole::Live)
+        .collect::<Vec<_>>();
+    match live.split_first() {
+        Some((_, rest)) => rest.iter().for_each(|i| roles[*i] = OutputRole::Mirror),
+        None => {
+            if let Some(i) =
+                (0..monitors.len()).find(|i| scores[*i] == 0 && monitors[*i].origin != (0, 0))
+            {
+                roles[i] = OutputRole::Live;
+            }
+        }
+    }
+
+    roles
+}
+
+/// Sets the role of `monitor` in `configs`, replacing what was remembered
+/// for it. A new live monitor takes over from the previous one.
+pub fn set_role(configs: &mut Vec<OutputConfig>, monitor: &MonitorInfo, role: OutputRole) {
+    let config = OutputConfig::new(role, monitor);
+    configs.retain(|c| c.connector != config.connector || c.model != config.model);
+    if role == OutputRole::Live {
+        configs.retain(|c| c.role != OutputRole::Live);
+    }
+    configs.push(config);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn monitor(connector: &str, model: &str, origin: (i32, i32)) -> MonitorInfo {
+        MonitorInfo {
+            connector: Some(connector.to_string()),
+            model: Some(model.to_string()),
+            origin,
+        }
+    }
+
+    #[test]
+    fn test_config_entry() {
+        let config = OutputConfig::new(OutputRole::Mirror, &monitor("HDMI-1", "Epson", (0, 0)));
+        assert_eq!(config.to_entry(), "mirror|HDMI-1|Epson");
+        assert_eq!(OutputConfig::parse("mirror|HDMI-1|Epson"), Some(config));
+        let config = OutputConfig::parse("stage|DP-2|Dell").unwrap();
+        assert_eq!(config.role, OutputRole::Stage);
+        let config = OutputConfig::new(OutputRole::LowerThird, &monitor("DP-3", "", (0, 0)));
+        assert_eq!(config.to_entry(), "lower-third|DP-3|");
+        assert_eq!(OutputConfig::parse(&config.to_entry()), Some(config));
+
+        // the model may hold the separator
+        let config = OutputConfig::parse("live||LG | 27").unwrap();
+        assert_eq!(config.model, "LG | 27");
+        assert_eq!(config.role, OutputRole::Live);
+
+        assert_eq!(OutputConfig::parse("projector|HDMI-1|Epson"), None);
+        assert_eq!(OutputConfig::parse("live||"), None);
+        assert_eq!(OutputConfig::parse("live"), None);
+
+        let entries = vec![String::from("bad"), String::from("live|DP-1|")];
+        assert_eq!(parse_outputs(&entries).len(), 1);
+        assert_eq!(format_outputs(&parse_outputs(&entries)), vec!["live|DP-1|"]);
+    }
+
+    #[test]
+    fn test_assign_default_live() {
+        let monitors = [
+            monitor("eDP-1", "Laptop", (0, 0)),
+            monitor("HDMI-1", "Epson", (1920, 0)),
+        ];
+        let roles = assign_roles(&[], &monitors);
+        assert_eq!(roles, vec![OutputRole::None, OutputRole::Live]);
+
+        // a single screen is not taken over
+        let roles = assign_roles(&[], &monitors[..1]);
+        assert_eq!(roles, vec![OutputRole::None]);
+    }
+
+    #[test]
+    fn test_assign_configured_roles() {
+        let monitors = [
+            monitor("eDP-1", "Laptop", (0, 0)),
+            monitor("HDMI-1", "Epson", (1920, 0)),
+            monitor("DP-1", "Samsung", (3840, 0)),
+        ];
+        let configs = [
+            OutputConfig::new(OutputRole::Live, &monitors[2]),
+            OutputConfig::new(OutputRole::Mirror, &monitors[1]),
+        ];
+        let roles = assign_roles(&configs, &monitors);
+        assert_eq!(
+            roles,
+            vec![OutputRole::None, OutputRole::Mirror, OutputRole::Live]
+        );
+
+        // a monitor turned off keeps the default off it
+        let configs = [OutputConfig::new(OutputRole::None, &monitors[1])];
+        let roles = assign_roles(&configs, &monitors[..2]);
+        assert_eq!(roles, vec![OutputRole::None, OutputRole::None]);
+    }
+
+    #[test]
+    fn test_assign_moved_monitor() {
+        let configs = [OutputConfig::new(
+            OutputRole::Live,
+            &monitor("HDMI-1", "Epson", (0, 0)),
+        )];
+
+        // same projector on another port
+        let monitors = [
+            monitor("HDMI-1", "Laptop", (0, 0)),
+            monitor("HDMI-2", "Epson", (1920, 0)),
+        ];
+        let roles = assign_roles(&configs, &monitors);
+        assert_eq!(roles, vec![OutputRole::None, OutputRole::Live]);
+
+        // another model on the same port is not the configured one
+        let monitors = [
+            monitor("HDMI-1", "BenQ", (0, 0)),
+            monitor("HDMI-2", "LG", (1920, 0)),
+        ];
+        let roles = assign_roles(&configs, &monitors);
+        assert_eq!(roles, vec![OutputRole::None, OutputRole::Live]);
+
+        // two projectors of the same model
+        let monitors = [
+            monitor("eDP-1", "Laptop", (0, 0)),
+            monitor("HDMI-2", "Epson", (1920, 0)),
+            monitor("HDMI-1", "Epson", (3840, 0)),
+        ];
+        let configs = [
+            OutputConfig::new(OutputRole::Live, &monitors[2]),
+            OutputConfig::new(OutputRole::Mirror, &monitors[1]),
+        ];
+        let roles = assign_roles(&configs, &monitors);
+        assert_eq!(
+            roles,
+            vec![OutputRole::None, OutputRole::Mirror, OutputRole::Live]
+        );
+    }
+
+    #[test]
+    fn test_set_role() {
+        let laptop = monitor("eDP-1", "Laptop", (0, 0));
+        let epson = monitor("HDMI-1", "Epson", (1920, 0));
+        let mut configs = vec![];
+
+        set_role(&mut configs, &epson, OutputRole::Live);
+        set_role(&mut configs, &epson, OutputRole::Mirror);
+        assert_eq!(configs, vec![OutputConfig::new(OutputRole::Mirror, &epson)]);
+
+        set_role(&mut configs, &epson, OutputRole::Live);
+        set_role(&mut configs, &laptop, OutputRole::Live);
+        assert_eq!(configs, vec![OutputConfig::new(OutputRole::Live, &laptop)]);
+
+        // only the live role is unique
+        let benq = monitor("HDMI-2", "BenQ", (3840, 0));
+        set_role(&mut configs, &epson, OutputRole::Stage);
+        set_role(&mut configs, &benq, OutputRole::Stage);
+        assert_eq!(configs.len(), 3);
+    }
+}
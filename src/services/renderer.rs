@@ -0,0 +1,612 @@
+use std::f64::consts::PI;
+
+use gtk::{gdk, gdk::prelude::GdkCairoContextExt, gdk_pixbuf::Pixbuf, glib};
+
+use crate::{
+    services::{
+        file_manager::FileManager,
+        text_fit,
+        timer::{self, SystemClock},
+    },
+    widgets::canvas::{
+        serialise::{
+            BackgroundGradient, CanvasData, CanvasItemData, CanvasItemType, GradientKind, ImageFit,
+            ItemStyle, Resolution, SlideData, SlideManagerData, TextAlign, TextItemData,
+        },
+        shape_item::draw_shape,
+        text_item::{FONT_SCALE, data_layout, measure_layout, paint_text, text_box},
+        timer_item::draw_timer,
+    },
+};
+
+/// PDF pages are sized in points, slides in pixels at 96 dpi
+const PDF_POINTS_PER_PIXEL: f64 = 72.0 / 96.0;
+
+#[derive(Debug)]
+pub enum RenderError {
+    Cairo(cairo::Error),
+    Io(cairo::IoError),
+    Custom(String),
+}
+
+impl std::fmt::Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cairo(e) => write!(f, "cairo error: {e}"),
+            Self::Io(e) => write!(f, "cairo io error: {e}"),
+            Self::Custom(e) => f.write_str(e),
+        }
+    }
+}
+
+impl From<cairo::Error> for RenderError {
+    fn from(value: cairo::Error) -> Self {
+        Self::Cairo(value)
+    }
+}
+
+impl From<cairo::IoError> for RenderError {
+    fn from(value: cairo::IoError) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<String> for RenderError {
+    fn from(value: String) -> Self {
+        Self::Custom(value)
+    }
+}
+
+/// Draws slides from their saved data with pango and cairo, so slides can
+/// be exported without a window or a display.
+///
+/// Background and item videos, background blur and item shadows are not
+/// drawn.
+pub struct SlideRenderer {
+    /// smallest font size text auto-fit shrinks to
+    pub min_font_size: f64,
+}
+
+impl SlideRenderer {
+    pub fn new(min_font_size: f64) -> Self {
+        Self { min_font_size }
+    }
+
+    /// `slide` as a PNG at the full size of `resolution`
+    pub fn png(&self, slide: &SlideData, resolution: Resolution) -> Result<Vec<u8>, RenderError> {
+        let surface = self.image(slide, resolution)?;
+        let mut png = Vec::new();
+        surface.write_to_png(&mut png)?;
+        Ok(png)
+    }
+
+    /// Every slide of `data` as a PNG at its design resolution
+    pub fn pngs(&self, data: &SlideManagerData) -> Result<Vec<Vec<u8>>, RenderError> {
+        data.slides
+            .iter()
+            .map(|s| self.png(s, data.resolution))
+            .collect()
+    }
+
+    /// `slide` drawn on an image the size of `resolution`
+    pub fn image(
+        &self,
+        slide: &SlideData,
+        resolution: Resolution,
+    ) -> Result<cairo::ImageSurface, RenderError> {
+        let (width, height) = (resolution.width.max(1), resolution.height.max(1));
+        let surface = cairo::ImageSurface::create(cairo::Format::ARgb32, width, height)?;
+        {
+            let cr = cairo::Context::new(&surface)?;
+            self.draw(&cr, slide, resolution, width as f64, height as f64);
+        }
+        surface.flush();
+        Ok(surface)
+    }
+
+    /// A PDF with a page for every slide of `data`
+    pub fn pdf(&self, data: &SlideManagerData) -> Result<Vec<u8>, RenderError> {
+        let resolution = data.resolution;
+        let width = resolution.width.max(1) as f64 * PDF_POINTS_PER_PIXEL;
+        let height = resolution.height.max(1) as f64 * PDF_POINTS_PER_PIXEL;
+
+        let surface = cairo::PdfSurface::for_stream(width, height, Vec::<u8>::new())?;
+        surface.set_metadata(cairo::PdfMetadata::Title, &data.title)?;
+        {
+            let cr = cairo::Context::new(&surface)?;
+            for slide in &data.slides {
+                self.draw(&cr, slide, resolution, width, height);
+                cr.show_page()?;
+            }
+        }
+
+        let stream = surface
+            .finish_output_stream()
+            .map_err(|e| e.error.to_string())?;
+        let pdf = stream
+            .downcast::<Vec<u8>>()
+            .map_err(|_| "Unexpected PDF stream".to_string())?;
+        Ok(*pdf)
+    }
+
+    /// Draws `slide` to fill `width` by `height` on `cr`, which should
+    /// have the shape of `resolution`
+    pub fn draw(
+        &self,
+        cr: &cairo::Context,
+        slide: &SlideData,
+        resolution: Resolution,
+        width: f64,
+        height: f64,
+    ) {
+        let slide_size = (resolution.slide_width(), Resolution::SLIDE_HEIGHT);
+
+        cr.save().ok();
+        cr.rectangle(0.0, 0.0, width, height);
+        cr.clip();
+        cr.scale(width / slide_size.0, height / slide_size.1);
+
+        draw_background(cr, &slide.canvas_data, slide_size);
+        for item in &slide.items {
+            self.draw_item(cr, item, resolution);
+        }
+
+        cr.restore().ok();
+    }
+
+    fn draw_item(&self, cr: &cairo::Context, item: &CanvasItemData, resolution: Resolution) {
+        let (x, width) = match item.w < 0 {
+            true => (0.0, resolution.slide_width()),
+            false => (item.x as f64, item.w as f64),
+        };
+        let (y, height) = match item.h < 0 {
+            true => (0.0, Resolution::SLIDE_HEIGHT),
+            false => (item.y as f64, item.h as f64),
+        };
+        let style = &item.style;
+
+        cr.save().ok();
+        cr.translate(x + width / 2.0, y + height / 2.0);
+        cr.rotate(style.rotation.to_radians());
+        cr.translate(-width / 2.0, -height / 2.0);
+        cr.push_group();
+
+        cr.save().ok();
+        if style.border_radius > 0.0 {
+            rounded_rectangle(cr, 0.0, 0.0, width, height, style.border_radius);
+            cr.clip();
+        }
+
+        match &item.item_type {
+            CanvasItemType::Text(text) => self.draw_text(cr, item, text, resolution),
+            CanvasItemType::Shape(shape) => draw_shape(cr, shape, width, height, 1.0),
+            CanvasItemType::Image(image) => {
+                if let Some(pixbuf) = load_image(&image.url) {
+                    let src = (pixbuf.width() as f64, pixbuf.height() as f64);
+                    let place = ImageFit::Contain.place(src, (width, height), (0.5, 0.5), 1.0);
+                    paint_pixbuf(cr, &pixbuf, place, false);
+                }
+            }
+            // exports show a timer as it is before its slide goes live
+            CanvasItemType::Timer(data) => {
+                let text = timer::idle_text(data, &SystemClock);
+                draw_timer(cr, data, &text, width, height, 1.0);
+            }
+            CanvasItemType::Video(_) | CanvasItemType::Unknown => (),
+        }
+
+        cr.restore().ok();
+        draw_border(cr, style, width, height);
+
+        if cr.pop_group_to_source().is_ok() {
+            cr.paint_with_alpha(style.opacity.clamp(0.0, 1.0)).ok();
+        }
+        cr.restore().ok();
+    }
+
+    fn draw_text(
+        &self,
+        cr: &cairo::Context,
+        item: &CanvasItemData,
+        text: &TextItemData,
+        resolution: Resolution,
+    ) {
+        let markup = String::from_utf8(glib::base64_decode(&text.text_data)).unwrap_or_default();
+        if markup.trim().is_empty() {
+            return;
+        }
+
+        let (width, height) = text_box(item, text.padding, resolution);
+        let layout = data_layout(text, &markup, width);
+        pangocairo::functions::update_layout(cr, &layout);
+
+        let fit = text_fit::fit_text(
+            text.fit,
+            FONT_SCALE * text.font_size as f64,
+            FONT_SCALE * self.min_font_size,
+            (width, height),
+            |size| measure_layout(&layout, size),
+        );
+        let (_, layout_height) = measure_layout(&layout, fit.size);
+
+        let y = match text.align {
+            TextAlign::Top => 0.0,
+            TextAlign::Middle => (height - layout_height) / 2.0,
+            TextAlign::Bottom => height - layout_height,
+        };
+
+        cr.save().ok();
+        cr.translate(text.padding, text.padding);
+        paint_text(
+            cr,
+            &layout,
+            y,
+            1.0,
+            text.text_outline.then_some(&text.outline_style),
+            text.text_shadow.then_some(&text.shadow_style),
+            &text.color,
+        );
+        cr.restore().ok();
+    }
+}
+
+fn set_source_color(cr: &cairo::Context, color: &str) -> bool {
+    let Ok(color) = gdk::RGBA::parse(color) else {
+        return false;
+    };
+    cr.set_source_rgba(
+        color.red().into(),
+        color.green().into(),
+        color.blue().into(),
+        color.alpha().into(),
+    );
+    true
+}
+
+fn draw_background(cr: &cairo::Context, data: &CanvasData, size: (f64, f64)) {
+    if set_source_color(cr, &data.background_color) {
+        cr.paint().ok();
+    }
+
+    if let Some(gradient) = &data.background_gradient {
+        draw_gradient(cr, gradient, size);
+    }
+
+    let Some(path) = data
+        .background_pattern
+        .as_ref()
+        .filter(|p| !FileManager::is_video(std::path::Path::new(p)))
+    else {
+        return;
+    };
+    let Some(pixbuf) = load_image(path) else {
+        return;
+    };
+
+    let options = &data.background_image;
+    let src = (pixbuf.width() as f64, pixbuf.height() as f64);
+    let position = (options.position_x, options.position_y);
+    let place = options.fit.place(src, size, position, 1.0);
+    paint_pixbuf(cr, &pixbuf, place, options.fit == ImageFit::Tile);
+
+    if options.dim > 0.0 {
+        cr.set_source_rgba(0.0, 0.0, 0.0, options.dim.clamp(0.0, 1.0));
+        cr.paint().ok();
+    }
+}
+
+/// Paints `gradient` the way CSS would over a `size` box
+fn draw_gradient(cr: &cairo::Context, gradient: &BackgroundGradient, size: (f64, f64)) {
+    let (width, height) = size;
+    let (cx, cy) = (width / 2.0, height / 2.0);
+
+    let add_stops = |pattern: &cairo::Gradient| {
+        for stop in &gradient.stops {
+            if let Ok(color) = gdk::RGBA::parse(&stop.color) {
+                pattern.add_color_stop_rgba(
+                    stop.offset.clamp(0.0, 1.0),
+                    color.red().into(),
+                    color.green().into(),
+                    color.blue().into(),
+                    color.alpha().into(),
+                );
+            }
+        }
+    };
+
+    match gradient.kind {
+        GradientKind::Linear => {
+            // 0deg points up and angles turn clockwise, the line is long
+            // enough for the corners to get the first and last colours
+            let angle = gradient.angle.to_radians();
+            let (dx, dy) = (angle.sin(), -angle.cos());
+            let half = (width * dx.abs() + height * dy.abs()) / 2.0;
+            let pattern = cairo::LinearGradient::new(
+                cx - dx * half,
+                cy - dy * half,
+                cx + dx * half,
+                cy + dy * half,
+            );
+            add_stops(&pattern);
+            cr.set_source(&pattern).ok();
+        }
+        GradientKind::Radial => {
+            let radius = (width * width + height * height).sqrt() / 2.0;
+            let pattern = cairo::RadialGradient::new(cx, cy, 0.0, cx, cy, radius);
+            add_stops(&pattern);
+            cr.set_source(&pattern).ok();
+        }
+    }
+    cr.paint().ok();
+}
+
+fn draw_border(cr: &cairo::Context, style: &ItemStyle, width: f64, height: f64) {
+    if style.border_width <= 0.0 || !set_source_color(cr, &style.border_color) {
+        return;
+    }
+
+    let inset = style.border_width / 2.0;
+    rounded_rectangle(
+        cr,
+        inset,
+        inset,
+        (width - style.border_width).max(0.0),
+        (height - style.border_width).max(0.0),
+        (style.border_radius - inset).max(0.0),
+    );
+    cr.set_line_width(style.border_width);
+    cr.stroke().ok();
+}
+
+fn rounded_rectangle(cr: &cairo::Context, x: f64, y: f64, w: f64, h: f64, radius: f64) {
+    let r = radius.min(w / 2.0).min(h / 2.0);
+    cr.new_sub_path();
+    cr.arc(x + w - r, y + r, r, -PI / 2.0, 0.0);
+    cr.arc(x + w - r, y + h - r, r, 0.0, PI / 2.0);
+    cr.arc(x + r, y + h - r, r, PI / 2.0, PI);
+    cr.arc(x + r, y + r, r, PI, 3.0 * PI / 2.0);
+    cr.close_path();
+}
+
+fn load_image(path: &str) -> Option<Pixbuf> {
+    if path.is_empty() {
+        return None;
+    }
+
+    Pixbuf::from_file(path)
+        .map_err(|e| glib::g_warning!("SlideRenderer", "Failed to load {path}: {:?}", e))
+        .ok()
+}
+
+/// Paints `pixbuf` at `place`, as `(x, y, width, height)`, repeating it
+/// over the clip when `tile` is set
+fn paint_pixbuf(cr: &cairo::Context, pixbuf: &Pixbuf, place: (f64, f64, f64, f64), tile: bool) {
+    let (x, y, width, height) = place;
+    if width <= 0.0 || height <= 0.0 {
+        return;
+    }
+
+    cr.save().ok();
+    cr.translate(x, y);
+    cr.scale(
+        width / pixbuf.width().max(1) as f64,
+        height / pixbuf.height().max(1) as f64,
+    );
+    cr.set_source_pixbuf(pixbuf, 0.0, 0.0);
+    if tile {
+        cr.source().set_extend(cairo::Extend::Repeat);
+    } else {
+        cr.rectangle(0.0, 0.0, pixbuf.width() as f64, pixbuf.height() as f64);
+        cr.clip();
+    }
+    cr.paint().ok();
+    cr.restore().ok();
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::widgets::canvas::serialise::{
+        GradientStop, ShapeItemData, TextJustification, TextOutline,
+    };
+
+    /// Set to write the golden images again instead of comparing with them
+    const UPDATE_GOLDEN: &str = "OW_UPDATE_GOLDEN";
+    /// Largest difference allowed in any channel of a golden image pixel
+    const GOLDEN_TOLERANCE: u8 = 2;
+
+    const SMALL: Resolution = Resolution::new(160, 90);
+
+    fn renderer() -> SlideRenderer {
+        SlideRenderer::new(8.0)
+    }
+
+    fn slide(background: &str, items: Vec<CanvasItemData>) -> SlideData {
+        let canvas_data = CanvasData {
+            background_color: background.into(),
+            ..CanvasData::default()
+        };
+        SlideData::new(0, items, Vec::new(), canvas_data)
+    }
+
+    fn shape(x: i32, y: i32, w: i32, h: i32, fill: &str) -> CanvasItemData {
+        let data = ShapeItemData {
+            fill: fill.into(),
+            ..ShapeItemData::default()
+        };
+        CanvasItemData::new(x, y, w, h, CanvasItemType::Shape(data))
+    }
+
+    /// `(r, g, b, a)` of the pixel at `x`, `y`
+    fn pixel(surface: &mut cairo::ImageSurface, x: usize, y: usize) -> (u8, u8, u8, u8) {
+        let stride = surface.stride() as usize;
+        let data = surface.data().expect("surface data");
+        let start = y * stride + x * 4;
+        let argb = u32::from_ne_bytes(data[start..start + 4].try_into().unwrap());
+        (
+            (argb >> 16) as u8,
+            (argb >> 8) as u8,
+            argb as u8,
+            (argb >> 24) as u8,
+        )
+    }
+
+    /// Compares `surface` with `res/golden/{name}.png`, writing it instead
+    /// when `OW_UPDATE_GOLDEN` is set
+    fn assert_golden(name: &str, surface: &mut cairo::ImageSurface) {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("res/golden")
+            .join(format!("{name}.png"));
+
+        if std::env::var_os(UPDATE_GOLDEN).is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            let mut file = std::fs::File::create(&path).unwrap();
+            surface.write_to_png(&mut file).unwrap();
+            return;
+        }
+
+        let Ok(mut file) = std::fs::File::open(&path) else {
+            panic!(
+                "missing golden image {}, render it with {UPDATE_GOLDEN}=1 cargo test",
+                path.display()
+            );
+        };
+        let mut golden = cairo::ImageSurface::create_from_png(&mut file).unwrap();
+        assert_eq!(
+            (golden.width(), golden.height()),
+            (surface.width(), surface.height()),
+            "{name} size"
+        );
+
+        for y in 0..surface.height() as usize {
+            for x in 0..surface.width() as usize {
+                let (a, b) = (pixel(surface, x, y), pixel(&mut golden, x, y));
+                let close = |a: u8, b: u8| a.abs_diff(b) <= GOLDEN_TOLERANCE;
+                assert!(
+                    close(a.0, b.0) && close(a.1, b.1) && close(a.2, b.2) && close(a.3, b.3),
+                    "{name} differs at {x},{y}: {a:?} != {b:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_render_background_and_shapes() {
+        // the slide is 2667 by 1500, the shape covers the top left quarter
+        let data = slide("#ff0000ff", vec![shape(0, 0, 1333, 750, "#0000ffff")]);
+        let mut surface = renderer().image(&data, SMALL).unwrap();
+
+        assert_eq!((surface.width(), surface.height()), (160, 90));
+        assert_eq!(pixel(&mut surface, 20, 20), (0, 0, 255, 255));
+        assert_eq!(pixel(&mut surface, 120, 70), (255, 0, 0, 255));
+        assert_eq!(pixel(&mut surface, 20, 70), (255, 0, 0, 255));
+        assert_golden("background_and_shapes", &mut surface);
+    }
+
+    #[test]
+    fn test_render_item_style() {
+        let mut faded = shape(0, 0, -1, -1, "#ffffffff");
+        faded.style.opacity = 0.5;
+        let data = slide("#000000ff", vec![faded]);
+        let mut surface = renderer().image(&data, SMALL).unwrap();
+
+        let (r, g, b, a) = pixel(&mut surface, 80, 45);
+        assert!((126..=129).contains(&r), "{r}");
+        assert_eq!((g, b, a), (r, r, 255));
+
+        // a quarter turn of a wide bar leaves the sides of the slide alone
+        let mut turned = shape(833, 700, 1000, 100, "#ffffffff");
+        turned.style.rotation = 90.0;
+        let data = slide("#000000ff", vec![turned]);
+        let mut surface = renderer().image(&data, SMALL).unwrap();
+
+        assert_eq!(pixel(&mut surface, 80, 20), (255, 255, 255, 255));
+        assert_eq!(pixel(&mut surface, 70, 45), (0, 0, 0, 255));
+        assert_golden("item_style", &mut surface);
+    }
+
+    #[test]
+    fn test_render_gradient() {
+        let mut data = slide("#000000ff", Vec::new());
+        data.canvas_data.background_gradient = Some(BackgroundGradient {
+            kind: GradientKind::Linear,
+            angle: 180.0,
+            stops: vec![
+                GradientStop {
+                    color: "#000000ff".into(),
+                    offset: 0.0,
+                },
+                GradientStop {
+                    color: "#ffffffff".into(),
+                    offset: 1.0,
+                },
+            ],
+        });
+        let mut surface = renderer().image(&data, SMALL).unwrap();
+
+        let (top, middle, bottom) = (
+            pixel(&mut surface, 80, 0).0,
+            pixel(&mut surface, 80, 45).0,
+            pixel(&mut surface, 80, 89).0,
+        );
+        assert!(top < 10 && bottom > 245, "{top} {bottom}");
+        assert!((120..=135).contains(&middle), "{middle}");
+        // the same colour across the slide
+        assert_eq!(pixel(&mut surface, 0, 45), pixel(&mut surface, 159, 45));
+        assert_golden("gradient", &mut surface);
+    }
+
+    #[test]
+    fn test_render_text() {
+        let text = TextItemData {
+            text_data: glib::base64_encode(b"Amazing grace").to_string(),
+            font: "Sans".into(),
+            font_size: 20.0,
+            justification: TextJustification::Center,
+            color: "#ffffffff".into(),
+            outline_style: TextOutline::default(),
+            ..TextItemData::default()
+        };
+        let item = CanvasItemData::new(0, 0, -1, -1, CanvasItemType::Text(text));
+        let data = slide("#000000ff", vec![item]);
+        let mut surface = renderer().image(&data, Resolution::new(320, 180)).unwrap();
+
+        // fonts differ between machines so only look for ink, in the
+        // middle band where vertically centred text goes
+        let inked = (0..320)
+            .flat_map(|x| (60..120).map(move |y| (x, y)))
+            .filter(|&(x, y)| pixel(&mut surface, x, y).0 > 128)
+            .count();
+        assert!(inked > 0);
+        let top_inked = (0..320)
+            .filter(|&x| pixel(&mut surface, x, 5).0 > 128)
+            .count();
+        assert_eq!(top_inked, 0);
+    }
+
+    #[test]
+    fn test_render_png_and_pdf() {
+        let mut data = SlideManagerData::new(
+            0,
+            0,
+            [
+                slide("#ff0000ff", Vec::new()),
+                slide("#00ff00ff", Vec::new()),
+            ],
+        );
+        data.resolution = SMALL;
+        data.title = "Sunday".into();
+
+        let pngs = renderer().pngs(&data).unwrap();
+        assert_eq!(pngs.len(), 2);
+        assert!(pngs[0].starts_with(b"\x89PNG"));
+        let mut png = cairo::ImageSurface::create_from_png(&mut pngs[1].as_slice()).unwrap();
+        assert_eq!((png.width(), png.height()), (160, 90));
+        assert_eq!(pixel(&mut png, 10, 10), (0, 255, 0, 255));
+
+        let pdf = renderer().pdf(&data).unwrap();
+        assert!(pdf.starts_with(b"%PDF"));
+    }
+}
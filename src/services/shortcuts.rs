@@ -0,0 +1,326 @@
+/// Separates the action of a `shortcuts` setting entry from its keys
+const FIELD_SEPARATOR: char = '|';
+/// Separates the keys of a `shortcuts` setting entry
+const KEY_SEPARATOR: char = ',';
+/// Prefix of the name of a [ShortcutAction::Verse] action
+const VERSE_PREFIX: &str = "verse:";
+
+/// What a shortcut does to the live output
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ShortcutAction {
+    /// puts the preview item live
+    GoLive,
+    NextSlide,
+    PreviousSlide,
+    /// puts the next item of the schedule live
+    NextItem,
+    PreviousItem,
+    /// shows the next slide of the live item with the verse tag, e.g. `v1`
+    Verse(String),
+    Clear,
+    Black,
+    Logo,
+}
+
+impl ShortcutAction {
+    /// Actions that are always listed, verse actions come from the setting
+    pub const FIXED: [ShortcutAction; 8] = [
+        ShortcutAction::GoLive,
+        ShortcutAction::NextSlide,
+        ShortcutAction::PreviousSlide,
+        ShortcutAction::NextItem,
+        ShortcutAction::PreviousItem,
+        ShortcutAction::Clear,
+        ShortcutAction::Black,
+        ShortcutAction::Logo,
+    ];
+
+    pub fn label(&self) -> String {
+        let label = match self {
+            ShortcutAction::GoLive => "Go live",
+            ShortcutAction::NextSlide => "Next slide",
+            ShortcutAction::PreviousSlide => "Previous slide",
+            ShortcutAction::NextItem => "Next schedule item",
+            ShortcutAction::PreviousItem => "Previous schedule item",
+            ShortcutAction::Verse(tag) => return format!("Jump to {}", tag.to_uppercase()),
+            ShortcutAction::Clear => "Clear",
+            ShortcutAction::Black => "Black screen",
+            ShortcutAction::Logo => "Logo",
+        };
+        String::from(label)
+    }
+
+    pub fn name(&self) -> String {
+        let name = match self {
+            ShortcutAction::GoLive => "go-live",
+            ShortcutAction::NextSlide => "next-slide",
+            ShortcutAction::PreviousSlide => "previous-slide",
+            ShortcutAction::NextItem => "next-item",
+            ShortcutAction::PreviousItem => "previous-item",
+            ShortcutAction::Verse(tag) => return format!("{VERSE_PREFIX}{tag}"),
+            ShortcutAction::Clear => "clear",
+            ShortcutAction::Black => "black",
+            ShortcutAction::Logo => "logo",
+        };
+        String::from(name)
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        if let Some(tag) = name.strip_prefix(VERSE_PREFIX) {
+            let tag = normalise_tag(tag);
+            return (!tag.is_empty()).then_some(ShortcutAction::Verse(tag));
+        }
+        Self::FIXED.into_iter().find(|a| a.name() == name)
+    }
+}
+
+/// Keys of an action, as gtk accelerators like `<Control>Return`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shortcut {
+    pub action: ShortcutAction,
+    pub keys: Vec<String>,
+}
+
+impl Shortcut {
+    pub fn new(action: ShortcutAction, keys: &[&str]) -> Self {
+        Self {
+            action,
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+        }
+    }
+
+    /// Parses an `action|key,key` entry, no keys turns the action off
+    pub fn parse(entry: &str) -> Option<Self> {
+        let (name, keys) = entry.split_once(FIELD_SEPARATOR)?;
+        let action = ShortcutAction::from_name(name)?;
+        let keys = keys
+            .split(KEY_SEPARATOR)
+            .map(str::trim)
+            .filter(|k| !k.is_empty())
+            .map(String::from)
+            .collect();
+        Some(Self { action, keys })
+    }
+
+    pub fn to_entry(&self) -> String {
+        let keys = self.keys.join(&KEY_SEPARATOR.to_string());
+        format!("{}{FIELD_SEPARATOR}{keys}", self.action.name())
+    }
+}
+
+/// The keys used when the setting is empty. Presentation clickers send
+/// Page_Up and Page_Down, and `b` or `period` for a black screen
+pub fn default_shortcuts() -> Vec<Shortcut> {
+    let verse = |tag: &str| ShortcutAction::Verse(tag.to_string());
+    vec![
+        Shortcut::new(ShortcutAction::GoLive, &["F5", "<Control>Return"]),
+        Shortcut::new(ShortcutAction::NextSlide, &["Page_Down"]),
+        Shortcut::new(ShortcutAction::PreviousSlide, &["Page_Up"]),
+        Shortcut::new(ShortcutAction::NextItem, &["<Control>Page_Down"]),
+        Shortcut::new(ShortcutAction::PreviousItem, &["<Control>Page_Up"]),
+        Shortcut::new(ShortcutAction::Clear, &["x"]),
+        Shortcut::new(ShortcutAction::Black, &["b", "period"]),
+        Shortcut::new(ShortcutAction::Logo, &["l"]),
+        Shortcut::new(verse("v1"), &["1"]),
+        Shortcut::new(verse("v2"), &["2"]),
+        Shortcut::new(verse("v3"), &["3"]),
+        Shortcut::new(verse("v4"), &["4"]),
+        Shortcut::new(verse("c"), &["c"]),
+        Shortcut::new(verse("b"), &["<Shift>b"]),
+    ]
+}
+
+/// Parses the `shortcuts` setting. Its entries replace the keys of the
+/// default actions and add verse actions, invalid entries are skipped.
+/// A verse action without keys has been removed, so it is left out
+pub fn parse_shortcuts(entries: &[String]) -> Vec<Shortcut> {
+    let mut shortcuts = default_shortcuts();
+    for shortcut in entries.iter().filter_map(|e| Shortcut::parse(e)) {
+        match shortcuts.iter_mut().find(|s| s.action == shortcut.action) {
+            Some(s) => s.keys = shortcut.keys,
+            None => shortcuts.push(shortcut),
+        }
+    }
+    shortcuts.retain(|s| !matches!(s.action, ShortcutAction::Verse(_)) || !s.keys.is_empty());
+    shortcuts
+}
+
+/// Entries for the `shortcuts` setting, only what differs from the defaults
+pub fn format_shortcuts(shortcuts: &[Shortcut]) -> Vec<String> {
+    let defaults = default_shortcuts();
+    let mut entries = shortcuts
+        .iter()
+        .filter(|s| !defaults.contains(s))
+        .map(Shortcut::to_entry)
+        .collect::<Vec<_>>();
+
+    // a removed verse action keeps none of its default keys
+    let removed = defaults
+        .into_iter()
+        .filter(|d| !shortcuts.iter().any(|s| s.action == d.action))
+        .map(|d| Shortcut::new(d.action, &[]).to_entry());
+    entries.extend(removed);
+    entries
+}
+
+/// Sets the keys of `action`, adding it when it is a new verse action.
+/// A key only does one thing, so it is taken off the other actions
+pub fn set_keys(shortcuts: &mut Vec<Shortcut>, action: &ShortcutAction, keys: &[String]) {
+    for shortcut in shortcuts.iter_mut() {
+        shortcut.keys.retain(|k| !keys.contains(k));
+    }
+
+    match shortcuts.iter_mut().find(|s| s.action == *action) {
+        Some(s) => s.keys = keys.to_vec(),
+        None => shortcuts.push(Shortcut {
+            action: action.clone(),
+            keys: keys.to_vec(),
+        }),
+    }
+}
+
+/// Verse tags are matched without case or spaces, `V 1` is `v1`
+pub fn normalise_tag(tag: &str) -> String {
+    tag.split_whitespace().collect::<String>().to_lowercase()
+}
+
+/// Position of the slide to show for `tag` when the slide at `current` is
+/// live. The search starts after `current` and wraps, so a repeated chorus
+/// is shown in order. A tag without a number also matches numbered ones,
+/// `c` finds `c1` when there is no `c`
+pub fn find_tag<'a, I>(tags: I, current: u32, tag: &str) -> Option<u32>
+where
+    I: IntoIterator<Item = Option<&'a str>>,
+{
+    let tag = normalise_tag(tag);
+    let tags = tags
+        .into_iter()
+        .map(|t| t.map(normalise_tag).unwrap_or_default())
+        .collect::<Vec<_>>();
+
+    let exact = |t: &String| *t == tag;
+    let numbered = |t: &String| {
+        t.strip_prefix(tag.as_str())
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+    };
+    let matches: &dyn Fn(&String) -> bool = match tags.iter().any(exact) {
+        true => &exact,
+        false => &numbered,
+    };
+
+    let len = tags.len() as u32;
+    (1..=len)
+        .map(|step| (current + step) % len)
+        .find(|i| matches(&tags[*i as usize]))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn keys(keys: &[&str]) -> Vec<String> {
+        keys.iter().map(|k| k.to_string()).collect()
+    }
+
+    #[test]
+    fn test_shortcut_entry() {
+        let shortcut = Shortcut::parse("next-slide|Page_Down, Right").unwrap();
+        assert_eq!(shortcut.action, ShortcutAction::NextSlide);
+        assert_eq!(shortcut.keys, keys(&["Page_Down", "Right"]));
+        assert_eq!(shortcut.to_entry(), "next-slide|Page_Down,Right");
+
+        let shortcut = Shortcut::parse("verse:V 2|<Shift>2").unwrap();
+        assert_eq!(shortcut.action, ShortcutAction::Verse(String::from("v2")));
+        assert_eq!(shortcut.to_entry(), "verse:v2|<Shift>2");
+        assert_eq!(shortcut.action.label(), "Jump to V2");
+
+        // no keys turns the action off
+        assert_eq!(Shortcut::parse("black|").unwrap().keys, keys(&[]));
+
+        assert_eq!(Shortcut::parse("next-slide"), None);
+        assert_eq!(Shortcut::parse("rewind|r"), None);
+        assert_eq!(Shortcut::parse("verse:|v"), None);
+    }
+
+    #[test]
+    fn test_parse_and_format() {
+        assert_eq!(parse_shortcuts(&[]), default_shortcuts());
+        assert!(format_shortcuts(&default_shortcuts()).is_empty());
+
+        let entries = vec![
+            String::from("black|period"),
+            String::from("bad"),
+            String::from("verse:e|e"),
+        ];
+        let shortcuts = parse_shortcuts(&entries);
+        let black = shortcuts.iter().find(|s| s.action == ShortcutAction::Black);
+        assert_eq!(black.unwrap().keys, keys(&["period"]));
+        assert_eq!(shortcuts.len(), default_shortcuts().len() + 1);
+        assert_eq!(
+            format_shortcuts(&shortcuts),
+            vec!["black|period", "verse:e|e"]
+        );
+
+        // a removed default verse action stays removed
+        let mut shortcuts = default_shortcuts();
+        shortcuts.retain(|s| s.action != ShortcutAction::Verse(String::from("v4")));
+        let entries = format_shortcuts(&shortcuts);
+        assert_eq!(entries, vec!["verse:v4|"]);
+        let shortcuts = parse_shortcuts(&entries);
+        assert!(!shortcuts.iter().any(|s| s.action.name() == "verse:v4"));
+        assert_eq!(shortcuts.len(), default_shortcuts().len() - 1);
+
+        // a fixed action keeps its row without keys
+        let shortcuts = parse_shortcuts(&[String::from("black|")]);
+        let black = shortcuts.iter().find(|s| s.action == ShortcutAction::Black);
+        assert_eq!(black.unwrap().keys, keys(&[]));
+    }
+
+    #[test]
+    fn test_set_keys() {
+        let mut shortcuts = default_shortcuts();
+
+        // `b` moves from black to the bridge
+        let bridge = ShortcutAction::Verse(String::from("b"));
+        set_keys(&mut shortcuts, &bridge, &keys(&["b"]));
+        let black = shortcuts.iter().find(|s| s.action == ShortcutAction::Black);
+        assert_eq!(black.unwrap().keys, keys(&["period"]));
+        let found = shortcuts.iter().find(|s| s.action == bridge);
+        assert_eq!(found.unwrap().keys, keys(&["b"]));
+
+        let len = shortcuts.len();
+        let ending = ShortcutAction::Verse(String::from("e"));
+        set_keys(&mut shortcuts, &ending, &keys(&["e"]));
+        assert_eq!(shortcuts.len(), len + 1);
+    }
+
+    #[test]
+    fn test_find_tag() {
+        let tags = [
+            Some("V1"),
+            Some("C"),
+            Some("v2"),
+            Some("c"),
+            None,
+            Some("B"),
+        ];
+        let find = |current, tag| find_tag(tags.iter().copied(), current, tag);
+
+        assert_eq!(find(0, "v2"), Some(2));
+        assert_eq!(find(0, "V 1"), Some(0));
+        // the next chorus after the live slide, wrapping
+        assert_eq!(find(0, "c"), Some(1));
+        assert_eq!(find(1, "c"), Some(3));
+        assert_eq!(find(4, "C"), Some(1));
+        assert_eq!(find(0, "b"), Some(5));
+        assert_eq!(find(0, "v3"), None);
+        assert_eq!(find_tag([], 0, "c"), None);
+
+        // numbered tags match only when there is no exact one
+        let tags = [Some("v1"), Some("c1"), Some("v10"), Some("c2")];
+        assert_eq!(find_tag(tags, 1, "c"), Some(3));
+        assert_eq!(find_tag(tags, 0, "v1"), Some(0));
+        assert_eq!(find_tag(tags, 0, "v"), Some(2));
+        assert_eq!(find_tag([Some("chorus")], 0, "c"), None);
+    }
+}
@@ -11,7 +11,7 @@ use crate::utils::{self, WidgetChildrenExt};
 use crate::widgets::canvas::canvas::Canvas;
 use crate::widgets::canvas::canvas_item::{CanvasItem, CanvasItemExt};
 use crate::widgets::canvas::serialise::{CanvasData, SlideData};
-use crate::widgets::canvas::text_item::TextItem;
+use crate::widgets::canvas::text_item::{TextItem, fit_item_data};
 
 mod imp {
     use std::cell::{Cell, RefCell};
@@ -21,8 +21,9 @@ mod imp {
     use gtk::prelude::*;
     use gtk::subclass::prelude::*;
 
+    use crate::services::transition::SlideTransition;
     use crate::widgets::canvas::canvas::Canvas;
-    use crate::widgets::canvas::serialise::SlideData;
+    use crate::widgets::canvas::serialise::{AudioCue, SlideData};
 
     #[derive(Properties)]
     #[properties(wrapper_type = super::Slide)]
@@ -39,12 +40,25 @@ mod imp {
         pub notes: RefCell<String>,
         #[property(set, get, builder(gtk::StackTransitionType::None))]
         pub transition: RefCell<gtk::StackTransitionType>,
+        #[property(set, get)]
+        pub transition_options: RefCell<SlideTransition>,
 
         #[property(get, set/* =Self::set_visible_ */, default_value=true, construct)]
         pub visible: Cell<bool>,
 
         #[property(get, set, construct, default_value = false)]
         pub presentation_mode: Cell<bool>,
+
+        #[property(get, set, nullable)]
+        pub audio: RefCell<Option<AudioCue>>,
+        /// see [SlideData::duration]
+        pub duration: Cell<Option<u32>>,
+        /// see [SlideData::tag]
+        pub tag: RefCell<Option<String>>,
+
+        /// text is cut off or shrunk to the minimum size, see `update_fit_warning`
+        #[property(get)]
+        pub fit_warning: Cell<bool>,
     }
 
     #[glib::object_subclass]
@@ -65,8 +79,13 @@ mod imp {
                 preview_data: RefCell::new(glib::Bytes::from(&[])),
                 notes: RefCell::new(String::default()),
                 transition: RefCell::new(gtk::StackTransitionType::None),
+                transition_options: RefCell::default(),
                 visible: Cell::new(true),
                 presentation_mode: Cell::new(false),
+                audio: RefCell::new(None),
+                duration: Cell::new(None),
+                tag: RefCell::new(None),
+                fit_warning: Cell::new(false),
             }
         }
     }
@@ -86,8 +105,13 @@ impl Default for Slide {
 
 impl Slide {
     const PREVIEW_WIDTH: i32 = 200;
-    pub fn preview_height() -> i32 {
-        (Self::PREVIEW_WIDTH as f32 / AppConfig::aspect_ratio()) as i32
+    /// Height of the preview, from the shape of the slide
+    pub fn preview_height(&self) -> i32 {
+        let ratio = self
+            .canvas()
+            .map(|c| c.resolution().ratio())
+            .unwrap_or_else(AppConfig::aspect_ratio);
+        (Self::PREVIEW_WIDTH as f32 / ratio) as i32
     }
     pub fn preview_width() -> i32 {
         Self::PREVIEW_WIDTH
@@ -188,6 +212,7 @@ impl Slide {
         }
 
         self.set_transition(utils::int_to_transition(save_data.transition));
+        self.set_transition_options(save_data.transition_options);
 
         self.imp().save_data.replace(None);
     }
@@ -229,27 +254,25 @@ impl Slide {
         }
 
         let raw_notes = glib::base64_encode(self.notes().as_bytes());
-        SlideData::new(
+        let mut data = SlideData::new(
             utils::transition_to_int(self.transition()),
             c_item_data,
             self.preview_data().to_vec(),
             canvas.serialise(),
-        )
+        );
+        data.transition_options = self.transition_options();
+        data.audio = self.audio();
+        data.duration = imp.duration.get();
+        data.tag = imp.tag.borrow().clone();
+        data
     }
 
     pub fn delete(&self) {
-        let Some(canvas) = self.canvas() else {
+        if self.canvas().is_none() {
             return;
         };
-        // let Some(window) = canvas.imp().window.upgrade() else {
-        //     return;
-        // };
-        //
-        // let action = TypedHistoryAction::slide_changed(&self.clone(), "visible");
-        // window
-        //     .history_manager()
-        //     .add_undoable_action(action.into(), Some(true));
 
+        // NOTE: the slide manager records the deletion when the slide is hidden
         self.set_visible(false);
     }
 
@@ -272,7 +295,7 @@ impl Slide {
 
             let snapshot = gtk::Snapshot::new();
             let w = Self::preview_width() as f64;
-            let h = Self::preview_height() as f64;
+            let h = s.preview_height() as f64;
             paintable.snapshot(&snapshot, w, h);
 
             let Some(node) = snapshot.to_node() else {
@@ -310,10 +333,11 @@ impl Slide {
                 gio::Cancellable::NONE,
             );
 
+            // the saved preview already has the shape of the slide
             if let Some(pix_buf) = pix_buf.ok()
                 && let Some(pix) = pix_buf.scale_simple(
                     Self::preview_width(),
-                    Self::preview_height(),
+                    Self::preview_width() * pix_buf.height() / pix_buf.width().max(1),
                     gdk_pixbuf::InterpType::Bilinear,
                 )
             {
@@ -323,9 +347,29 @@ impl Slide {
         }
 
         self.set_transition(utils::int_to_transition(save_data.transition));
+        self.set_transition_options(save_data.transition_options);
+        self.set_audio(save_data.audio);
+        self.imp().duration.set(save_data.duration);
+        self.imp().tag.replace(save_data.tag);
         // self.set_notes(save_data.notes);
     }
 
+    /// Measures the text of the slide and updates [Self::fit_warning]
+    pub fn update_fit_warning(&self) {
+        let resolution = self.canvas().map(|c| c.resolution()).unwrap_or_default();
+        let warning = self
+            .serialise()
+            .items
+            .iter()
+            .filter_map(|item| fit_item_data(item, resolution))
+            .any(|fit| fit.needs_warning());
+
+        if warning != self.fit_warning() {
+            self.imp().fit_warning.set(warning);
+            self.notify_fit_warning();
+        }
+    }
+
     pub fn canvas(&self) -> Option<Canvas> {
         self.imp().canvas.borrow().clone()
     }
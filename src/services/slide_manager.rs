@@ -1,6 +1,7 @@
 use std::usize;
 
 use gtk::{
+    gio,
     glib::{
         self,
         object::{Cast, ObjectExt},
@@ -10,13 +11,20 @@ use gtk::{
 };
 
 use crate::{
-    services::slide::Slide,
+    services::{history_manager::HistoryActionKind, slide::Slide},
     utils::WidgetChildrenExt,
     widgets::canvas::{
         CanvasItemType,
         canvas_item::CanvasItem,
-        serialise::{SlideData, SlideManagerData},
+        screen_cover::{ScreenCover, ScreenMode},
+        serialise::{
+            Resolution, ShapeKind, SlideData, SlideManagerData, TimerFinish, TimerItemData,
+        },
+        shape_item::ShapeItem,
         text_item::TextItem,
+        timer_item::TimerItem,
+        transition_layer::TransitionLayer,
+        video_item::VideoItem,
     },
 };
 
@@ -32,6 +40,8 @@ mod signals {
     pub const ITEM_CLICKED: &str = "item-clicked";
     pub const NEW_SLIDE_CREATED: &str = "new-slide-created";
     pub const SLIDES_SORTED: &str = "slides-sorted";
+    pub const CHANGED: &str = "changed";
+    pub const TIMER_FINISHED: &str = "timer-finished";
 }
 
 mod imp {
@@ -46,7 +56,7 @@ mod imp {
     use super::*;
     use crate::services::settings::ApplicationSettings;
     use crate::services::slide::Slide;
-    use crate::utils::{WidgetChildrenExt, int_to_transition, transition_to_int};
+    use crate::utils::{WidgetChildrenExt, WidgetExtrasExt, int_to_transition, transition_to_int};
     use crate::widgets::canvas::canvas_item::CanvasItem;
     // use crate::services::utils::{self, AspectRatio};
     // use crate::spice_window::SpiceWindow;
@@ -63,6 +73,10 @@ mod imp {
         pub animation: Cell<bool>,
         #[property(get, set)]
         pub title: RefCell<String>,
+        /// shape of the slides, the items are not moved when it is set,
+        /// see [SlideManagerData::set_resolution]
+        #[property(get, set=Self::set_resolution_)]
+        pub resolution: Cell<Resolution>,
 
         #[doc = "property setter is private "]
         pub(super) slides: RefCell<Vec<Slide>>,
@@ -87,6 +101,10 @@ mod imp {
 
         #[property(get, set)]
         pub log: Cell<bool>,
+
+        /// slideshow with the transition layer and screen cover over it,
+        /// see [super::SlideManager::screen]
+        pub screen: RefCell<Option<(gtk::Overlay, TransitionLayer, ScreenCover)>>,
     }
 
     #[glib::object_subclass]
@@ -120,6 +138,16 @@ mod imp {
                         .param_types([Slide::static_type()])
                         .build(),
                     Signal::builder(super::signals::SLIDES_SORTED).build(),
+                    Signal::builder(super::signals::CHANGED)
+                        .param_types([
+                            HistoryActionKind::static_type(),
+                            Option::<String>::static_type(),
+                            bool::static_type(),
+                        ])
+                        .build(),
+                    Signal::builder(super::signals::TIMER_FINISHED)
+                        .param_types([TimerFinish::static_type()])
+                        .build(),
                 ]
             })
         }
@@ -132,6 +160,16 @@ mod imp {
                 .emit_item_clicked(self.current_item.borrow().as_ref());
         }
 
+        fn set_resolution_(&self, resolution: Resolution) {
+            self.resolution.set(resolution);
+            let end_slide = self.end_presentation_slide.borrow().clone();
+            for slide in self.slides.borrow().iter().chain([&end_slide]) {
+                if let Some(canvas) = slide.canvas() {
+                    canvas.set_resolution(resolution);
+                }
+            }
+        }
+
         fn get_preview_slide_(&self) -> Option<Slide> {
             let ps = self.preview_slide.borrow().clone();
 
@@ -169,17 +207,34 @@ mod imp {
                 canvas.unselect_all(None);
             }
 
+            let options = val.transition_options();
+            let duration =
+                options.duration_or(ApplicationSettings::get_instance().transition_duration());
+            // custom effects are drawn by the transition layer from a snapshot
+            // of the outgoing slide, the stack just switches
+            let layer = self
+                .screen
+                .borrow()
+                .as_ref()
+                .map(|(_, layer, _)| layer.clone());
+            let from = match (obj.animation(), &layer, options.effect) {
+                (true, Some(_), Some(_)) => obj.slideshow().snap(),
+                _ => None,
+            };
+
             if obj.animation() {
                 let mut t = transition_to_int(val.transition());
 
                 if t == 0 {
                     t = ApplicationSettings::get_instance().transition();
                 }
+                if from.is_some() {
+                    t = 0;
+                }
 
                 obj.slideshow().set_transition_type(int_to_transition(t));
-                let settings = ApplicationSettings::get_instance();
                 obj.slideshow()
-                    .set_transition_duration((settings.transition_duration() * 1000.0) as u32);
+                    .set_transition_duration((duration * 1000.0) as u32);
             }
 
             if self.slides.borrow().contains(&val) {
@@ -199,6 +254,10 @@ mod imp {
             if let Some(canvas) = val.canvas() {
                 obj.slideshow().set_visible_child(&canvas);
             }
+            if let (Some(layer), Some(from)) = (layer, from) {
+                let to = gtk::WidgetPaintable::new(Some(&obj.slideshow()));
+                layer.play(&from, &to, &options, duration);
+            }
             obj.emit_current_slide_changed(&val);
         }
     }
@@ -245,6 +304,50 @@ impl Default for SlideManager {
 }
 
 impl SlideManager {
+    /// The slideshow with a layer over it for custom transitions, for live
+    /// outputs. Use it in place of [Self::slideshow], which becomes its child.
+    pub fn screen(&self) -> gtk::Overlay {
+        let imp = self.imp();
+        if let Some((screen, _, _)) = imp.screen.borrow().as_ref() {
+            return screen.clone();
+        }
+
+        let screen = gtk::Overlay::new();
+        let layer = TransitionLayer::default();
+        let cover = ScreenCover::default();
+        screen.set_child(Some(&self.slideshow()));
+        screen.add_overlay(&layer);
+        screen.add_overlay(&cover);
+        imp.screen.replace(Some((screen.clone(), layer, cover)));
+        screen
+    }
+
+    /// Shows black or the logo over [Self::screen] instead of the slides
+    pub fn set_screen_mode(&self, mode: ScreenMode) {
+        self.screen();
+        if let Some((_, _, cover)) = self.imp().screen.borrow().as_ref() {
+            cover.set_mode(mode);
+        }
+    }
+
+    pub fn screen_mode(&self) -> ScreenMode {
+        self.imp()
+            .screen
+            .borrow()
+            .as_ref()
+            .map(|(_, _, cover)| cover.mode())
+            .unwrap_or_default()
+    }
+
+    /// Keeps `frame` at the shape of the slides, letterboxing them
+    /// in whatever space the frame is given
+    pub fn bind_aspect_frame(&self, frame: &gtk::AspectFrame) {
+        self.bind_property("resolution", frame, "ratio")
+            .transform_to(|_, resolution: Resolution| Some(resolution.ratio()))
+            .sync_create()
+            .build();
+    }
+
     pub fn emit_item_clicked(&self, item: Option<&CanvasItem>) {
         self.emit_by_name::<()>(signals::ITEM_CLICKED, &[&item]);
     }
@@ -265,10 +368,57 @@ impl SlideManager {
     //     self.emit_by_name::<()>(signals::ASPECT_RATIO_CHANGED, &[item]);
     // }
 
+    pub fn emit_timer_finished(&self, action: TimerFinish) {
+        self.emit_by_name::<()>(signals::TIMER_FINISHED, &[&action]);
+    }
+
     pub fn emit_slides_sorted(&self) {
         self.emit_by_name::<()>(signals::SLIDES_SORTED, &[]);
     }
 
+    /// Reports an undoable edit, `target` identifies the edited slide or item
+    /// and `force` keeps the edit from being merged with the previous one
+    pub fn emit_changed(&self, kind: HistoryActionKind, target: Option<String>, force: bool) {
+        self.emit_by_name::<()>(signals::CHANGED, &[&kind, &target, &force]);
+    }
+
+    /// Reports a style edit of the current item, or of the current slide
+    /// when no item is selected
+    pub fn emit_style_changed(&self) {
+        let target = self
+            .current_slide()
+            .and_then(|s| self.history_target(&s, self.current_item().as_ref()));
+        self.emit_changed(HistoryActionKind::StyleChanged, target, false);
+    }
+
+    pub fn connect_changed<F: Fn(&Self, HistoryActionKind, Option<String>, bool) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_closure(
+            signals::CHANGED,
+            false,
+            glib::closure_local!(move |obj: &Self,
+                                       kind: HistoryActionKind,
+                                       target: Option<String>,
+                                       force: bool| {
+                f(obj, kind, target, force)
+            }),
+        )
+    }
+
+    /// A countdown on the shown slide reached zero and asks for `action`
+    pub fn connect_timer_finished<F: Fn(&Self, TimerFinish) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_closure(
+            signals::TIMER_FINISHED,
+            false,
+            glib::closure_local!(move |obj: &Self, action: TimerFinish| f(obj, action)),
+        )
+    }
+
     pub fn connect_new_slide_created<F: Fn(&Self, &Slide) -> () + 'static>(&self, f: F) {
         self.connect_closure(
             signals::NEW_SLIDE_CREATED,
@@ -383,6 +533,7 @@ impl SlideManager {
 
         let mut data = SlideManagerData::new(current_slide_index, preview_slide_index, data);
         data.title = self.title();
+        data.resolution = self.resolution();
         data
     }
 
@@ -459,6 +610,7 @@ impl SlideManager {
         };
 
         self.set_title(data.title);
+        self.set_resolution(data.resolution);
 
         for slide_object in &data.slides {
             let s = self.new_slide(Some(slide_object.clone()), false);
@@ -490,6 +642,7 @@ impl SlideManager {
         let canvas = slide
             .canvas()
             .expect("Error creating new slide: Could not get slide canvas");
+        canvas.set_resolution(self.resolution());
 
         canvas.connect_item_clicked(glib::clone!(
             #[weak(rename_to=sm)]
@@ -509,6 +662,12 @@ impl SlideManager {
             move || sm.previous_slide()
         ));
 
+        canvas.connect_timer_finished(glib::clone!(
+            #[weak(rename_to=sm)]
+            self,
+            move |action| sm.emit_timer_finished(action)
+        ));
+
         canvas.connect_ratio_changed(glib::clone!(
             #[weak(rename_to=sm)]
             self,
@@ -539,16 +698,16 @@ impl SlideManager {
             }
         ));
 
-        // if undoable_action {
-        //     slide.set_visible(false);
-        //     let action = TypedHistoryAction::slide_changed(&slide, "visible");
-        //     if let Some(window) = self.window() {
-        //         window
-        //             .history_manager()
-        //             .add_undoable_action(action.into(), Some(true));
-        //     }
-        //     slide.set_visible(true);
-        // }
+        canvas.connect_item_changed(glib::clone!(
+            #[weak(rename_to=sm)]
+            self,
+            #[weak]
+            slide,
+            move |item, kind, force| {
+                let target = sm.history_target(&slide, Some(item));
+                sm.emit_changed(kind, target, force);
+            }
+        ));
 
         if let Some(current_slide) = self.current_slide()
             && let Some(index) = self.slides().iter().position(|v| v.eq(&current_slide))
@@ -563,6 +722,8 @@ impl SlideManager {
 
         if undoable_action {
             self.set_current_slide(Some(slide.clone()));
+            let target = self.history_target(&slide, None);
+            self.emit_changed(HistoryActionKind::SlideAdded, target, true);
         }
 
         slide.connect_visible_notify(
@@ -577,6 +738,9 @@ impl SlideManager {
                     if visible {
                         sm.set_current_slide(Some(slide.clone()));
                     } else {
+                        let target = sm.history_target(slide, None);
+                        sm.emit_changed(HistoryActionKind::SlideDeleted, target, true);
+
                         let mut next_slide = sm.get_next_slide(&slide);
 
                         if next_slide.is_none() {
@@ -684,6 +848,8 @@ impl SlideManager {
             self.set_slides(next_slide, Some(index));
 
             self.emit_slides_sorted();
+            let target = self.history_target(slide, None);
+            self.emit_changed(HistoryActionKind::SlideReordered, target, true);
         }
     }
 
@@ -696,6 +862,8 @@ impl SlideManager {
             self.set_slides(prev_slide, Some(index));
 
             self.emit_slides_sorted();
+            let target = self.history_target(slide, None);
+            self.emit_changed(HistoryActionKind::SlideReordered, target, true);
         }
     }
 
@@ -703,6 +871,21 @@ impl SlideManager {
         self.slides().iter().position(|v| v.eq(current))
     }
 
+    /// Key of a slide, or an item on it, used to merge continuous edits
+    fn history_target(&self, slide: &Slide, item: Option<&CanvasItem>) -> Option<String> {
+        let slide_pos = self.get_slide_pos(slide)?;
+        let Some(item) = item else {
+            return Some(slide_pos.to_string());
+        };
+
+        let item_pos = slide
+            .canvas()?
+            .widget()
+            .get_children::<CanvasItem>()
+            .position(|v| v == *item)?;
+        Some(format!("{slide_pos}:{item_pos}"))
+    }
+
     pub fn request_new_item(&self, item_type: CanvasItemType) -> Option<CanvasItem> {
         let mut item: Option<CanvasItem> = None;
 
@@ -721,8 +904,10 @@ impl SlideManager {
             //     if let Some(file) = file {
             //         item = Some(ImageItem::from_file(Some(&canvas), &file).upcast::<CanvasItem>());
             //     }
-            // } else if item_type == CanvasItemType::SHAPE {
-            //     item = Some(ColorItem::new(Some(&canvas), None).upcast::<CanvasItem>());
+        } else if item_type == CanvasItemType::SHAPE {
+            return self.request_new_shape(ShapeKind::default());
+        } else if item_type == CanvasItemType::TIMER {
+            return self.request_new_timer(TimerItemData::default());
         }
 
         if let Some(item) = item.clone()
@@ -735,6 +920,47 @@ impl SlideManager {
         return item;
     }
 
+    /// Adds a new `shape` to the current slide
+    pub fn request_new_shape(&self, shape: ShapeKind) -> Option<CanvasItem> {
+        let Some(canvas) = self.current_slide().and_then(|v| v.canvas()) else {
+            eprintln!("Error requesting new shape: could not get current slide canvas");
+            return None;
+        };
+
+        let item = ShapeItem::new(Some(&canvas), None);
+        item.set_shape(shape);
+
+        let item = item.upcast::<CanvasItem>();
+        canvas.add_item(item.clone(), true);
+        Some(item)
+    }
+
+    /// Adds a timer showing `data` to the current slide
+    pub fn request_new_timer(&self, data: TimerItemData) -> Option<CanvasItem> {
+        let Some(canvas) = self.current_slide().and_then(|v| v.canvas()) else {
+            eprintln!("Error requesting new timer: could not get current slide canvas");
+            return None;
+        };
+
+        let item = TimerItem::new(Some(&canvas), None);
+        item.set_timer(data);
+
+        let item = item.upcast::<CanvasItem>();
+        canvas.add_item(item.clone(), true);
+        Some(item)
+    }
+
+    pub fn request_new_video(&self, file: &gio::File) -> Option<CanvasItem> {
+        let Some(canvas) = self.current_slide().and_then(|v| v.canvas()) else {
+            eprintln!("Error requesting new video: could not get current slide canvas");
+            return None;
+        };
+
+        let item = VideoItem::from_file(Some(&canvas), file).upcast::<CanvasItem>();
+        canvas.add_item(item.clone(), true);
+        Some(item)
+    }
+
     pub fn jump_to_checkpoint(&self) {
         // if let Some(win) = self.window()
         //     && !win.is_presenting()
@@ -0,0 +1,232 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::dto::SongData;
+
+/// Songs sharing at least this share of lyric words are considered duplicates
+pub const LYRIC_SIMILARITY_THRESHOLD: f64 = 0.8;
+/// Lower bound used when the titles already look alike
+const SIMILAR_TITLE_LYRIC_THRESHOLD: f64 = 0.5;
+
+/// Lower cased title without bracketed notes, punctuation
+/// and repeated whitespace
+///
+/// `"Amazing Grace (My Chains Are Gone)"` becomes `"amazing grace"`
+pub fn normalize_title(title: &str) -> String {
+    let mut depth = 0;
+    let mut text = String::with_capacity(title.len());
+
+    for ch in title.chars() {
+        match ch {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = (depth - 1).max(0),
+            _ if depth > 0 => (),
+            ch if ch.is_alphanumeric() => text.extend(ch.to_lowercase()),
+            _ => text.push(' '),
+        }
+    }
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Lower cased words of the lyrics with pango markup removed
+fn lyric_words(text: &str) -> HashSet<String> {
+    let mut plain = String::with_capacity(text.len());
+    let mut in_tag = false;
+    let mut in_entity = false;
+
+    for ch in text.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            '&' if !in_tag => in_entity = true,
+            ';' if in_entity => in_entity = false,
+            _ if in_tag || in_entity => (),
+            ch if ch.is_alphanumeric() => plain.extend(ch.to_lowercase()),
+            _ => plain.push(' '),
+        }
+    }
+
+    plain.split_whitespace().map(String::from).collect()
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let shared = a.intersection(b).count();
+    let total = a.union(b).count();
+    shared as f64 / total as f64
+}
+
+/// Whether the words of one normalized title start the other,
+/// so `"amazing grace"` extends `"amazing"` but not `"a"`
+fn title_extends(a: &str, b: &str) -> bool {
+    let a = a.split_whitespace().collect::<Vec<_>>();
+    let b = b.split_whitespace().collect::<Vec<_>>();
+    a.starts_with(&b) || b.starts_with(&a)
+}
+
+fn song_lyrics(song: &SongData) -> String {
+    song.verses
+        .iter()
+        .map(|v| v.text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Groups song ids that are likely the same song.
+///
+/// Two songs are duplicates when their normalized titles match,
+/// when their lyrics are nearly the same, or when one title extends
+/// the other and the lyrics are similar.
+/// Only groups with more than one song are returned.
+pub fn find_duplicates(songs: &[SongData]) -> Vec<Vec<u32>> {
+    let titles = songs
+        .iter()
+        .map(|s| normalize_title(&s.title))
+        .collect::<Vec<_>>();
+    let words = songs
+        .iter()
+        .map(|s| lyric_words(&song_lyrics(s)))
+        .collect::<Vec<_>>();
+
+    // union-find over song positions
+    let mut parent = (0..songs.len()).collect::<Vec<_>>();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    for i in 0..songs.len() {
+        for j in (i + 1)..songs.len() {
+            let same_title = !titles[i].is_empty() && titles[i] == titles[j];
+            let similar_title = !titles[i].is_empty()
+                && !titles[j].is_empty()
+                && title_extends(&titles[i], &titles[j]);
+
+            let duplicate = same_title || {
+                let similarity = jaccard(&words[i], &words[j]);
+                similarity >= LYRIC_SIMILARITY_THRESHOLD
+                    || (similar_title && similarity >= SIMILAR_TITLE_LYRIC_THRESHOLD)
+            };
+
+            if duplicate {
+                let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+                parent[b] = a;
+            }
+        }
+    }
+
+    let mut groups: HashMap<usize, Vec<u32>> = HashMap::new();
+    for (i, song) in songs.iter().enumerate() {
+        let r = root(&mut parent, i);
+        groups.entry(r).or_default().push(song.song_id);
+    }
+
+    let mut groups = groups
+        .into_values()
+        .filter(|g| g.len() > 1)
+        .collect::<Vec<_>>();
+    groups.sort();
+    groups
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dto::SongVerse;
+
+    /// Share of words the two lyrics have in common, between `0.0` and `1.0`
+    fn lyric_similarity(a: &str, b: &str) -> f64 {
+        jaccard(&lyric_words(a), &lyric_words(b))
+    }
+
+    fn song(id: u32, title: &str, verses: &[&str]) -> SongData {
+        let verses = verses
+            .iter()
+            .map(|v| SongVerse::new(v.to_string(), None, None))
+            .collect();
+        SongData::new(id, title.to_string(), verses)
+    }
+
+    #[test]
+    fn test_normalize_title() {
+        assert_eq!(normalize_title("Amazing Grace"), "amazing grace");
+        assert_eq!(
+            normalize_title("  Amazing   Grace! (My Chains Are Gone) "),
+            "amazing grace"
+        );
+        assert_eq!(normalize_title("It Is Well [Hymn 377]"), "it is well");
+        assert_eq!(normalize_title("10,000 Reasons"), "10 000 reasons");
+    }
+
+    #[test]
+    fn test_lyric_similarity() {
+        assert_eq!(lyric_similarity("a b c", "a b c"), 1.0);
+        assert_eq!(lyric_similarity("a b", "c d"), 0.0);
+        assert_eq!(lyric_similarity("", "a"), 0.0);
+        assert_eq!(lyric_similarity("<b>Holy</b> holy", "holy &amp; HOLY"), 1.0);
+    }
+
+    #[test]
+    fn test_find_duplicates_by_title() {
+        let songs = vec![
+            song(1, "Amazing Grace", &["Amazing grace how sweet the sound"]),
+            song(2, "amazing grace.", &["Different words entirely here"]),
+            song(3, "How Great Thou Art", &["O Lord my God"]),
+        ];
+        assert_eq!(find_duplicates(&songs), vec![vec![1, 2]]);
+    }
+
+    #[test]
+    fn test_find_duplicates_by_lyrics() {
+        let lyrics = "Great is thy faithfulness O God my Father";
+        let songs = vec![
+            song(1, "Great Is Thy Faithfulness", &[lyrics]),
+            song(2, "Faithfulness", &[lyrics, "morning by morning"]),
+            song(3, "Unrelated", &["nothing in common"]),
+        ];
+        assert_eq!(find_duplicates(&songs), vec![vec![1, 2]]);
+    }
+
+    #[test]
+    fn test_find_duplicates_similar_title() {
+        let songs = vec![
+            song(1, "Amazing Grace", &["amazing grace how sweet the sound"]),
+            song(
+                2,
+                "Amazing Grace My Chains Are Gone",
+                &["amazing grace how sweet the sound my chains are gone"],
+            ),
+        ];
+        assert_eq!(find_duplicates(&songs), vec![vec![1, 2]]);
+    }
+
+    #[test]
+    fn test_find_duplicates_title_prefix_is_whole_words() {
+        let lyrics = "amazing grace how sweet the sound";
+        let songs = vec![
+            song(1, "A", &[lyrics, "that saved a wretch like me"]),
+            song(
+                2,
+                "Amazing Grace",
+                &[lyrics, "i once was lost but now am found"],
+            ),
+        ];
+        assert!(find_duplicates(&songs).is_empty());
+
+        assert!(title_extends("amazing", "amazing grace"));
+        assert!(!title_extends("a", "amazing grace"));
+        assert!(!title_extends("amazing g", "amazing grace"));
+    }
+
+    #[test]
+    fn test_find_duplicates_none() {
+        let songs = vec![song(1, "A", &["one two"]), song(2, "B", &["three four"])];
+        assert!(find_duplicates(&songs).is_empty());
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+
+use gtk::glib;
+
+use crate::dto::SongVerse;
+
+/// How the second language of a bilingual song is projected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecondaryLanguageStyle {
+    /// language code as used by OpenLyrics, e.g. `en` or `pt-BR`
+    pub lang: String,
+    pub font: String,
+    /// size relative to the verse text
+    pub size_percent: u32,
+    pub italic: bool,
+}
+
+/// Translation of a verse for `lang`.
+///
+/// An exact match wins, otherwise the primary subtags are compared
+/// so `pt` finds `pt-BR` and the other way around
+pub fn find_translation<'a>(
+    translations: &'a BTreeMap<String, String>,
+    lang: &str,
+) -> Option<&'a str> {
+    let primary = |l: &str| {
+        l.split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase()
+    };
+
+    translations
+        .iter()
+        .find(|(l, _)| l.eq_ignore_ascii_case(lang))
+        .or_else(|| {
+            translations
+                .iter()
+                .find(|(l, _)| primary(l) == primary(lang))
+        })
+        .map(|(_, text)| text.as_str())
+}
+
+/// Verse markup with the translation placed below it in its own style
+pub fn bilingual_markup(text: &str, translation: &str, style: &SecondaryLanguageStyle) -> String {
+    let font = glib::markup_escape_text(&style.font);
+    let font_style = match style.italic {
+        true => "italic",
+        false => "normal",
+    };
+
+    format!(
+        r#"{text}
+<span font_family="{font}" size="{}%" style="{font_style}">{translation}</span>"#,
+        style.size_percent
+    )
+}
+
+/// Puts the translations of the `original` verses back on the edited
+/// `verses`, which only hold the verse text. A verse gets the translations
+/// of the original verse with its tag, else of the one with its text, so
+/// verses that were added, removed or moved keep their own
+pub fn restore_translations(verses: &mut [SongVerse], original: &[SongVerse]) {
+    let mut unused = original.iter().collect::<Vec<_>>();
+
+    for verse in verses.iter_mut() {
+        let found = unused
+            .iter()
+            .position(|o| verse.tag.is_some() && o.tag == verse.tag)
+            .or_else(|| unused.iter().position(|o| o.text == verse.text));
+
+        if let Some(i) = found {
+            verse.translations = unused.remove(i).translations.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn translations() -> BTreeMap<String, String> {
+        BTreeMap::from([
+            ("de".to_string(), "Grosser Gott".to_string()),
+            ("pt-BR".to_string(), "Grande Deus".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_find_translation() {
+        let t = translations();
+        assert_eq!(find_translation(&t, "de"), Some("Grosser Gott"));
+        assert_eq!(find_translation(&t, "DE"), Some("Grosser Gott"));
+        assert_eq!(find_translation(&t, "pt"), Some("Grande Deus"));
+        assert_eq!(find_translation(&t, "de-AT"), Some("Grosser Gott"));
+        assert_eq!(find_translation(&t, "fr"), None);
+    }
+
+    #[test]
+    fn test_bilingual_markup() {
+        let style = SecondaryLanguageStyle {
+            lang: "de".into(),
+            font: "Noto & Sans".into(),
+            size_percent: 70,
+            italic: true,
+        };
+
+        assert_eq!(
+            bilingual_markup("Holy God", "Grosser Gott", &style),
+            "Holy God\n<span font_family=\"Noto &amp; Sans\" size=\"70%\" style=\"italic\">Grosser Gott</span>"
+        );
+    }
+
+    fn verse(text: &str, tag: Option<&str>, translation: Option<&str>) -> SongVerse {
+        let mut verse = SongVerse::new(text.to_string(), tag.map(String::from), None);
+        if let Some(t) = translation {
+            verse.translations.insert("de".to_string(), t.to_string());
+        }
+        verse
+    }
+
+    fn german(verses: &[SongVerse]) -> Vec<Option<&str>> {
+        verses
+            .iter()
+            .map(|v| v.translations.get("de").map(String::as_str))
+            .collect()
+    }
+
+    #[test]
+    fn test_restore_translations() {
+        let original = [
+            verse("Holy God", Some("v1"), Some("Grosser Gott")),
+            verse("Lord of all", Some("c"), Some("Herr der Welt")),
+            verse("We praise thy name", None, Some("Wir loben dich")),
+        ];
+
+        // moved and edited verses follow their tag
+        let mut edited = [
+            verse("Lord of all, we bow", Some("c"), None),
+            verse("Holy God", Some("v1"), None),
+        ];
+        restore_translations(&mut edited, &original);
+        assert_eq!(
+            german(&edited),
+            [Some("Herr der Welt"), Some("Grosser Gott")]
+        );
+
+        // untagged verses follow their text, a new verse gets nothing
+        let mut edited = [
+            verse("A new verse", None, None),
+            verse("We praise thy name", None, None),
+            verse("Holy God", None, None),
+        ];
+        restore_translations(&mut edited, &original);
+        assert_eq!(
+            german(&edited),
+            [None, Some("Wir loben dich"), Some("Grosser Gott")]
+        );
+
+        // a deleted verse takes its translation with it
+        let mut edited = [
+            verse("Holy God", Some("v1"), None),
+            verse("We praise thy name", None, None),
+        ];
+        restore_translations(&mut edited, &original);
+        assert_eq!(
+            german(&edited),
+            [Some("Grosser Gott"), Some("Wir loben dich")]
+        );
+
+        // a repeated verse gets the translation once per original
+        let mut edited = [
+            verse("Holy God", Some("v1"), None),
+            verse("Holy God", Some("v1"), None),
+        ];
+        restore_translations(&mut edited, &original[..1]);
+        assert_eq!(german(&edited), [Some("Grosser Gott"), None]);
+    }
+}
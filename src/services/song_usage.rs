@@ -0,0 +1,201 @@
+use std::collections::HashSet;
+
+use crate::dto::SongUsageReport;
+
+/// Column layout of the CCLI usage report
+const CSV_HEADER: [&str; 5] = [
+    "CCLI Song Number",
+    "Song Title",
+    "Author",
+    "Copyright",
+    "Times Used",
+];
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum UsageChange {
+    /// a song went live and should be logged
+    Started {
+        song_id: u32,
+        slide_count: u32,
+    },
+    /// more slides of the logged song were shown
+    SlidesShown {
+        usage_id: u32,
+        slide_count: u32,
+    },
+    None,
+}
+
+#[derive(Debug)]
+struct LiveSong {
+    song_id: u32,
+    usage_id: Option<u32>,
+    slides: HashSet<u32>,
+}
+
+/// Follows the song on the live output.
+///
+/// Sending different slides of the song that is already live
+/// counts as one use, only the number of slides shown grows.
+#[derive(Debug, Default)]
+pub struct SongUsageTracker {
+    live: Option<LiveSong>,
+}
+
+impl SongUsageTracker {
+    /// An item went live showing `slide`, `song_id` is `None` for non-song items
+    pub fn item_live(&mut self, song_id: Option<u32>, slide: u32) -> UsageChange {
+        let Some(song_id) = song_id else {
+            self.live = None;
+            return UsageChange::None;
+        };
+
+        if let Some(live) = &self.live
+            && live.song_id == song_id
+        {
+            return self.slide_shown(slide);
+        }
+
+        self.live = Some(LiveSong {
+            song_id,
+            usage_id: None,
+            slides: HashSet::from([slide]),
+        });
+
+        UsageChange::Started {
+            song_id,
+            slide_count: 1,
+        }
+    }
+
+    /// The live output moved to `slide` of the current item
+    pub fn slide_shown(&mut self, slide: u32) -> UsageChange {
+        let Some(live) = &mut self.live else {
+            return UsageChange::None;
+        };
+
+        if !live.slides.insert(slide) {
+            return UsageChange::None;
+        }
+
+        match live.usage_id {
+            Some(usage_id) => UsageChange::SlidesShown {
+                usage_id,
+                slide_count: live.slides.len() as u32,
+            },
+            None => UsageChange::None,
+        }
+    }
+
+    /// Id of the logged usage of the live song
+    pub fn set_usage_id(&mut self, usage_id: Option<u32>) {
+        if let Some(live) = &mut self.live {
+            live.usage_id = usage_id;
+        }
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Usage report as CSV in the column layout expected by CCLI reporting
+pub fn usage_report_csv(entries: &[SongUsageReport]) -> String {
+    let mut csv = CSV_HEADER.join(",");
+    csv.push_str("\r\n");
+
+    for entry in entries {
+        let row = [
+            entry.details.ccli_number.clone().unwrap_or_default(),
+            entry.title.clone(),
+            entry.details.author.clone().unwrap_or_default(),
+            entry.details.copyright.clone().unwrap_or_default(),
+            entry.times_used.to_string(),
+        ];
+
+        let row = row.iter().map(|v| csv_field(v)).collect::<Vec<_>>();
+        csv.push_str(&row.join(","));
+        csv.push_str("\r\n");
+    }
+
+    csv
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dto::SongDetails;
+
+    #[test]
+    fn test_tracker_logs_song_once() {
+        let mut tracker = SongUsageTracker::default();
+
+        assert_eq!(
+            tracker.item_live(Some(4), 0),
+            UsageChange::Started {
+                song_id: 4,
+                slide_count: 1
+            }
+        );
+        tracker.set_usage_id(Some(10));
+
+        assert_eq!(
+            tracker.slide_shown(1),
+            UsageChange::SlidesShown {
+                usage_id: 10,
+                slide_count: 2
+            }
+        );
+        assert_eq!(tracker.slide_shown(0), UsageChange::None);
+        assert_eq!(
+            tracker.item_live(Some(4), 2),
+            UsageChange::SlidesShown {
+                usage_id: 10,
+                slide_count: 3
+            }
+        );
+    }
+
+    #[test]
+    fn test_tracker_new_song() {
+        let mut tracker = SongUsageTracker::default();
+        tracker.item_live(Some(1), 0);
+        tracker.set_usage_id(Some(1));
+
+        assert_eq!(tracker.item_live(None, 0), UsageChange::None);
+        assert_eq!(tracker.slide_shown(1), UsageChange::None);
+        assert_eq!(
+            tracker.item_live(Some(1), 0),
+            UsageChange::Started {
+                song_id: 1,
+                slide_count: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_usage_report_csv() {
+        let entries = vec![SongUsageReport {
+            song_id: 1,
+            title: "Holy, Holy, Holy".into(),
+            details: SongDetails {
+                ccli_number: Some("1156".into()),
+                author: Some("Reginald \"Reg\" Heber".into()),
+                copyright: None,
+            },
+            times_used: 3,
+            slides_shown: 12,
+            last_used: 0,
+        }];
+
+        assert_eq!(
+            usage_report_csv(&entries),
+            "CCLI Song Number,Song Title,Author,Copyright,Times Used\r\n\
+             1156,\"Holy, Holy, Holy\",\"Reginald \"\"Reg\"\" Heber\",,3\r\n"
+        );
+    }
+}
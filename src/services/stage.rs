@@ -0,0 +1,89 @@
+/// Height of the stage display the text size is based on, in text lines
+const LINES_PER_SCREEN: f64 = 14.0;
+const MIN_TEXT_SIZE: f64 = 8.0;
+
+/// How the stage display arranges the current and next slides
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StageLayout {
+    /// the current slide over the next one
+    #[default]
+    CurrentAndNext,
+    /// the current slide next to the next one
+    SideBySide,
+    CurrentOnly,
+}
+
+impl StageLayout {
+    /// Layout for the `stage-layout` setting
+    pub fn from_index(index: u32) -> Self {
+        match index {
+            1 => Self::SideBySide,
+            2 => Self::CurrentOnly,
+            _ => Self::CurrentAndNext,
+        }
+    }
+
+    pub fn shows_next(self) -> bool {
+        self != Self::CurrentOnly
+    }
+}
+
+/// `m:ss` or, from an hour on, `h:mm:ss`
+pub fn format_elapsed(seconds: i64) -> String {
+    let seconds = seconds.max(0);
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    match hours {
+        0 => format!("{minutes}:{seconds:02}"),
+        _ => format!("{hours}:{minutes:02}:{seconds:02}"),
+    }
+}
+
+/// Size in pixels of the current slide text on a stage display `height`
+/// pixels high, the next slide and notes use a fraction of it
+pub fn text_size(height: i32, scale: f64) -> f64 {
+    (height as f64 / LINES_PER_SCREEN * scale).max(MIN_TEXT_SIZE)
+}
+
+/// Index of the slide after `current` in an item of `len` slides
+pub fn next_index(current: Option<usize>, len: usize) -> Option<usize> {
+    current.map(|i| i + 1).filter(|i| *i < len)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(0), "0:00");
+        assert_eq!(format_elapsed(65), "1:05");
+        assert_eq!(format_elapsed(59 * 60 + 59), "59:59");
+        assert_eq!(format_elapsed(3600), "1:00:00");
+        assert_eq!(format_elapsed(2 * 3600 + 5 * 60 + 9), "2:05:09");
+        assert_eq!(format_elapsed(-10), "0:00");
+    }
+
+    #[test]
+    fn test_text_size() {
+        assert_eq!(text_size(1400, 1.0), 100.0);
+        assert_eq!(text_size(1400, 1.5), 150.0);
+        assert_eq!(text_size(0, 1.0), MIN_TEXT_SIZE);
+    }
+
+    #[test]
+    fn test_next_index() {
+        assert_eq!(next_index(Some(0), 3), Some(1));
+        assert_eq!(next_index(Some(2), 3), None);
+        assert_eq!(next_index(None, 3), None);
+    }
+
+    #[test]
+    fn test_layout_from_index() {
+        assert_eq!(StageLayout::from_index(0), StageLayout::CurrentAndNext);
+        assert_eq!(StageLayout::from_index(1), StageLayout::SideBySide);
+        assert_eq!(StageLayout::from_index(2), StageLayout::CurrentOnly);
+        assert_eq!(StageLayout::from_index(9), StageLayout::CurrentAndNext);
+        assert!(!StageLayout::CurrentOnly.shows_next());
+    }
+}
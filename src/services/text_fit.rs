@@ -0,0 +1,197 @@
+use gtk::glib;
+use serde::{Deserialize, Serialize};
+
+/// Steps of the binary search for the largest size that fits
+const FIT_STEPS: usize = 20;
+
+/// How a text item handles text that is too big for its box
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, glib::Enum)]
+#[enum_type(name = "OwTextFit")]
+pub enum TextFit {
+    /// shrink the text until it fits, down to the minimum size
+    #[default]
+    #[serde(rename = "shrink")]
+    Shrink,
+    /// keep the font size, letting the text overflow
+    #[serde(rename = "fixed")]
+    Fixed,
+    /// keep the font size and make the box taller
+    #[serde(rename = "grow")]
+    Grow,
+    /// shrink down to the minimum size, then split the slide
+    #[serde(rename = "split")]
+    Split,
+}
+
+/// Result of fitting text to its box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextFitLayout {
+    pub size: f64,
+    /// height the box needs, only taller than the box for [TextFit::Grow]
+    pub height: f64,
+    /// the text does not fit in the box at `size`
+    pub overflow: bool,
+    /// the text was shrunk to the minimum size
+    pub at_minimum: bool,
+}
+
+impl TextFitLayout {
+    /// Whether the text is shown too small or cut off
+    pub fn needs_warning(&self) -> bool {
+        self.overflow || self.at_minimum
+    }
+}
+
+/// Fits text to a `width` × `height` box.
+///
+/// `measure` lays the text out at a size and returns its `(width, height)`, in the
+/// same units as the box. `size` and `min_size` are the largest and smallest sizes
+/// the text may take.
+pub fn fit_text(
+    mode: TextFit,
+    size: f64,
+    min_size: f64,
+    (width, height): (f64, f64),
+    mut measure: impl FnMut(f64) -> (f64, f64),
+) -> TextFitLayout {
+    let mut fits = |s: f64| {
+        let (w, h) = measure(s);
+        w <= width && h <= height
+    };
+    let min_size = min_size.min(size);
+    let layout = TextFitLayout {
+        size,
+        height,
+        overflow: false,
+        at_minimum: false,
+    };
+
+    if width <= 0.0 || height <= 0.0 {
+        return layout;
+    }
+
+    match mode {
+        TextFit::Fixed => TextFitLayout {
+            overflow: !fits(size),
+            ..layout
+        },
+        TextFit::Grow => TextFitLayout {
+            height: measure(size).1.max(height),
+            ..layout
+        },
+        TextFit::Shrink | TextFit::Split => {
+            if fits(size) {
+                return layout;
+            }
+            if !fits(min_size) {
+                return TextFitLayout {
+                    size: min_size,
+                    overflow: true,
+                    at_minimum: true,
+                    ..layout
+                };
+            }
+
+            let (mut lo, mut hi) = (min_size, size);
+            for _ in 0..FIT_STEPS {
+                let mid = (lo + hi) / 2.0;
+                match fits(mid) {
+                    true => lo = mid,
+                    false => hi = mid,
+                }
+            }
+
+            TextFitLayout {
+                size: lo,
+                at_minimum: lo <= min_size,
+                ..layout
+            }
+        }
+    }
+}
+
+/// Number of the `lines` of a slide to keep when splitting it, so the rest
+/// move to a new slide. `None` when all of them fit.
+///
+/// `fits` tells whether the first `n` lines fit. At least one line is kept.
+pub fn split_point(lines: usize, mut fits: impl FnMut(usize) -> bool) -> Option<usize> {
+    if lines <= 1 || fits(lines) {
+        return None;
+    }
+
+    let mut keep = 1;
+    while keep + 1 < lines && fits(keep + 1) {
+        keep += 1;
+    }
+    Some(keep)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// text of `lines` lines as wide as 10 units per size and 2 units high per size
+    fn measure(lines: usize) -> impl FnMut(f64) -> (f64, f64) {
+        move |size| (size * 10.0, size * 2.0 * lines as f64)
+    }
+
+    #[test]
+    fn test_shrink_fits_box() {
+        let layout = fit_text(TextFit::Shrink, 20.0, 4.0, (1000.0, 100.0), measure(2));
+        assert_eq!(layout.size, 20.0);
+        assert!(!layout.needs_warning());
+
+        // 4 lines need 8 units per size, 100 high fits 12.5
+        let layout = fit_text(TextFit::Shrink, 20.0, 4.0, (1000.0, 100.0), measure(4));
+        assert!(layout.size <= 12.5 && layout.size > 12.4, "{}", layout.size);
+        assert!(!layout.overflow);
+        assert!(!layout.at_minimum);
+    }
+
+    #[test]
+    fn test_shrink_stops_at_minimum() {
+        // 20 lines only fit at 2.5
+        let layout = fit_text(TextFit::Shrink, 20.0, 4.0, (1000.0, 100.0), measure(20));
+        assert_eq!(layout.size, 4.0);
+        assert!(layout.overflow);
+        assert!(layout.at_minimum);
+        assert!(layout.needs_warning());
+
+        let split = fit_text(TextFit::Split, 20.0, 4.0, (1000.0, 100.0), measure(20));
+        assert_eq!(split, layout);
+    }
+
+    #[test]
+    fn test_fixed_and_grow() {
+        let layout = fit_text(TextFit::Fixed, 20.0, 4.0, (1000.0, 100.0), measure(4));
+        assert_eq!(layout.size, 20.0);
+        assert!(layout.overflow);
+        assert!(!layout.at_minimum);
+
+        let layout = fit_text(TextFit::Grow, 20.0, 4.0, (1000.0, 100.0), measure(4));
+        assert_eq!(layout.size, 20.0);
+        assert_eq!(layout.height, 160.0);
+        assert!(!layout.needs_warning());
+
+        // the box never shrinks
+        let layout = fit_text(TextFit::Grow, 20.0, 4.0, (1000.0, 100.0), measure(1));
+        assert_eq!(layout.height, 100.0);
+    }
+
+    #[test]
+    fn test_fit_empty_box() {
+        let layout = fit_text(TextFit::Shrink, 20.0, 4.0, (0.0, 0.0), measure(4));
+        assert_eq!(layout.size, 20.0);
+        assert!(!layout.needs_warning());
+    }
+
+    #[test]
+    fn test_split_point() {
+        // 3 lines fit
+        assert_eq!(split_point(7, |n| n <= 3), Some(3));
+        assert_eq!(split_point(3, |n| n <= 3), None);
+        // a single line that does not fit is still kept
+        assert_eq!(split_point(4, |_| false), Some(1));
+        assert_eq!(split_point(1, |_| false), None);
+    }
+}
@@ -0,0 +1,292 @@
+use gtk::glib;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    db::query::Query,
+    dto::SongDetails,
+    widgets::canvas::serialise::{
+        CanvasItemData, CanvasItemType, SlideData, SlideManagerData, TextItemData,
+    },
+};
+
+/// Content that has a default theme of its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemeContent {
+    Song,
+    Scripture,
+    Presentation,
+}
+
+impl ThemeContent {
+    pub const ALL: [Self; 3] = [Self::Song, Self::Scripture, Self::Presentation];
+
+    /// Value stored in `theme_defaults.content`
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Song => "song",
+            Self::Scripture => "scripture",
+            Self::Presentation => "presentation",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Song => "Songs",
+            Self::Scripture => "Scriptures",
+            Self::Presentation => "Presentations",
+        }
+    }
+}
+
+/// Named slide look shared by many slides.
+///
+/// The template holds the background, text box geometry and text style,
+/// its first text item takes the slide text and the second the footer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SlideTheme {
+    /// database id, `0` until saved
+    #[serde(skip)]
+    pub id: u32,
+    #[serde(skip)]
+    pub name: String,
+    pub template: SlideData,
+    #[serde(rename = "show-footer")]
+    pub show_footer: bool,
+}
+
+impl Default for SlideTheme {
+    fn default() -> Self {
+        Self {
+            id: 0,
+            name: String::new(),
+            template: Self::default_template(SlideData::from_default()),
+            show_footer: false,
+        }
+    }
+}
+
+impl SlideTheme {
+    /// `slide` with a footer box along the bottom, styled like its text
+    pub fn default_template(mut slide: SlideData) -> SlideData {
+        let text = slide.items.iter().find_map(|item| match &item.item_type {
+            CanvasItemType::Text(text) => Some(text.clone()),
+            _ => None,
+        });
+
+        if let Some(text) = text {
+            let footer = TextItemData {
+                font_size: (text.font_size / 2.0).max(1.0),
+                text_outline: false,
+                ..text
+            };
+            slide.items.push(CanvasItemData::new(
+                0,
+                1340,
+                -1,
+                140,
+                CanvasItemType::Text(footer),
+            ));
+        }
+
+        slide
+    }
+
+    /// `slide` in this theme, keeping its text, audio and duration.
+    ///
+    /// `footer` is plain text, it is left out when empty or when the
+    /// theme has no footer.
+    pub fn apply(&self, slide: &SlideData, footer: Option<&str>) -> SlideData {
+        let text = slide.items.iter().find_map(|item| match &item.item_type {
+            CanvasItemType::Text(text) => Some(text.text_data.clone()),
+            _ => None,
+        });
+        let footer = footer
+            .filter(|f| self.show_footer && !f.is_empty())
+            .map(|f| glib::base64_encode(glib::markup_escape_text(f).as_bytes()).to_string());
+
+        let mut themed = self.template.clone();
+        themed.preview = Vec::new();
+        themed.audio = slide.audio.clone();
+        themed.duration = slide.duration;
+
+        let mut text_items = 0;
+        themed.items.retain_mut(|item| {
+            let CanvasItemType::Text(text_item) = &mut item.item_type else {
+                return true;
+            };
+
+            text_items += 1;
+            match text_items {
+                1 => text_item.text_data = text.clone().unwrap_or_default(),
+                2 => match &footer {
+                    Some(footer) => text_item.text_data = footer.clone(),
+                    None => return false,
+                },
+                _ => (),
+            }
+            true
+        });
+
+        themed
+    }
+
+    /// Restyles the slides of `data` that still have the built in look,
+    /// slides designed by hand are left alone
+    pub fn apply_to_plain(&self, data: &mut SlideManagerData, footer: Option<&str>) {
+        let plain = SlideData::from_default();
+        for slide in &mut data.slides {
+            if without_text(slide) == plain {
+                *slide = self.apply(slide, footer);
+            }
+        }
+    }
+
+    pub fn default_for(content: ThemeContent) -> Option<Self> {
+        Query::get_default_theme(content).unwrap_or_else(|e| {
+            eprintln!("SQL ERROR: {:?}", e);
+            None
+        })
+    }
+
+    /// The theme chosen for the song `song_id`, else the default theme for songs
+    pub fn for_song(song_id: u32) -> Option<Self> {
+        // a merged song has the theme of the song it was merged into
+        let song_id = match song_id {
+            0 => None,
+            id => Query::resolve_song_id(id).unwrap_or_else(|e| {
+                eprintln!("SQL ERROR: {:?}", e);
+                None
+            }),
+        };
+        let theme_id = song_id.and_then(|id| {
+            Query::get_song_theme_id(id).unwrap_or_else(|e| {
+                eprintln!("SQL ERROR: {:?}", e);
+                None
+            })
+        });
+
+        match theme_id {
+            Some(id) => Query::get_theme(id).unwrap_or_else(|e| {
+                eprintln!("SQL ERROR: {:?}", e);
+                None
+            }),
+            None => Self::default_for(ThemeContent::Song),
+        }
+    }
+}
+
+/// `slide` with the text of its text items removed
+fn without_text(slide: &SlideData) -> SlideData {
+    let mut slide = slide.clone();
+    for item in &mut slide.items {
+        if let CanvasItemType::Text(text_item) = &mut item.item_type {
+            text_item.text_data = String::new();
+        }
+    }
+    slide
+}
+
+/// Footer line of a song, e.g. `Amazing Grace · John Newton · © Public Domain · CCLI 22025`
+pub fn song_footer(title: &str, details: &SongDetails) -> String {
+    let author = details.author.clone();
+    let copyright = details.copyright.as_ref().map(|c| format!("© {c}"));
+    let ccli = details.ccli_number.as_ref().map(|n| format!("CCLI {n}"));
+
+    [Some(title.to_string()), author, copyright, ccli]
+        .into_iter()
+        .flatten()
+        .filter(|part| !part.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join(" · ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::widgets::canvas::serialise::{AudioCue, CanvasData};
+
+    fn text(text_data: &str, font_size: f32) -> CanvasItemData {
+        let text = TextItemData {
+            text_data: text_data.to_string(),
+            font_size,
+            ..TextItemData::default()
+        };
+        CanvasItemData::new(0, 0, -1, -1, CanvasItemType::Text(text))
+    }
+
+    fn text_of(slide: &SlideData) -> Vec<(String, f32)> {
+        slide
+            .items
+            .iter()
+            .filter_map(|item| match &item.item_type {
+                CanvasItemType::Text(t) => Some((t.text_data.clone(), t.font_size)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn theme(show_footer: bool) -> SlideTheme {
+        let canvas_data = CanvasData {
+            background_color: "#112233ff".into(),
+            ..CanvasData::default()
+        };
+        SlideTheme {
+            id: 1,
+            name: "Dark".into(),
+            template: SlideData::new(0, [text("", 40.0), text("", 10.0)], vec![], canvas_data),
+            show_footer,
+        }
+    }
+
+    #[test]
+    fn test_apply_keeps_text_and_audio() {
+        let mut slide = SlideData::new(
+            0,
+            [text("bGlmZQ==", 20.0)],
+            vec![1, 2],
+            CanvasData::default(),
+        );
+        slide.audio = Some(AudioCue::default());
+        slide.duration = Some(6);
+
+        let themed = theme(false).apply(&slide, Some("Footer"));
+        assert_eq!(themed.canvas_data.background_color, "#112233ff");
+        assert_eq!(themed.audio, slide.audio);
+        assert_eq!(themed.duration, Some(6));
+        assert!(themed.preview.is_empty());
+        assert_eq!(text_of(&themed), vec![("bGlmZQ==".to_string(), 40.0)]);
+    }
+
+    #[test]
+    fn test_apply_footer() {
+        let slide = SlideData::new(0, [text("bGlmZQ==", 20.0)], vec![], CanvasData::default());
+
+        let themed = theme(true).apply(&slide, Some("A & B"));
+        let footer = glib::base64_encode(b"A &amp; B").to_string();
+        assert_eq!(
+            text_of(&themed),
+            vec![("bGlmZQ==".to_string(), 40.0), (footer, 10.0)]
+        );
+
+        let themed = theme(true).apply(&slide, Some(""));
+        assert_eq!(text_of(&themed).len(), 1);
+    }
+
+    #[test]
+    fn test_song_footer() {
+        let details = SongDetails {
+            ccli_number: Some("22025".into()),
+            author: None,
+            copyright: Some("Public Domain".into()),
+        };
+        assert_eq!(
+            song_footer("Amazing Grace", &details),
+            "Amazing Grace · © Public Domain · CCLI 22025"
+        );
+        assert_eq!(
+            song_footer("Amazing Grace", &SongDetails::default()),
+            "Amazing Grace"
+        );
+    }
+}
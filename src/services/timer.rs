@@ -0,0 +1,256 @@
+use gtk::glib;
+
+use crate::widgets::canvas::serialise::{TimerItemData, TimerMode};
+
+const DAY: f64 = 24.0 * 60.0 * 60.0;
+
+/// Source of the current time, so timers can be driven by a fake clock
+pub trait Clock {
+    /// Local wall clock time in seconds since the Unix epoch, the time of
+    /// day is this modulo a day
+    fn now(&self) -> f64;
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> f64 {
+        let Ok(now) = glib::DateTime::now_local() else {
+            return 0.0;
+        };
+        (now.to_unix() + now.utc_offset().as_seconds()) as f64
+            + now.microsecond() as f64 / 1_000_000.0
+    }
+}
+
+/// Seconds after midnight of a `HH:MM` or `HH:MM:SS` time
+pub fn parse_time_of_day(value: &str) -> Option<u32> {
+    let mut parts = value.trim().split(':');
+    let h: u32 = parts.next()?.trim().parse().ok()?;
+    let m: u32 = parts.next()?.trim().parse().ok()?;
+    let s: u32 = match parts.next() {
+        Some(s) => s.trim().parse().ok()?,
+        None => 0,
+    };
+    if parts.next().is_some() || h > 23 || m > 59 || s > 59 {
+        return None;
+    }
+    Some(h * 3600 + m * 60 + s)
+}
+
+/// Format used when an item has none
+pub fn default_format(mode: TimerMode) -> &'static str {
+    match mode {
+        TimerMode::Clock => "{hh}:{mm}",
+        _ => "{mm}:{ss}",
+    }
+}
+
+/// Writes `seconds` with `format`.
+///
+/// `{hh}`, `{mm}` and `{ss}` are zero padded to two digits, `{h}`, `{m}`
+/// and `{s}` are not. Without an hour token the minutes count every
+/// hour, so `{mm}:{ss}` shows `90:00` for an hour and a half. `{h12}` and
+/// `{ampm}` write the hour on a 12 hour clock.
+pub fn format_time(format: &str, seconds: u64) -> String {
+    let has_hours = format.contains("{h");
+    let (h, m, s) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    let minutes = match has_hours {
+        true => m,
+        false => seconds / 60,
+    };
+    let h12 = match h % 12 {
+        0 => 12,
+        h => h,
+    };
+    let ampm = match h % 24 < 12 {
+        true => "AM",
+        false => "PM",
+    };
+
+    format
+        .replace("{h12}", &h12.to_string())
+        .replace("{ampm}", ampm)
+        .replace("{hh}", &format!("{h:02}"))
+        .replace("{h}", &h.to_string())
+        .replace("{mm}", &format!("{minutes:02}"))
+        .replace("{m}", &minutes.to_string())
+        .replace("{ss}", &format!("{s:02}"))
+        .replace("{s}", &s.to_string())
+}
+
+/// What a timer shows while its slide is live
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimerState {
+    mode: TimerMode,
+    format: String,
+    started: f64,
+    /// when a countdown reaches zero
+    deadline: Option<f64>,
+    finished: bool,
+}
+
+impl TimerState {
+    /// Starts timing `data` from now
+    pub fn start(data: &TimerItemData, clock: &impl Clock) -> Self {
+        let now = clock.now();
+        let deadline = match data.mode {
+            TimerMode::Countdown => Some(now + data.duration as f64),
+            TimerMode::CountdownTo => {
+                let target = parse_time_of_day(&data.target).unwrap_or_default() as f64;
+                // a time that has passed today is tomorrow's
+                Some(now + (target - now.rem_euclid(DAY)).rem_euclid(DAY))
+            }
+            TimerMode::CountUp | TimerMode::Clock => None,
+        };
+
+        Self {
+            mode: data.mode,
+            format: match data.format.is_empty() {
+                true => default_format(data.mode).to_string(),
+                false => data.format.clone(),
+            },
+            started: now,
+            deadline,
+            finished: false,
+        }
+    }
+
+    /// The text to show now, and whether the countdown just reached
+    /// zero, which is only reported once
+    pub fn tick(&mut self, clock: &impl Clock) -> (String, bool) {
+        let now = clock.now();
+        let seconds = match (self.mode, self.deadline) {
+            // a partly gone second still shows, so zero shows on time
+            (_, Some(deadline)) => (deadline - now).max(0.0).ceil(),
+            (TimerMode::Clock, _) => now.rem_euclid(DAY).floor(),
+            _ => (now - self.started).max(0.0).floor(),
+        };
+
+        let finished_now = self.deadline.is_some_and(|d| now >= d) && !self.finished;
+        self.finished |= finished_now;
+        (format_time(&self.format, seconds as u64), finished_now)
+    }
+
+    #[cfg(test)]
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+}
+
+/// The text of `data` before it starts
+pub fn idle_text(data: &TimerItemData, clock: &impl Clock) -> String {
+    TimerState::start(data, clock).tick(clock).0
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct FakeClock(Cell<f64>);
+
+    impl FakeClock {
+        fn at(seconds: f64) -> Self {
+            Self(Cell::new(seconds))
+        }
+
+        fn advance(&self, seconds: f64) {
+            self.0.set(self.0.get() + seconds);
+        }
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> f64 {
+            self.0.get()
+        }
+    }
+
+    fn timer(mode: TimerMode) -> TimerItemData {
+        TimerItemData {
+            mode,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_time_of_day() {
+        assert_eq!(parse_time_of_day("09:30"), Some(9 * 3600 + 30 * 60));
+        assert_eq!(parse_time_of_day(" 23:59:10 "), Some(86350));
+        assert_eq!(parse_time_of_day("24:00"), None);
+        assert_eq!(parse_time_of_day("9"), None);
+        assert_eq!(parse_time_of_day("10:00:00:00"), None);
+    }
+
+    #[test]
+    fn test_format_time() {
+        assert_eq!(format_time("{mm}:{ss}", 5400), "90:00");
+        assert_eq!(format_time("{hh}:{mm}:{ss}", 5405), "01:30:05");
+        assert_eq!(format_time("{m} min {s} s", 65), "1 min 5 s");
+        assert_eq!(format_time("{h12}:{mm} {ampm}", 0), "12:00 AM");
+        assert_eq!(
+            format_time("{h12}:{mm} {ampm}", 13 * 3600 + 5 * 60),
+            "1:05 PM"
+        );
+        assert_eq!(format_time("Starts soon", 10), "Starts soon");
+    }
+
+    #[test]
+    fn test_countdown() {
+        let clock = FakeClock::at(1000.0);
+        let mut data = timer(TimerMode::Countdown);
+        data.duration = 90;
+
+        let mut state = TimerState::start(&data, &clock);
+        assert_eq!(state.tick(&clock), ("01:30".into(), false));
+        clock.advance(0.5);
+        assert_eq!(state.tick(&clock), ("01:30".into(), false));
+        clock.advance(89.0);
+        assert_eq!(state.tick(&clock), ("00:01".into(), false));
+        clock.advance(0.5);
+        assert_eq!(state.tick(&clock), ("00:00".into(), true));
+        assert!(state.finished());
+
+        // finishing is reported once
+        clock.advance(5.0);
+        assert_eq!(state.tick(&clock), ("00:00".into(), false));
+    }
+
+    #[test]
+    fn test_countdown_to() {
+        // 18:00 local
+        let clock = FakeClock::at(10.0 * DAY + 18.0 * 3600.0);
+        let mut data = timer(TimerMode::CountdownTo);
+        data.target = "18:30".into();
+
+        let mut state = TimerState::start(&data, &clock);
+        assert_eq!(state.tick(&clock).0, "30:00");
+        clock.advance(30.0 * 60.0);
+        assert_eq!(state.tick(&clock), ("00:00".into(), true));
+
+        // a passed time counts to tomorrow
+        data.target = "17:00".into();
+        data.format = "{hh}:{mm}".into();
+        assert_eq!(idle_text(&data, &clock), "22:30");
+    }
+
+    #[test]
+    fn test_count_up_and_clock() {
+        let clock = FakeClock::at(3.0 * DAY + 9.0 * 3600.0 + 5.0 * 60.0);
+
+        let mut state = TimerState::start(&timer(TimerMode::CountUp), &clock);
+        clock.advance(61.9);
+        assert_eq!(state.tick(&clock), ("01:01".into(), false));
+
+        let mut data = timer(TimerMode::Clock);
+        assert_eq!(idle_text(&data, &clock), "09:06");
+        data.format = "{h12}:{mm}:{ss} {ampm}".into();
+        let mut state = TimerState::start(&data, &clock);
+        clock.advance(DAY / 2.0);
+        assert_eq!(state.tick(&clock), ("9:06:01 PM".into(), false));
+        assert!(!state.finished());
+    }
+}
@@ -0,0 +1,281 @@
+use gtk::glib;
+use serde::{Deserialize, Serialize};
+
+/// Blur radius of [TransitionEffect::BlurIn] at the start, in slide pixels
+const MAX_BLUR: f64 = 40.0;
+/// Size the incoming slide of [TransitionEffect::Zoom] starts at
+const ZOOM_START: f64 = 0.6;
+
+/// Transitions drawn by the transition layer from snapshots of the
+/// outgoing and incoming slides
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionEffect {
+    /// fade the outgoing slide to black, then the incoming one in
+    #[default]
+    #[serde(rename = "fade-through-black")]
+    FadeThroughBlack,
+    /// grow the incoming slide from the centre
+    #[serde(rename = "zoom")]
+    Zoom,
+    /// reveal the incoming slide across the screen
+    #[serde(rename = "wipe")]
+    Wipe,
+    /// blend the incoming slide over the outgoing one
+    #[serde(rename = "dissolve")]
+    Dissolve,
+    /// fade the incoming slide in while it comes into focus
+    #[serde(rename = "blur-in")]
+    BlurIn,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+    #[serde(rename = "linear")]
+    Linear,
+    #[serde(rename = "ease-in")]
+    EaseIn,
+    #[serde(rename = "ease-out")]
+    EaseOut,
+    #[default]
+    #[serde(rename = "ease-in-out")]
+    EaseInOut,
+}
+
+impl Easing {
+    /// Eased progress for `t`, both from 0.0 to 1.0
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::Linear => t,
+            Self::EaseIn => t * t * t,
+            Self::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Self::EaseInOut => match t < 0.5 {
+                true => 4.0 * t * t * t,
+                false => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+            },
+        }
+    }
+}
+
+/// The way a [TransitionEffect::Wipe] moves
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum WipeDirection {
+    #[default]
+    #[serde(rename = "left")]
+    Left,
+    #[serde(rename = "right")]
+    Right,
+    #[serde(rename = "up")]
+    Up,
+    #[serde(rename = "down")]
+    Down,
+}
+
+/// How a slide is brought on screen.
+///
+/// Without an effect the slide uses the stack transition in `SlideData::transition`.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, glib::Boxed)]
+#[boxed_type(name = "SlideTransition")]
+#[serde(default)]
+pub struct SlideTransition {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effect: Option<TransitionEffect>,
+    /// seconds, the `transition-duration` setting is used when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<f64>,
+    pub easing: Easing,
+    pub direction: WipeDirection,
+}
+
+impl SlideTransition {
+    /// `duration` or else `default`, both in seconds
+    pub fn duration_or(&self, default: f64) -> f64 {
+        self.duration
+            .filter(|d| *d >= 0.0)
+            .unwrap_or(default)
+            .max(0.0)
+    }
+}
+
+/// What to draw at one point of a transition.
+///
+/// The outgoing slide is drawn over black, then the incoming slide over it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransitionFrame {
+    pub from_opacity: f64,
+    pub to_opacity: f64,
+    /// scale of the incoming slide around its centre
+    pub to_scale: f64,
+    /// blur radius of the incoming slide, in slide pixels
+    pub to_blur: f64,
+    /// visible part of the incoming slide as `(x, y, width, height)`, from 0.0 to 1.0
+    pub to_clip: (f64, f64, f64, f64),
+}
+
+impl TransitionFrame {
+    /// Frame of `effect` at `progress`, from 0.0 (outgoing slide) to 1.0 (incoming slide)
+    pub fn at(effect: TransitionEffect, direction: WipeDirection, progress: f64) -> Self {
+        let t = progress.clamp(0.0, 1.0);
+        let frame = Self {
+            from_opacity: 1.0,
+            to_opacity: 1.0,
+            to_scale: 1.0,
+            to_blur: 0.0,
+            to_clip: (0.0, 0.0, 1.0, 1.0),
+        };
+
+        match effect {
+            TransitionEffect::FadeThroughBlack => Self {
+                from_opacity: (1.0 - 2.0 * t).max(0.0),
+                to_opacity: (2.0 * t - 1.0).max(0.0),
+                ..frame
+            },
+            TransitionEffect::Zoom => Self {
+                from_opacity: 1.0 - t,
+                to_opacity: t,
+                to_scale: ZOOM_START + (1.0 - ZOOM_START) * t,
+                ..frame
+            },
+            TransitionEffect::Wipe => Self {
+                to_clip: match direction {
+                    WipeDirection::Left => (1.0 - t, 0.0, t, 1.0),
+                    WipeDirection::Right => (0.0, 0.0, t, 1.0),
+                    WipeDirection::Up => (0.0, 1.0 - t, 1.0, t),
+                    WipeDirection::Down => (0.0, 0.0, 1.0, t),
+                },
+                ..frame
+            },
+            TransitionEffect::Dissolve => Self {
+                to_opacity: t,
+                ..frame
+            },
+            TransitionEffect::BlurIn => Self {
+                from_opacity: 1.0 - t,
+                to_opacity: t,
+                to_blur: MAX_BLUR * (1.0 - t),
+                ..frame
+            },
+        }
+    }
+}
+
+/// Linear progress `elapsed` seconds into a transition of `duration` seconds
+pub fn progress(elapsed: f64, duration: f64) -> f64 {
+    match duration > 0.0 {
+        true => (elapsed / duration).clamp(0.0, 1.0),
+        false => 1.0,
+    }
+}
+
+impl SlideTransition {
+    /// Frame `elapsed` seconds into the transition, `None` when it has no effect
+    pub fn frame(&self, elapsed: f64, duration: f64) -> Option<TransitionFrame> {
+        let effect = self.effect?;
+        let t = self.easing.apply(progress(elapsed, duration));
+        Some(TransitionFrame::at(effect, self.direction, t))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EFFECTS: [TransitionEffect; 5] = [
+        TransitionEffect::FadeThroughBlack,
+        TransitionEffect::Zoom,
+        TransitionEffect::Wipe,
+        TransitionEffect::Dissolve,
+        TransitionEffect::BlurIn,
+    ];
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_easing() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+        ] {
+            assert!(close(easing.apply(0.0), 0.0));
+            assert!(close(easing.apply(1.0), 1.0));
+            assert!(close(easing.apply(2.0), 1.0));
+        }
+
+        assert!(close(Easing::Linear.apply(0.25), 0.25));
+        assert!(close(Easing::EaseIn.apply(0.5), 0.125));
+        assert!(close(Easing::EaseOut.apply(0.5), 0.875));
+        assert!(close(Easing::EaseInOut.apply(0.5), 0.5));
+        assert!(Easing::EaseInOut.apply(0.25) < 0.25);
+        assert!(Easing::EaseInOut.apply(0.75) > 0.75);
+    }
+
+    #[test]
+    fn test_frame_ends() {
+        for effect in EFFECTS {
+            let start = TransitionFrame::at(effect, WipeDirection::Left, 0.0);
+            let end = TransitionFrame::at(effect, WipeDirection::Left, 1.0);
+
+            // only the outgoing slide shows at the start
+            assert!(close(start.from_opacity, 1.0), "{effect:?}");
+            assert!(
+                close(start.to_opacity, 0.0) || close(start.to_clip.2, 0.0),
+                "{effect:?}"
+            );
+
+            // and only the incoming slide at the end
+            assert!(close(end.to_opacity, 1.0), "{effect:?}");
+            assert!(close(end.to_scale, 1.0), "{effect:?}");
+            assert!(close(end.to_blur, 0.0), "{effect:?}");
+            assert_eq!(end.to_clip, (0.0, 0.0, 1.0, 1.0), "{effect:?}");
+        }
+    }
+
+    #[test]
+    fn test_fade_through_black() {
+        let frame =
+            TransitionFrame::at(TransitionEffect::FadeThroughBlack, WipeDirection::Left, 0.5);
+        assert!(close(frame.from_opacity, 0.0));
+        assert!(close(frame.to_opacity, 0.0));
+
+        let frame = TransitionFrame::at(
+            TransitionEffect::FadeThroughBlack,
+            WipeDirection::Left,
+            0.25,
+        );
+        assert!(close(frame.from_opacity, 0.5));
+        assert!(close(frame.to_opacity, 0.0));
+    }
+
+    #[test]
+    fn test_wipe_directions() {
+        let clip = |direction| TransitionFrame::at(TransitionEffect::Wipe, direction, 0.25).to_clip;
+        assert_eq!(clip(WipeDirection::Left), (0.75, 0.0, 0.25, 1.0));
+        assert_eq!(clip(WipeDirection::Right), (0.0, 0.0, 0.25, 1.0));
+        assert_eq!(clip(WipeDirection::Up), (0.0, 0.75, 1.0, 0.25));
+        assert_eq!(clip(WipeDirection::Down), (0.0, 0.0, 1.0, 0.25));
+    }
+
+    #[test]
+    fn test_slide_transition_frame() {
+        let transition = SlideTransition {
+            effect: Some(TransitionEffect::Zoom),
+            duration: Some(2.0),
+            easing: Easing::Linear,
+            direction: WipeDirection::default(),
+        };
+
+        let frame = transition.frame(1.0, 2.0).unwrap();
+        assert!(close(frame.to_opacity, 0.5));
+        assert!(close(frame.to_scale, 0.8));
+        assert_eq!(transition.frame(5.0, 2.0), transition.frame(2.0, 2.0));
+        assert_eq!(SlideTransition::default().frame(1.0, 2.0), None);
+
+        assert!(close(progress(1.0, 0.0), 1.0));
+        assert!(close(transition.duration_or(0.5), 2.0));
+        assert!(close(SlideTransition::default().duration_or(0.5), 0.5));
+    }
+}
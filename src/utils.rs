@@ -11,7 +11,10 @@ use crate::widgets::canvas::canvas::Canvas;
 use crate::widgets::canvas::canvas_item::CanvasItem;
 use crate::widgets::canvas::image_item::ImageItem;
 use crate::widgets::canvas::serialise::{CanvasItemData, CanvasItemType};
+use crate::widgets::canvas::shape_item::ShapeItem;
 use crate::widgets::canvas::text_item::TextItem;
+use crate::widgets::canvas::timer_item::TimerItem;
+use crate::widgets::canvas::video_item::VideoItem;
 
 pub trait TextBufferExtraExt: IsA<gtk::TextBuffer> {
     fn full_text(&self) -> glib::GString {
@@ -155,6 +158,18 @@ pub fn canvas_item_from_data(data: CanvasItemData, canvas: Option<&Canvas>) -> O
             let item = ImageItem::new(canvas, Some(data)).upcast::<CanvasItem>();
             Some(item)
         }
+        CanvasItemType::Shape(_) => {
+            let item = ShapeItem::new(canvas, Some(data)).upcast::<CanvasItem>();
+            Some(item)
+        }
+        CanvasItemType::Video(_) => {
+            let item = VideoItem::new(canvas, Some(data)).upcast::<CanvasItem>();
+            Some(item)
+        }
+        CanvasItemType::Timer(_) => {
+            let item = TimerItem::new(canvas, Some(data)).upcast::<CanvasItem>();
+            Some(item)
+        }
         // Some("color") => {
         //     let item = ColorItem::new(canvas, Some(data)).upcast::<CanvasItem>();
         //     Some(item)
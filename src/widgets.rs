@@ -1,8 +1,12 @@
 pub mod activity_viewer;
+pub mod audio_cue_editor;
+pub mod audio_transport;
+pub mod auto_advance_controls;
 pub mod canvas;
 pub mod entry_combo;
 pub mod extended_screen;
 pub mod group_toggle_button;
+pub mod lower_third_screen;
 pub mod message_alert;
 pub mod message_alert_editor;
 pub mod message_alert_editor_window;
@@ -11,3 +15,6 @@ pub mod message_alert_wrapper;
 pub mod schedule_activity_viewer;
 pub mod search;
 pub mod settings_window;
+pub mod song_usage_window;
+pub mod stage_screen;
+pub mod theme_window;
@@ -5,8 +5,13 @@ use gtk::{
 };
 
 use crate::{
+    services::live_output::LiveOutput,
     utils::{ListViewExtra, WidgetChildrenExt},
-    widgets::canvas::{serialise::SlideManagerData, text_item::TextItem},
+    widgets::canvas::{
+        screen_cover::ScreenMode,
+        serialise::{SlideData, SlideManagerData},
+        text_item::TextItem,
+    },
 };
 
 const MIN_GRID_WIDTH: i32 = 300;
@@ -39,7 +44,6 @@ mod imp {
     };
 
     use crate::{
-        app_config::AppConfig,
         services::{slide::Slide, slide_manager::SlideManager},
         utils::{TextBufferExtraExt, WidgetExtrasExt},
         widgets::{activity_viewer::signals, canvas::serialise::SlideManagerData},
@@ -243,11 +247,11 @@ mod imp {
 
                 let aspect_frame = gtk::AspectFrame::builder()
                     // .height_request(super::MIN_GRID_HEIGHT)
-                    .ratio(AppConfig::aspect_ratio())
                     .obey_child(false)
                     .xalign(0.0)
                     .build();
-                aspect_frame.set_child(Some(&self.slide_manager.borrow().slideshow()));
+                self.slide_manager.borrow().bind_aspect_frame(&aspect_frame);
+                aspect_frame.set_child(Some(&self.slide_manager.borrow().screen()));
                 aspect_frame.set_parent(&frame);
 
                 // layout.add_constraint(gtk::Constraint::new(
@@ -408,6 +412,25 @@ impl ActivityViewer {
         self.clear_display(imp.clear.get());
     }
 
+    /// Data of the slide at `position` in the loaded item
+    pub fn slide_data(&self, position: u32) -> Option<SlideData> {
+        self.imp()
+            .slide_manager_data
+            .borrow()
+            .slides
+            .get(position as usize)
+            .cloned()
+    }
+
+    /// Selects the slide at `position`, as if it was clicked
+    pub fn select_slide(&self, position: u32) {
+        if let Some(model) = self.imp().listview.borrow().model()
+            && position < model.n_items()
+        {
+            model.select_item(position, true);
+        }
+    }
+
     pub fn connect_activate_slide<F: Fn(&Self, &SlideManagerData) + 'static>(
         &self,
         f: F,
@@ -488,4 +511,35 @@ impl ActivityViewer {
             }
         }
     }
+
+    /// Shows black or the logo in place of the slides, until set back to
+    /// [ScreenMode::Live]
+    pub fn set_screen_mode(&self, mode: ScreenMode) {
+        self.imp().slide_manager.borrow().set_screen_mode(mode);
+    }
+
+    /// Shows what `live` puts on the outputs, the slides picked here
+    /// still go through "slide-change"
+    pub fn follow(&self, live: &LiveOutput) {
+        live.connect_item_changed(glib::clone!(
+            #[weak(rename_to=obj)]
+            self,
+            move |_, data| obj.load_data(data)
+        ));
+        live.connect_slide_changed(glib::clone!(
+            #[weak(rename_to=obj)]
+            self,
+            move |_, position| obj.select_slide(position)
+        ));
+        live.connect_clear_notify(glib::clone!(
+            #[weak(rename_to=obj)]
+            self,
+            move |live| obj.clear_display(live.clear())
+        ));
+        live.connect_screen_mode_notify(glib::clone!(
+            #[weak(rename_to=obj)]
+            self,
+            move |live| obj.set_screen_mode(live.screen_mode())
+        ));
+    }
 }
@@ -0,0 +1,251 @@
+use gtk::{
+    glib::{self, object::ObjectExt, subclass::types::ObjectSubclassIsExt},
+    prelude::{BoxExt, ButtonExt, CheckButtonExt, GridExt, RangeExt, WidgetExt},
+};
+
+use crate::{
+    app_config::AppConfigDir, services::file_manager::FileManager, utils::WidgetExtrasExt,
+    widgets::canvas::serialise::AudioCue,
+};
+
+mod signals {
+    pub const CHANGED: &str = "changed";
+}
+
+mod imp {
+    use std::{
+        cell::{Cell, RefCell},
+        sync::OnceLock,
+    };
+
+    use gtk::{
+        glib::{
+            self,
+            subclass::{
+                Signal,
+                object::{ObjectImpl, ObjectImplExt},
+                types::{ObjectSubclass, ObjectSubclassExt},
+            },
+        },
+        prelude::{BoxExt, OrientableExt},
+        subclass::{box_::BoxImpl, widget::WidgetImpl},
+    };
+
+    use super::signals;
+    use crate::widgets::canvas::serialise::AudioCue;
+
+    #[derive(Debug, Default)]
+    pub struct AudioCueEditor {
+        pub cue: RefCell<Option<AudioCue>>,
+
+        pub file_label: RefCell<gtk::Label>,
+        pub remove_btn: RefCell<gtk::Button>,
+        pub play_on_show: RefCell<gtk::CheckButton>,
+        pub stop_on_leave: RefCell<gtk::CheckButton>,
+        pub loop_audio: RefCell<gtk::CheckButton>,
+        pub volume: RefCell<gtk::Scale>,
+        pub options: RefCell<gtk::Grid>,
+
+        pub(super) updating_props: Cell<bool>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for AudioCueEditor {
+        const NAME: &'static str = "AudioCueEditor";
+        type Type = super::AudioCueEditor;
+        type ParentType = gtk::Box;
+    }
+
+    impl ObjectImpl for AudioCueEditor {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+            obj.set_orientation(gtk::Orientation::Vertical);
+            obj.set_spacing(6);
+        }
+
+        fn signals() -> &'static [glib::subclass::Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+
+            SIGNALS.get_or_init(|| vec![Signal::builder(signals::CHANGED).build()])
+        }
+    }
+    impl WidgetImpl for AudioCueEditor {}
+    impl BoxImpl for AudioCueEditor {}
+}
+
+glib::wrapper! {
+    pub struct AudioCueEditor(ObjectSubclass<imp::AudioCueEditor>)
+        @extends  gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Orientable, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Default for AudioCueEditor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AudioCueEditor {
+    pub fn new() -> Self {
+        let obj: Self = glib::Object::new();
+        obj.build_ui();
+        obj.update_props();
+        obj
+    }
+
+    fn build_ui(&self) {
+        let imp = self.imp();
+        self.set_margin_all(6);
+
+        let file_row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        let file_label = imp.file_label.borrow().clone();
+        file_label.set_hexpand(true);
+        file_label.set_xalign(0.0);
+        file_label.set_ellipsize(gtk::pango::EllipsizeMode::Middle);
+        file_label.set_max_width_chars(24);
+
+        let choose_btn = gtk::Button::with_label("Choose…");
+        choose_btn.connect_clicked(glib::clone!(
+            #[weak(rename_to=obj)]
+            self,
+            move |btn| {
+                let Some(file) = FileManager::open_audio(btn.toplevel_window().as_ref()) else {
+                    return;
+                };
+                let Some(path) = FileManager::file_to_link(&file, AppConfigDir::SlideMedia) else {
+                    return;
+                };
+
+                let mut cue = obj.cue().unwrap_or_default();
+                cue.url = path;
+                obj.set_cue(Some(cue));
+                obj.emit_changed();
+            }
+        ));
+
+        let remove_btn = imp.remove_btn.borrow().clone();
+        remove_btn.set_icon_name("user-trash-symbolic");
+        remove_btn.add_css_class("flat");
+        remove_btn.set_tooltip("Remove audio");
+        remove_btn.connect_clicked(glib::clone!(
+            #[weak(rename_to=obj)]
+            self,
+            move |_| {
+                obj.set_cue(None);
+                obj.emit_changed();
+            }
+        ));
+
+        file_row.append(&file_label);
+        file_row.append(&choose_btn);
+        file_row.append(&remove_btn);
+
+        let options = imp.options.borrow().clone();
+        options.set_row_spacing(6);
+        options.set_column_spacing(12);
+
+        let play_on_show = imp.play_on_show.borrow().clone();
+        play_on_show.set_label(Some("Play when shown"));
+        let stop_on_leave = imp.stop_on_leave.borrow().clone();
+        stop_on_leave.set_label(Some("Stop when leaving"));
+        let loop_audio = imp.loop_audio.borrow().clone();
+        loop_audio.set_label(Some("Loop"));
+        for check in [&play_on_show, &stop_on_leave, &loop_audio] {
+            check.connect_toggled(glib::clone!(
+                #[weak(rename_to=obj)]
+                self,
+                move |_| obj.update_cue()
+            ));
+        }
+
+        let volume = imp.volume.borrow().clone();
+        volume.set_range(0.0, 100.0);
+        volume.set_increments(5.0, 10.0);
+        volume.set_hexpand(true);
+        volume.connect_value_changed(glib::clone!(
+            #[weak(rename_to=obj)]
+            self,
+            move |_| obj.update_cue()
+        ));
+
+        options.attach(&play_on_show, 0, 0, 2, 1);
+        options.attach(&stop_on_leave, 0, 1, 2, 1);
+        options.attach(&loop_audio, 0, 2, 2, 1);
+        options.attach(&gtk::Label::new(Some("Volume")), 0, 3, 1, 1);
+        options.attach(&volume, 1, 3, 1, 1);
+
+        self.append(&file_row);
+        self.append(&options);
+    }
+
+    pub fn cue(&self) -> Option<AudioCue> {
+        self.imp().cue.borrow().clone()
+    }
+
+    /// Shows `cue` without emitting "changed"
+    pub fn set_cue(&self, cue: Option<AudioCue>) {
+        self.imp().cue.replace(cue);
+        self.update_props();
+    }
+
+    fn update_cue(&self) {
+        let imp = self.imp();
+        if imp.updating_props.get() {
+            return;
+        }
+
+        let Some(mut cue) = self.cue() else {
+            return;
+        };
+        cue.play_on_show = imp.play_on_show.borrow().is_active();
+        cue.stop_on_leave = imp.stop_on_leave.borrow().is_active();
+        cue.loop_audio = imp.loop_audio.borrow().is_active();
+        cue.volume = imp.volume.borrow().value() / 100.0;
+
+        imp.cue.replace(Some(cue));
+        self.emit_changed();
+    }
+
+    fn update_props(&self) {
+        let imp = self.imp();
+        let cue = self.cue();
+
+        imp.remove_btn.borrow().set_sensitive(cue.is_some());
+        imp.options.borrow().set_sensitive(cue.is_some());
+
+        let cue = match cue {
+            Some(cue) => {
+                let name = std::path::Path::new(&cue.url)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                imp.file_label.borrow().set_label(&name);
+                cue
+            }
+            None => {
+                imp.file_label.borrow().set_label("No audio");
+                AudioCue::default()
+            }
+        };
+
+        imp.updating_props.set(true);
+        imp.play_on_show.borrow().set_active(cue.play_on_show);
+        imp.stop_on_leave.borrow().set_active(cue.stop_on_leave);
+        imp.loop_audio.borrow().set_active(cue.loop_audio);
+        imp.volume.borrow().set_value(cue.volume * 100.0);
+        imp.updating_props.set(false);
+    }
+
+    fn emit_changed(&self) {
+        self.emit_by_name::<()>(signals::CHANGED, &[]);
+    }
+
+    pub fn connect_changed<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            signals::CHANGED,
+            false,
+            glib::closure_local!(move |obj: &Self| f(obj)),
+        )
+    }
+}
@@ -0,0 +1,116 @@
+use gtk::{
+    glib::{self, object::ObjectExt, subclass::types::ObjectSubclassIsExt},
+    prelude::{BoxExt, ButtonExt, WidgetExt},
+};
+
+use crate::{services::audio_player::AudioPlayer, utils::WidgetExtrasExt};
+
+mod imp {
+    use std::cell::RefCell;
+
+    use gtk::{
+        glib::{
+            self,
+            subclass::{
+                object::{ObjectImpl, ObjectImplExt},
+                types::{ObjectSubclass, ObjectSubclassExt},
+            },
+        },
+        prelude::WidgetExt,
+        subclass::{box_::BoxImpl, widget::WidgetImpl},
+    };
+
+    use crate::services::audio_player::AudioPlayer;
+
+    #[derive(Debug, Default)]
+    pub struct AudioTransport {
+        pub player: glib::WeakRef<AudioPlayer>,
+        pub title_label: RefCell<gtk::Label>,
+        pub controls: RefCell<gtk::MediaControls>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for AudioTransport {
+        const NAME: &'static str = "AudioTransport";
+        type Type = super::AudioTransport;
+        type ParentType = gtk::Box;
+    }
+
+    impl ObjectImpl for AudioTransport {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().set_css_classes(&["toolbar"]);
+        }
+    }
+    impl WidgetImpl for AudioTransport {}
+    impl BoxImpl for AudioTransport {}
+}
+
+glib::wrapper! {
+    pub struct AudioTransport(ObjectSubclass<imp::AudioTransport>)
+        @extends  gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Orientable, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Default for AudioTransport {
+    fn default() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl AudioTransport {
+    /// Transport for the audio cue playing in `player`,
+    /// only visible while a cue is loaded
+    pub fn new(player: &AudioPlayer) -> Self {
+        let obj: Self = glib::Object::new();
+        let imp = obj.imp();
+        imp.player.set(Some(player));
+
+        obj.set_spacing(6);
+        obj.set_margin_all(3);
+
+        let icon = gtk::Image::from_icon_name("audio-x-generic-symbolic");
+        let title_label = imp.title_label.borrow().clone();
+        title_label.set_ellipsize(gtk::pango::EllipsizeMode::Middle);
+        title_label.set_max_width_chars(20);
+
+        let controls = imp.controls.borrow().clone();
+        controls.set_hexpand(true);
+
+        let stop_btn = gtk::Button::builder()
+            .icon_name("media-playback-stop-symbolic")
+            .css_classes(["flat"])
+            .build();
+        stop_btn.set_tooltip("Stop audio");
+        stop_btn.connect_clicked(glib::clone!(
+            #[weak]
+            obj,
+            move |_| {
+                if let Some(player) = obj.imp().player.upgrade() {
+                    player.stop();
+                }
+            }
+        ));
+
+        obj.append(&icon);
+        obj.append(&title_label);
+        obj.append(&controls);
+        obj.append(&stop_btn);
+
+        player
+            .bind_property("title", &title_label, "label")
+            .sync_create()
+            .build();
+        player
+            .bind_property("media", &controls, "media-stream")
+            .sync_create()
+            .build();
+        player
+            .bind_property("media", &obj, "visible")
+            .transform_to(|_, media: Option<gtk::MediaFile>| Some(media.is_some()))
+            .sync_create()
+            .build();
+
+        obj
+    }
+}
@@ -0,0 +1,132 @@
+use gtk::{
+    glib::{self, subclass::types::ObjectSubclassIsExt},
+    prelude::{BoxExt, ButtonExt, WidgetExt},
+};
+
+use crate::{services::auto_advancer::AutoAdvancer, utils::WidgetExtrasExt};
+
+mod imp {
+    use gtk::{
+        glib::{
+            self,
+            subclass::{
+                object::{ObjectImpl, ObjectImplExt},
+                types::{ObjectSubclass, ObjectSubclassExt},
+            },
+        },
+        prelude::WidgetExt,
+        subclass::{box_::BoxImpl, widget::WidgetImpl},
+    };
+
+    use crate::services::auto_advancer::AutoAdvancer;
+
+    #[derive(Debug, Default)]
+    pub struct AutoAdvanceControls {
+        pub advancer: glib::WeakRef<AutoAdvancer>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for AutoAdvanceControls {
+        const NAME: &'static str = "AutoAdvanceControls";
+        type Type = super::AutoAdvanceControls;
+        type ParentType = gtk::Box;
+    }
+
+    impl ObjectImpl for AutoAdvanceControls {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().set_css_classes(&["toolbar"]);
+        }
+    }
+    impl WidgetImpl for AutoAdvanceControls {}
+    impl BoxImpl for AutoAdvanceControls {}
+}
+
+glib::wrapper! {
+    pub struct AutoAdvanceControls(ObjectSubclass<imp::AutoAdvanceControls>)
+        @extends  gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Orientable, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Default for AutoAdvanceControls {
+    fn default() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl AutoAdvanceControls {
+    /// Pause and resume for the live item of `advancer`, only visible
+    /// while it advances or a loop can be resumed
+    pub fn new(advancer: &AutoAdvancer) -> Self {
+        let obj: Self = glib::Object::new();
+        obj.imp().advancer.set(Some(advancer));
+
+        obj.set_spacing(6);
+        obj.set_margin_all(3);
+
+        let icon = gtk::Image::from_icon_name("media-playlist-repeat-symbolic");
+        let label = gtk::Label::builder().hexpand(true).xalign(0.0).build();
+
+        let pause_btn = gtk::Button::builder().css_classes(["flat"]).build();
+        pause_btn.connect_clicked(glib::clone!(
+            #[weak]
+            advancer,
+            move |_| match advancer.paused() {
+                true => advancer.resume(),
+                false => advancer.pause(),
+            }
+        ));
+
+        let resume_btn = gtk::Button::builder()
+            .label("Resume loop")
+            .css_classes(["flat"])
+            .build();
+        resume_btn.set_tooltip("Show the interrupted loop again");
+        resume_btn.connect_clicked(glib::clone!(
+            #[weak]
+            advancer,
+            move |_| advancer.resume_loop()
+        ));
+
+        obj.append(&icon);
+        obj.append(&label);
+        obj.append(&pause_btn);
+        obj.append(&resume_btn);
+
+        let update = glib::clone!(
+            #[weak]
+            obj,
+            #[weak]
+            label,
+            #[weak]
+            pause_btn,
+            #[weak]
+            resume_btn,
+            move |advancer: &AutoAdvancer| {
+                let (text, icon, tooltip) = match advancer.paused() {
+                    true => (
+                        "Auto advance paused",
+                        "media-playback-start-symbolic",
+                        "Resume",
+                    ),
+                    false => ("Auto advance", "media-playback-pause-symbolic", "Pause"),
+                };
+                label.set_label(match advancer.running() {
+                    true => text,
+                    false => "Loop interrupted",
+                });
+                pause_btn.set_icon_name(icon);
+                pause_btn.set_tooltip(tooltip);
+                pause_btn.set_visible(advancer.running());
+                resume_btn.set_visible(advancer.can_resume());
+                obj.set_visible(advancer.running() || advancer.can_resume());
+            }
+        );
+        advancer.connect_running_notify(update.clone());
+        advancer.connect_paused_notify(update.clone());
+        advancer.connect_can_resume_notify(update.clone());
+        update(advancer);
+
+        obj
+    }
+}
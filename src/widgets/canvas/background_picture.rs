@@ -0,0 +1,171 @@
+use gtk::glib;
+use gtk::glib::subclass::prelude::*;
+use gtk::prelude::*;
+use gtk::{gdk, graphene};
+
+use crate::widgets::canvas::serialise::{ImageFit, ImageOptions};
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use gtk::subclass::widget::WidgetImpl;
+
+    use super::*;
+
+    /// Draws a slide background image or video with its `ImageOptions`
+    pub struct BackgroundPicture {
+        pub paintable: RefCell<Option<gdk::Paintable>>,
+        pub handlers: RefCell<Vec<glib::SignalHandlerId>>,
+        pub options: RefCell<ImageOptions>,
+        /// slide to screen scale, sizes blur and tiles
+        pub ratio: Cell<f64>,
+    }
+
+    impl Default for BackgroundPicture {
+        fn default() -> Self {
+            Self {
+                paintable: RefCell::default(),
+                handlers: RefCell::default(),
+                options: RefCell::default(),
+                ratio: Cell::new(1.0),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for BackgroundPicture {
+        const NAME: &'static str = "BackgroundPicture";
+        type Type = super::BackgroundPicture;
+        type ParentType = gtk::Widget;
+    }
+
+    impl ObjectImpl for BackgroundPicture {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+            obj.set_hexpand(true);
+            obj.set_vexpand(true);
+            obj.set_overflow(gtk::Overflow::Hidden);
+        }
+
+        fn dispose(&self) {
+            self.obj().set_paintable(None::<&gdk::Paintable>);
+        }
+    }
+
+    impl WidgetImpl for BackgroundPicture {
+        fn snapshot(&self, snapshot: &gtk::Snapshot) {
+            let Some(paintable) = self.paintable.borrow().clone() else {
+                return;
+            };
+
+            let obj = self.obj();
+            let (width, height) = (obj.width() as f64, obj.height() as f64);
+            if width <= 0.0 || height <= 0.0 {
+                return;
+            }
+
+            let options = self.options.borrow().clone();
+            let ratio = self.ratio.get();
+            let bounds = graphene::Rect::new(0.0, 0.0, width as f32, height as f32);
+
+            let src = (
+                paintable.intrinsic_width() as f64,
+                paintable.intrinsic_height() as f64,
+            );
+            // e.g. a video that has not loaded its first frame yet
+            let fit = match src.0 > 0.0 && src.1 > 0.0 {
+                true => options.fit,
+                false => ImageFit::Stretch,
+            };
+            let (x, y, w, h) = fit.place(
+                src,
+                (width, height),
+                (options.position_x, options.position_y),
+                ratio,
+            );
+
+            snapshot.push_clip(&bounds);
+            if options.blur > 0.0 {
+                snapshot.push_blur(options.blur * ratio);
+            }
+            if fit == ImageFit::Tile {
+                let tile = graphene::Rect::new(x as f32, y as f32, w as f32, h as f32);
+                snapshot.push_repeat(&bounds, Some(&tile));
+            }
+
+            snapshot.save();
+            snapshot.translate(&graphene::Point::new(x as f32, y as f32));
+            paintable.snapshot(snapshot, w, h);
+            snapshot.restore();
+
+            if fit == ImageFit::Tile {
+                snapshot.pop();
+            }
+            if options.blur > 0.0 {
+                snapshot.pop();
+            }
+            if options.dim > 0.0 {
+                let dim = gdk::RGBA::new(0.0, 0.0, 0.0, options.dim.clamp(0.0, 1.0) as f32);
+                snapshot.append_color(&dim, &bounds);
+            }
+            snapshot.pop();
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct BackgroundPicture(ObjectSubclass<imp::BackgroundPicture>)
+        @extends gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Default for BackgroundPicture {
+    fn default() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl BackgroundPicture {
+    pub fn paintable(&self) -> Option<gdk::Paintable> {
+        self.imp().paintable.borrow().clone()
+    }
+
+    pub fn set_paintable(&self, paintable: Option<&impl IsA<gdk::Paintable>>) {
+        let imp = self.imp();
+        if let Some(old) = imp.paintable.take() {
+            for handler in imp.handlers.take() {
+                old.disconnect(handler);
+            }
+        }
+
+        if let Some(paintable) = paintable {
+            let paintable = paintable.clone().upcast::<gdk::Paintable>();
+            // videos invalidate on every frame
+            let contents = paintable.connect_invalidate_contents(glib::clone!(
+                #[weak(rename_to=obj)]
+                self,
+                move |_| obj.queue_draw()
+            ));
+            let size = paintable.connect_invalidate_size(glib::clone!(
+                #[weak(rename_to=obj)]
+                self,
+                move |_| obj.queue_draw()
+            ));
+            imp.handlers.replace(vec![contents, size]);
+            imp.paintable.replace(Some(paintable));
+        }
+
+        self.queue_draw();
+    }
+
+    pub fn set_options(&self, options: &ImageOptions) {
+        self.imp().options.replace(options.clone());
+        self.queue_draw();
+    }
+
+    pub fn set_ratio(&self, ratio: f64) {
+        self.imp().ratio.set(ratio);
+        self.queue_draw();
+    }
+}
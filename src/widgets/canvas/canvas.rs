@@ -6,9 +6,10 @@ use gtk::prelude::WidgetExt;
 
 use gtk::{EventControllerKey, GestureClick, Overlay, gdk, glib};
 
+use crate::services::history_manager::HistoryActionKind;
 use crate::utils::{self, WidgetChildrenExt};
 use crate::widgets::canvas::canvas_item::{CanvasItem, CanvasItemExt};
-use crate::widgets::canvas::serialise::CanvasData;
+use crate::widgets::canvas::serialise::{CanvasData, TimerFinish};
 
 mod imp {
     use gtk::glib::Properties;
@@ -24,10 +25,15 @@ mod imp {
     use std::usize;
 
     use super::*;
+    use crate::services::history_manager::HistoryActionKind;
     use crate::utils::WidgetChildrenExt;
     use crate::widgets::canvas::canvas_grid::CanvasGrid;
-    use crate::widgets::canvas::canvas_item::CanvasItem;
-    use crate::widgets::canvas::serialise::CanvasData;
+    use crate::widgets::canvas::canvas_item::{CanvasItem, CanvasItemExt};
+    use crate::widgets::canvas::serialise::{
+        BackgroundGradient, CanvasData, ImageOptions, Resolution, VideoOptions,
+    };
+    use crate::widgets::canvas::timer_item::TimerItem;
+    use crate::widgets::canvas::video_item::VideoItem;
 
     pub(super) const CANVAS_CSS: &str = "
     .view {
@@ -40,6 +46,9 @@ mod imp {
     pub struct ImpCanvas {
         #[property(get, set=Self::set_current_ratio_, construct, default_value=1.0, type = f64)]
         pub current_ratio: Cell<f64>,
+        /// shape of the slide, which sets how wide its 1500 pixels high are
+        #[property(get, set=Self::set_resolution_)]
+        pub resolution: Cell<Resolution>,
         // _current_ratio: Cell<f64>,
         // pub window: glib::WeakRef<SpiceWindow>,
         pub current_allocated_width: Cell<f64>,
@@ -55,6 +64,16 @@ mod imp {
         pub background_color: RefCell<String>,
         #[property(get, set, default_value = "", construct)]
         pub background_pattern: RefCell<String>,
+        #[property(get, set)]
+        pub background_video: RefCell<VideoOptions>,
+        /// drawn over `background_color`
+        #[property(get, set, nullable)]
+        pub background_gradient: RefCell<Option<BackgroundGradient>>,
+        #[property(get, set)]
+        pub background_image: RefCell<ImageOptions>,
+        /// videos only play while set, otherwise a still frame is shown
+        #[property(get, set=Self::set_media_playing_)]
+        pub media_playing: Cell<bool>,
 
         pub widget: RefCell<gtk::Overlay>,
         // pub canvas_items: RefCell<Vec<CanvasItem>>,
@@ -87,6 +106,8 @@ mod imp {
     pub const NEXT_SLIDE: &str = "next-slide";
     pub const PREVIOUS_SLIDE: &str = "previous-slide";
     pub const CLICKED: &str = "clicked";
+    pub const ITEM_CHANGED: &str = "item-changed";
+    pub const TIMER_FINISHED: &str = "timer-finished";
 
     #[glib::derived_properties]
     impl ObjectImpl for ImpCanvas {
@@ -193,6 +214,16 @@ mod imp {
                     Signal::builder(CLICKED)
                         .param_types([GestureClick::static_type()])
                         .build(),
+                    Signal::builder(ITEM_CHANGED)
+                        .param_types([
+                            CanvasItem::static_type(),
+                            HistoryActionKind::static_type(),
+                            bool::static_type(),
+                        ])
+                        .build(),
+                    Signal::builder(TIMER_FINISHED)
+                        .param_types([TimerFinish::static_type()])
+                        .build(),
                 ]
             })
         }
@@ -207,6 +238,22 @@ mod imp {
     impl BoxImpl for ImpCanvas {}
 
     impl ImpCanvas {
+        fn set_media_playing_(&self, value: bool) {
+            if value == self.media_playing.get() {
+                return;
+            }
+            self.media_playing.set(value);
+
+            let obj = self.obj();
+            obj.style();
+            for item in obj.widget().get_children::<VideoItem>() {
+                item.style();
+            }
+            for item in obj.widget().get_children::<TimerItem>() {
+                item.style();
+            }
+        }
+
         fn set_current_ratio_(&self, value: f64) {
             if value <= 0.0 {
                 return;
@@ -218,9 +265,16 @@ mod imp {
             }
         }
 
+        fn set_resolution_(&self, value: Resolution) {
+            if self.resolution.replace(value) != value {
+                self.calculate_ratio();
+                self.widget.borrow().queue_allocate();
+            }
+        }
+
         pub(super) fn calculate_ratio(&self) {
-            let max_width = 1500.0;
-            let max_height = 1500.0;
+            let max_width = self.resolution.get().slide_width();
+            let max_height = Resolution::SLIDE_HEIGHT;
 
             let widget = self.widget.borrow();
             // NOTE: may have to address this in the constructor
@@ -254,6 +308,12 @@ mod imp {
                 .replace(save_data.background_color.clone());
             self.background_pattern
                 .replace(save_data.background_pattern.clone().unwrap_or_default());
+            self.background_video
+                .replace(save_data.background_video.clone());
+            self.background_gradient
+                .replace(save_data.background_gradient.clone());
+            self.background_image
+                .replace(save_data.background_image.clone());
         }
 
         pub(super) fn reorder_overlay<W: IsA<gtk::Widget>>(&self, child: &W, index: usize) {
@@ -337,18 +397,27 @@ impl Canvas {
 
     pub fn style(&self) {
         if let Some(canvas_grid) = self.imp().grid.borrow().clone() {
+            let mut background = self.imp().background_color.borrow().clone();
+            if let Some(gradient) = self.imp().background_gradient.borrow().as_ref() {
+                background = format!("{}, {}", gradient.to_css(), background);
+            }
             utils::set_style(
                 &canvas_grid.clone(),
-                &imp::CANVAS_CSS.replace("{}", &self.imp().background_color.borrow().clone()),
+                &imp::CANVAS_CSS.replace("{}", &background),
             );
 
-            canvas_grid.style(self.imp().background_pattern.borrow().clone());
+            canvas_grid.style(
+                self.imp().background_pattern.borrow().clone(),
+                &self.imp().background_video.borrow(),
+                &self.imp().background_image.borrow(),
+                self.media_playing(),
+            );
         }
 
         self.emit_request_draw_preview();
     }
 
-    pub fn move_up(&self, item_: &CanvasItem, _add_undo_action: Option<bool>) {
+    pub fn move_up(&self, item_: &CanvasItem, add_undo_action: Option<bool>) {
         let mut index = 0;
 
         for child in self.widget().get_children::<CanvasItem>() {
@@ -358,19 +427,14 @@ impl Canvas {
             index += 1
         }
 
-        // if add_undo_action.unwrap_or(true) {
-        //     if let Some(window) = self.imp().window.upgrade() {
-        //         let action = TypedHistoryAction::depth_changed(item_, self, true);
-        //         window
-        //             .history_manager()
-        //             .add_undoable_action(action.into(), Some(true));
-        //     };
-        // }
-
         self.imp().reorder_overlay(item_, index + 1);
+
+        if add_undo_action.unwrap_or(true) {
+            self.emit_item_changed(item_, HistoryActionKind::ItemReordered, true);
+        }
     }
 
-    pub fn move_down(&self, item_: &CanvasItem, _add_undo_action: Option<bool>) {
+    pub fn move_down(&self, item_: &CanvasItem, add_undo_action: Option<bool>) {
         let overlay = self.widget();
         // let mut index: i32 = 0;
 
@@ -382,14 +446,9 @@ impl Canvas {
             self.imp().reorder_overlay(item_, index.saturating_sub(1));
         }
 
-        // if add_undo_action.unwrap_or(true) {
-        //     if let Some(window) = self.imp().window.upgrade() {
-        //         let action = TypedHistoryAction::depth_changed(item_, self, false);
-        //         window
-        //             .history_manager()
-        //             .add_undoable_action(action.into(), Some(true));
-        //     };
-        // }
+        if add_undo_action.unwrap_or(true) {
+            self.emit_item_changed(item_, HistoryActionKind::ItemReordered, true);
+        }
     }
 
     pub fn clear_all(&self) {
@@ -402,7 +461,7 @@ impl Canvas {
         }
     }
 
-    pub fn add_item(&self, canvas_item: CanvasItem, _undoable_action: bool) -> CanvasItem {
+    pub fn add_item(&self, canvas_item: CanvasItem, undoable_action: bool) -> CanvasItem {
         self.widget().add_overlay(&canvas_item);
 
         canvas_item.connect_checkposition({
@@ -442,16 +501,9 @@ impl Canvas {
             }
         ));
 
-        // if undoable_action {
-        //     canvas_item.set_visible(false);
-        //     let action = TypedHistoryAction::item_changed(&canvas_item, "visible");
-        //     if let Some(window) = self.imp().window.upgrade() {
-        //         window
-        //             .history_manager()
-        //             .add_undoable_action(action.into(), None);
-        //     };
-        //     canvas_item.set_visible(true);
-        // }
+        if undoable_action {
+            self.emit_item_changed(&canvas_item, HistoryActionKind::ItemAdded, true);
+        }
 
         self.emit_request_draw_preview();
 
@@ -470,6 +522,9 @@ impl Canvas {
         CanvasData {
             background_color: self.imp().background_color.borrow().clone(),
             background_pattern: (!bg_pattern.is_empty()).then(|| bg_pattern),
+            background_video: self.imp().background_video.borrow().clone(),
+            background_gradient: self.imp().background_gradient.borrow().clone(),
+            background_image: self.imp().background_image.borrow().clone(),
         }
     }
 
@@ -522,6 +577,12 @@ impl Canvas {
         self.emit_by_name::<()>(imp::ITEM_CLICKED, &[&item]);
     }
 
+    /// Reports an undoable change of `item`, `force` keeps it
+    /// from being merged with the previous change
+    pub fn emit_item_changed(&self, item: &CanvasItem, kind: HistoryActionKind, force: bool) {
+        self.emit_by_name::<()>(imp::ITEM_CHANGED, &[item, &kind, &force]);
+    }
+
     pub fn emit_request_draw_preview(&self) {
         self.emit_by_name::<()>(imp::REQUEST_DRAW_PREVIEW, &[]);
     }
@@ -531,6 +592,10 @@ impl Canvas {
     pub fn emit_previous_slide(&self) {
         self.emit_by_name::<()>(imp::PREVIOUS_SLIDE, &[]);
     }
+    /// A countdown on the canvas reached zero and asks for `action`
+    pub fn emit_timer_finished(&self, action: TimerFinish) {
+        self.emit_by_name::<()>(imp::TIMER_FINISHED, &[&action]);
+    }
 
     pub fn connect_clicked<F: Fn(&GestureClick) + 'static>(&self, f: F) -> glib::SignalHandlerId {
         self.connect_closure(
@@ -569,6 +634,16 @@ impl Canvas {
             }),
         )
     }
+    pub fn connect_timer_finished<F: Fn(TimerFinish) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_closure(
+            imp::TIMER_FINISHED,
+            false,
+            glib::closure_local!(move |_: &Self, action: TimerFinish| f(action)),
+        )
+    }
     pub fn connect_item_clicked<F: Fn(Option<CanvasItem>) + 'static>(
         &self,
         f: F,
@@ -581,6 +656,21 @@ impl Canvas {
             }),
         )
     }
+    pub fn connect_item_changed<F: Fn(&CanvasItem, HistoryActionKind, bool) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_closure(
+            imp::ITEM_CHANGED,
+            false,
+            glib::closure_local!(move |_: &Self,
+                                       ci: &CanvasItem,
+                                       kind: HistoryActionKind,
+                                       force: bool| {
+                f(ci, kind, force);
+            }),
+        )
+    }
     pub fn connect_ratio_changed<F: Fn(f64) + 'static>(&self, f: F) -> glib::SignalHandlerId {
         self.connect_closure(
             imp::RATIO_CHANGED,
@@ -9,7 +9,10 @@ use std::str;
 
 use crate::services::file_manager::FileManager;
 use crate::utils::{self, WidgetExtrasExt};
+use crate::widgets::canvas::background_picture::BackgroundPicture;
 use crate::widgets::canvas::canvas::Canvas;
+use crate::widgets::canvas::serialise::{ImageOptions, VideoOptions};
+use crate::widgets::canvas::video_item::play_video;
 
 mod imp {
     use super::*;
@@ -21,7 +24,9 @@ mod imp {
 
         //
         pub(super) stack: RefCell<gtk::Stack>,
-        pub(super) picture: RefCell<gtk::Picture>,
+        pub(super) picture: RefCell<BackgroundPicture>,
+        /// playing background video with the path and options it was started with
+        pub(super) media: RefCell<Option<(String, VideoOptions, gtk::MediaFile)>>,
     }
 
     #[glib::object_subclass]
@@ -33,6 +38,7 @@ mod imp {
 
     impl ObjectImpl for ImpCanvasGrid {
         fn dispose(&self) {
+            self.obj().stop_media();
             self.picture.borrow().unparent();
             self.stack.borrow().unparent();
             self.grid.borrow().unparent();
@@ -90,7 +96,12 @@ impl CanvasGrid {
         let stack = imp.stack.borrow().clone();
         grid.attach(&stack, 0, 0, 1, 1);
         let picture = imp.picture.borrow().clone();
-        picture.set_content_fit(gtk::ContentFit::Cover);
+        picture.set_ratio(canvas.current_ratio());
+        canvas.connect_ratio_changed(glib::clone!(
+            #[weak]
+            picture,
+            move |ratio| picture.set_ratio(ratio)
+        ));
 
         stack.add_named(&picture, Some("image"));
         stack.set_visible_child_name("image");
@@ -100,13 +111,56 @@ impl CanvasGrid {
         obj
     }
 
-    pub fn style(&self, pattern: String) {
+    pub fn style(
+        &self,
+        pattern: String,
+        video: &VideoOptions,
+        image: &ImageOptions,
+        playing: bool,
+    ) {
         let grid = self.imp().grid.borrow().clone();
 
         let has_pattern = !pattern.is_empty() && Path::new(&pattern).exists();
+        let is_video = has_pattern && FileManager::is_video(Path::new(&pattern));
+        if !(is_video && playing) {
+            self.stop_media();
+        }
 
         let picture = self.imp().picture.borrow().clone();
+        picture.set_options(image);
         let res = match has_pattern {
+            true if is_video && playing => {
+                let started = self
+                    .imp()
+                    .media
+                    .borrow()
+                    .as_ref()
+                    .is_some_and(|(current, options, _)| *current == pattern && options == video);
+                if !started {
+                    self.stop_media();
+                    let media = play_video(&pattern, video);
+                    picture.set_paintable(Some(&media));
+                    self.imp()
+                        .media
+                        .replace(Some((pattern, video.clone(), media)));
+                }
+                Self::pattern_css()
+            }
+            true if is_video => {
+                let path = std::path::PathBuf::from(pattern);
+                FileManager::get_video_frame(&path, video.start_offset, None, {
+                    let grid = self.downgrade();
+                    move |v| {
+                        // playback may have started while the frame loaded
+                        if let Some(grid) = grid.upgrade()
+                            && grid.imp().media.borrow().is_none()
+                        {
+                            picture.set_paintable(v.as_ref());
+                        }
+                    }
+                });
+                Self::pattern_css()
+            }
             true => {
                 let path = std::path::PathBuf::from(pattern);
                 FileManager::get_background_image(&path.clone(), None, move |v| {
@@ -123,6 +177,12 @@ impl CanvasGrid {
         utils::set_style(&grid, &res);
     }
 
+    fn stop_media(&self) {
+        if let Some((_, _, media)) = self.imp().media.take() {
+            media.pause();
+        }
+    }
+
     fn pattern_css() -> String {
         format!(
             r##".ow-pattern {{
@@ -14,12 +14,12 @@ mod imp {
     use gtk::prelude::*;
     use gtk::subclass::prelude::*;
 
+    use crate::services::history_manager::HistoryActionKind;
     use crate::utils::{self, WidgetChildrenExt, WidgetExtrasExt};
-    // use crate::services::history_manager::history_action::{HistoryAction, TypedHistoryAction};
     // use crate::services::utils::{self, rect};
     use crate::widgets::canvas::canvas::Canvas;
     use crate::widgets::canvas::grabber::Grabber;
-    use crate::widgets::canvas::serialise::CanvasItemData;
+    use crate::widgets::canvas::serialise::{CanvasItemData, ItemStyle};
 
     use super::*;
 
@@ -35,7 +35,6 @@ mod imp {
         #[property(get, set, default_value = 0)]
         pub delta_y: Cell<i32>,
 
-        // pub(super) undo_move_action: RefCell<HistoryAction>,
         #[property(get=Self::item_visible_, set=Self::set_item_visible_)]
         pub item_visible: Cell<bool>,
 
@@ -59,7 +58,7 @@ mod imp {
         pub real_height: Cell<i32>,
         pub real_x: Cell<i32>,
         pub real_y: Cell<i32>,
-        pub rotation: Cell<f64>,
+        pub item_style: RefCell<ItemStyle>,
 
         pub grid: RefCell<gtk::Grid>,
         pub grabber_revealer: RefCell<gtk::Revealer>,
@@ -360,6 +359,7 @@ mod imp {
             self.real_height.set(save_data.h);
             self.real_x.set(save_data.x);
             self.real_y.set(save_data.y);
+            self.item_style.replace(save_data.style);
 
             self.obj().load_item_data();
             self.emit_checkposition();
@@ -494,9 +494,6 @@ mod imp {
                 return true;
             }
 
-            // let undo_move_action = TypedHistoryAction::item_moved(&self.obj().clone());
-            // self.undo_move_action.replace(undo_move_action.into());
-
             self.start_x.set(x);
             self.start_y.set(y);
 
@@ -530,18 +527,21 @@ mod imp {
                 return false;
             }
 
-            // if let Some(canvas) = self.canvas.upgrade()
-            //     && let Some(window) = canvas.imp().window.upgrade()
-            // {
-            //     window
-            //         .history_manager()
-            //         .add_undoable_action(self.undo_move_action.borrow().clone(), None);
-            // }
+            let kind = match self.start_w.get() == self.real_width.get()
+                && self.start_h.get() == self.real_height.get()
+            {
+                true => HistoryActionKind::ItemMoved,
+                false => HistoryActionKind::ItemResized,
+            };
 
             self.emit_move_item(self.delta_x.get(), self.delta_y.get());
             self.delta_x.set(0);
             self.delta_y.set(0);
 
+            if let Some(canvas) = self.canvas.upgrade() {
+                canvas.emit_item_changed(&self.obj(), kind, false);
+            }
+
             false
         }
 
@@ -661,19 +661,13 @@ mod imp {
         }
 
         pub fn delete(&self) {
-            let Some(_) = self.canvas.upgrade() else {
+            let Some(canvas) = self.canvas.upgrade() else {
                 return;
             };
-            // let Some(window) = canvas.imp().window.upgrade() else {
-            //     return;
-            // };
-            // let obj = self.obj().clone();
-            // let action = TypedHistoryAction::item_changed(&obj, "item-visible");
-            // window
-            //     .history_manager()
-            //     .add_undoable_action(action.into(), Some(true));
 
-            self.obj().set_item_visible(false);
+            let obj = self.obj().clone();
+            obj.set_item_visible(false);
+            canvas.emit_item_changed(&obj, HistoryActionKind::ItemDeleted, true);
         }
 
         fn item_visible_(&self) -> bool {
@@ -689,7 +683,8 @@ mod imp {
             let w = self.real_width.get();
             let h = self.real_height.get();
             let item_type = self.obj().serialise_item();
-            let data = CanvasItemData::new(x, y, w, h, item_type);
+            let mut data = CanvasItemData::new(x, y, w, h, item_type);
+            data.style = self.item_style.borrow().clone();
             data
         }
 
@@ -699,13 +694,51 @@ mod imp {
             panic!("Implement virtual method `serialise_item` for your widget")
         }
         fn style_default(&self) {
+            let style = self.item_style.borrow().clone();
+            let ratio = self
+                .canvas
+                .upgrade()
+                .map(|c| c.current_ratio())
+                .unwrap_or(1.0);
+
+            let border = match style.border_width > 0.0 {
+                true => format!(
+                    "{}px solid {}",
+                    style.border_width * ratio,
+                    style.border_color
+                ),
+                false => "none".to_string(),
+            };
+            let shadow = match &style.shadow {
+                Some(shadow) => format!(
+                    "{}px {}px {}px {}",
+                    shadow.offset_x * ratio,
+                    shadow.offset_y * ratio,
+                    shadow.blur * ratio,
+                    shadow.color
+                ),
+                None => "none".to_string(),
+            };
+
             let css = format!(
                 ".ow-canvas-item {{
                   transform: rotate({}deg);
+                  opacity: {};
+                  border: {border};
+                  border-radius: {}px;
+                  box-shadow: {shadow};
                 }}",
-                self.rotation.get()
+                style.rotation,
+                style.opacity.clamp(0.0, 1.0),
+                style.border_radius * ratio,
             );
-            utils::set_style(&self.obj().clone(), &css);
+
+            let obj = self.obj();
+            obj.set_overflow(match style.border_radius > 0.0 {
+                true => gtk::Overflow::Hidden,
+                false => gtk::Overflow::Visible,
+            });
+            utils::set_style(&obj.clone(), &css);
         }
 
         pub fn is_presentation_mode(&self) -> bool {
@@ -726,7 +759,7 @@ use gtk::prelude::*;
 use gtk::subclass::box_::BoxImpl;
 use gtk::subclass::prelude::*;
 
-use crate::widgets::canvas::serialise::{CanvasItemData, CanvasItemType};
+use crate::widgets::canvas::serialise::{CanvasItemData, CanvasItemType, ItemStyle};
 
 pub(super) mod signals {
     pub const CLICKED: &str = "clicked";
@@ -767,6 +800,22 @@ pub trait CanvasItemExt: IsA<CanvasItem> {
         self.upcast_ref::<CanvasItem>().imp().delete();
     }
 
+    fn item_style(&self) -> ItemStyle {
+        self.upcast_ref::<CanvasItem>()
+            .imp()
+            .item_style
+            .borrow()
+            .clone()
+    }
+    /// Replaces the shared item styling and restyles the item
+    fn set_item_style(&self, style: ItemStyle) {
+        self.upcast_ref::<CanvasItem>()
+            .imp()
+            .item_style
+            .replace(style);
+        self.style();
+    }
+
     fn add_overlay_child<W: IsA<gtk::Widget>>(&self, child: &W) {
         self.upcast_ref::<CanvasItem>()
             .imp()
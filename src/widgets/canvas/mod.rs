@@ -1,15 +1,22 @@
+pub mod background_picture;
 pub mod canvas;
 pub mod canvas_grid;
 pub mod canvas_item;
 mod grabber;
 pub mod image_item;
+pub mod screen_cover;
 pub mod serialise;
+pub mod shape_item;
 pub mod text_item;
+pub mod timer_item;
+pub mod transition_layer;
+pub mod video_item;
 
 #[derive(Default, Debug, PartialEq, Eq, Clone, Copy)]
 pub enum CanvasItemType {
     #[default]
     TEXT,
     IMAGE,
-    // SHAPE,
+    SHAPE,
+    TIMER,
 }
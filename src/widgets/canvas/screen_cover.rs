@@ -0,0 +1,188 @@
+use adw::prelude::AnimationExt;
+use gtk::glib;
+use gtk::glib::subclass::prelude::*;
+use gtk::prelude::*;
+use gtk::{gdk, graphene};
+
+use crate::services::{file_manager::FileManager, settings::ApplicationSettings};
+
+/// What the live output shows
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, glib::Enum)]
+#[enum_type(name = "OwScreenMode")]
+pub enum ScreenMode {
+    /// the current slide
+    #[default]
+    Live,
+    /// a black screen
+    Black,
+    /// the `logo-image` over the `logo-background` colour
+    Logo,
+}
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use gtk::subclass::widget::WidgetImpl;
+
+    use super::*;
+
+    /// Covers the slideshow with black or the logo, fading in and out
+    /// over the `transition-duration` setting
+    #[derive(Default)]
+    pub struct ScreenCover {
+        pub mode: Cell<ScreenMode>,
+        /// mode drawn, kept while fading back to [ScreenMode::Live]
+        pub shown: Cell<ScreenMode>,
+        pub opacity: Cell<f64>,
+        pub animation: RefCell<Option<adw::TimedAnimation>>,
+        pub logo: RefCell<Option<gdk::Texture>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ScreenCover {
+        const NAME: &'static str = "ScreenCover";
+        type Type = super::ScreenCover;
+        type ParentType = gtk::Widget;
+    }
+
+    impl ObjectImpl for ScreenCover {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+            obj.set_can_target(false);
+            obj.set_overflow(gtk::Overflow::Hidden);
+
+            let settings = ApplicationSettings::get_instance();
+            settings.connect_logo_image_changed(glib::clone!(
+                #[weak]
+                obj,
+                move |_| obj.load_logo()
+            ));
+            settings.connect_logo_background_changed(glib::clone!(
+                #[weak]
+                obj,
+                move |_| obj.queue_draw()
+            ));
+            obj.load_logo();
+        }
+    }
+
+    impl WidgetImpl for ScreenCover {
+        fn snapshot(&self, snapshot: &gtk::Snapshot) {
+            let opacity = self.opacity.get();
+            let shown = self.shown.get();
+            if opacity <= 0.0 || shown == ScreenMode::Live {
+                return;
+            }
+
+            let obj = self.obj();
+            let (width, height) = (obj.width() as f32, obj.height() as f32);
+            let bounds = graphene::Rect::new(0.0, 0.0, width, height);
+
+            snapshot.push_opacity(opacity);
+            let background = match shown {
+                ScreenMode::Logo => {
+                    let hex = ApplicationSettings::get_instance().logo_background();
+                    gdk::RGBA::parse(hex.as_str()).unwrap_or(gdk::RGBA::BLACK)
+                }
+                _ => gdk::RGBA::BLACK,
+            };
+            snapshot.append_color(&background, &bounds);
+
+            if shown == ScreenMode::Logo
+                && let Some(logo) = self.logo.borrow().as_ref()
+            {
+                // fit the logo inside the screen, centred
+                let (w, h) = (logo.width() as f32, logo.height() as f32);
+                let scale = (width / w).min(height / h);
+                let (w, h) = (w * scale, h * scale);
+                let rect = graphene::Rect::new((width - w) / 2.0, (height - h) / 2.0, w, h);
+                snapshot.append_texture(logo, &rect);
+            }
+            snapshot.pop();
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct ScreenCover(ObjectSubclass<imp::ScreenCover>)
+        @extends gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Default for ScreenCover {
+    fn default() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl ScreenCover {
+    pub fn mode(&self) -> ScreenMode {
+        self.imp().mode.get()
+    }
+
+    /// Fades the cover for `mode` in, or out for [ScreenMode::Live]
+    pub fn set_mode(&self, mode: ScreenMode) {
+        let imp = self.imp();
+        if imp.mode.replace(mode) == mode {
+            return;
+        }
+        if let Some(animation) = imp.animation.take() {
+            animation.pause();
+        }
+
+        let to = match mode {
+            ScreenMode::Live => 0.0,
+            _ => {
+                imp.shown.set(mode);
+                1.0
+            }
+        };
+        let duration = ApplicationSettings::get_instance().transition_duration();
+        if !self.is_mapped() || duration <= 0.0 {
+            imp.opacity.set(to);
+            self.queue_draw();
+            return;
+        }
+
+        let target = adw::CallbackAnimationTarget::new(glib::clone!(
+            #[weak(rename_to = obj)]
+            self,
+            move |opacity| {
+                obj.imp().opacity.set(opacity);
+                obj.queue_draw();
+            }
+        ));
+        let animation = adw::TimedAnimation::new(
+            self,
+            imp.opacity.get(),
+            to,
+            (duration * 1000.0) as u32,
+            target,
+        );
+        animation.play();
+        imp.animation.replace(Some(animation));
+    }
+
+    fn load_logo(&self) {
+        let path = ApplicationSettings::get_instance().logo_image();
+        if path.is_empty() {
+            self.imp().logo.replace(None);
+            self.queue_draw();
+            return;
+        }
+
+        FileManager::get_background_image(
+            std::path::Path::new(&path),
+            None,
+            glib::clone!(
+                #[weak(rename_to = obj)]
+                self,
+                move |texture| {
+                    obj.imp().logo.replace(texture);
+                    obj.queue_draw();
+                }
+            ),
+        );
+    }
+}
@@ -1,7 +1,10 @@
 use gtk::glib;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::{app_config::AppConfig, services::settings::ApplicationSettings};
+use crate::{
+    app_config::AppConfig,
+    services::{settings::ApplicationSettings, text_fit::TextFit, transition::SlideTransition},
+};
 
 pub const MAGIC_HEADER: &[u8] = b"OPW\x01";
 
@@ -13,13 +16,229 @@ pub struct TextItemData {
     pub font: String,
     #[serde(rename = "font-size")]
     pub font_size: f32,
-    pub justification: u32, // should be enum
-    pub align: u32,         // should be enum
+    pub justification: TextJustification,
+    pub align: TextAlign,
+    /// multiple of the font line height
+    #[serde(rename = "line-spacing", default = "default_line_spacing")]
+    pub line_spacing: f64,
+    #[serde(rename = "letter-spacing", default)]
+    pub letter_spacing: f64,
+    /// space between the item edges and the text
+    #[serde(default)]
+    pub padding: f64,
+    #[serde(default)]
+    pub direction: TextDirection,
+    /// what happens when the text is too big for the item
+    #[serde(default)]
+    pub fit: TextFit,
+    /// rgba hex of text without a colour of its own in the markup
+    #[serde(default = "default_text_color")]
+    pub color: String,
 
     #[serde(rename = "text-outline")]
     pub text_outline: bool,
     #[serde(rename = "text-shadow")]
     pub text_shadow: bool,
+    #[serde(rename = "outline-style", default)]
+    pub outline_style: TextOutline,
+    #[serde(rename = "shadow-style", default)]
+    pub shadow_style: DropShadow,
+}
+
+fn default_line_spacing() -> f64 {
+    1.0
+}
+
+fn default_text_color() -> String {
+    "#ffffffff".into()
+}
+
+/// Enums that older files stored as a bare index of their variant
+pub trait IndexedEnum: Sized + Copy + PartialEq + Default + 'static {
+    const ALL: &'static [Self];
+
+    fn name(self) -> &'static str;
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|v| v.name() == name)
+    }
+
+    fn from_index(index: u32) -> Option<Self> {
+        Self::ALL.get(index as usize).copied()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum IndexedRepr {
+    Index(u32),
+    Name(String),
+}
+
+/// Reads a variant name or an old index, unknown values fall back to the default
+fn deserialize_indexed<'de, D: Deserializer<'de>, T: IndexedEnum>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    let value = match IndexedRepr::deserialize(deserializer)? {
+        IndexedRepr::Index(index) => T::from_index(index),
+        IndexedRepr::Name(name) => T::from_name(&name),
+    };
+    Ok(value.unwrap_or_default())
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, glib::Enum)]
+#[enum_type(name = "OwTextJustification")]
+pub enum TextJustification {
+    Left,
+    #[default]
+    Center,
+    Right,
+    Fill,
+}
+
+impl IndexedEnum for TextJustification {
+    const ALL: &'static [Self] = &[Self::Left, Self::Center, Self::Right, Self::Fill];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Left => "left",
+            Self::Center => "center",
+            Self::Right => "right",
+            Self::Fill => "fill",
+        }
+    }
+}
+
+impl Serialize for TextJustification {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for TextJustification {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_indexed(deserializer)
+    }
+}
+
+/// Vertical position of the text inside its item
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, glib::Enum)]
+#[enum_type(name = "OwTextAlign")]
+pub enum TextAlign {
+    Top,
+    #[default]
+    Middle,
+    Bottom,
+}
+
+impl IndexedEnum for TextAlign {
+    const ALL: &'static [Self] = &[Self::Top, Self::Middle, Self::Bottom];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Top => "top",
+            Self::Middle => "middle",
+            Self::Bottom => "bottom",
+        }
+    }
+}
+
+impl Serialize for TextAlign {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for TextAlign {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_indexed(deserializer)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, glib::Enum)]
+#[enum_type(name = "OwTextDirection")]
+pub enum TextDirection {
+    #[default]
+    #[serde(rename = "ltr")]
+    Ltr,
+    #[serde(rename = "rtl")]
+    Rtl,
+}
+
+/// Stroke drawn around text when `text_outline` is on, sizes are in slide pixels
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, glib::Boxed)]
+#[boxed_type(name = "TextOutline")]
+#[serde(default)]
+pub struct TextOutline {
+    /// rgba hex, e.g. `#000000ff`
+    pub color: String,
+    pub width: f64,
+}
+
+impl Default for TextOutline {
+    fn default() -> Self {
+        Self {
+            color: "#000000ff".into(),
+            width: 6.0,
+        }
+    }
+}
+
+/// Shadow cast by text or a whole canvas item, sizes are in slide pixels
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, glib::Boxed)]
+#[boxed_type(name = "DropShadow")]
+#[serde(default)]
+pub struct DropShadow {
+    pub color: String,
+    pub blur: f64,
+    #[serde(rename = "offset-x")]
+    pub offset_x: f64,
+    #[serde(rename = "offset-y")]
+    pub offset_y: f64,
+}
+
+impl Default for DropShadow {
+    fn default() -> Self {
+        Self {
+            color: "#000000ff".into(),
+            blur: 0.0,
+            offset_x: 10.0,
+            offset_y: 10.0,
+        }
+    }
+}
+
+/// Styling shared by every canvas item, sizes are in slide pixels
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, glib::Boxed)]
+#[boxed_type(name = "ItemStyle")]
+#[serde(default)]
+pub struct ItemStyle {
+    /// degrees, clockwise
+    pub rotation: f64,
+    /// 0.0 to 1.0
+    pub opacity: f64,
+    #[serde(rename = "border-color")]
+    pub border_color: String,
+    /// no border when 0
+    #[serde(rename = "border-width")]
+    pub border_width: f64,
+    #[serde(rename = "border-radius")]
+    pub border_radius: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shadow: Option<DropShadow>,
+}
+
+impl Default for ItemStyle {
+    fn default() -> Self {
+        Self {
+            rotation: 0.0,
+            opacity: 1.0,
+            border_color: "#ffffffff".into(),
+            border_width: 0.0,
+            border_radius: 0.0,
+            shadow: None,
+        }
+    }
 }
 
 #[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, /* Eq, */ glib::Boxed)]
@@ -28,12 +247,178 @@ pub struct ImageItemData {
     pub url: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, glib::Boxed)]
+#[boxed_type(name = "VideoOptions")]
+#[serde(default)]
+pub struct VideoOptions {
+    #[serde(rename = "loop")]
+    pub loop_video: bool,
+    pub muted: bool,
+    /// milliseconds into the video where playback starts
+    #[serde(rename = "start-offset")]
+    pub start_offset: u32,
+}
+
+impl Default for VideoOptions {
+    fn default() -> Self {
+        Self {
+            loop_video: true,
+            muted: true,
+            start_offset: 0,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq, glib::Boxed)]
+#[boxed_type(name = "VideoItemData")]
+pub struct VideoItemData {
+    pub url: String,
+    #[serde(flatten)]
+    pub options: VideoOptions,
+}
+
+/// Audio file played alongside a slide or a whole schedule item
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, glib::Boxed)]
+#[boxed_type(name = "AudioCue", nullable)]
+#[serde(default)]
+pub struct AudioCue {
+    pub url: String,
+    #[serde(rename = "play-on-show")]
+    pub play_on_show: bool,
+    #[serde(rename = "stop-on-leave")]
+    pub stop_on_leave: bool,
+    #[serde(rename = "loop")]
+    pub loop_audio: bool,
+    /// 0.0 to 1.0
+    pub volume: f64,
+}
+
+impl Default for AudioCue {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            play_on_show: true,
+            stop_on_leave: true,
+            loop_audio: false,
+            volume: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, glib::Enum)]
+#[enum_type(name = "OwShapeKind")]
+pub enum ShapeKind {
+    #[default]
+    #[serde(rename = "rectangle")]
+    Rectangle,
+    #[serde(rename = "rounded-rectangle")]
+    RoundedRectangle,
+    #[serde(rename = "ellipse")]
+    Ellipse,
+    #[serde(rename = "line")]
+    Line,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, /* Eq, */ glib::Boxed)]
+#[boxed_type(name = "ShapeItemData")]
+#[serde(default)]
+pub struct ShapeItemData {
+    pub shape: ShapeKind,
+    /// rgba hex, e.g. `#000000b3`
+    pub fill: String,
+    pub stroke: String,
+    #[serde(rename = "stroke-width")]
+    pub stroke_width: f64,
+    /// 0.0 to 1.0, applied to fill and stroke together
+    pub opacity: f64,
+    #[serde(rename = "corner-radius")]
+    pub corner_radius: f64,
+}
+
+impl Default for ShapeItemData {
+    fn default() -> Self {
+        Self {
+            shape: ShapeKind::default(),
+            fill: "#000000b3".into(),
+            stroke: "#ffffffff".into(),
+            stroke_width: 0.0,
+            opacity: 1.0,
+            corner_radius: 24.0,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, glib::Enum)]
+#[enum_type(name = "OwTimerMode")]
+pub enum TimerMode {
+    /// counts down `duration` from when the slide goes live
+    #[default]
+    #[serde(rename = "countdown")]
+    Countdown,
+    /// counts down to the next `target` time of day
+    #[serde(rename = "countdown-to")]
+    CountdownTo,
+    #[serde(rename = "count-up")]
+    CountUp,
+    #[serde(rename = "clock")]
+    Clock,
+}
+
+/// What the live output does when a countdown reaches zero
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, glib::Enum)]
+#[enum_type(name = "OwTimerFinish")]
+pub enum TimerFinish {
+    #[default]
+    #[serde(rename = "none")]
+    None,
+    #[serde(rename = "next-slide")]
+    NextSlide,
+    #[serde(rename = "show-logo")]
+    ShowLogo,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, glib::Boxed)]
+#[boxed_type(name = "TimerItemData")]
+#[serde(default)]
+pub struct TimerItemData {
+    pub mode: TimerMode,
+    /// seconds, for [TimerMode::Countdown]
+    pub duration: u32,
+    /// time of day as `HH:MM` or `HH:MM:SS`, for [TimerMode::CountdownTo]
+    pub target: String,
+    /// see [crate::services::timer::format_time], empty for the mode default
+    pub format: String,
+    pub font: String,
+    #[serde(rename = "font-size")]
+    pub font_size: f32,
+    pub color: String,
+    #[serde(rename = "on-finish")]
+    pub on_finish: TimerFinish,
+}
+
+impl Default for TimerItemData {
+    fn default() -> Self {
+        Self {
+            mode: TimerMode::default(),
+            duration: 300,
+            target: "09:00".into(),
+            format: String::new(),
+            font: "Sans Bold".into(),
+            font_size: 60.0,
+            color: default_text_color(),
+            on_finish: TimerFinish::default(),
+        }
+    }
+}
+
 #[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq /* Eq */)]
 pub struct CanvasItemData {
     pub x: i32,
     pub y: i32,
     pub w: i32,
     pub h: i32,
+    #[serde(default)]
+    pub style: ItemStyle,
     #[serde(flatten)]
     pub item_type: CanvasItemType,
 }
@@ -44,19 +429,267 @@ impl CanvasItemData {
             y,
             w,
             h,
+            style: ItemStyle::default(),
             item_type,
         }
     }
+
+    /// Moves the item from a slide of `from` resolution to one of `to`.
+    ///
+    /// Slides are always 1500 pixels high so only the horizontal geometry
+    /// changes. Text boxes stretch with the slide so their margins keep
+    /// their share of the width, other items keep their size and centre
+    /// and shrink when wider than the new slide. Auto sized items fill
+    /// whatever slide they are on.
+    pub fn remap(&mut self, from: Resolution, to: Resolution) {
+        let (old_width, new_width) = (from.slide_width(), to.slide_width());
+        if old_width == new_width || self.w < 0 {
+            return;
+        }
+        let scale = new_width / old_width;
+
+        if let CanvasItemType::Text(_) = self.item_type {
+            self.x = (self.x as f64 * scale).round() as i32;
+            self.w = (self.w as f64 * scale).round() as i32;
+            return;
+        }
+
+        let (mut w, mut h) = (self.w as f64, self.h as f64);
+        let centre = (self.x as f64 + w / 2.0) * scale;
+        let centre_y = self.y as f64 + h / 2.0;
+        if w > new_width {
+            h *= new_width / w;
+            w = new_width;
+        }
+        self.x = (centre - w / 2.0).round() as i32;
+        self.y = (centre_y - h / 2.0).round() as i32;
+        self.w = w.round() as i32;
+        self.h = h.round() as i32;
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, glib::Boxed)]
+/// Design resolution of a presentation, which sets the shape of its slides.
+/// Outputs of another shape letterbox the slides.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, glib::Boxed)]
+#[boxed_type(name = "OwResolution")]
+pub struct Resolution {
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Default for Resolution {
+    fn default() -> Self {
+        let (width, height) = AppConfig::aspect_size();
+        Self { width, height }
+    }
+}
+
+impl Resolution {
+    /// Height of every slide in the units canvas items are placed in
+    pub const SLIDE_HEIGHT: f64 = 1500.0;
+
+    /// Resolutions offered when choosing one, with their names
+    pub const PRESETS: [(&str, Resolution); 6] = [
+        ("16:9 (1920×1080)", Resolution::new(1920, 1080)),
+        ("16:10 (1920×1200)", Resolution::new(1920, 1200)),
+        ("4:3 (1024×768)", Resolution::new(1024, 768)),
+        ("21:9 (2560×1080)", Resolution::new(2560, 1080)),
+        ("32:9 (3840×1080)", Resolution::new(3840, 1080)),
+        ("9:16 (1080×1920)", Resolution::new(1080, 1920)),
+    ];
+
+    pub const fn new(width: i32, height: i32) -> Self {
+        Self { width, height }
+    }
+
+    pub fn ratio(&self) -> f32 {
+        self.ratio_f64() as f32
+    }
+
+    fn ratio_f64(&self) -> f64 {
+        self.width.max(1) as f64 / self.height.max(1) as f64
+    }
+
+    /// Width of a slide in canvas item units
+    pub fn slide_width(&self) -> f64 {
+        Self::SLIDE_HEIGHT * self.ratio_f64()
+    }
+
+    /// Size of a slide shown as large as possible inside `width` by
+    /// `height`, leaving bars on the sides or top and bottom
+    pub fn letterbox(&self, width: f64, height: f64) -> (f64, f64) {
+        let ratio = self.ratio_f64();
+        match width / height.max(1.0) > ratio {
+            true => (height * ratio, height),
+            false => (width, width / ratio),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, glib::Enum)]
+#[enum_type(name = "OwGradientKind")]
+pub enum GradientKind {
+    #[default]
+    #[serde(rename = "linear")]
+    Linear,
+    #[serde(rename = "radial")]
+    Radial,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct GradientStop {
+    /// rgba hex, e.g. `#000000ff`
+    pub color: String,
+    /// 0.0 to 1.0 along the gradient
+    pub offset: f64,
+}
+
+/// Gradient painted over the background colour and under any image or video
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, glib::Boxed)]
+#[boxed_type(name = "BackgroundGradient", nullable)]
+#[serde(default)]
+pub struct BackgroundGradient {
+    pub kind: GradientKind,
+    /// degrees, only used by linear gradients, 180 runs top to bottom
+    pub angle: f64,
+    pub stops: Vec<GradientStop>,
+}
+
+impl Default for BackgroundGradient {
+    fn default() -> Self {
+        Self {
+            kind: GradientKind::default(),
+            angle: 180.0,
+            stops: vec![
+                GradientStop {
+                    color: "#383e41ff".into(),
+                    offset: 0.0,
+                },
+                GradientStop {
+                    color: "#000000ff".into(),
+                    offset: 1.0,
+                },
+            ],
+        }
+    }
+}
+
+impl BackgroundGradient {
+    pub fn to_css(&self) -> String {
+        let stops = self
+            .stops
+            .iter()
+            .map(|s| format!("{} {}%", s.color, s.offset.clamp(0.0, 1.0) * 100.0))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        match self.kind {
+            GradientKind::Linear => format!("linear-gradient({}deg, {})", self.angle, stops),
+            GradientKind::Radial => format!("radial-gradient(circle, {})", stops),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, glib::Enum)]
+#[enum_type(name = "OwImageFit")]
+pub enum ImageFit {
+    /// fill the slide, cropping the overflow
+    #[default]
+    #[serde(rename = "cover")]
+    Cover,
+    /// show the whole image, leaving bars
+    #[serde(rename = "contain")]
+    Contain,
+    /// fill the slide, ignoring the aspect ratio
+    #[serde(rename = "stretch")]
+    Stretch,
+    /// repeat the image at its own size
+    #[serde(rename = "tile")]
+    Tile,
+}
+
+impl ImageFit {
+    /// Where an image of `src` size is drawn inside `dest`, as `(x, y, width, height)`.
+    ///
+    /// `position` is `(x, y)` from 0.0 to 1.0, picking which part of the image stays
+    /// visible when cropped or where it sits when it does not fill `dest`.
+    /// `scale` sizes tiles, which are otherwise drawn at the image's own size.
+    pub fn place(
+        &self,
+        src: (f64, f64),
+        dest: (f64, f64),
+        position: (f64, f64),
+        scale: f64,
+    ) -> (f64, f64, f64, f64) {
+        let (src_w, src_h) = (src.0.max(1.0), src.1.max(1.0));
+        let (width, height) = match self {
+            Self::Stretch => return (0.0, 0.0, dest.0, dest.1),
+            Self::Cover => {
+                let s = (dest.0 / src_w).max(dest.1 / src_h);
+                (src_w * s, src_h * s)
+            }
+            Self::Contain => {
+                let s = (dest.0 / src_w).min(dest.1 / src_h);
+                (src_w * s, src_h * s)
+            }
+            Self::Tile => (src_w * scale, src_h * scale),
+        };
+
+        let x = (dest.0 - width) * position.0.clamp(0.0, 1.0);
+        let y = (dest.1 - height) * position.1.clamp(0.0, 1.0);
+        (x, y, width, height)
+    }
+}
+
+/// How a background image or video is laid out, sizes are in slide pixels
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, glib::Boxed)]
+#[boxed_type(name = "ImageOptions")]
+#[serde(default)]
+pub struct ImageOptions {
+    pub fit: ImageFit,
+    /// 0.0 (left) to 1.0 (right)
+    #[serde(rename = "position-x")]
+    pub position_x: f64,
+    /// 0.0 (top) to 1.0 (bottom)
+    #[serde(rename = "position-y")]
+    pub position_y: f64,
+    /// opacity of the black overlay, 0.0 to 1.0
+    pub dim: f64,
+    pub blur: f64,
+}
+
+impl Default for ImageOptions {
+    fn default() -> Self {
+        Self {
+            fit: ImageFit::default(),
+            position_x: 0.5,
+            position_y: 0.5,
+            dim: 0.0,
+            blur: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, /* Eq, */ glib::Boxed)]
 #[boxed_type(name = "CanvasData")]
 #[serde(default)]
 pub struct CanvasData {
     #[serde(rename = "background-color")]
     pub background_color: String,
+    /// image or video file
     #[serde(rename = "background-pattern")]
     pub background_pattern: Option<String>,
+    /// only used when `background_pattern` is a video
+    #[serde(rename = "background-video")]
+    pub background_video: VideoOptions,
+    #[serde(
+        rename = "background-gradient",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub background_gradient: Option<BackgroundGradient>,
+    /// layout of `background_pattern`
+    #[serde(rename = "background-image")]
+    pub background_image: ImageOptions,
 }
 
 impl Default for CanvasData {
@@ -64,6 +697,9 @@ impl Default for CanvasData {
         Self {
             background_color: "#383e41ff".into(),
             background_pattern: None,
+            background_video: VideoOptions::default(),
+            background_gradient: None,
+            background_image: ImageOptions::default(),
         }
     }
 }
@@ -75,6 +711,12 @@ pub enum CanvasItemType {
     Text(TextItemData),
     #[serde(rename = "image")]
     Image(ImageItemData),
+    #[serde(rename = "shape")]
+    Shape(ShapeItemData),
+    #[serde(rename = "video")]
+    Video(VideoItemData),
+    #[serde(rename = "timer")]
+    Timer(TimerItemData),
     #[default]
     Unknown,
 }
@@ -84,10 +726,21 @@ pub enum CanvasItemType {
 #[serde(default)]
 pub struct SlideData {
     pub transition: u32,
+    #[serde(rename = "transition-options")]
+    pub transition_options: SlideTransition,
     pub items: Vec<CanvasItemData>,
     pub preview: Vec<u8>,
     #[serde(flatten)]
     pub canvas_data: CanvasData,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio: Option<AudioCue>,
+    /// seconds the slide stays up when its item advances on its own,
+    /// otherwise the item [AutoAdvance::duration]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<u32>,
+    /// tag of the song verse on the slide, e.g. `v1` or `c`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
 }
 
 impl SlideData {
@@ -99,9 +752,13 @@ impl SlideData {
     ) -> Self {
         Self {
             transition,
+            transition_options: SlideTransition::default(),
             items: items.into_iter().collect(),
             preview,
             canvas_data,
+            audio: None,
+            duration: None,
+            tag: None,
         }
     }
 
@@ -113,10 +770,18 @@ impl SlideData {
             text_data: String::new(),
             font: settings.song_font(),
             font_size: 20.0,
-            justification: 1,
-            align: 1,
+            justification: TextJustification::Center,
+            align: TextAlign::Middle,
+            line_spacing: 1.0,
+            letter_spacing: 0.0,
+            padding: 0.0,
+            direction: TextDirection::Ltr,
+            fit: TextFit::default(),
+            color: default_text_color(),
             text_outline: true,
             text_shadow: true,
+            outline_style: TextOutline::default(),
+            shadow_style: DropShadow::default(),
         };
 
         // let canvas_item = CanvasItemData::new(0, 0, 2755, 1566, CanvasItemType::Text(text));
@@ -135,6 +800,30 @@ impl From<SlideData> for CanvasData {
         Self {
             background_color: value.canvas_data.background_color,
             background_pattern: value.canvas_data.background_pattern,
+            background_video: value.canvas_data.background_video,
+            background_gradient: value.canvas_data.background_gradient,
+            background_image: value.canvas_data.background_image,
+        }
+    }
+}
+
+/// Moves a schedule item through its slides on its own while it is live
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, glib::Boxed)]
+#[boxed_type(name = "AutoAdvance")]
+#[serde(default)]
+pub struct AutoAdvance {
+    /// seconds each slide stays up unless it has a duration of its own
+    pub duration: u32,
+    /// start over from the first slide after the last one
+    #[serde(rename = "loop")]
+    pub loop_slides: bool,
+}
+
+impl Default for AutoAdvance {
+    fn default() -> Self {
+        Self {
+            duration: 10,
+            loop_slides: true,
         }
     }
 }
@@ -148,9 +837,22 @@ pub struct SlideManagerData {
     #[serde(rename = "preview-slide")]
     pub preview_slide: u32,
     pub title: String,
-    // aspect_ratio
+    /// shape the slides were designed for, see [SlideManagerData::set_resolution]
+    pub resolution: Resolution,
     pub slides: Vec<SlideData>,
     pub note: String,
+    /// library song this item was created from
+    #[serde(rename = "song-id", skip_serializing_if = "Option::is_none")]
+    pub song_id: Option<u32>,
+    /// plays while any slide of this item is live
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio: Option<AudioCue>,
+    /// theme last applied to the slides of this item
+    #[serde(rename = "theme-id", skip_serializing_if = "Option::is_none")]
+    pub theme_id: Option<u32>,
+    /// timing of the slides when they advance without an operator
+    #[serde(rename = "auto-advance", skip_serializing_if = "Option::is_none")]
+    pub auto_advance: Option<AutoAdvance>,
 }
 
 impl Default for SlideManagerData {
@@ -159,8 +861,13 @@ impl Default for SlideManagerData {
             current_slide: u32::default(),
             preview_slide: u32::default(),
             title: String::default(),
+            resolution: Resolution::default(),
             slides: Vec::default(),
             note: String::from("No Item"),
+            song_id: None,
+            audio: None,
+            theme_id: None,
+            auto_advance: None,
         }
     }
 }
@@ -178,6 +885,15 @@ impl SlideManagerData {
         data
     }
 
+    /// Changes the design resolution, moving the slide items to fit it
+    pub fn set_resolution(&mut self, resolution: Resolution) {
+        let from = self.resolution;
+        for item in self.slides.iter_mut().flat_map(|s| s.items.iter_mut()) {
+            item.remap(from, resolution);
+        }
+        self.resolution = resolution;
+    }
+
     pub fn from_list(
         title: String,
         position: u32,
@@ -209,3 +925,177 @@ impl SlideManagerData {
         sm_data
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_text_enums_from_old_index() {
+        let justification: TextJustification = serde_json::from_str("2").unwrap();
+        assert_eq!(justification, TextJustification::Right);
+
+        let align: TextAlign = serde_json::from_str("0").unwrap();
+        assert_eq!(align, TextAlign::Top);
+    }
+
+    #[test]
+    fn test_text_enums_by_name() {
+        let justification: TextJustification = serde_json::from_str("\"fill\"").unwrap();
+        assert_eq!(justification, TextJustification::Fill);
+        assert_eq!(
+            serde_json::to_string(&TextAlign::Bottom).unwrap(),
+            "\"bottom\""
+        );
+    }
+
+    #[test]
+    fn test_text_enums_unknown_value() {
+        let align: TextAlign = serde_json::from_str("9").unwrap();
+        assert_eq!(align, TextAlign::Middle);
+
+        let justification: TextJustification = serde_json::from_str("\"up\"").unwrap();
+        assert_eq!(justification, TextJustification::Center);
+    }
+
+    #[test]
+    fn test_image_fit_place() {
+        let src = (200.0, 100.0);
+        let dest = (100.0, 100.0);
+        let centre = (0.5, 0.5);
+
+        assert_eq!(
+            ImageFit::Cover.place(src, dest, centre, 1.0),
+            (-50.0, 0.0, 200.0, 100.0)
+        );
+        assert_eq!(
+            ImageFit::Contain.place(src, dest, centre, 1.0),
+            (0.0, 25.0, 100.0, 50.0)
+        );
+        assert_eq!(
+            ImageFit::Stretch.place(src, dest, centre, 1.0),
+            (0.0, 0.0, 100.0, 100.0)
+        );
+        assert_eq!(
+            ImageFit::Tile.place(src, dest, (0.0, 0.0), 0.5),
+            (0.0, 0.0, 100.0, 50.0)
+        );
+        // keep the right edge of a cropped image in view
+        assert_eq!(
+            ImageFit::Cover.place(src, dest, (1.0, 0.5), 1.0),
+            (-100.0, 0.0, 200.0, 100.0)
+        );
+    }
+
+    #[test]
+    fn test_canvas_data_background_defaults() {
+        let data: CanvasData =
+            serde_json::from_str(r##"{"background-color":"#000000ff"}"##).unwrap();
+        assert_eq!(data.background_gradient, None);
+        assert_eq!(data.background_image, ImageOptions::default());
+
+        let gradient = BackgroundGradient {
+            kind: GradientKind::Radial,
+            ..BackgroundGradient::default()
+        };
+        assert_eq!(
+            gradient.to_css(),
+            "radial-gradient(circle, #383e41ff 0%, #000000ff 100%)"
+        );
+    }
+
+    #[test]
+    fn test_resolution_letterbox() {
+        let wide = Resolution::new(1920, 1080);
+        assert_eq!(wide.letterbox(1024.0, 768.0), (1024.0, 576.0));
+        assert_eq!(
+            Resolution::new(1024, 768).letterbox(1920.0, 1080.0),
+            (1440.0, 1080.0)
+        );
+        assert_eq!(wide.letterbox(1920.0, 1080.0), (1920.0, 1080.0));
+        assert_eq!(Resolution::new(1024, 768).slide_width(), 2000.0);
+    }
+
+    #[test]
+    fn test_item_remap() {
+        let (wide, square) = (Resolution::new(1920, 1080), Resolution::new(1024, 768));
+
+        let text = CanvasItemType::Text(TextItemData::default());
+        let mut item = CanvasItemData::new(100, 50, 2400, 1400, text.clone());
+        item.remap(wide, square);
+        assert_eq!((item.x, item.y, item.w, item.h), (75, 50, 1800, 1400));
+
+        let image = CanvasItemType::Image(ImageItemData::default());
+        let mut item = CanvasItemData::new(1000, 500, 600, 400, image.clone());
+        item.remap(wide, square);
+        assert_eq!((item.x, item.y, item.w, item.h), (675, 500, 600, 400));
+
+        // too wide for the new slide
+        let mut item = CanvasItemData::new(0, 0, 2400, 1200, image);
+        item.remap(wide, square);
+        assert_eq!((item.x, item.y, item.w, item.h), (-100, 100, 2000, 1000));
+
+        let mut item = CanvasItemData::new(0, 0, -1, -1, text);
+        item.remap(wide, square);
+        assert_eq!((item.x, item.y, item.w, item.h), (0, 0, -1, -1));
+    }
+
+    #[test]
+    fn test_slide_manager_data_resolution() {
+        let data: SlideManagerData = serde_json::from_str(r#"{"title":"old"}"#).unwrap();
+        assert_eq!(data.resolution, Resolution::default());
+
+        let text = CanvasItemType::Text(TextItemData::default());
+        let mut data = SlideManagerData::new(
+            0,
+            0,
+            [SlideData::new(
+                0,
+                [CanvasItemData::new(400, 0, 800, 100, text)],
+                vec![],
+                CanvasData::default(),
+            )],
+        );
+        data.set_resolution(Resolution::new(3840, 1080));
+        assert_eq!(data.resolution, Resolution::new(3840, 1080));
+        assert_eq!(
+            (data.slides[0].items[0].x, data.slides[0].items[0].w),
+            (800, 1600)
+        );
+    }
+
+    #[test]
+    fn test_auto_advance_serialise() {
+        let data: SlideManagerData = serde_json::from_str(r#"{"title":"old"}"#).unwrap();
+        assert_eq!(data.auto_advance, None);
+        let json = serde_json::to_string(&data).unwrap();
+        assert!(!json.contains("auto-advance"));
+
+        let data: SlideManagerData =
+            serde_json::from_str(r#"{"auto-advance":{"loop":false},"slides":[{"duration":4},{}]}"#)
+                .unwrap();
+        let auto_advance = data.auto_advance.unwrap();
+        assert_eq!(auto_advance.duration, AutoAdvance::default().duration);
+        assert!(!auto_advance.loop_slides);
+        assert_eq!(data.slides[0].duration, Some(4));
+        assert_eq!(data.slides[1].duration, None);
+    }
+
+    #[test]
+    fn test_timer_item_serialise() {
+        let item: CanvasItemData = serde_json::from_str(
+            r#"{"x":0,"y":0,"w":400,"h":200,"type":"timer","mode":"countdown-to","target":"18:30","on-finish":"show-logo"}"#,
+        )
+        .unwrap();
+        let CanvasItemType::Timer(timer) = &item.item_type else {
+            panic!("not a timer: {:?}", item.item_type);
+        };
+        assert_eq!(timer.mode, TimerMode::CountdownTo);
+        assert_eq!(timer.target, "18:30");
+        assert_eq!(timer.on_finish, TimerFinish::ShowLogo);
+        assert_eq!(timer.duration, TimerItemData::default().duration);
+
+        let json = serde_json::to_string(&item).unwrap();
+        assert_eq!(serde_json::from_str::<CanvasItemData>(&json).unwrap(), item);
+    }
+}
@@ -0,0 +1,260 @@
+use std::f64::consts::PI;
+
+use gtk::glib;
+use gtk::glib::subclass::prelude::*;
+use gtk::prelude::*;
+
+use crate::widgets::canvas::canvas::Canvas;
+use crate::widgets::canvas::canvas_item::{CanvasItem, CanvasItemExt};
+use crate::widgets::canvas::serialise::{CanvasItemData, ShapeItemData, ShapeKind};
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use gtk::glib::Properties;
+    use gtk::subclass::{box_::BoxImpl, widget::WidgetImpl};
+
+    use super::*;
+    use crate::widgets::canvas::canvas_item::{CanvasItemImpl, CanvasItemImplExt};
+    use crate::widgets::canvas::serialise::CanvasItemType;
+
+    #[derive(Properties)]
+    #[properties(wrapper_type = super::ShapeItem)]
+    pub struct ShapeItem {
+        pub drawing_area: RefCell<gtk::DrawingArea>,
+
+        #[property(get, set, builder(ShapeKind::default()))]
+        pub shape: Cell<ShapeKind>,
+        #[property(get, set)]
+        pub fill: RefCell<String>,
+        #[property(get, set)]
+        pub stroke: RefCell<String>,
+        #[property(get, set, minimum = 0.0)]
+        pub stroke_width: Cell<f64>,
+        #[property(get, set, minimum = 0.0, maximum = 1.0)]
+        pub opacity: Cell<f64>,
+        #[property(get, set, minimum = 0.0)]
+        pub corner_radius: Cell<f64>,
+    }
+
+    impl Default for ShapeItem {
+        fn default() -> Self {
+            let data = ShapeItemData::default();
+            Self {
+                drawing_area: RefCell::default(),
+                shape: Cell::new(data.shape),
+                fill: RefCell::new(data.fill),
+                stroke: RefCell::new(data.stroke),
+                stroke_width: Cell::new(data.stroke_width),
+                opacity: Cell::new(data.opacity),
+                corner_radius: Cell::new(data.corner_radius),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ShapeItem {
+        const NAME: &'static str = "ShapeItem";
+        type Type = super::ShapeItem;
+        type ParentType = CanvasItem;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for ShapeItem {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj().clone();
+
+            let da = gtk::DrawingArea::builder()
+                .vexpand(true)
+                .hexpand(true)
+                .sensitive(false)
+                .build();
+            da.set_draw_func(glib::clone!(
+                #[weak]
+                obj,
+                move |_, cr, width, height| obj.draw(cr, width as f64, height as f64)
+            ));
+            obj.add_overlay_child(&da);
+            self.drawing_area.replace(da);
+
+            obj.connect_notify_local(None, |obj, _| obj.imp().drawing_area.borrow().queue_draw());
+        }
+
+        fn dispose(&self) {
+            self.drawing_area.borrow().unparent();
+        }
+    }
+    impl WidgetImpl for ShapeItem {}
+    impl BoxImpl for ShapeItem {}
+
+    impl CanvasItemImpl for ShapeItem {
+        fn load_item_data(&self) {
+            let Some(json_data) = self.obj().get_save_data() else {
+                return;
+            };
+
+            let CanvasItemType::Shape(data) = json_data.item_type else {
+                return;
+            };
+
+            let obj = self.obj();
+            obj.set_shape(data.shape);
+            obj.set_fill(data.fill);
+            obj.set_stroke(data.stroke);
+            obj.set_stroke_width(data.stroke_width.max(0.0));
+            obj.set_opacity(data.opacity.clamp(0.0, 1.0));
+            obj.set_corner_radius(data.corner_radius.max(0.0));
+        }
+
+        fn serialise_item(&self) -> CanvasItemType {
+            let obj = self.obj();
+            CanvasItemType::Shape(ShapeItemData {
+                shape: obj.shape(),
+                fill: obj.fill(),
+                stroke: obj.stroke(),
+                stroke_width: obj.stroke_width(),
+                opacity: obj.opacity(),
+                corner_radius: obj.corner_radius(),
+            })
+        }
+
+        fn style(&self) {
+            self.parent_style();
+            self.drawing_area.borrow().queue_draw();
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct ShapeItem(ObjectSubclass<imp::ShapeItem>)
+        @extends CanvasItem, gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Orientable, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl ShapeItem {
+    pub fn new(canvas: Option<&Canvas>, save_data: Option<CanvasItemData>) -> Self {
+        let data = match save_data {
+            Some(d) => serde_json::to_string(&d).ok(),
+            None => None,
+        };
+
+        let obj: Self = glib::Object::builder()
+            .property("canvas", canvas)
+            .property("save-data", data)
+            .build();
+        obj.load_data();
+
+        if let Some(canvas) = canvas {
+            canvas.connect_ratio_changed(glib::clone!(
+                #[weak]
+                obj,
+                move |_| obj.style()
+            ));
+            obj.style();
+        }
+
+        obj
+    }
+
+    fn draw(&self, cr: &gtk::cairo::Context, width: f64, height: f64) {
+        let ratio = self
+            .upcast_ref::<CanvasItem>()
+            .canvas()
+            .map(|c| c.current_ratio())
+            .unwrap_or(1.0);
+
+        let data = ShapeItemData {
+            shape: self.shape(),
+            fill: self.fill(),
+            stroke: self.stroke(),
+            stroke_width: self.stroke_width(),
+            opacity: self.opacity(),
+            corner_radius: self.corner_radius(),
+        };
+        draw_shape(cr, &data, width, height, ratio);
+    }
+}
+
+/// Draws `data` in a `width` by `height` box at the origin, `ratio` is
+/// screen pixels per slide pixel
+pub fn draw_shape(
+    cr: &gtk::cairo::Context,
+    data: &ShapeItemData,
+    width: f64,
+    height: f64,
+    ratio: f64,
+) {
+    let shape = data.shape;
+    let stroke_width = match shape {
+        // a line without a stroke would not be visible
+        ShapeKind::Line => data.stroke_width.max(1.0),
+        _ => data.stroke_width,
+    } * ratio;
+    let inset = stroke_width / 2.0;
+    let (w, h) = (
+        (width - stroke_width).max(0.0),
+        (height - stroke_width).max(0.0),
+    );
+
+    cr.push_group();
+    match shape {
+        ShapeKind::Rectangle => cr.rectangle(inset, inset, w, h),
+        ShapeKind::RoundedRectangle => {
+            let r = (data.corner_radius * ratio).min(w / 2.0).min(h / 2.0);
+            cr.new_sub_path();
+            cr.arc(inset + w - r, inset + r, r, -PI / 2.0, 0.0);
+            cr.arc(inset + w - r, inset + h - r, r, 0.0, PI / 2.0);
+            cr.arc(inset + r, inset + h - r, r, PI / 2.0, PI);
+            cr.arc(inset + r, inset + r, r, PI, 3.0 * PI / 2.0);
+            cr.close_path();
+        }
+        ShapeKind::Ellipse => {
+            if w > 0.0 && h > 0.0 {
+                cr.save().ok();
+                cr.translate(width / 2.0, height / 2.0);
+                cr.scale(w / 2.0, h / 2.0);
+                cr.arc(0.0, 0.0, 1.0, 0.0, 2.0 * PI);
+                cr.restore().ok();
+            }
+        }
+        // horizontal divider unless the box is taller than wide
+        ShapeKind::Line if width >= height => {
+            cr.move_to(0.0, height / 2.0);
+            cr.line_to(width, height / 2.0);
+        }
+        ShapeKind::Line => {
+            cr.move_to(width / 2.0, 0.0);
+            cr.line_to(width / 2.0, height);
+        }
+    }
+
+    if shape != ShapeKind::Line
+        && let Ok(fill) = gtk::gdk::RGBA::parse(&data.fill)
+    {
+        set_source(cr, &fill);
+        cr.fill_preserve().ok();
+    }
+
+    if stroke_width > 0.0
+        && let Ok(stroke) = gtk::gdk::RGBA::parse(&data.stroke)
+    {
+        set_source(cr, &stroke);
+        cr.set_line_width(stroke_width);
+        cr.stroke().ok();
+    }
+    cr.new_path();
+
+    if cr.pop_group_to_source().is_ok() {
+        cr.paint_with_alpha(data.opacity).ok();
+    }
+}
+
+fn set_source(cr: &gtk::cairo::Context, color: &gtk::gdk::RGBA) {
+    cr.set_source_rgba(
+        color.red() as f64,
+        color.green() as f64,
+        color.blue() as f64,
+        color.alpha() as f64,
+    );
+}
@@ -1,4 +1,5 @@
 use gtk::{
+    gdk,
     glib::{
         self,
         object::{Cast, ObjectExt},
@@ -12,17 +13,27 @@ use gtk::{
 };
 
 use crate::{
-    // services::history_manager::history_action::TypedHistoryAction,
-    services::settings::ApplicationSettings,
+    services::{
+        history_manager::HistoryActionKind,
+        settings::ApplicationSettings,
+        text_fit::{self, TextFit, TextFitLayout},
+    },
     utils::{self, buffer_markup::TextBufferExtra},
     widgets::canvas::{
         canvas::Canvas,
         canvas_item::{CanvasItem, CanvasItemExt},
-        serialise::CanvasItemData,
+        serialise::{
+            CanvasItemData, CanvasItemType, DropShadow, Resolution, TextAlign, TextItemData,
+            TextJustification, TextOutline,
+        },
     },
 };
 
 const PLACEHOLDER_TEXT: &str = "Click to add text...";
+/// slide pixels per unit of font size
+pub const FONT_SCALE: f64 = 5.3;
+/// copies drawn on each side of a blurred text shadow
+const SHADOW_BLUR_STEPS: i32 = 2;
 
 mod stacks {
     pub const EDIT: &str = "edit";
@@ -31,7 +42,6 @@ mod stacks {
 
 mod imp {
     use std::cell::{Cell, RefCell};
-    use std::u32;
 
     use glib::subclass::object::ObjectImpl;
     use glib::subclass::types::ObjectSubclass;
@@ -44,7 +54,10 @@ mod imp {
     use crate::widgets::canvas::canvas_item::{
         CanvasItem, CanvasItemExt, CanvasItemImpl, CanvasItemImplExt,
     };
-    use crate::widgets::canvas::serialise::{CanvasItemType, TextItemData};
+    use crate::widgets::canvas::serialise::{
+        CanvasItemType, DropShadow, TextAlign, TextDirection, TextItemData, TextJustification,
+        TextOutline,
+    };
 
     #[derive(Properties, Debug, Default)]
     #[properties(wrapper_type = super::TextItem)]
@@ -53,10 +66,23 @@ mod imp {
         pub drawing_area: RefCell<gtk::DrawingArea>,
         pub stack: RefCell<gtk::Stack>,
 
-        #[property(get, set, default_value = 1, construct)]
-        pub justification: Cell<u32>,
-        #[property(get, set, default_value = 1, construct)]
-        pub align: Cell<u32>,
+        #[property(get, set, builder(TextJustification::Center))]
+        pub justification: Cell<TextJustification>,
+        #[property(get, set, builder(TextAlign::Middle))]
+        pub align: Cell<TextAlign>,
+        #[property(get, set, minimum = 0.1, default_value = 1.0, construct)]
+        pub line_spacing: Cell<f64>,
+        #[property(get, set)]
+        pub letter_spacing: Cell<f64>,
+        #[property(get, set, minimum = 0.0)]
+        pub padding: Cell<f64>,
+        #[property(get, set, builder(TextDirection::Ltr))]
+        pub text_direction: Cell<TextDirection>,
+        #[property(get, set, builder(TextFit::Shrink))]
+        pub fit: Cell<TextFit>,
+        /// rgba hex, markup colours take precedence
+        #[property(get, set, default_value = "#ffffffff", construct)]
+        pub text_color: RefCell<String>,
         #[property(get, set, default_value = 16.0, construct)]
         pub font_size: Cell<f32>,
         pub display_font_size: Cell<f32>,
@@ -66,6 +92,10 @@ mod imp {
         pub text_shadow: Cell<bool>,
         #[property(get, set, default_value = false, construct)]
         pub text_outline: Cell<bool>,
+        #[property(get, set)]
+        pub outline_style: RefCell<TextOutline>,
+        #[property(get, set)]
+        pub shadow_style: RefCell<DropShadow>,
 
         #[property(get=Self::get_editing_, set=Self::set_editing_)]
         pub editing: Cell<bool>,
@@ -106,8 +136,16 @@ mod imp {
             self.obj().set_font(text_data.font);
             self.obj().set_justification(text_data.justification);
             self.obj().set_align(text_data.align);
+            self.obj().set_line_spacing(text_data.line_spacing.max(0.1));
+            self.obj().set_letter_spacing(text_data.letter_spacing);
+            self.obj().set_padding(text_data.padding.max(0.0));
+            self.obj().set_text_direction(text_data.direction);
+            self.obj().set_fit(text_data.fit);
+            self.obj().set_text_color(text_data.color);
             self.obj().set_text_outline(text_data.text_outline);
             self.obj().set_text_shadow(text_data.text_shadow);
+            self.obj().set_outline_style(text_data.outline_style);
+            self.obj().set_shadow_style(text_data.shadow_style);
         }
 
         fn serialise_item(&self) -> CanvasItemType {
@@ -125,9 +163,17 @@ mod imp {
                 font_size,
                 justification: obj.justification(),
                 align: obj.align(),
+                line_spacing: obj.line_spacing(),
+                letter_spacing: obj.letter_spacing(),
+                padding: obj.padding(),
+                direction: obj.text_direction(),
+                fit: obj.fit(),
+                color: obj.text_color(),
                 text_data: encoded.clone(),
                 text_outline: obj.text_outline(),
                 text_shadow: obj.text_shadow(),
+                outline_style: obj.outline_style(),
+                shadow_style: obj.shadow_style(),
             };
             let data = CanvasItemType::Text(data);
 
@@ -149,31 +195,37 @@ mod imp {
             }
 
             let entry = self.entry.borrow().clone();
-            match obj.justification() {
-                0 => {
-                    entry.set_justification(gtk::Justification::Left);
-                }
-                1 => {
-                    entry.set_justification(gtk::Justification::Center);
-                }
-                2 => {
-                    entry.set_justification(gtk::Justification::Right);
-                }
-                3 => {
-                    entry.set_justification(gtk::Justification::Fill);
-                }
-                4_u32..=u32::MAX => {}
-            };
-
-            match self.align.get() {
-                0 => self.entry.borrow().set_valign(gtk::Align::Start),
-
-                1 => self.entry.borrow().set_valign(gtk::Align::Center),
+            entry.set_justification(match obj.justification() {
+                TextJustification::Left => gtk::Justification::Left,
+                TextJustification::Center => gtk::Justification::Center,
+                TextJustification::Right => gtk::Justification::Right,
+                TextJustification::Fill => gtk::Justification::Fill,
+            });
 
-                2 => self.entry.borrow().set_valign(gtk::Align::End),
+            entry.set_valign(match obj.align() {
+                TextAlign::Top => gtk::Align::Start,
+                TextAlign::Middle => gtk::Align::Center,
+                TextAlign::Bottom => gtk::Align::End,
+            });
 
-                3_u32..=u32::MAX => (),
+            let direction = match obj.text_direction() {
+                TextDirection::Ltr => gtk::TextDirection::Ltr,
+                TextDirection::Rtl => gtk::TextDirection::Rtl,
             };
+            entry.set_direction(direction);
+            self.drawing_area.borrow().set_direction(direction);
+
+            let ratio = obj
+                .upcast_ref::<CanvasItem>()
+                .canvas()
+                .map(|c| c.current_ratio())
+                .unwrap_or(1.0);
+            let padding = (obj.padding() * ratio) as i32;
+            let stack = self.stack.borrow();
+            stack.set_margin_top(padding);
+            stack.set_margin_bottom(padding);
+            stack.set_margin_start(padding);
+            stack.set_margin_end(padding);
 
             self.resize_entry();
         }
@@ -249,10 +301,12 @@ mod imp {
 
             let ratio = canvas.current_ratio();
             let outline = if obj.text_outline() {
-                let offset = 3.0 * ratio;
+                let outline_style = obj.outline_style();
+                let color = outline_style.color;
+                let offset = outline_style.width / 2.0 * ratio;
                 let radius = 1.0 * ratio;
                 &format!(
-                    "#000 {} #000 {} #000 {} #000 {}",
+                    "{color} {} {color} {} {color} {} {color} {}",
                     format!("-{}px -{}px {}px,", offset, offset, radius),
                     format!("{}px -{}px {}px,", offset, offset, radius),
                     format!("-{}px  {}px {}px,", offset, offset, radius),
@@ -263,9 +317,14 @@ mod imp {
             };
 
             let shadow = if obj.text_shadow() {
-                let offset = 8.0 * ratio;
-                let radius = 8.0 * ratio;
-                &format!("{outline}, -{}px {}px {}px black", offset, offset, radius)
+                let shadow_style = obj.shadow_style();
+                &format!(
+                    "{outline}, {}px {}px {}px {}",
+                    shadow_style.offset_x * ratio,
+                    shadow_style.offset_y * ratio,
+                    shadow_style.blur * ratio,
+                    shadow_style.color
+                )
             } else {
                 outline
             };
@@ -273,13 +332,16 @@ mod imp {
             let css = format!(
                 ".colored, textview.view {{
                     font: {font_css};
-                    color: white;
+                    color: {};
                     padding: 0px;
                     background: 0;
-                    /* letter-spacing: {}px; */
+                    letter-spacing: {}px;
+                    line-height: {};
                     text-shadow: {shadow};
                 }}",
-                2.0 * ratio
+                obj.text_color(),
+                obj.letter_spacing() * ratio,
+                obj.line_spacing()
             );
 
             css
@@ -454,18 +516,18 @@ impl TextItem {
                     ti.calculate_size();
                 }
 
-                // let action = TypedHistoryAction::item_changed(&ti, "text");
-                // let Some(window) = ci.canvas().and_then(|c| c.imp().window.upgrade()) else {
-                //     return;
-                // };
-                //
-                // window
-                //     .history_manager()
-                //     .add_undoable_action(action.into(), Some(ti.imp().first_change_in_edit.get()));
-
-                ti.imp().first_change_in_edit.set(false);
                 ti.imp().drawing_area.borrow().queue_draw();
                 ti.style();
+
+                let ci = ti.upcast_ref::<CanvasItem>();
+                if let Some(canvas) = ci.canvas() {
+                    canvas.emit_item_changed(
+                        ci,
+                        HistoryActionKind::TextEdited,
+                        ti.imp().first_change_in_edit.get(),
+                    );
+                }
+                ti.imp().first_change_in_edit.set(false);
                 // ti.imp().previous_text.replace(label.label().to_string());
             }
         ));
@@ -494,6 +556,66 @@ impl TextItem {
         self.imp().entry.borrow().buffer()
     }
 
+    /// Fit of the text at slide scale, see [fit_item_data]
+    pub fn fit_layout(&self) -> Option<TextFitLayout> {
+        fit_item_data(
+            &self.upcast_ref::<CanvasItem>().serialise(),
+            self.resolution(),
+        )
+    }
+
+    /// Resolution of the slide the item is on
+    fn resolution(&self) -> Resolution {
+        self.upcast_ref::<CanvasItem>()
+            .canvas()
+            .map(|c| c.resolution())
+            .unwrap_or_default()
+    }
+
+    /// Number of lines to keep on this slide when the rest of the text
+    /// does not fit at the minimum size, `None` when it all fits
+    pub fn split_point(&self) -> Option<usize> {
+        let data = self.upcast_ref::<CanvasItem>().serialise();
+        let CanvasItemType::Text(text) = &data.item_type else {
+            return None;
+        };
+
+        let (width, height) = text_box(&data, text.padding, self.resolution());
+        let layout = data_layout(text, "", width);
+        let min_size = FONT_SCALE * ApplicationSettings::get_instance().min_font_size();
+        let size = min_size.min(FONT_SCALE * text.font_size as f64);
+
+        let buffer = self.buffer();
+        let lines = buffer.line_count().max(0) as usize;
+        text_fit::split_point(lines, |n| {
+            let end = buffer
+                .iter_at_line(n as i32)
+                .unwrap_or_else(|| buffer.end_iter());
+            let text = buffer.text(&buffer.start_iter(), &end, false);
+            layout.set_text(text.trim_end_matches('\n'));
+            measure_layout(&layout, size).1 <= height
+        })
+    }
+
+    /// Makes the item `height` slide pixels tall if it is shorter,
+    /// items filling the slide are left alone
+    fn grow_to(&self, height: f64) {
+        let ci = self.clone().upcast::<CanvasItem>();
+        let mut rect = ci.rectangle();
+        let height = height.ceil() as i32 + (self.padding() * 2.0) as i32;
+        if rect.height < 0 || rect.height >= height {
+            return;
+        }
+
+        rect.height = height;
+        // not while drawing
+        glib::idle_add_local_once(glib::clone!(
+            #[weak]
+            ci,
+            move || ci.set_rectangle(rect)
+        ));
+    }
+
     fn check_font_size(&self) -> f32 {
         // self.font_size()
         // self.imp().alt_font_size.get().min(self.font_size())
@@ -545,55 +667,28 @@ impl TextItem {
 
                 font_desc.set_size((size * pango::SCALE as f64) as i32);
                 layout.set_font_description(Some(&font_desc));
-                layout.set_markup(&text);
+                ti.set_layout_markup(&layout, &text, canvas.current_ratio());
 
                 let (_, layout_height) = layout.pixel_size();
 
                 let yalign_offset = match ti.align() {
-                    0 => 0,                            // top
-                    1 => (height - layout_height) / 2, // center
-                    2 => height - layout_height,       // bottom
-                    _ => 0,
+                    TextAlign::Top => 0,
+                    TextAlign::Middle => (height - layout_height) / 2,
+                    TextAlign::Bottom => height - layout_height,
                 };
 
                 let ratio = canvas.current_ratio();
-                if ti.text_outline() {
-                    let radius = 6.0 * ratio;
-
-                    cr.set_source_rgba(0.0, 0.0, 0.0, 1.0);
-                    cr.set_line_width(radius * 2.0);
-                    cr.set_line_join(gtk::cairo::LineJoin::Round);
-                    cr.move_to(0.0, yalign_offset as f64);
-
-                    pangocairo::functions::layout_path(cr, &layout);
-                    cr.stroke().ok();
-                }
-
-                if ti.text_shadow() {
-                    let offset = 10.0 * ratio;
-
-                    let rg = regex::Regex::new(r#"\s*foreground\s*=\s*"[^"]*""#).unwrap();
-                    let text = text.clone();
-                    let text = rg.replace_all(&text, "");
-
-                    // NOTE: this copies all the values.
-                    // below are the ones I need
-                    // - font_description
-                    // - width
-                    // - wrap
-                    // - alignment
-                    let shadow_layout = layout.copy();
-                    shadow_layout.set_markup(&text);
-
-                    // draw shadow by offsetting a black copy underneath
-                    cr.set_source_rgba(0.0, 0.0, 0.0, 1.0);
-                    cr.move_to(offset, (yalign_offset as f64) + offset);
-                    pangocairo::functions::show_layout(cr, &shadow_layout);
-                }
-
-                cr.set_source_rgba(1.0, 1.0, 1.0, 1.0);
-                cr.move_to(0.0, yalign_offset as f64);
-                pangocairo::functions::show_layout(cr, &layout);
+                let outline = ti.text_outline().then(|| ti.outline_style());
+                let shadow = ti.text_shadow().then(|| ti.shadow_style());
+                paint_text(
+                    cr,
+                    &layout,
+                    yalign_offset as f64,
+                    ratio,
+                    outline.as_ref(),
+                    shadow.as_ref(),
+                    &ti.text_color(),
+                );
             }
         ));
 
@@ -606,10 +701,31 @@ impl TextItem {
             ti.style();
         });
         ti.connect_align_notify(|ti| ti.imp().drawing_area.borrow().queue_draw());
+        ti.connect_line_spacing_notify(|ti| ti.imp().drawing_area.borrow().queue_draw());
+        ti.connect_letter_spacing_notify(|ti| ti.imp().drawing_area.borrow().queue_draw());
+        ti.connect_outline_style_notify(|ti| ti.imp().drawing_area.borrow().queue_draw());
+        ti.connect_shadow_style_notify(|ti| ti.imp().drawing_area.borrow().queue_draw());
+        ti.connect_text_color_notify(|ti| ti.imp().drawing_area.borrow().queue_draw());
+        ti.connect_fit_notify(|ti| ti.imp().drawing_area.borrow().queue_draw());
 
         ti.imp().drawing_area.replace(da.clone());
     }
 
+    /// Sets `text` on `layout` along with the item letter spacing,
+    /// which markup would otherwise reset
+    fn set_layout_markup(&self, layout: &pango::Layout, text: &str, ratio: f64) {
+        layout.set_markup(text);
+
+        let spacing = self.letter_spacing() * ratio;
+        if spacing != 0.0 {
+            let attrs = layout.attributes().unwrap_or_default();
+            attrs.insert(pango::AttrInt::new_letter_spacing(
+                (spacing * pango::SCALE as f64) as i32,
+            ));
+            layout.set_attributes(Some(&attrs));
+        }
+    }
+
     fn calculate_size(
         &self,
     ) -> Option<(
@@ -641,27 +757,40 @@ impl TextItem {
 
         let layout = da.create_pango_layout(None);
 
-        let px = 5.3 * canvas.current_ratio() * ti.font_size() as f64;
+        let ratio = canvas.current_ratio();
+        let px = FONT_SCALE * ratio * ti.font_size() as f64;
 
         let mut font_desc = pango::FontDescription::new();
         font_desc.set_family(&ti.font());
         font_desc.set_size((px * pango::SCALE as f64) as i32);
         layout.set_font_description(Some(&font_desc));
-        layout.set_markup(&text);
+        ti.set_layout_markup(&layout, &text, canvas.current_ratio());
 
         layout.set_width(width * pango::SCALE);
         layout.set_wrap(pango::WrapMode::WordChar);
 
         // Match justification
         let alignment = match ti.justification() {
-            0 => pango::Alignment::Left,
-            1 => pango::Alignment::Center,
-            2 => pango::Alignment::Right,
-            _ => pango::Alignment::Left,
+            TextJustification::Left | TextJustification::Fill => pango::Alignment::Left,
+            TextJustification::Center => pango::Alignment::Center,
+            TextJustification::Right => pango::Alignment::Right,
         };
         layout.set_alignment(alignment);
-
-        let size = fit_text_layout(&layout, px, width, height);
+        layout.set_justify(ti.justification() == TextJustification::Fill);
+        layout.set_line_spacing(ti.line_spacing() as f32);
+
+        let min_px = FONT_SCALE * ratio * ApplicationSettings::get_instance().min_font_size();
+        let fit = text_fit::fit_text(
+            ti.fit(),
+            px,
+            min_px,
+            (width as f64, height as f64),
+            |size| measure_layout(&layout, size),
+        );
+        if fit.height > height as f64 {
+            ti.grow_to(fit.height / ratio);
+        }
+        let size = fit.size;
 
         ti.imp().display_font_size.set(size as f32);
 
@@ -669,32 +798,156 @@ impl TextItem {
     }
 }
 
-fn fit_text_layout(layout: &pango::Layout, max_px: f64, width: i32, height: i32) -> f64 {
-    if width <= 0 || height <= 0 {
-        return max_px;
-    }
+fn set_source_color(cr: &gtk::cairo::Context, color: &gdk::RGBA, alpha: f64) {
+    cr.set_source_rgba(
+        color.red().into(),
+        color.green().into(),
+        color.blue().into(),
+        color.alpha() as f64 * alpha,
+    );
+}
 
-    let mut lo = 6.0_f64;
-    let mut hi = max_px;
-    let mut best = lo;
+/// Draws `layout` at `y` with its outline and shadow under it, `ratio` is
+/// screen pixels per slide pixel
+pub fn paint_text(
+    cr: &gtk::cairo::Context,
+    layout: &pango::Layout,
+    y: f64,
+    ratio: f64,
+    outline: Option<&TextOutline>,
+    shadow: Option<&DropShadow>,
+    color: &str,
+) {
+    if let Some(outline) = outline {
+        let color = gdk::RGBA::parse(&outline.color).unwrap_or(gdk::RGBA::BLACK);
+
+        set_source_color(cr, &color, 1.0);
+        cr.set_line_width(outline.width * ratio * 2.0);
+        cr.set_line_join(gtk::cairo::LineJoin::Round);
+        cr.move_to(0.0, y);
+
+        pangocairo::functions::layout_path(cr, layout);
+        cr.stroke().ok();
+    }
 
-    for _ in 0..20 {
-        let mid = (lo + hi) / 2.0;
+    if let Some(shadow) = shadow {
+        let color = gdk::RGBA::parse(&shadow.color).unwrap_or(gdk::RGBA::BLACK);
 
-        // temporarily set size to measure
-        if let Some(mut fd) = layout.font_description() {
-            fd.set_size((mid * pango::SCALE as f64) as i32);
-            layout.set_font_description(Some(&fd));
+        // the shadow is one colour, so drop the colours of the text runs
+        let shadow_layout = layout.copy();
+        if let Some(attrs) = layout.attributes().and_then(|a| a.copy()) {
+            let _ = attrs.filter(|a| a.type_() == pango::AttrType::Foreground);
+            shadow_layout.set_attributes(Some(&attrs));
         }
 
-        let (pw, ph) = layout.pixel_size();
-        if pw <= width && ph <= height {
-            best = mid;
-            lo = mid;
-        } else {
-            hi = mid;
+        // draw shadow by offsetting a copy underneath, blur is
+        // approximated by spreading translucent copies around it
+        let x = shadow.offset_x * ratio;
+        let y = y + shadow.offset_y * ratio;
+        let blur = shadow.blur * ratio;
+        let spread = match blur > 0.0 {
+            true => SHADOW_BLUR_STEPS,
+            false => 0,
+        };
+
+        set_source_color(cr, &color, 1.0 / (spread * 2 + 1) as f64);
+        for i in -spread..=spread {
+            for j in -spread..=spread {
+                let (dx, dy) = match spread {
+                    0 => (0.0, 0.0),
+                    _ => (
+                        blur * i as f64 / spread as f64,
+                        blur * j as f64 / spread as f64,
+                    ),
+                };
+                cr.move_to(x + dx, y + dy);
+                pangocairo::functions::show_layout(cr, &shadow_layout);
+            }
         }
     }
 
-    best
+    let color = gdk::RGBA::parse(color).unwrap_or(gdk::RGBA::WHITE);
+    set_source_color(cr, &color, 1.0);
+    cr.move_to(0.0, y);
+    pangocairo::functions::show_layout(cr, layout);
+}
+
+/// `(width, height)` of `layout` with its font at `px`
+pub fn measure_layout(layout: &pango::Layout, px: f64) -> (f64, f64) {
+    if let Some(mut fd) = layout.font_description() {
+        fd.set_size((px * pango::SCALE as f64) as i32);
+        layout.set_font_description(Some(&fd));
+    }
+
+    let (w, h) = layout.pixel_size();
+    (w as f64, h as f64)
+}
+
+/// Size of the text box of `item` in slide pixels, `-1` fills a slide
+/// of `resolution`
+pub fn text_box(item: &CanvasItemData, padding: f64, resolution: Resolution) -> (f64, f64) {
+    let width = match item.w < 0 {
+        true => resolution.slide_width(),
+        false => item.w as f64,
+    };
+    let height = match item.h < 0 {
+        true => Resolution::SLIDE_HEIGHT,
+        false => item.h as f64,
+    };
+    (
+        (width - padding * 2.0).max(0.0),
+        (height - padding * 2.0).max(0.0),
+    )
+}
+
+/// Layout of `text` at slide scale, for measuring without a widget
+pub fn data_layout(text: &TextItemData, markup: &str, width: f64) -> pango::Layout {
+    use pango::prelude::FontMapExt;
+
+    let context = pangocairo::FontMap::default().create_context();
+    let layout = pango::Layout::new(&context);
+
+    let mut font_desc = pango::FontDescription::new();
+    font_desc.set_family(&text.font);
+    layout.set_font_description(Some(&font_desc));
+    layout.set_markup(markup);
+    if text.letter_spacing != 0.0 {
+        let attrs = layout.attributes().unwrap_or_default();
+        attrs.insert(pango::AttrInt::new_letter_spacing(
+            (text.letter_spacing * pango::SCALE as f64) as i32,
+        ));
+        layout.set_attributes(Some(&attrs));
+    }
+
+    layout.set_width((width * pango::SCALE as f64) as i32);
+    layout.set_wrap(pango::WrapMode::WordChar);
+    layout.set_alignment(match text.justification {
+        TextJustification::Left | TextJustification::Fill => pango::Alignment::Left,
+        TextJustification::Center => pango::Alignment::Center,
+        TextJustification::Right => pango::Alignment::Right,
+    });
+    layout.set_justify(text.justification == TextJustification::Fill);
+    layout.set_line_spacing(text.line_spacing as f32);
+    layout
+}
+
+/// Fits a text item to its box on a slide of `resolution` without
+/// drawing it, `None` for other items
+pub fn fit_item_data(item: &CanvasItemData, resolution: Resolution) -> Option<TextFitLayout> {
+    let CanvasItemType::Text(text) = &item.item_type else {
+        return None;
+    };
+
+    let markup = String::from_utf8(glib::base64_decode(&text.text_data)).unwrap_or_default();
+    let (width, height) = text_box(item, text.padding, resolution);
+    let layout = data_layout(text, &markup, width);
+    let min_size = FONT_SCALE * ApplicationSettings::get_instance().min_font_size();
+
+    Some(text_fit::fit_text(
+        text.fit,
+        FONT_SCALE * text.font_size as f64,
+        min_size,
+        (width, height),
+        |size| measure_layout(&layout, size),
+    ))
 }
@@ -0,0 +1,256 @@
+use std::time::Duration;
+
+use gtk::glib;
+use gtk::glib::subclass::prelude::*;
+use gtk::pango;
+use gtk::prelude::*;
+
+use crate::services::timer::{self, SystemClock, TimerState};
+use crate::widgets::canvas::canvas::Canvas;
+use crate::widgets::canvas::canvas_item::{CanvasItem, CanvasItemExt};
+use crate::widgets::canvas::serialise::{CanvasItemData, TimerFinish, TimerItemData};
+use crate::widgets::canvas::text_item::{FONT_SCALE, measure_layout, paint_text};
+
+/// How often a running timer checks the clock, often enough that the
+/// seconds do not visibly lag
+const TICK: Duration = Duration::from_millis(200);
+
+mod imp {
+    use std::cell::RefCell;
+
+    use gtk::glib::Properties;
+    use gtk::subclass::{box_::BoxImpl, widget::WidgetImpl};
+
+    use super::*;
+    use crate::widgets::canvas::canvas_item::{CanvasItemImpl, CanvasItemImplExt};
+    use crate::widgets::canvas::serialise::CanvasItemType;
+
+    #[derive(Default, Properties)]
+    #[properties(wrapper_type = super::TimerItem)]
+    pub struct TimerItem {
+        pub drawing_area: RefCell<gtk::DrawingArea>,
+
+        #[property(get, set = Self::set_timer_)]
+        pub timer: RefCell<TimerItemData>,
+
+        /// text drawn for the timer
+        pub(super) text: RefCell<String>,
+        /// set while the slide is live
+        pub(super) state: RefCell<Option<TimerState>>,
+        pub(super) tick: RefCell<Option<glib::SourceId>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for TimerItem {
+        const NAME: &'static str = "TimerItem";
+        type Type = super::TimerItem;
+        type ParentType = CanvasItem;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for TimerItem {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj().clone();
+
+            let da = gtk::DrawingArea::builder()
+                .vexpand(true)
+                .hexpand(true)
+                .sensitive(false)
+                .build();
+            da.set_draw_func(glib::clone!(
+                #[weak]
+                obj,
+                move |_, cr, width, height| obj.draw(cr, width as f64, height as f64)
+            ));
+            obj.add_overlay_child(&da);
+            self.drawing_area.replace(da);
+        }
+
+        fn dispose(&self) {
+            self.stop();
+            self.drawing_area.borrow().unparent();
+        }
+    }
+    impl WidgetImpl for TimerItem {}
+    impl BoxImpl for TimerItem {}
+
+    impl CanvasItemImpl for TimerItem {
+        fn load_item_data(&self) {
+            let Some(json_data) = self.obj().get_save_data() else {
+                return;
+            };
+
+            let CanvasItemType::Timer(data) = json_data.item_type else {
+                return;
+            };
+            self.obj().set_timer(data);
+        }
+
+        fn serialise_item(&self) -> CanvasItemType {
+            CanvasItemType::Timer(self.timer.borrow().clone())
+        }
+
+        fn style(&self) {
+            self.parent_style();
+
+            let obj = self.obj();
+            let playing = obj
+                .upcast_ref::<CanvasItem>()
+                .canvas()
+                .is_some_and(|c| c.media_playing());
+
+            match playing {
+                true if self.state.borrow().is_none() => self.start(),
+                true => (),
+                false => self.stop(),
+            }
+            self.drawing_area.borrow().queue_draw();
+        }
+    }
+
+    impl TimerItem {
+        fn set_timer_(&self, value: TimerItemData) {
+            self.timer.replace(value);
+
+            // an edited timer starts over
+            let running = self.state.borrow().is_some();
+            self.stop();
+            if running {
+                self.start();
+            }
+            self.drawing_area.borrow().queue_draw();
+        }
+
+        fn start(&self) {
+            let state = TimerState::start(&self.timer.borrow(), &SystemClock);
+            self.state.replace(Some(state));
+            self.update();
+
+            let id = glib::timeout_add_local(
+                TICK,
+                glib::clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    #[upgrade_or]
+                    glib::ControlFlow::Break,
+                    move || {
+                        imp.update();
+                        glib::ControlFlow::Continue
+                    }
+                ),
+            );
+            self.tick.replace(Some(id));
+        }
+
+        /// Shows the idle text until the slide is live again
+        fn stop(&self) {
+            if let Some(id) = self.tick.take() {
+                id.remove();
+            }
+            self.state.replace(None);
+            self.text
+                .replace(timer::idle_text(&self.timer.borrow(), &SystemClock));
+        }
+
+        fn update(&self) {
+            let (text, finished) = match self.state.borrow_mut().as_mut() {
+                Some(state) => state.tick(&SystemClock),
+                None => return,
+            };
+
+            if *self.text.borrow() != text {
+                self.text.replace(text);
+                self.drawing_area.borrow().queue_draw();
+            }
+
+            let action = self.timer.borrow().on_finish;
+            if finished
+                && action != TimerFinish::None
+                && let Some(canvas) = self.obj().upcast_ref::<CanvasItem>().canvas()
+            {
+                canvas.emit_timer_finished(action);
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct TimerItem(ObjectSubclass<imp::TimerItem>)
+        @extends CanvasItem, gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Orientable, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl TimerItem {
+    pub fn new(canvas: Option<&Canvas>, save_data: Option<CanvasItemData>) -> Self {
+        let data = match save_data {
+            Some(d) => serde_json::to_string(&d).ok(),
+            None => None,
+        };
+
+        let obj: Self = glib::Object::builder()
+            .property("canvas", canvas)
+            .property("save-data", data)
+            .build();
+        obj.load_data();
+
+        if let Some(canvas) = canvas {
+            canvas.connect_ratio_changed(glib::clone!(
+                #[weak]
+                obj,
+                move |_| obj.style()
+            ));
+        }
+        obj.style();
+
+        obj
+    }
+
+    fn draw(&self, cr: &gtk::cairo::Context, width: f64, height: f64) {
+        let ratio = self
+            .upcast_ref::<CanvasItem>()
+            .canvas()
+            .map(|c| c.current_ratio())
+            .unwrap_or(1.0);
+
+        let text = self.imp().text.borrow();
+        draw_timer(cr, &self.timer(), &text, width, height, ratio);
+    }
+}
+
+/// Draws `text` for `data` centred in a `width` by `height` box at the
+/// origin, shrinking it to fit. `ratio` is screen pixels per slide pixel
+pub fn draw_timer(
+    cr: &gtk::cairo::Context,
+    data: &TimerItemData,
+    text: &str,
+    width: f64,
+    height: f64,
+    ratio: f64,
+) {
+    let layout = pangocairo::functions::create_layout(cr);
+    layout.set_font_description(Some(&pango::FontDescription::from_string(&data.font)));
+    layout.set_text(text);
+
+    let px = FONT_SCALE * ratio * data.font_size as f64;
+    let (w, h) = measure_layout(&layout, px);
+    if w <= 0.0 || h <= 0.0 {
+        return;
+    }
+
+    let scale = (width / w).min(height / h).min(1.0);
+    let (w, h) = measure_layout(&layout, px * scale);
+
+    cr.save().ok();
+    cr.translate((width - w) / 2.0, 0.0);
+    paint_text(
+        cr,
+        &layout,
+        (height - h) / 2.0,
+        ratio,
+        None,
+        None,
+        &data.color,
+    );
+    cr.restore().ok();
+}
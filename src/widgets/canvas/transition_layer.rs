@@ -0,0 +1,187 @@
+use gtk::glib;
+use gtk::glib::subclass::prelude::*;
+use gtk::prelude::*;
+use gtk::{gdk, graphene};
+
+use crate::services::transition::SlideTransition;
+use crate::widgets::canvas::serialise::Resolution;
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use gtk::subclass::widget::WidgetImpl;
+
+    use super::*;
+
+    /// Plays a [SlideTransition] over the slideshow, drawing the outgoing
+    /// slide from a snapshot and the incoming one from the live slideshow
+    #[derive(Default)]
+    pub struct TransitionLayer {
+        pub from: RefCell<Option<gdk::Paintable>>,
+        pub to: RefCell<Option<gdk::Paintable>>,
+        pub transition: RefCell<SlideTransition>,
+        /// seconds
+        pub duration: Cell<f64>,
+        /// seconds since the first frame
+        pub elapsed: Cell<f64>,
+        pub tick: RefCell<Option<gtk::TickCallbackId>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for TransitionLayer {
+        const NAME: &'static str = "TransitionLayer";
+        type Type = super::TransitionLayer;
+        type ParentType = gtk::Widget;
+    }
+
+    impl ObjectImpl for TransitionLayer {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+            obj.set_can_target(false);
+            obj.set_visible(false);
+            obj.set_overflow(gtk::Overflow::Hidden);
+        }
+
+        fn dispose(&self) {
+            self.obj().finish();
+        }
+    }
+
+    impl WidgetImpl for TransitionLayer {
+        fn snapshot(&self, snapshot: &gtk::Snapshot) {
+            let (Some(from), Some(to)) = (self.from.borrow().clone(), self.to.borrow().clone())
+            else {
+                return;
+            };
+            let Some(frame) = self
+                .transition
+                .borrow()
+                .frame(self.elapsed.get(), self.duration.get())
+            else {
+                return;
+            };
+
+            let obj = self.obj();
+            let (width, height) = (obj.width() as f64, obj.height() as f64);
+            if width <= 0.0 || height <= 0.0 {
+                return;
+            }
+            // same scale the canvas uses, the layer has the shape of the slides
+            let ratio = height / Resolution::SLIDE_HEIGHT;
+            let bounds = graphene::Rect::new(0.0, 0.0, width as f32, height as f32);
+
+            snapshot.append_color(&gdk::RGBA::BLACK, &bounds);
+
+            if frame.from_opacity > 0.0 {
+                snapshot.push_opacity(frame.from_opacity);
+                from.snapshot(snapshot, width, height);
+                snapshot.pop();
+            }
+
+            if frame.to_opacity <= 0.0 {
+                return;
+            }
+            let (x, y, w, h) = frame.to_clip;
+            let clip = graphene::Rect::new(
+                (x * width) as f32,
+                (y * height) as f32,
+                (w * width) as f32,
+                (h * height) as f32,
+            );
+            snapshot.push_clip(&clip);
+            snapshot.push_opacity(frame.to_opacity);
+            if frame.to_blur > 0.0 {
+                snapshot.push_blur(frame.to_blur * ratio);
+            }
+
+            snapshot.save();
+            let centre = graphene::Point::new((width / 2.0) as f32, (height / 2.0) as f32);
+            snapshot.translate(&centre);
+            snapshot.scale(frame.to_scale as f32, frame.to_scale as f32);
+            snapshot.translate(&graphene::Point::new(-centre.x(), -centre.y()));
+            to.snapshot(snapshot, width, height);
+            snapshot.restore();
+
+            if frame.to_blur > 0.0 {
+                snapshot.pop();
+            }
+            snapshot.pop();
+            snapshot.pop();
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct TransitionLayer(ObjectSubclass<imp::TransitionLayer>)
+        @extends gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Default for TransitionLayer {
+    fn default() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl TransitionLayer {
+    /// Plays `transition` from `from` to `to` over `duration` seconds,
+    /// cutting short any transition still playing
+    pub fn play(
+        &self,
+        from: &impl IsA<gdk::Paintable>,
+        to: &impl IsA<gdk::Paintable>,
+        transition: &SlideTransition,
+        duration: f64,
+    ) {
+        self.finish();
+        if transition.effect.is_none() || duration <= 0.0 {
+            return;
+        }
+
+        let imp = self.imp();
+        imp.from.replace(Some(from.clone().upcast()));
+        imp.to.replace(Some(to.clone().upcast()));
+        imp.transition.replace(transition.clone());
+        imp.duration.set(duration);
+        imp.elapsed.set(0.0);
+        self.set_visible(true);
+
+        let start = std::cell::Cell::new(None);
+        let tick = self.add_tick_callback(move |obj, clock| {
+            let now = clock.frame_time();
+            let start = match start.get() {
+                Some(start) => start,
+                None => {
+                    start.set(Some(now));
+                    now
+                }
+            };
+            let elapsed = (now - start) as f64 / 1_000_000.0;
+
+            let imp = obj.imp();
+            imp.elapsed.set(elapsed);
+            obj.queue_draw();
+
+            if elapsed < imp.duration.get() {
+                return glib::ControlFlow::Continue;
+            }
+            // the callback is removed by returning Break
+            imp.tick.replace(None);
+            obj.finish();
+            glib::ControlFlow::Break
+        });
+        imp.tick.replace(Some(tick));
+    }
+
+    /// Stops the transition, leaving the incoming slide on screen
+    pub fn finish(&self) {
+        let imp = self.imp();
+        if let Some(tick) = imp.tick.take() {
+            tick.remove();
+        }
+        imp.from.replace(None);
+        imp.to.replace(None);
+        self.set_visible(false);
+    }
+}
@@ -0,0 +1,264 @@
+use gtk::gio;
+use gtk::glib;
+use gtk::glib::subclass::prelude::*;
+use gtk::prelude::*;
+
+use crate::widgets::canvas::canvas::Canvas;
+use crate::widgets::canvas::canvas_item::{CanvasItem, CanvasItemExt};
+use crate::widgets::canvas::serialise::{CanvasItemData, VideoItemData, VideoOptions};
+
+mod imp {
+    use std::cell::RefCell;
+
+    use gtk::{
+        gdk,
+        glib::object::{Cast, CastNone},
+        subclass::{box_::BoxImpl, widget::WidgetImpl},
+    };
+
+    use super::*;
+    use crate::{
+        services::file_manager::FileManager,
+        utils::{self, WidgetExtrasExt},
+        widgets::canvas::{
+            canvas_item::{CanvasItemImpl, CanvasItemImplExt},
+            image_item::IMAGE_MISSING_CSS,
+            serialise::CanvasItemType,
+        },
+    };
+
+    #[derive(Default)]
+    pub struct VideoItem {
+        pub picture: RefCell<gtk::Picture>,
+        pub(super) url: RefCell<String>,
+        pub(super) options: RefCell<VideoOptions>,
+        /// shown while the canvas is not playing media
+        pub(super) still: RefCell<Option<gdk::Paintable>>,
+        pub(super) media: RefCell<Option<gtk::MediaFile>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for VideoItem {
+        const NAME: &'static str = "VideoItem";
+        type Type = super::VideoItem;
+        type ParentType = CanvasItem;
+    }
+
+    impl ObjectImpl for VideoItem {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj().clone();
+
+            utils::set_style(&obj, IMAGE_MISSING_CSS);
+
+            let picture = self.picture.borrow().clone();
+            picture.set_sensitive(false);
+            picture.set_margin_all(6);
+            picture.set_content_fit(gtk::ContentFit::Contain);
+            obj.add_overlay_child(&picture);
+
+            // hidden items, e.g. on a cleared display, should not be heard
+            obj.connect_visible_notify(|obj| obj.style());
+        }
+
+        fn dispose(&self) {
+            self.stop();
+            self.picture.borrow().unparent();
+            self.still.replace(None);
+        }
+    }
+    impl WidgetImpl for VideoItem {}
+    impl BoxImpl for VideoItem {}
+
+    impl CanvasItemImpl for VideoItem {
+        fn load_item_data(&self) {
+            let Some(json_data) = self.obj().get_save_data() else {
+                return;
+            };
+
+            let CanvasItemType::Video(data) = json_data.item_type else {
+                return;
+            };
+
+            self.options.replace(data.options);
+            self.set_url(data.url);
+        }
+
+        fn serialise_item(&self) -> CanvasItemType {
+            CanvasItemType::Video(VideoItemData {
+                url: self.url.borrow().clone(),
+                options: self.options.borrow().clone(),
+            })
+        }
+
+        fn style(&self) {
+            self.parent_style();
+
+            let obj = self.obj();
+            let picture = self.picture.borrow().clone();
+            let playing = obj.is_visible()
+                && obj
+                    .upcast_ref::<CanvasItem>()
+                    .canvas()
+                    .is_some_and(|c| c.media_playing());
+
+            if self.url.borrow().is_empty() {
+                self.stop();
+                picture.set_paintable(None::<&gdk::Paintable>);
+                obj.add_css_class("missing-image");
+                return;
+            }
+            obj.remove_css_class("missing-image");
+
+            if playing {
+                if self.media.borrow().is_none() {
+                    let media = super::play_video(&self.url.borrow(), &self.options.borrow());
+                    self.media.replace(Some(media));
+                }
+                picture.set_paintable(self.media.borrow().as_ref());
+            } else {
+                self.stop();
+                picture.set_paintable(self.still.borrow().as_ref());
+            }
+        }
+    }
+
+    impl VideoItem {
+        pub(super) fn stop(&self) {
+            if let Some(media) = self.media.take() {
+                media.pause();
+            }
+        }
+
+        pub(super) fn set_url(&self, url: String) {
+            self.stop();
+            self.still.replace(None);
+            self.url.replace(url.clone());
+
+            if url.is_empty() {
+                self.obj().style();
+                return;
+            }
+
+            FileManager::get_video_frame(
+                std::path::Path::new(&url),
+                self.options.borrow().start_offset,
+                None,
+                glib::clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    move |v| {
+                        imp.still.replace(v.and_upcast::<gdk::Paintable>());
+                        imp.obj().style();
+                    }
+                ),
+            );
+            self.obj().style();
+        }
+
+        pub(super) fn register_change_video(&self) {
+            self.obj().connect_double_clicked(|obj| {
+                let Some(obj) = obj.downcast_ref::<super::VideoItem>() else {
+                    return;
+                };
+                let Some(file) = FileManager::open_video(obj.toplevel_window().as_ref()) else {
+                    return;
+                };
+                let Some(path) =
+                    FileManager::file_to_link(&file, crate::app_config::AppConfigDir::SlideMedia)
+                else {
+                    return;
+                };
+
+                obj.imp().set_url(path);
+            });
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct VideoItem(ObjectSubclass<imp::VideoItem>)
+        @extends CanvasItem, gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Orientable, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl VideoItem {
+    pub fn new(canvas: Option<&Canvas>, save_data: Option<CanvasItemData>) -> Self {
+        let data = match save_data {
+            Some(d) => serde_json::to_string(&d).ok(),
+            None => None,
+        };
+
+        let obj: Self = glib::Object::builder()
+            .property("canvas", canvas)
+            .property("save-data", data)
+            .build();
+
+        obj.imp().register_change_video();
+        obj.load_data();
+
+        if canvas.is_some() {
+            obj.style();
+        }
+
+        obj
+    }
+
+    pub fn from_file(canvas: Option<&Canvas>, file: &gio::File) -> Self {
+        let obj = Self::new(canvas, None);
+        if let Some(path) = file.path() {
+            obj.imp().set_url(path.display().to_string());
+        }
+
+        obj
+    }
+
+    pub fn url(&self) -> String {
+        self.imp().url.borrow().clone()
+    }
+
+    pub fn options(&self) -> VideoOptions {
+        self.imp().options.borrow().clone()
+    }
+
+    pub fn set_options(&self, options: VideoOptions) {
+        let offset_changed = self.imp().options.borrow().start_offset != options.start_offset;
+        self.imp().options.replace(options);
+
+        // restart playback and reload the still frame with the new options
+        if offset_changed {
+            self.imp().set_url(self.url());
+        } else {
+            self.imp().stop();
+            self.style();
+        }
+    }
+}
+
+/// Starts playing the video at `path`, looping back to the start offset
+pub fn play_video(path: &str, options: &VideoOptions) -> gtk::MediaFile {
+    let media = gtk::MediaFile::for_filename(path);
+    media.set_muted(options.muted);
+
+    let offset = options.start_offset as i64 * 1000;
+    if offset > 0 {
+        media.connect_prepared_notify(move |m| {
+            if m.is_prepared() && m.is_seekable() {
+                m.seek(offset);
+            }
+        });
+    }
+
+    // MediaStream loops back to 0, not to the start offset
+    if options.loop_video {
+        media.connect_ended_notify(move |m| {
+            if m.is_ended() {
+                m.seek(offset);
+                m.play();
+            }
+        });
+    }
+
+    media.play();
+    media
+}
@@ -6,11 +6,13 @@ use gtk::{
 };
 
 use crate::{
-    app_config::AppConfig,
-    services::message_alert_manager::MessageAlertManager,
+    services::{
+        live_output::LiveOutput, message_alert_manager::MessageAlertManager, slide::Slide,
+        slide_manager::SlideManager,
+    },
     utils::WidgetChildrenExt,
     widgets::{
-        canvas::{canvas_item::CanvasItem, serialise::SlideManagerData},
+        canvas::{canvas_item::CanvasItem, screen_cover::ScreenMode, serialise::SlideManagerData},
         message_alert_wrapper::MessageAlertWapper,
     },
 };
@@ -25,6 +27,7 @@ mod imp {
     use gtk::{
         glib::{
             self, Properties,
+            object::ObjectExt,
             subclass::{
                 Signal,
                 object::{ObjectImpl, ObjectImplExt},
@@ -32,7 +35,7 @@ mod imp {
             },
         },
         prelude::GtkWindowExt,
-        subclass::{widget::WidgetImpl, window::WindowImpl},
+        subclass::{prelude::DerivedObjectProperties, widget::WidgetImpl, window::WindowImpl},
     };
 
     use crate::{app_config::AppConfig, services::slide_manager::SlideManager};
@@ -42,6 +45,12 @@ mod imp {
     pub struct ExtendedScreen {
         pub(super) slide_manager: RefCell<SlideManager>,
         pub clear: Cell<bool>,
+        /// note of the live item
+        #[property(get, set)]
+        pub note: RefCell<String>,
+        /// unix time the first item went live, `0` before
+        #[property(get, set)]
+        pub service_start: Cell<i64>,
     }
 
     #[glib::object_subclass]
@@ -51,6 +60,7 @@ mod imp {
         type ParentType = gtk::Window;
     }
 
+    #[glib::derived_properties]
     impl ObjectImpl for ExtendedScreen {
         fn constructed(&self) {
             self.parent_constructed();
@@ -71,9 +81,12 @@ mod imp {
             let sm = self.slide_manager.borrow();
             sm.set_animation(true);
             sm.show_end_presentation_slide();
+            sm.connect_current_slide_changed(super::ExtendedScreen::play_slide_media);
 
+            // outputs of another shape than the slides show bars around them
             let frame = gtk::AspectFrame::new(0.5, 0.5, AppConfig::aspect_ratio(), false);
-            frame.set_child(Some(&sm.slideshow()));
+            frame.set_child(Some(&sm.screen()));
+            sm.bind_aspect_frame(&frame);
 
             obj.connect_default_width_notify(super::ExtendedScreen::on_resize);
             obj.connect_default_height_notify(super::ExtendedScreen::on_resize);
@@ -130,6 +143,10 @@ impl ExtendedScreen {
         };
 
         sm.set_title(data.title.clone());
+        self.set_note(data.note.clone());
+        if self.service_start() == 0 {
+            self.reset_service_timer();
+        }
         {
             if let Some(mut end_slide) = data.slides.first().cloned() {
                 end_slide.items.clear();
@@ -157,6 +174,40 @@ impl ExtendedScreen {
         self.clear_display(imp.clear.get());
     }
 
+    pub fn slide_manager(&self) -> SlideManager {
+        self.imp().slide_manager.borrow().clone()
+    }
+
+    /// Shows what `live` puts on the outputs
+    pub fn follow(&self, live: &LiveOutput) {
+        live.connect_item_changed(glib::clone!(
+            #[weak(rename_to=obj)]
+            self,
+            move |_, data| obj.load_data(data)
+        ));
+        live.connect_slide_changed(glib::clone!(
+            #[weak(rename_to=obj)]
+            self,
+            move |_, position| obj.set_pos(position)
+        ));
+        live.connect_clear_notify(glib::clone!(
+            #[weak(rename_to=obj)]
+            self,
+            move |live| obj.clear_display(live.clear())
+        ));
+        live.connect_screen_mode_notify(glib::clone!(
+            #[weak(rename_to=obj)]
+            self,
+            move |live| obj.set_screen_mode(live.screen_mode())
+        ));
+    }
+
+    /// Starts the service timer of the stage displays from now
+    pub fn reset_service_timer(&self) {
+        let now = glib::DateTime::now_local().map(|t| t.to_unix());
+        self.set_service_start(now.unwrap_or_default());
+    }
+
     pub fn set_pos(&self, position: u32) {
         let imp = self.imp();
         let sm = imp.slide_manager.borrow();
@@ -195,6 +246,12 @@ impl ExtendedScreen {
         }
     }
 
+    /// Shows black or the logo in place of the slides, until set back to
+    /// [ScreenMode::Live]
+    pub fn set_screen_mode(&self, mode: ScreenMode) {
+        self.imp().slide_manager.borrow().set_screen_mode(mode);
+    }
+
     pub fn set_alert_manager(&self, alert_manager: &MessageAlertManager) {
         let imp = self.imp();
         let sm = imp.slide_manager.borrow();
@@ -208,11 +265,22 @@ impl ExtendedScreen {
         a_frame.set_child(Some(&alert_wrapper));
     }
 
+    /// Videos only play on the live slide, the others show a still frame
+    fn play_slide_media(sm: &SlideManager, current: &Slide) {
+        let end_slide = sm.imp().end_presentation_slide.borrow().clone();
+        for slide in sm.slides().iter().chain([&end_slide]) {
+            if let Some(canvas) = slide.canvas() {
+                canvas.set_media_playing(slide == current);
+            }
+        }
+    }
+
     fn on_resize(obj: &Self) {
         let width = obj.width();
 
         if width > 0 {
-            obj.set_default_size(width, (width as f32 / AppConfig::aspect_ratio()) as i32);
+            let ratio = obj.slide_manager().resolution().ratio();
+            obj.set_default_size(width, (width as f32 / ratio) as i32);
         }
     }
 }
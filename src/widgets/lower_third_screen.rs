@@ -0,0 +1,193 @@
+use gtk::{
+    cairo, gdk,
+    glib::{self, subclass::types::ObjectSubclassIsExt},
+    pango,
+    prelude::WidgetExt,
+};
+
+use crate::{
+    services::{
+        lower_third,
+        settings::ApplicationSettings,
+        text_fit::{self, TextFit},
+    },
+    utils::TextBufferExtraExt,
+    widgets::extended_screen::ExtendedScreen,
+};
+
+/// Output height the `lower-third-font-size` setting is for
+const REFERENCE_HEIGHT: f64 = 1080.0;
+/// Space between the region edges and the text, from the region height
+const PADDING: f64 = 0.1;
+
+mod imp {
+    use std::cell::RefCell;
+
+    use gtk::{
+        gio::prelude::SettingsExt,
+        glib::{
+            self, Properties,
+            object::ObjectExt,
+            subclass::{
+                object::{ObjectImpl, ObjectImplExt},
+                types::{ObjectSubclass, ObjectSubclassExt},
+            },
+        },
+        prelude::{DrawingAreaExtManual, GtkWindowExt, WidgetExt},
+        subclass::{prelude::DerivedObjectProperties, widget::WidgetImpl, window::WindowImpl},
+    };
+
+    use crate::services::{settings::ApplicationSettings, slide_manager::SlideManager};
+
+    /// Shows the live slide text as a lower third over a transparent
+    /// background, for a livestream to put over the camera
+    #[derive(Default, Properties)]
+    #[properties(wrapper_type = super::LowerThirdScreen)]
+    pub struct LowerThirdScreen {
+        #[property(get, construct_only)]
+        pub(super) slide_manager: glib::WeakRef<SlideManager>,
+
+        pub(super) text: RefCell<String>,
+        pub(super) area: gtk::DrawingArea,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for LowerThirdScreen {
+        const NAME: &'static str = "LowerThirdScreen";
+        type Type = super::LowerThirdScreen;
+        type ParentType = gtk::Window;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for LowerThirdScreen {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+            obj.set_title(Some("Lower third"));
+            obj.set_default_size(1280, 720);
+            obj.add_css_class("transparent_bg");
+
+            self.area.set_draw_func(glib::clone!(
+                #[weak]
+                obj,
+                move |_, cr, width, height| obj.draw(cr, width, height)
+            ));
+            obj.set_child(Some(&self.area));
+
+            if let Some(sm) = self.slide_manager.upgrade() {
+                sm.connect_current_slide_changed(glib::clone!(
+                    #[weak]
+                    obj,
+                    move |_, _| obj.update_text()
+                ));
+            }
+
+            ApplicationSettings::get_instance().connect_changed(
+                None,
+                glib::clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    move |_, key| {
+                        if key.starts_with("lower-third") {
+                            imp.area.queue_draw();
+                        }
+                    }
+                ),
+            );
+
+            obj.update_text();
+        }
+    }
+
+    impl WidgetImpl for LowerThirdScreen {}
+    impl WindowImpl for LowerThirdScreen {}
+}
+
+glib::wrapper! {
+    pub struct LowerThirdScreen(ObjectSubclass<imp::LowerThirdScreen>)
+        @extends gtk::Window, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl LowerThirdScreen {
+    /// A lower third following the live slides of `screen`
+    pub fn new(screen: &ExtendedScreen) -> Self {
+        glib::Object::builder()
+            .property("slide_manager", screen.slide_manager())
+            .build()
+    }
+
+    fn update_text(&self) {
+        let imp = self.imp();
+        let text = self
+            .slide_manager()
+            .and_then(|sm| sm.current_slide())
+            .and_then(|s| s.entry_buffer())
+            .map(|b| b.full_text().to_string())
+            .unwrap_or_default();
+
+        imp.text.replace(text);
+        imp.area.queue_draw();
+    }
+
+    fn draw(&self, cr: &cairo::Context, width: i32, height: i32) {
+        let text = self.imp().text.borrow().clone();
+        if text.trim().is_empty() {
+            return;
+        }
+
+        let settings = ApplicationSettings::get_instance();
+        let (x, y, w, h) = lower_third::region(
+            width as f64,
+            height as f64,
+            settings.lower_third_height(),
+            settings.lower_third_margin(),
+        );
+        if w <= 0.0 || h <= 0.0 {
+            return;
+        }
+
+        let set_color = |hex: &str| {
+            let color = gdk::RGBA::parse(hex).unwrap_or(gdk::RGBA::WHITE);
+            cr.set_source_rgba(
+                color.red().into(),
+                color.green().into(),
+                color.blue().into(),
+                color.alpha().into(),
+            );
+        };
+        set_color(&settings.lower_third_background());
+        cr.rectangle(x, y, w, h);
+        let _ = cr.fill();
+
+        let padding = h * PADDING;
+        let (text_w, text_h) = (w - padding * 2.0, h - padding * 2.0);
+        let layout = pangocairo::functions::create_layout(cr);
+        layout.set_alignment(pango::Alignment::Center);
+        layout.set_wrap(pango::WrapMode::WordChar);
+        layout.set_width((text_w * pango::SCALE as f64) as i32);
+        let lines = settings.lower_third_lines() as usize;
+        layout.set_text(&lower_third::reflow(&text, lines));
+
+        let mut font = pango::FontDescription::from_string(&settings.lower_third_font());
+        let mut measure = |size: f64| {
+            font.set_absolute_size(size * pango::SCALE as f64);
+            layout.set_font_description(Some(&font));
+            let (w, h) = layout.pixel_size();
+            (w as f64, h as f64)
+        };
+        let size = settings.lower_third_font_size() * height as f64 / REFERENCE_HEIGHT;
+        let fit = text_fit::fit_text(
+            TextFit::Shrink,
+            size,
+            lower_third::MIN_TEXT_SIZE,
+            (text_w, text_h),
+            &mut measure,
+        );
+        let (_, layout_h) = measure(fit.size);
+
+        set_color(&settings.lower_third_color());
+        cr.move_to(x + padding, y + (h - layout_h) / 2.0);
+        pangocairo::functions::show_layout(cr, &layout);
+    }
+}
@@ -57,7 +57,7 @@ mod imp {
                 return;
             };
             let overlay = gtk::Overlay::new();
-            overlay.set_child(Some(&sm.slideshow()));
+            overlay.set_child(Some(&sm.screen()));
 
             {
                 // its simpler than it looks
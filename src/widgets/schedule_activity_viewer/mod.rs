@@ -24,8 +24,17 @@ mod imp {
     use std::{cell::RefCell, sync::OnceLock};
 
     use crate::{
-        utils::WidgetChildrenExt,
-        widgets::{canvas::serialise::SlideManagerData, search::songs::edit_modal::SongEditWindow},
+        db::query::Query,
+        services::{
+            file_manager::FileManager, renderer::SlideRenderer, settings::ApplicationSettings,
+            theme::song_footer,
+        },
+        utils::{WidgetChildrenExt, WidgetExtrasExt},
+        widgets::{
+            audio_cue_editor::AudioCueEditor,
+            canvas::serialise::{AutoAdvance, SlideManagerData},
+            search::songs::edit_modal::SongEditWindow,
+        },
     };
 
     use super::*;
@@ -209,13 +218,102 @@ mod imp {
                             // setting title here forces the schedule item title
                             // to update accordingly
                             item.set_title(smd.title.clone());
-                            item.set_slide_data(smd);
+                            let mut smd = smd.clone();
+                            smd.audio = item.slide_data().audio;
+                            smd.theme_id = item.slide_data().theme_id;
+                            smd.auto_advance = item.slide_data().auto_advance;
+                            item.set_slide_data(&smd);
                         }
                     ));
 
                     edit_window.show(Some(item.slide_data()));
                 }
             ));
+            let audio_action = SimpleAction::new("edit_audio", None);
+            audio_action.connect_activate(clone!(
+                #[strong]
+                listview,
+                move |_sa, _v| {
+                    let model = listview
+                        .model()
+                        .and_downcast::<gtk::SingleSelection>()
+                        .expect("Expected gtk::SingleSelection");
+
+                    let Some(item) = model.selected_item().and_downcast::<ScheduleData>() else {
+                        return;
+                    };
+
+                    Self::show_audio_window(&listview, &item);
+                }
+            ));
+            let theme_action = SimpleAction::new("edit_theme", None);
+            theme_action.connect_activate(clone!(
+                #[strong]
+                listview,
+                move |_sa, _v| {
+                    let model = listview
+                        .model()
+                        .and_downcast::<gtk::SingleSelection>()
+                        .expect("Expected gtk::SingleSelection");
+
+                    let Some(item) = model.selected_item().and_downcast::<ScheduleData>() else {
+                        return;
+                    };
+
+                    Self::show_theme_window(&listview, &item);
+                }
+            ));
+            let advance_action = SimpleAction::new("edit_auto_advance", None);
+            advance_action.connect_activate(clone!(
+                #[strong]
+                listview,
+                move |_sa, _v| {
+                    let model = listview
+                        .model()
+                        .and_downcast::<gtk::SingleSelection>()
+                        .expect("Expected gtk::SingleSelection");
+
+                    let Some(item) = model.selected_item().and_downcast::<ScheduleData>() else {
+                        return;
+                    };
+
+                    Self::show_auto_advance_window(&listview, &item);
+                }
+            ));
+            let export_action = SimpleAction::new("export_pdf", None);
+            export_action.connect_activate(clone!(
+                #[strong]
+                listview,
+                move |_sa, _v| {
+                    let model = listview
+                        .model()
+                        .and_downcast::<gtk::SingleSelection>()
+                        .expect("Expected gtk::SingleSelection");
+
+                    let Some(item) = model.selected_item().and_downcast::<ScheduleData>() else {
+                        return;
+                    };
+
+                    Self::export_pdf(&listview, &item);
+                }
+            ));
+            let export_images_action = SimpleAction::new("export_images", None);
+            export_images_action.connect_activate(clone!(
+                #[strong]
+                listview,
+                move |_sa, _v| {
+                    let model = listview
+                        .model()
+                        .and_downcast::<gtk::SingleSelection>()
+                        .expect("Expected gtk::SingleSelection");
+
+                    let Some(item) = model.selected_item().and_downcast::<ScheduleData>() else {
+                        return;
+                    };
+
+                    Self::export_images(&listview, &item);
+                }
+            ));
             let remove_action = SimpleAction::new("remove_item", None);
             remove_action.connect_activate(clone!(
                 #[strong]
@@ -240,15 +338,32 @@ mod imp {
             listview.insert_action_group("schedule", Some(&menu_action_group));
             menu_action_group.add_action(&add_action);
             menu_action_group.add_action(&edit_action);
+            menu_action_group.add_action(&audio_action);
+            menu_action_group.add_action(&theme_action);
+            menu_action_group.add_action(&advance_action);
+            menu_action_group.add_action(&export_action);
+            menu_action_group.add_action(&export_images_action);
             menu_action_group.add_action(&remove_action);
 
             let menu = gtk::gio::Menu::new();
             let add_item = MenuItem::new(Some("Add Item"), Some("schedule.add_item"));
             let edit_item = MenuItem::new(Some("Edit Item"), Some("schedule.edit_item"));
+            let audio_item = MenuItem::new(Some("Item Audio"), Some("schedule.edit_audio"));
+            let theme_item = MenuItem::new(Some("Item Theme"), Some("schedule.edit_theme"));
+            let advance_item =
+                MenuItem::new(Some("Auto Advance"), Some("schedule.edit_auto_advance"));
+            let export_item = MenuItem::new(Some("Export PDF"), Some("schedule.export_pdf"));
+            let export_images_item =
+                MenuItem::new(Some("Export Images"), Some("schedule.export_images"));
             let remove_item = MenuItem::new(Some("Remove Item"), Some("schedule.remove_item"));
             menu.insert_item(0, &add_item);
             menu.insert_item(1, &edit_item);
-            menu.insert_item(2, &remove_item);
+            menu.insert_item(2, &audio_item);
+            menu.insert_item(3, &theme_item);
+            menu.insert_item(4, &advance_item);
+            menu.insert_item(5, &export_item);
+            menu.insert_item(6, &export_images_item);
+            menu.insert_item(7, &remove_item);
 
             let popover_menu = gtk::PopoverMenu::from_model(Some(&menu));
             popover_menu.set_has_arrow(false);
@@ -267,6 +382,10 @@ mod imp {
 
                 let enable = model.selected_item().is_some();
                 edit_action.set_enabled(enable);
+                audio_action.set_enabled(enable);
+                theme_action.set_enabled(enable);
+                advance_action.set_enabled(enable);
+                export_action.set_enabled(enable);
                 remove_action.set_enabled(enable);
                 //
 
@@ -279,6 +398,333 @@ mod imp {
             self.listview.add_controller(gesture_click);
         }
 
+        /// Edits how long the slides of `item` stay up when it advances on its own
+        fn show_auto_advance_window(listview: &gtk::ListView, item: &ScheduleData) {
+            let window = gtk::Window::builder()
+                .title(format!("Auto advance for \"{}\"", item.title()))
+                .default_width(320)
+                .modal(true)
+                .css_classes(["dialog"])
+                .build();
+            window.set_transient_for(listview.toplevel_window().as_ref());
+
+            let box_ui = gtk::Box::new(gtk::Orientation::Vertical, 0);
+            window.set_child(Some(&box_ui));
+
+            let data = item.slide_data();
+            let auto_advance = data.auto_advance.clone().unwrap_or_default();
+
+            let enabled = gtk::CheckButton::with_label("Advance automatically");
+            enabled.set_active(data.auto_advance.is_some());
+            let duration = gtk::SpinButton::with_range(1.0, 3600.0, 1.0);
+            duration.set_value(auto_advance.duration as f64);
+            let loop_slides = gtk::CheckButton::with_label("Loop");
+            loop_slides.set_active(auto_advance.loop_slides);
+
+            let options = gtk::Grid::builder()
+                .row_spacing(6)
+                .column_spacing(12)
+                .build();
+            options.set_margin_all(6);
+            options.attach(&gtk::Label::new(Some("Seconds per slide")), 0, 0, 1, 1);
+            options.attach(&duration, 1, 0, 1, 1);
+            options.attach(&loop_slides, 0, 1, 2, 1);
+
+            let slides_list = gtk::ListBox::builder()
+                .selection_mode(gtk::SelectionMode::None)
+                .build();
+            let mut spins = Vec::new();
+            for (index, slide) in data.slides.iter().enumerate() {
+                let row = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+                row.set_margin_all(3);
+                let label = gtk::Label::builder()
+                    .label(format!("Slide {}", index + 1))
+                    .hexpand(true)
+                    .xalign(0.0)
+                    .build();
+                let spin = gtk::SpinButton::with_range(0.0, 3600.0, 1.0);
+                spin.set_value(slide.duration.unwrap_or(0) as f64);
+                spin.set_tooltip("Seconds on screen, 0 uses the seconds per slide");
+                row.append(&label);
+                row.append(&spin);
+                slides_list.append(&row);
+                spins.push(spin);
+            }
+            let slides_scroll = gtk::ScrolledWindow::builder()
+                .child(&slides_list)
+                .propagate_natural_height(true)
+                .max_content_height(240)
+                .build();
+            options.attach(&slides_scroll, 0, 2, 2, 1);
+
+            let toggle_row = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+            toggle_row.set_margin_all(6);
+            toggle_row.append(&enabled);
+            box_ui.append(&toggle_row);
+            box_ui.append(&options);
+            box_ui.append(&gtk::Separator::new(gtk::Orientation::Horizontal));
+            enabled
+                .bind_property("active", &options, "sensitive")
+                .sync_create()
+                .build();
+
+            let update = glib::clone!(
+                #[weak]
+                item,
+                #[weak]
+                enabled,
+                #[weak]
+                duration,
+                #[weak]
+                loop_slides,
+                #[strong]
+                spins,
+                move || {
+                    let mut smd = item.slide_data();
+                    smd.auto_advance = enabled.is_active().then(|| AutoAdvance {
+                        duration: duration.value() as u32,
+                        loop_slides: loop_slides.is_active(),
+                    });
+                    for (slide, spin) in smd.slides.iter_mut().zip(&spins) {
+                        slide.duration = (spin.value() > 0.0).then(|| spin.value() as u32);
+                    }
+                    item.set_slide_data(&smd);
+                }
+            );
+            enabled.connect_toggled(clone!(
+                #[strong]
+                update,
+                move |_| update()
+            ));
+            loop_slides.connect_toggled(clone!(
+                #[strong]
+                update,
+                move |_| update()
+            ));
+            for spin in spins.iter().chain([&duration]) {
+                spin.connect_value_changed(clone!(
+                    #[strong]
+                    update,
+                    move |_| update()
+                ));
+            }
+
+            let footer_box = gtk::Box::builder()
+                .margin_top(3)
+                .margin_bottom(3)
+                .margin_start(3)
+                .margin_end(3)
+                .spacing(3)
+                .build();
+            footer_box.append(&gtk::Box::builder().hexpand(true).build());
+            let close_btn = gtk::Button::with_label("Close");
+            close_btn.connect_clicked(glib::clone!(
+                #[weak]
+                window,
+                move |_| window.close()
+            ));
+            footer_box.append(&close_btn);
+            box_ui.append(&footer_box);
+
+            window.present();
+        }
+
+        /// Saves the slides of `item` as a PDF with a page for each
+        fn export_pdf(listview: &gtk::ListView, item: &ScheduleData) {
+            let renderer = SlideRenderer::new(ApplicationSettings::get_instance().min_font_size());
+            let pdf = match renderer.pdf(&item.slide_data()) {
+                Ok(pdf) => pdf,
+                Err(e) => {
+                    glib::g_warning!("ScheduleActivityViewer", "Failed to render PDF: {e}");
+                    return;
+                }
+            };
+
+            let mut filters = glib::List::new();
+            let filter = gtk::FileFilter::new();
+            filter.set_name(Some("PDF"));
+            filter.add_pattern("*.pdf");
+            filters.push_back(filter);
+
+            FileManager::save_file(
+                &format!("Export \"{}\"", item.title()),
+                "Export",
+                &mut filters,
+                &pdf,
+                listview.toplevel_window().as_ref(),
+            );
+        }
+
+        /// Saves each slide of `item` as a PNG in a folder, numbered in
+        /// slide order
+        fn export_images(listview: &gtk::ListView, item: &ScheduleData) {
+            let renderer = SlideRenderer::new(ApplicationSettings::get_instance().min_font_size());
+            let pngs = match renderer.pngs(&item.slide_data()) {
+                Ok(pngs) => pngs,
+                Err(e) => {
+                    glib::g_warning!("ScheduleActivityViewer", "Failed to render images: {e}");
+                    return;
+                }
+            };
+
+            let Some(dir) = FileManager::open_folder(
+                &format!("Export \"{}\"", item.title()),
+                "Export",
+                listview.toplevel_window().as_ref(),
+            )
+            .and_then(|f| f.path()) else {
+                return;
+            };
+
+            let title = item.title().replace(['/', '\\'], "-");
+            for (i, png) in pngs.iter().enumerate() {
+                let path = dir.join(format!("{title} {}.png", i + 1));
+                if let Err(e) = std::fs::write(&path, png) {
+                    glib::g_warning!(
+                        "ScheduleActivityViewer",
+                        "Failed to write {:?}: {:?}",
+                        path,
+                        e
+                    );
+                    return;
+                }
+            }
+        }
+
+        /// Edits the audio cue that plays while `item` is live
+        fn show_audio_window(listview: &gtk::ListView, item: &ScheduleData) {
+            let window = gtk::Window::builder()
+                .title(format!("Audio for \"{}\"", item.title()))
+                .default_width(320)
+                .modal(true)
+                .css_classes(["dialog"])
+                .build();
+            window.set_transient_for(listview.toplevel_window().as_ref());
+
+            let box_ui = gtk::Box::new(gtk::Orientation::Vertical, 0);
+            window.set_child(Some(&box_ui));
+
+            let editor = AudioCueEditor::new();
+            editor.set_cue(item.slide_data().audio);
+            editor.connect_changed(glib::clone!(
+                #[weak]
+                item,
+                move |editor| {
+                    let mut smd = item.slide_data();
+                    smd.audio = editor.cue();
+                    item.set_slide_data(&smd);
+                }
+            ));
+            box_ui.append(&editor);
+            box_ui.append(&gtk::Separator::new(gtk::Orientation::Horizontal));
+
+            let footer_box = gtk::Box::builder()
+                .margin_top(3)
+                .margin_bottom(3)
+                .margin_start(3)
+                .margin_end(3)
+                .spacing(3)
+                .build();
+            footer_box.append(&gtk::Box::builder().hexpand(true).build());
+            let close_btn = gtk::Button::with_label("Close");
+            close_btn.connect_clicked(glib::clone!(
+                #[weak]
+                window,
+                move |_| window.close()
+            ));
+            footer_box.append(&close_btn);
+            box_ui.append(&footer_box);
+
+            window.present();
+        }
+
+        /// Restyles every slide of `item` with a theme picked from the library
+        fn show_theme_window(listview: &gtk::ListView, item: &ScheduleData) {
+            let window = gtk::Window::builder()
+                .title(format!("Theme for \"{}\"", item.title()))
+                .default_width(320)
+                .modal(true)
+                .css_classes(["dialog"])
+                .build();
+            window.set_transient_for(listview.toplevel_window().as_ref());
+
+            let box_ui = gtk::Box::new(gtk::Orientation::Vertical, 0);
+            window.set_child(Some(&box_ui));
+
+            let themes = Query::get_themes().unwrap_or_else(|e| {
+                eprintln!("SQL ERROR: {:?}", e);
+                Vec::new()
+            });
+            let names = themes.iter().map(|t| t.name.as_str()).collect::<Vec<_>>();
+            let dropdown = gtk::DropDown::from_strings(&names);
+            dropdown.set_hexpand(true);
+            if let Some(position) = item
+                .slide_data()
+                .theme_id
+                .and_then(|id| themes.iter().position(|t| t.id == id))
+            {
+                dropdown.set_selected(position as u32);
+            }
+
+            let theme_row = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+            theme_row.set_margin_all(6);
+            theme_row.append(&gtk::Label::new(Some("Theme")));
+            theme_row.append(&dropdown);
+            box_ui.append(&theme_row);
+            box_ui.append(&gtk::Separator::new(gtk::Orientation::Horizontal));
+
+            let footer_box = gtk::Box::builder()
+                .margin_top(3)
+                .margin_bottom(3)
+                .margin_start(3)
+                .margin_end(3)
+                .spacing(3)
+                .build();
+            footer_box.append(&gtk::Box::builder().hexpand(true).build());
+            let apply_btn = gtk::Button::with_label("Apply");
+            apply_btn.set_sensitive(!themes.is_empty());
+            apply_btn.connect_clicked(glib::clone!(
+                #[weak]
+                item,
+                #[weak]
+                window,
+                #[weak]
+                dropdown,
+                move |_| {
+                    let Some(theme) = themes.get(dropdown.selected() as usize) else {
+                        return;
+                    };
+
+                    let mut smd = item.slide_data();
+                    let footer = smd.song_id.filter(|_| theme.show_footer).map(|id| {
+                        // schedules saved before a merge still hold the merged song
+                        let id = Query::resolve_song_id(id).ok().flatten().unwrap_or(id);
+                        let details = Query::get_song_details(id).unwrap_or_default();
+                        song_footer(&smd.title, &details)
+                    });
+                    smd.slides = smd
+                        .slides
+                        .iter()
+                        .map(|slide| theme.apply(slide, footer.as_deref()))
+                        .collect();
+                    smd.theme_id = Some(theme.id);
+                    item.set_slide_data(&smd);
+                    window.close();
+                }
+            ));
+            let close_btn = gtk::Button::with_label("Close");
+            close_btn.connect_clicked(glib::clone!(
+                #[weak]
+                window,
+                move |_| window.close()
+            ));
+            footer_box.append(&apply_btn);
+            footer_box.append(&close_btn);
+            box_ui.append(&footer_box);
+
+            window.present();
+        }
+
         fn register_drag(&self) {
             let listview = self.listview.clone();
 
@@ -518,6 +964,31 @@ impl ScheduleActivityViewer {
         }
     }
 
+    /// Selects and activates the item after the selected one, or the one
+    /// before it when `forward` is not set. `false` when there is no such item
+    pub fn activate_next_item(&self, forward: bool) -> bool {
+        let listview = self.imp().listview.clone();
+        let Some(model) = listview.model().and_downcast::<gtk::SingleSelection>() else {
+            return false;
+        };
+
+        let position = match (model.selected(), forward) {
+            (gtk::INVALID_LIST_POSITION, _) => Some(0),
+            (p, true) => Some(p + 1),
+            (p, false) => p.checked_sub(1),
+        };
+        let Some(position) = position.filter(|p| *p < model.n_items()) else {
+            return false;
+        };
+
+        listview.scroll_to(position, gtk::ListScrollFlags::SELECT, None);
+        let Some(data) = model.selected_item().and_downcast::<ScheduleData>() else {
+            return false;
+        };
+        self.emit_activate(&data.slide_data());
+        true
+    }
+
     pub fn connect_activate<F: Fn(&Self, &SlideManagerData) + 'static>(
         &self,
         f: F,
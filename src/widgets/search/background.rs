@@ -146,7 +146,8 @@ mod imp {
         fn handle_add_background(&self, btn: &gtk::Button) {
             let file_filter = gtk::FileFilter::new();
             file_filter.add_mime_type("image/*");
-            file_filter.set_name(Some("Images"));
+            file_filter.add_mime_type("video/*");
+            file_filter.set_name(Some("Images and Videos"));
 
             let mut list = glib::List::<gtk::FileFilter>::new();
             list.push_back(file_filter);
@@ -1,6 +1,9 @@
 pub mod edit_modal;
+mod history_window;
 mod list_item;
-mod toolbar;
+mod merge_window;
+mod tags_window;
+pub(crate) mod toolbar;
 
 use gtk::glib;
 use gtk::prelude::*;
@@ -19,8 +22,25 @@ enum SearchMode {
     Verse,
 }
 
+/// Number of songs listed under "Recently used"
+const RECENT_SONG_LIMIT: u32 = 25;
+
+/// Part of the library shown, combined with the text search
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum LibraryFilter {
+    #[default]
+    All,
+    Favourites,
+    Recent,
+    Tag(u32),
+}
+
 mod imp {
-    use std::{cell::RefCell, collections::HashSet, sync::OnceLock};
+    use std::{
+        cell::RefCell,
+        collections::{HashMap, HashSet},
+        sync::OnceLock,
+    };
 
     use gtk::{
         gio::{
@@ -39,8 +59,9 @@ mod imp {
             value::ToValue,
         },
         prelude::{
-            EditableExt, EntryExt, FilterExt, GestureExt, GestureSingleExt, GtkWindowExt,
-            ListItemExt, PopoverExt, SelectionModelExt, WidgetExt,
+            ActionExt, EditableExt, EntryExt, FilterExt, GestureExt, GestureSingleExt,
+            GtkWindowExt, ListItemExt, PopoverExt, SelectionModelExt, SorterExt, ToVariant,
+            WidgetExt,
         },
         subclass::{
             box_::BoxImpl,
@@ -55,15 +76,18 @@ mod imp {
     use crate::{
         application::OwApplication,
         db::query::Query,
-        dto::SongObject,
+        dto::{SongDetails, SongObject},
+        services::song_languages,
         utils::{ListViewExtra, WidgetExtrasExt},
         widgets::{
             canvas::serialise::SlideManagerData,
             search::songs::{
-                SearchMode,
+                LibraryFilter, RECENT_SONG_LIMIT, SearchMode,
                 edit_modal::{EditorType, SongEditWindow},
                 list_item::SongListItem,
+                merge_window::SongMergeWindow,
                 signals,
+                tags_window::SongTagsWindow,
             },
         },
     };
@@ -75,10 +99,16 @@ mod imp {
         listview: gtk::TemplateChild<gtk::ListView>,
         #[template_child]
         search_field: gtk::TemplateChild<gtk::Entry>,
+        #[template_child]
+        library_filter: gtk::TemplateChild<gtk::DropDown>,
 
         //
         search_mode: RefCell<SearchMode>,
+        library_filters: RefCell<Vec<LibraryFilter>>,
         filter: RefCell<gtk::CustomFilter>,
+        sorter: RefCell<gtk::CustomSorter>,
+        /// Position of each recently used song, most recent first
+        recent_order: RefCell<HashMap<u32, usize>>,
         search_timeout_id: RefCell<Option<glib::SourceId>>,
     }
 
@@ -108,7 +138,28 @@ mod imp {
                 song_obj.filter_active()
             });
             self.filter.replace(filter.clone());
-            let filter_model = gtk::FilterListModel::new(Some(store), Some(filter));
+            let sorter = gtk::CustomSorter::new(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                #[upgrade_or]
+                gtk::Ordering::Equal,
+                move |a, b| {
+                    let order = imp.recent_order.borrow();
+                    let rank = |item: &glib::Object| {
+                        let song_obj = item
+                            .downcast_ref::<SongObject>()
+                            .expect("Should be `SongObject`");
+                        order
+                            .get(&song_obj.song_id())
+                            .copied()
+                            .unwrap_or(usize::MAX)
+                    };
+                    rank(a).cmp(&rank(b)).into()
+                }
+            ));
+            self.sorter.replace(sorter.clone());
+            let sort_model = gtk::SortListModel::new(Some(store), Some(sorter));
+            let filter_model = gtk::FilterListModel::new(Some(sort_model), Some(filter));
 
             let model = gtk::SingleSelection::new(Some(filter_model));
             listview.set_model(Some(&model));
@@ -157,6 +208,13 @@ mod imp {
             self.register_listview_activate();
             self.register_context_menu();
             self.register_search_field_events();
+
+            self.load_library_filters();
+            self.library_filter.connect_selected_notify(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |_| imp.apply_filters()
+            ));
         }
 
         fn signals() -> &'static [glib::subclass::Signal] {
@@ -279,12 +337,59 @@ mod imp {
                 move |_sa, _v| imp.remove_song()
             ));
 
+            let find_duplicates_action = gio::SimpleAction::new("find-duplicates", None);
+            find_duplicates_action.connect_activate(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |_sa, _v| imp.open_merge_window()
+            ));
+
+            let favourite_action =
+                gio::SimpleAction::new_stateful("favourite", None, &false.to_variant());
+            favourite_action.connect_activate(glib::clone!(
+                #[strong]
+                model,
+                #[weak(rename_to=imp)]
+                self,
+                move |sa, _v| {
+                    let Some(song) = model.selected_item().and_downcast::<SongObject>() else {
+                        return;
+                    };
+                    let favourite = !sa.state().and_then(|v| v.get::<bool>()).unwrap_or(false);
+
+                    match Query::set_song_favourite(song.song_id(), favourite) {
+                        Ok(_) => {
+                            sa.set_state(&favourite.to_variant());
+                            imp.apply_filters();
+                        }
+                        Err(e) => eprintln!("SQL ERROR: {:?}", e),
+                    }
+                }
+            ));
+
+            let collections_action = gio::SimpleAction::new("collections", None);
+            collections_action.connect_activate(glib::clone!(
+                #[strong]
+                model,
+                #[weak(rename_to=imp)]
+                self,
+                move |_sa, _v| {
+                    let Some(song) = model.selected_item().and_downcast::<SongObject>() else {
+                        return;
+                    };
+                    imp.open_tags_window(&song);
+                }
+            ));
+
             let menu_action_group = gio::SimpleActionGroup::new();
             listview.insert_action_group("song", Some(&menu_action_group));
             menu_action_group.add_action(&add_song_action);
             menu_action_group.add_action(&edit_action);
             menu_action_group.add_action(&add_to_schedule_action);
             menu_action_group.add_action(&delete_action);
+            menu_action_group.add_action(&find_duplicates_action);
+            menu_action_group.add_action(&favourite_action);
+            menu_action_group.add_action(&collections_action);
 
             let menu = gtk::gio::Menu::new();
             let add_to_schedule =
@@ -300,6 +405,15 @@ mod imp {
                 4,
                 &gio::MenuItem::new(Some("Delete song"), Some("song.delete")),
             );
+            menu.insert_item(
+                5,
+                &gio::MenuItem::new(Some("Find duplicates"), Some("song.find-duplicates")),
+            );
+
+            let library_section = gio::Menu::new();
+            library_section.append(Some("Favourite"), Some("song.favourite"));
+            library_section.append(Some("Collections…"), Some("song.collections"));
+            menu.append_section(None, &library_section);
 
             let popover_menu = gtk::PopoverMenu::from_model(Some(&menu));
             popover_menu.set_has_arrow(false);
@@ -324,6 +438,20 @@ mod imp {
                     edit_action.set_enabled(enable);
                     add_to_schedule_action.set_enabled(enable);
                     delete_action.set_enabled(enable);
+                    favourite_action.set_enabled(enable);
+                    collections_action.set_enabled(enable);
+
+                    let favourite = item
+                        .map(|song| {
+                            Query::get_favourite_song_ids()
+                                .map(|ids| ids.contains(&song.song_id()))
+                                .unwrap_or_else(|e| {
+                                    eprintln!("SQL ERROR: {:?}", e);
+                                    false
+                                })
+                        })
+                        .unwrap_or(false);
+                    favourite_action.set_state(&favourite.to_variant());
 
                     let rect = gtk::gdk::Rectangle::new(x as i32, y as i32, 0, 0);
                     popover_menu.set_pointing_to(Some(&rect));
@@ -342,9 +470,7 @@ mod imp {
             search.connect_changed(glib::clone!(
                 #[weak(rename_to=imp)]
                 self,
-                #[strong]
-                list,
-                move |se| {
+                move |_se| {
                     if let Some(id) = imp.search_timeout_id.take() {
                         id.remove();
                     }
@@ -354,40 +480,8 @@ mod imp {
                         glib::clone!(
                             #[weak]
                             imp,
-                            #[strong]
-                            list,
-                            #[strong]
-                            se,
                             move || {
-                                if se.text().is_empty() {
-                                    list.get_items().iter().for_each(|v| {
-                                        let v = v
-                                            .downcast_ref::<SongObject>()
-                                            .expect("Should be `SongObject`");
-                                        v.set_filter_active(true);
-                                    });
-                                } else {
-                                    let title_search =
-                                        *imp.search_mode.borrow() == SearchMode::Title;
-                                    let query = Query::search_songs(&se.text(), title_search);
-
-                                    let songs_ids: HashSet<_> = match query {
-                                        Ok(q) => q.iter().map(|v| v.song_id).collect(),
-                                        Err(e) => {
-                                            eprintln!("SQL ERROR: {:?}", e);
-                                            HashSet::new()
-                                        }
-                                    };
-
-                                    list.get_items().iter().for_each(|v| {
-                                        let v = v
-                                            .downcast_ref::<SongObject>()
-                                            .expect("Should be `SongObject`");
-                                        v.set_filter_active(songs_ids.contains(&v.song_id()));
-                                    });
-                                }
-                                //
-                                imp.filter.borrow().changed(gtk::FilterChange::Different);
+                                imp.apply_filters();
                                 imp.search_timeout_id.take();
                             }
                         ),
@@ -455,11 +549,145 @@ mod imp {
                 obj,
                 move |_| obj.imp().reload_song_list()
             ));
+            app.connect_song_used(glib::clone!(
+                #[weak]
+                obj,
+                move |_| {
+                    if obj.imp().current_library_filter() == LibraryFilter::Recent {
+                        obj.imp().apply_filters();
+                    }
+                }
+            ));
+        }
+
+        /// Rebuilds the library filter choices, keeping the current selection
+        fn load_library_filters(&self) {
+            let tags = Query::get_song_tags().unwrap_or_else(|e| {
+                eprintln!("SQL ERROR: {:?}", e);
+                Vec::new()
+            });
+
+            let current = self.current_library_filter();
+
+            let mut filters = vec![
+                LibraryFilter::All,
+                LibraryFilter::Favourites,
+                LibraryFilter::Recent,
+            ];
+            let mut labels = vec!["All songs", "Favourites", "Recently used"];
+            filters.extend(tags.iter().map(|t| LibraryFilter::Tag(t.tag_id)));
+            labels.extend(tags.iter().map(|t| t.name.as_str()));
+
+            let position = filters.iter().position(|f| *f == current).unwrap_or(0);
+            self.library_filters.replace(filters);
+
+            self.library_filter
+                .set_model(Some(&gtk::StringList::new(&labels)));
+            self.library_filter.set_selected(position as u32);
+        }
+
+        fn current_library_filter(&self) -> LibraryFilter {
+            let selected = self.library_filter.selected() as usize;
+            self.library_filters
+                .borrow()
+                .get(selected)
+                .copied()
+                .unwrap_or_default()
+        }
+
+        /// Shows the songs matching both the library filter and the search text,
+        /// recently used songs in the order they were last shown
+        fn apply_filters(&self) {
+            let library_filter = self.current_library_filter();
+            let library_ids = match library_filter {
+                LibraryFilter::All => None,
+                LibraryFilter::Favourites => Some(Query::get_favourite_song_ids()),
+                LibraryFilter::Recent => Some(Query::get_recent_song_ids(RECENT_SONG_LIMIT)),
+                LibraryFilter::Tag(tag_id) => Some(Query::get_tagged_song_ids(tag_id)),
+            };
+            let library_ids = library_ids.map(|ids| {
+                ids.unwrap_or_else(|e| {
+                    eprintln!("SQL ERROR: {:?}", e);
+                    Vec::new()
+                })
+            });
+
+            let recent_order = match (library_filter, &library_ids) {
+                (LibraryFilter::Recent, Some(ids)) => {
+                    ids.iter().enumerate().map(|(i, id)| (*id, i)).collect()
+                }
+                _ => HashMap::new(),
+            };
+            self.recent_order.replace(recent_order);
+            let library_ids = library_ids.map(|ids| ids.into_iter().collect::<HashSet<_>>());
+
+            let text = self.search_field.text();
+            let search_ids = match text.is_empty() {
+                true => None,
+                false => {
+                    let title_search = *self.search_mode.borrow() == SearchMode::Title;
+                    match Query::search_songs(&text, title_search) {
+                        Ok(q) => Some(q.iter().map(|v| v.song_id).collect::<HashSet<_>>()),
+                        Err(e) => {
+                            eprintln!("SQL ERROR: {:?}", e);
+                            Some(HashSet::new())
+                        }
+                    }
+                }
+            };
+
+            self.listview.get_items().iter().for_each(|v| {
+                let v = v
+                    .downcast_ref::<SongObject>()
+                    .expect("Should be `SongObject`");
+                let id = v.song_id();
+                let active = library_ids.as_ref().is_none_or(|ids| ids.contains(&id))
+                    && search_ids.as_ref().is_none_or(|ids| ids.contains(&id));
+                v.set_filter_active(active);
+            });
+
+            self.filter.borrow().changed(gtk::FilterChange::Different);
+            self.sorter.borrow().changed(gtk::SorterChange::Different);
+        }
+
+        fn open_tags_window(&self, song: &SongObject) {
+            let tags_window = SongTagsWindow::new(song.song_id(), &song.title());
+            tags_window.set_transient_for(self.obj().toplevel_window().as_ref());
+            tags_window.connect_changed(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |_| {
+                    imp.load_library_filters();
+                    imp.apply_filters();
+                }
+            ));
+            tags_window.present();
         }
 
         fn open_edit_modal(&self, song: Option<SongObject>) {
             let edit_window = SongEditWindow::new(Some(EditorType::Song));
             let song_id = song.clone().map(|v| v.song_id()).unwrap_or_default();
+            edit_window.set_song_id(song_id);
+
+            let details = match song_id {
+                0 => SongDetails::default(),
+                id => Query::get_song_details(id).unwrap_or_else(|e| {
+                    eprintln!("SQL ERROR: {:?}", e);
+                    SongDetails::default()
+                }),
+            };
+            edit_window.set_ccli_number(details.ccli_number.as_deref());
+            if song_id != 0 {
+                let theme_id = Query::get_song_theme_id(song_id).unwrap_or_else(|e| {
+                    eprintln!("SQL ERROR: {:?}", e);
+                    None
+                });
+                edit_window.set_theme_id(theme_id);
+            }
+
+            // the editor only works on the verse text, translations
+            // are put back on the verses they belong to
+            let original = song.as_ref().map(|s| s.verses()).unwrap_or_default();
 
             edit_window.connect_save(glib::clone!(
                 #[weak(rename_to=imp)]
@@ -468,22 +696,48 @@ mod imp {
                     println!("SONG saved");
 
                     let song_obj = SongObject::from(smd.clone());
-                    song_obj.set_song_id(song_id);
-                    let song_data = song_obj.song_data();
+                    song_obj.set_song_id(w.song_id());
+                    let mut song_data = song_obj.song_data();
+                    song_languages::restore_translations(&mut song_data.verses, &original);
+                    song_data.details = SongDetails {
+                        ccli_number: w.ccli_number(),
+                        ..details.clone()
+                    };
+
                     let res = match w.is_new() {
                         true => Query::insert_song(&song_data),
-                        false => Query::update_song(&song_data),
+                        false => Query::update_song(&song_data).and_then(|_| {
+                            Query::update_song_details(song_data.song_id, &song_data.details)
+                                .map(|_| song_data.song_id)
+                        }),
                     };
-                    let _ = match res {
-                        Ok(()) => w.set_is_new(false),
+                    match res {
+                        Ok(id) => {
+                            w.set_song_id(id);
+                            w.set_is_new(false);
+                            if let Err(e) = Query::set_song_theme(id, w.theme_id()) {
+                                eprintln!("SQL ERROR: {:?}", e);
+                            }
+                        }
                         Err(x) => println!("SQL ERROR: {:?}", x),
                     };
 
                     imp.reload_song_list();
                 }
             ));
-            edit_window.show(song.map(SongObject::into));
+            edit_window.show(song.map(|s| s.slide_manager_data(false)));
+        }
+        fn open_merge_window(&self) {
+            let merge_window = SongMergeWindow::new();
+            merge_window.set_transient_for(self.obj().toplevel_window().as_ref());
+            merge_window.connect_merged(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |_| imp.reload_song_list()
+            ));
+            merge_window.present();
         }
+
         fn reload_song_list(&self) {
             let songs = Query::search_songs("", true);
 
@@ -496,6 +750,7 @@ mod imp {
                     self.listview.remove_all();
                     let song_slice: Vec<SongObject> = songs.into_iter().map(|s| s.into()).collect();
                     store.extend_from_slice(&song_slice);
+                    self.apply_filters();
                 }
                 Err(e) => eprintln!("SQL ERROR: {:?}", e),
             }
@@ -1,12 +1,19 @@
+use crate::db::query::Query;
+use crate::dto::SongObject;
+use crate::services::history_manager::{HistoryAction, HistoryActionKind};
 use crate::services::slide::Slide;
+use crate::services::text_fit::TextFit;
+use crate::services::theme::{SlideTheme, ThemeContent};
 use crate::utils::{ListViewExtra, WidgetChildrenExt};
-use crate::widgets::canvas::serialise::{SlideData, SlideManagerData};
-use gtk::glib;
+use crate::widgets::canvas::serialise::{Resolution, SlideData, SlideManagerData};
+use crate::widgets::search::songs::history_window::SongHistoryWindow;
 use gtk::glib::subclass::types::ObjectSubclassIsExt;
 use gtk::prelude::*;
+use gtk::{gio, glib};
 
 const WIDTH: i32 = 1000;
 const MIN_TEXT_WIDTH: i32 = 300;
+const FIT_WARNING: &str = "Text is shrunk to the minimum size or does not fit";
 
 mod signals {
     pub const SAVE: &str = "save";
@@ -21,13 +28,14 @@ pub enum EditorType {
 
 mod imp {
     use std::{
-        cell::{Cell, RefCell},
+        cell::{Cell, OnceCell, RefCell},
         sync::OnceLock,
     };
 
     use super::*;
     use crate::{
-        app_config::AppConfig, services::slide_manager::SlideManager, utils::WidgetExtrasExt,
+        services::{history_manager::HistoryManager, slide_manager::SlideManager},
+        utils::WidgetExtrasExt,
         widgets::search::songs::toolbar::song_editor_toolbar::SongEditorToolbar,
     };
     use gtk::{
@@ -52,6 +60,9 @@ mod imp {
     pub struct SongEditWindow {
         #[property(get, set)]
         pub is_new: Cell<bool>,
+        /// database id of the song being edited, `0` when not a saved song
+        #[property(get, set)]
+        pub song_id: Cell<u32>,
         pub screen: RefCell<gtk::Stack>,
         pub slide_manager: RefCell<SlideManager>,
         pub title_entry: RefCell<gtk::Entry>,
@@ -63,6 +74,25 @@ mod imp {
 
         pub(super) toolbar_box: RefCell<gtk::Box>,
         pub(super) editor_type: RefCell<EditorType>,
+        pub(super) history_btn: RefCell<gtk::Button>,
+        pub(super) ccli_box: RefCell<gtk::Box>,
+        pub(super) ccli_entry: RefCell<gtk::Entry>,
+        pub(super) theme_box: RefCell<gtk::Box>,
+        pub(super) theme_dropdown: RefCell<gtk::DropDown>,
+        /// ids of the themes listed after "Default" in the theme drop down
+        pub(super) theme_ids: RefCell<Vec<u32>>,
+        pub(super) resolution_box: RefCell<gtk::Box>,
+        pub(super) resolution_dropdown: RefCell<gtk::DropDown>,
+        /// resolutions listed in the resolution drop down
+        pub(super) resolutions: RefCell<Vec<Resolution>>,
+        pub(super) fit_banner: RefCell<adw::Banner>,
+
+        pub(super) history: RefCell<HistoryManager<SlideManagerData>>,
+        /// editor content after the last recorded change
+        pub(super) history_snapshot: RefCell<SlideManagerData>,
+        pub(super) restoring_history: Cell<bool>,
+        pub(super) undo_action: OnceCell<gio::SimpleAction>,
+        pub(super) redo_action: OnceCell<gio::SimpleAction>,
     }
 
     #[glib::object_subclass]
@@ -125,11 +155,25 @@ mod imp {
                     tv.set_top_margin(6);
                     tv.set_bottom_margin(6);
                     tv.set_height_request(40);
+                    tv.set_hexpand(true);
                     let li = list_item
                         .downcast_ref::<gtk::ListItem>()
                         .expect("Needs to be ListItem");
 
-                    li.set_child(Some(&tv));
+                    let warning = gtk::Image::builder()
+                        .icon_name("dialog-warning-symbolic")
+                        .tooltip_text(FIT_WARNING)
+                        .valign(gtk::Align::Start)
+                        .margin_top(6)
+                        .margin_end(6)
+                        .visible(false)
+                        .build();
+                    warning.add_css_class("warning");
+
+                    let row = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+                    row.append(&tv);
+                    row.append(&warning);
+                    li.set_child(Some(&row));
 
                     if let Some(obj) = obj.upgrade() {
                         obj.imp().setup_key_controller(&tv, &listview);
@@ -145,12 +189,17 @@ mod imp {
                     .and_downcast::<Slide>()
                     .expect("The item has to be an `Slide`.");
 
-                let textview = list_item
+                let row = list_item
                     .downcast_ref::<gtk::ListItem>()
                     .expect("Needs to be ListItem")
                     .child()
+                    .and_downcast::<gtk::Box>()
+                    .expect("The child has to be a `gtk::Box`.");
+                let textview = row
+                    .first_child()
                     .and_downcast::<gtk::TextView>()
-                    .expect("The child has to be a `TextView`.");
+                    .expect("The row has to start with a `TextView`.");
+                let warning = row.last_child().expect("The row has to end with an icon.");
 
                 // textview.set_margin_all(0);
                 textview.set_wrap_mode(gtk::WrapMode::Word);
@@ -161,11 +210,18 @@ mod imp {
 
                 slide.connect_visible_notify(glib::clone!(
                     #[weak]
-                    textview,
+                    row,
                     move |slide| {
-                        textview.parent().map(|w| w.set_visible(slide.visible()));
+                        row.parent().map(|w| w.set_visible(slide.visible()));
                     }
                 ));
+
+                warning.set_visible(slide.fit_warning());
+                slide.connect_fit_warning_notify(glib::clone!(
+                    #[weak]
+                    warning,
+                    move |slide| warning.set_visible(slide.fit_warning())
+                ));
             });
 
             self.list_view.replace(listview.clone());
@@ -202,6 +258,87 @@ mod imp {
                 entry_box.append(&title_label);
                 entry_box.append(&title_entry);
 
+                let ccli_box = gtk::Box::builder().margin_start(12).visible(false).build();
+                let ccli_label = gtk::Label::builder().label("CCLI #").margin_end(6).build();
+                let ccli_entry = gtk::Entry::builder()
+                    .placeholder_text("Song number")
+                    .input_purpose(gtk::InputPurpose::Digits)
+                    .width_chars(10)
+                    .build();
+                ccli_box.append(&ccli_label);
+                ccli_box.append(&ccli_entry);
+                entry_box.append(&ccli_box);
+                self.ccli_box.replace(ccli_box);
+                self.ccli_entry.replace(ccli_entry);
+
+                let theme_box = gtk::Box::builder().margin_start(12).visible(false).build();
+                let theme_label = gtk::Label::builder().label("Theme").margin_end(6).build();
+                let theme_dropdown = gtk::DropDown::from_strings(&["Default"]);
+                theme_dropdown.set_tooltip_text(Some("Theme the song is shown with"));
+                theme_box.append(&theme_label);
+                theme_box.append(&theme_dropdown);
+                entry_box.append(&theme_box);
+                self.theme_box.replace(theme_box);
+                self.theme_dropdown.replace(theme_dropdown);
+
+                let resolution_box = gtk::Box::builder().margin_start(12).build();
+                let resolution_label = gtk::Label::builder()
+                    .label("Resolution")
+                    .margin_end(6)
+                    .build();
+                let resolution_dropdown = gtk::DropDown::from_strings(&[]);
+                resolution_dropdown.set_tooltip_text(Some("Shape of the slides"));
+                resolution_dropdown.connect_selected_notify(glib::clone!(
+                    #[weak]
+                    obj,
+                    move |dropdown| {
+                        let resolution = obj
+                            .imp()
+                            .resolutions
+                            .borrow()
+                            .get(dropdown.selected() as usize)
+                            .copied();
+                        if let Some(resolution) = resolution {
+                            obj.set_resolution(resolution);
+                        }
+                    }
+                ));
+                resolution_box.append(&resolution_label);
+                resolution_box.append(&resolution_dropdown);
+                entry_box.append(&resolution_box);
+                self.resolution_box.replace(resolution_box);
+                self.resolution_dropdown.replace(resolution_dropdown);
+
+                let history_btn = gtk::Button::builder()
+                    .tooltip_text("Song history")
+                    .icon_name("document-open-recent-symbolic")
+                    .valign(gtk::Align::Center)
+                    .margin_end(6)
+                    .visible(false)
+                    .build();
+                history_btn.add_css_class("flat");
+                history_btn.connect_clicked(glib::clone!(
+                    #[weak]
+                    obj,
+                    move |_| obj.open_history()
+                ));
+                self.history_btn.replace(history_btn.clone());
+
+                for (action, icon, tooltip) in [
+                    ("editor.undo", "edit-undo-symbolic", "Undo (Ctrl+Z)"),
+                    ("editor.redo", "edit-redo-symbolic", "Redo (Ctrl+Shift+Z)"),
+                ] {
+                    let btn = gtk::Button::builder()
+                        .action_name(action)
+                        .icon_name(icon)
+                        .tooltip_text(tooltip)
+                        .valign(gtk::Align::Center)
+                        .build();
+                    btn.add_css_class("flat");
+                    box_header.append(&btn);
+                }
+                box_header.append(&history_btn);
+
                 box_header
             };
             box_ui.append(&box_header);
@@ -212,6 +349,7 @@ mod imp {
             toolbar_box.append(&SongEditorToolbar::new(&self.slide_manager.borrow()));
             toolbar_box.append(&gtk::Separator::new(gtk::Orientation::Horizontal));
             box_ui.append(&toolbar_box);
+            self.setup_history();
 
             let editor_box = {
                 // EDITOR SECTION
@@ -233,12 +371,25 @@ mod imp {
                 screen.set_margin_all(4);
                 let aspect_frame = gtk::AspectFrame::builder()
                     .css_name("pink_box")
-                    .ratio(AppConfig::aspect_ratio())
                     .obey_child(false)
                     .child(&screen)
                     .build();
+                self.slide_manager.borrow().bind_aspect_frame(&aspect_frame);
                 aspect_frame.set_size_request(300, -1);
-                pane.set_end_child(Some(&aspect_frame));
+                aspect_frame.set_vexpand(true);
+
+                let fit_banner = adw::Banner::new(FIT_WARNING);
+                fit_banner.connect_button_clicked(glib::clone!(
+                    #[weak]
+                    obj,
+                    move |_| obj.split_slide()
+                ));
+                self.fit_banner.replace(fit_banner.clone());
+
+                let screen_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+                screen_box.append(&fit_banner);
+                screen_box.append(&aspect_frame);
+                pane.set_end_child(Some(&screen_box));
 
                 let frame_box = gtk::Box::builder()
                     .orientation(gtk::Orientation::Vertical)
@@ -378,6 +529,62 @@ mod imp {
     impl WindowImpl for SongEditWindow {}
 
     impl SongEditWindow {
+        fn setup_history(&self) {
+            let obj = self.obj();
+
+            let undo_action = gio::SimpleAction::new("undo", None);
+            undo_action.set_enabled(false);
+            undo_action.connect_activate(glib::clone!(
+                #[weak]
+                obj,
+                move |_, _| obj.undo()
+            ));
+
+            let redo_action = gio::SimpleAction::new("redo", None);
+            redo_action.set_enabled(false);
+            redo_action.connect_activate(glib::clone!(
+                #[weak]
+                obj,
+                move |_, _| obj.redo()
+            ));
+
+            let action_group = gio::SimpleActionGroup::new();
+            action_group.add_action(&undo_action);
+            action_group.add_action(&redo_action);
+            obj.insert_action_group("editor", Some(&action_group));
+
+            let shortcuts = gtk::ShortcutController::new();
+            for (trigger, action) in [
+                ("<Control>z", "editor.undo"),
+                ("<Control><Shift>z", "editor.redo"),
+            ] {
+                shortcuts.add_shortcut(gtk::Shortcut::new(
+                    gtk::ShortcutTrigger::parse_string(trigger),
+                    Some(gtk::NamedAction::new(action)),
+                ));
+            }
+            obj.add_controller(shortcuts);
+
+            let _ = self.undo_action.set(undo_action);
+            let _ = self.redo_action.set(redo_action);
+
+            self.slide_manager.borrow().connect_changed(glib::clone!(
+                #[weak]
+                obj,
+                move |_, kind, target, force| {
+                    obj.record_change(kind, target, force);
+                    obj.update_fit_warning();
+                }
+            ));
+            self.slide_manager
+                .borrow()
+                .connect_current_slide_changed(glib::clone!(
+                    #[weak]
+                    obj,
+                    move |_, _| obj.update_fit_warning()
+                ));
+        }
+
         fn build_preview_list(&self, model: &impl IsA<gtk::SelectionModel>) -> gtk::ListView {
             let factory = gtk::SignalListItemFactory::new();
 
@@ -403,7 +610,7 @@ mod imp {
                 let pic = slide.preview();
                 pic.set_can_shrink(true);
                 pic.set_content_fit(gtk::ContentFit::Contain);
-                pic.set_height_request(Slide::preview_height());
+                pic.set_height_request(slide.preview_height());
                 pic.set_width_request(Slide::preview_width());
                 container.set_child(Some(&pic));
 
@@ -527,30 +734,128 @@ impl SongEditWindow {
         let t = editor_type.unwrap_or_default();
         if t == EditorType::Song {
             obj.imp().toolbar_box.borrow().set_visible(false);
+            obj.imp().ccli_box.borrow().set_visible(true);
+            obj.imp().theme_box.borrow().set_visible(true);
+            // songs are shown at the resolution of the live output
+            obj.imp().resolution_box.borrow().set_visible(false);
+            obj.load_themes();
         }
+        obj.list_resolutions(Resolution::default());
         obj.imp().editor_type.replace(t);
 
         obj
     }
 
     pub fn show(&self, song: Option<SlideManagerData>) {
-        let title = match *self.imp().editor_type.borrow() == EditorType::Song {
+        let is_song = *self.imp().editor_type.borrow() == EditorType::Song;
+        let title = match is_song {
             true => "Song",
             false => "Slide",
         };
         if let Some(data) = song {
             self.load_song(&data);
             self.imp().is_new.set(false);
+            self.imp().history_btn.borrow().set_visible(is_song);
             self.set_title(Some(&format!("Edit {title}")));
         } else {
             self.imp().is_new.set(true);
             self.add_new_verse();
             self.set_title(Some(&format!("Add {title}")));
         }
+        self.reset_history();
 
         self.present();
     }
 
+    /// CCLI song number entered in the song editor
+    pub fn ccli_number(&self) -> Option<String> {
+        let text = self.imp().ccli_entry.borrow().text().trim().to_string();
+        (!text.is_empty()).then_some(text)
+    }
+
+    pub fn set_ccli_number(&self, ccli_number: Option<&str>) {
+        self.imp()
+            .ccli_entry
+            .borrow()
+            .set_text(ccli_number.unwrap_or_default());
+    }
+
+    fn load_themes(&self) {
+        let themes = Query::get_themes().unwrap_or_else(|e| {
+            eprintln!("SQL ERROR: {:?}", e);
+            Vec::new()
+        });
+
+        let names = std::iter::once("Default")
+            .chain(themes.iter().map(|t| t.name.as_str()))
+            .collect::<Vec<_>>();
+        self.imp()
+            .theme_dropdown
+            .borrow()
+            .set_model(Some(&gtk::StringList::new(&names)));
+        self.imp()
+            .theme_ids
+            .replace(themes.iter().map(|t| t.id).collect());
+    }
+
+    /// Theme picked for the song, `None` for the default theme of songs
+    pub fn theme_id(&self) -> Option<u32> {
+        let selected = self.imp().theme_dropdown.borrow().selected() as usize;
+        selected
+            .checked_sub(1)
+            .and_then(|i| self.imp().theme_ids.borrow().get(i).copied())
+    }
+
+    pub fn set_theme_id(&self, theme_id: Option<u32>) {
+        let position = theme_id
+            .and_then(|id| self.imp().theme_ids.borrow().iter().position(|v| *v == id))
+            .map_or(0, |i| i + 1);
+        self.imp()
+            .theme_dropdown
+            .borrow()
+            .set_selected(position as u32);
+    }
+
+    /// Fills the resolution drop down with the presets, and `current`
+    /// when it is not one of them, and selects `current`
+    fn list_resolutions(&self, current: Resolution) {
+        let imp = self.imp();
+        let mut resolutions = Resolution::PRESETS.map(|(_, r)| r).to_vec();
+        let mut names = Resolution::PRESETS
+            .map(|(name, _)| name.to_string())
+            .to_vec();
+        if !resolutions.contains(&current) {
+            resolutions.push(current);
+            names.push(format!("{}×{}", current.width, current.height));
+        }
+
+        let position = resolutions.iter().position(|r| *r == current).unwrap_or(0);
+        imp.resolutions.replace(resolutions);
+        let dropdown = imp.resolution_dropdown.borrow().clone();
+        let names = names.iter().map(String::as_str).collect::<Vec<_>>();
+        dropdown.set_model(Some(&gtk::StringList::new(&names)));
+        dropdown.set_selected(position as u32);
+    }
+
+    /// Changes the shape of the slides, moving their items to fit it
+    fn set_resolution(&self, resolution: Resolution) {
+        let imp = self.imp();
+        if imp.slide_manager.borrow().resolution() == resolution {
+            return;
+        }
+
+        let mut data = self.editor_data();
+        data.set_resolution(resolution);
+
+        imp.restoring_history.set(true);
+        imp.slide_manager.borrow().reset();
+        imp.list_view.borrow().remove_all();
+        self.load_song(&data);
+        imp.restoring_history.set(false);
+
+        self.record_change(HistoryActionKind::StyleChanged, None, true);
+    }
+
     pub fn hide(&self) {
         self.close();
     }
@@ -560,9 +865,17 @@ impl SongEditWindow {
             return;
         };
 
+        let is_song = *self.imp().editor_type.borrow() == EditorType::Song;
+        let slide_data = match is_song {
+            true => None,
+            false => SlideTheme::default_for(ThemeContent::Presentation),
+        }
+        .map(|theme| theme.apply(&SlideData::from_default(), None))
+        .unwrap_or_else(SlideData::from_default);
+
         let sm = self.imp().slide_manager.borrow();
-        let slide = sm.new_slide(Some(SlideData::from_default()), true);
-        if *self.imp().editor_type.borrow() == EditorType::Song {
+        let slide = sm.new_slide(Some(slide_data), true);
+        if is_song {
             slide.set_presentation_mode(true);
         }
         page.append_item(&slide);
@@ -626,6 +939,72 @@ impl SongEditWindow {
         });
     }
 
+    /// Moves the lines of the selected slide that do not fit at the
+    /// minimum text size to a new slide after it
+    pub fn split_slide(&self) {
+        let imp = self.imp();
+        let list_view = imp.list_view.borrow().clone();
+        let Some(model) = list_view.model().and_downcast::<gtk::SingleSelection>() else {
+            return;
+        };
+        let Some(slide) = model.selected_item().and_downcast::<Slide>() else {
+            return;
+        };
+        let Some(keep) = slide.text_item().and_then(|t| t.split_point()) else {
+            return;
+        };
+
+        let sm = imp.slide_manager.borrow().clone();
+        imp.restoring_history.set(true);
+        let new_slide = sm.new_slide(Some(slide.serialise()), false);
+        new_slide.load_slide();
+        if *imp.editor_type.borrow() == EditorType::Song {
+            new_slide.set_presentation_mode(true);
+        }
+
+        if let Some(buffer) = slide.entry_buffer()
+            && let Some(mut start) = buffer.iter_at_line(keep as i32)
+        {
+            // the line break before the moved lines goes too
+            start.backward_char();
+            buffer.delete(&mut start, &mut buffer.end_iter());
+        }
+        if let Some(buffer) = new_slide.entry_buffer()
+            && let Some(mut end) = buffer.iter_at_line(keep as i32)
+        {
+            buffer.delete(&mut buffer.start_iter(), &mut end);
+        }
+
+        let position = model.selected() + 1;
+        list_view.insert_item(position, &new_slide);
+        model.select_item(position, true);
+        imp.restoring_history.set(false);
+
+        self.record_change(HistoryActionKind::SlideAdded, None, true);
+        slide.update_fit_warning();
+        self.update_fit_warning();
+    }
+
+    /// Shows the warning for the current slide, with a split button when
+    /// its text is set to split
+    fn update_fit_warning(&self) {
+        let imp = self.imp();
+        let banner = imp.fit_banner.borrow().clone();
+        let Some(slide) = imp.slide_manager.borrow().current_slide() else {
+            banner.set_revealed(false);
+            return;
+        };
+
+        slide.update_fit_warning();
+        let can_split = slide
+            .text_item()
+            .filter(|t| t.fit() == TextFit::Split)
+            .and_then(|t| t.split_point())
+            .is_some();
+        banner.set_button_label(can_split.then_some("Split slide"));
+        banner.set_revealed(slide.fit_warning());
+    }
+
     fn current_page(&self) -> Option<gtk::ListView> {
         let notebook = self.imp().notebook.borrow().clone();
         let imp = self.imp();
@@ -644,6 +1023,11 @@ impl SongEditWindow {
     }
 
     fn export_changes(&self) {
+        let data = self.editor_data();
+        self.emit_save(&data);
+    }
+
+    fn editor_data(&self) -> SlideManagerData {
         let imp = self.imp();
 
         let list_view = imp.list_view.borrow_mut().clone();
@@ -661,8 +1045,129 @@ impl SongEditWindow {
 
         let mut data = SlideManagerData::new(0, 0, slides);
         data.title = title.text().into();
+        data.resolution = imp.slide_manager.borrow().resolution();
 
-        self.emit_save(&data);
+        data
+    }
+
+    fn open_history(&self) {
+        let song_id = self.song_id();
+        let revisions = match Query::get_song_revisions(song_id) {
+            Ok(r) => r,
+            Err(e) => {
+                eprintln!("SQL ERROR: {:?}", e);
+                return;
+            }
+        };
+        let current = match Query::get_song(song_id) {
+            Ok(Some(song)) => song,
+            Ok(None) => SongObject::from(self.editor_data()).song_data(),
+            Err(e) => {
+                eprintln!("SQL ERROR: {:?}", e);
+                return;
+            }
+        };
+
+        let history_window = SongHistoryWindow::new(current, revisions);
+        history_window.set_transient_for(Some(self));
+        history_window.connect_restore(glib::clone!(
+            #[weak(rename_to=obj)]
+            self,
+            move |_, data| obj.replace_song(data)
+        ));
+        history_window.present();
+    }
+
+    /// Replaces the editor content, the change is saved
+    /// the next time the song is applied
+    fn replace_song(&self, data: &SlideManagerData) {
+        let imp = self.imp();
+        imp.slide_manager.borrow().reset();
+        imp.list_view.borrow().remove_all();
+        self.load_song(data);
+        self.reset_history();
+    }
+
+    /// Starts a new undo history from the current editor content
+    fn reset_history(&self) {
+        let imp = self.imp();
+        imp.history.borrow_mut().clear();
+        imp.history_snapshot.replace(self.editor_data());
+        self.update_history_actions();
+    }
+
+    fn record_change(&self, kind: HistoryActionKind, target: Option<String>, force: bool) {
+        let imp = self.imp();
+        if imp.restoring_history.get() {
+            return;
+        }
+
+        let after = self.editor_data();
+        if same_content(&imp.history_snapshot.borrow(), &after) {
+            return;
+        }
+
+        let before = imp.history_snapshot.replace(after.clone());
+        let time = glib::monotonic_time() / 1000;
+        imp.history.borrow_mut().add_undoable_action(
+            HistoryAction::new(kind, target, before, after, time),
+            Some(force),
+        );
+        self.update_history_actions();
+    }
+
+    pub fn undo(&self) {
+        let data = self.imp().history.borrow_mut().undo();
+        if let Some(data) = data {
+            self.restore_history(data);
+        }
+    }
+
+    pub fn redo(&self) {
+        let data = self.imp().history.borrow_mut().redo();
+        if let Some(data) = data {
+            self.restore_history(data);
+        }
+    }
+
+    /// Replaces the slides with a snapshot from the undo history,
+    /// the title is not part of the history
+    fn restore_history(&self, mut data: SlideManagerData) {
+        let imp = self.imp();
+        imp.restoring_history.set(true);
+
+        let model = imp
+            .list_view
+            .borrow()
+            .model()
+            .and_downcast::<gtk::SingleSelection>()
+            .expect("Expected gtk::SingleSelection");
+        let selected = model.selected();
+
+        data.title = imp.title_entry.borrow().text().into();
+        imp.slide_manager.borrow().reset();
+        imp.list_view.borrow().remove_all();
+        self.load_song(&data);
+
+        if model.n_items() > 0 {
+            model.select_item(selected.min(model.n_items() - 1), true);
+        }
+
+        imp.history_snapshot.replace(data);
+        imp.restoring_history.set(false);
+        self.update_history_actions();
+    }
+
+    fn update_history_actions(&self) {
+        let imp = self.imp();
+        let history = imp.history.borrow();
+
+        if let Some(undo_action) = imp.undo_action.get() {
+            undo_action.set_enabled(history.can_undo());
+        }
+        if let Some(redo_action) = imp.redo_action.get() {
+            redo_action.set_enabled(history.can_redo());
+        }
     }
 
     pub fn cancel_reponse(&self) {
@@ -673,11 +1178,15 @@ impl SongEditWindow {
         let listview = self.imp().list_view.borrow().clone();
         let sm = self.imp().slide_manager.borrow();
 
+        sm.set_resolution(data.resolution);
+        self.list_resolutions(data.resolution);
+
         for slide_data in data.slides.iter() {
             let slide = sm.new_slide(Some(slide_data.clone()), true);
             if *self.imp().editor_type.borrow() == EditorType::Song {
                 slide.set_presentation_mode(true);
             }
+            slide.update_fit_warning();
             listview.append_item(&slide);
         }
 
@@ -744,3 +1253,19 @@ impl SongEditWindow {
         )
     }
 }
+
+/// Slide previews are rendered some time after an edit and the title
+/// has its own undo in the entry, both are left out when comparing
+fn same_content(a: &SlideManagerData, b: &SlideManagerData) -> bool {
+    let without_preview = |s: &SlideData| SlideData {
+        preview: Vec::new(),
+        ..s.clone()
+    };
+
+    a.resolution == b.resolution
+        && a.slides.len() == b.slides.len()
+        && a.slides
+            .iter()
+            .zip(&b.slides)
+            .all(|(a, b)| without_preview(a) == without_preview(b))
+}
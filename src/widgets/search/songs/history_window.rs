@@ -0,0 +1,291 @@
+use crate::dto::{SongData, SongObject, SongRevision};
+use crate::services::diff::{DiffLine, line_diff};
+use crate::widgets::canvas::serialise::SlideManagerData;
+use gtk::glib;
+use gtk::glib::subclass::types::ObjectSubclassIsExt;
+use gtk::prelude::*;
+
+const ADDED_TAG: &str = "added";
+const REMOVED_TAG: &str = "removed";
+
+mod signals {
+    pub const RESTORE: &str = "restore";
+}
+
+mod imp {
+    use std::{cell::RefCell, sync::OnceLock};
+
+    use super::*;
+    use crate::utils::WidgetExtrasExt;
+    use gtk::{
+        glib::{
+            self,
+            subclass::{
+                Signal,
+                object::{ObjectImpl, ObjectImplExt},
+                types::{ObjectSubclass, ObjectSubclassExt},
+            },
+        },
+        subclass::{widget::WidgetImpl, window::WindowImpl},
+    };
+
+    #[derive(Default)]
+    pub struct SongHistoryWindow {
+        pub(super) current: RefCell<SongData>,
+        pub(super) revisions: RefCell<Vec<SongRevision>>,
+        pub(super) revision_list: RefCell<gtk::ListBox>,
+        pub(super) diff_view: RefCell<gtk::TextView>,
+        pub(super) restore_btn: RefCell<gtk::Button>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SongHistoryWindow {
+        const NAME: &'static str = "SongHistoryWindow";
+        type Type = super::SongHistoryWindow;
+        type ParentType = gtk::Window;
+    }
+
+    impl ObjectImpl for SongHistoryWindow {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            obj.set_title(Some("Song history"));
+            obj.set_default_width(800);
+            obj.set_default_height(500);
+            obj.set_modal(true);
+            obj.add_css_class("dialog");
+
+            let box_ui = gtk::Box::new(gtk::Orientation::Vertical, 0);
+            obj.set_child(Some(&box_ui));
+
+            let pane = gtk::Paned::builder()
+                .position(240)
+                .shrink_start_child(false)
+                .vexpand(true)
+                .build();
+            box_ui.append(&pane);
+
+            let revision_list = gtk::ListBox::builder()
+                .selection_mode(gtk::SelectionMode::Single)
+                .build();
+            revision_list.set_placeholder(Some(
+                &gtk::Label::builder()
+                    .label("No revisions yet")
+                    .margin_top(12)
+                    .build(),
+            ));
+            pane.set_start_child(Some(
+                &gtk::ScrolledWindow::builder()
+                    .child(&revision_list)
+                    .width_request(200)
+                    .build(),
+            ));
+
+            let diff_view = gtk::TextView::builder()
+                .editable(false)
+                .cursor_visible(false)
+                .monospace(true)
+                .wrap_mode(gtk::WrapMode::WordChar)
+                .build();
+            diff_view.set_margin_all(6);
+            let tag_table = diff_view.buffer().tag_table();
+            tag_table.add(
+                &gtk::TextTag::builder()
+                    .name(ADDED_TAG)
+                    .foreground("#26a269")
+                    .weight(700)
+                    .build(),
+            );
+            tag_table.add(
+                &gtk::TextTag::builder()
+                    .name(REMOVED_TAG)
+                    .foreground("#e01b24")
+                    .strikethrough(true)
+                    .build(),
+            );
+            pane.set_end_child(Some(
+                &gtk::ScrolledWindow::builder()
+                    .child(&diff_view)
+                    .hexpand(true)
+                    .build(),
+            ));
+
+            revision_list.connect_row_selected(glib::clone!(
+                #[weak]
+                obj,
+                move |_, row| obj.show_diff(row.map(|r| r.index()))
+            ));
+
+            box_ui.append(&gtk::Separator::new(gtk::Orientation::Horizontal));
+
+            let footer_box = gtk::Box::builder()
+                .margin_top(3)
+                .margin_bottom(3)
+                .margin_start(3)
+                .margin_end(3)
+                .spacing(3)
+                .build();
+            footer_box.append(&gtk::Box::builder().hexpand(true).build());
+
+            let restore_btn = gtk::Button::builder()
+                .label("Restore")
+                .tooltip_text("Load the selected revision into the editor")
+                .sensitive(false)
+                .build();
+            restore_btn.connect_clicked(glib::clone!(
+                #[weak]
+                obj,
+                move |_| obj.restore_selected()
+            ));
+
+            let close_btn = gtk::Button::with_label("Close");
+            close_btn.connect_clicked(glib::clone!(
+                #[weak]
+                obj,
+                move |_| obj.close()
+            ));
+
+            footer_box.append(&restore_btn);
+            footer_box.append(&close_btn);
+            box_ui.append(&footer_box);
+
+            self.revision_list.replace(revision_list);
+            self.diff_view.replace(diff_view);
+            self.restore_btn.replace(restore_btn);
+        }
+
+        fn signals() -> &'static [glib::subclass::Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+
+            SIGNALS.get_or_init(|| {
+                vec![
+                    Signal::builder(signals::RESTORE)
+                        .param_types([SlideManagerData::static_type()])
+                        .build(),
+                ]
+            })
+        }
+    }
+    impl WidgetImpl for SongHistoryWindow {}
+    impl WindowImpl for SongHistoryWindow {}
+}
+
+glib::wrapper! {
+pub struct SongHistoryWindow(ObjectSubclass<imp::SongHistoryWindow>)
+    @extends gtk::Window, gtk::Widget,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget,gtk::Native,gtk::Root, gtk::ShortcutManager;
+}
+
+impl Default for SongHistoryWindow {
+    fn default() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl SongHistoryWindow {
+    /// `revisions` are expected newest first, as returned by
+    /// [crate::db::query::Query::get_song_revisions]
+    pub fn new(current: SongData, revisions: Vec<SongRevision>) -> Self {
+        let obj: Self = glib::Object::new();
+        let imp = obj.imp();
+
+        let list = imp.revision_list.borrow().clone();
+        for revision in &revisions {
+            let date = glib::DateTime::from_unix_local(revision.created_at)
+                .and_then(|d| d.format("%Y-%m-%d %H:%M"))
+                .map(|d| d.to_string())
+                .unwrap_or_default();
+
+            let label = gtk::Label::builder()
+                .label(format!("{date}\n{}", revision.song.title))
+                .xalign(0.0)
+                .ellipsize(gtk::pango::EllipsizeMode::End)
+                .margin_top(6)
+                .margin_bottom(6)
+                .margin_start(6)
+                .margin_end(6)
+                .build();
+            list.append(&label);
+        }
+
+        imp.current.replace(current);
+        imp.revisions.replace(revisions);
+
+        if let Some(row) = list.row_at_index(0) {
+            list.select_row(Some(&row));
+        }
+
+        obj
+    }
+
+    /// Shows the changes made after the revision at `index`,
+    /// against the next newer revision or the current song
+    fn show_diff(&self, index: Option<i32>) {
+        let imp = self.imp();
+        let buffer = imp.diff_view.borrow().buffer();
+        buffer.set_text("");
+
+        let revisions = imp.revisions.borrow();
+        let revision = index.and_then(|i| revisions.get(i as usize));
+        imp.restore_btn.borrow().set_sensitive(revision.is_some());
+
+        let Some(index) = index else {
+            return;
+        };
+        let Some(revision) = revision else {
+            return;
+        };
+
+        let newer = match index {
+            0 => imp.current.borrow().plain_text(),
+            i => revisions
+                .get(i as usize - 1)
+                .map(|r| r.song.plain_text())
+                .unwrap_or_default(),
+        };
+
+        for line in line_diff(&revision.song.plain_text(), &newer) {
+            let (text, tag) = match line {
+                DiffLine::Same(text) => (format!("  {text}\n"), None),
+                DiffLine::Added(text) => (format!("+ {text}\n"), Some(ADDED_TAG)),
+                DiffLine::Removed(text) => (format!("- {text}\n"), Some(REMOVED_TAG)),
+            };
+
+            let mut end = buffer.end_iter();
+            match tag {
+                Some(tag) => buffer.insert_with_tags_by_name(&mut end, &text, &[tag]),
+                None => buffer.insert(&mut end, &text),
+            }
+        }
+    }
+
+    fn restore_selected(&self) {
+        let imp = self.imp();
+        let Some(row) = imp.revision_list.borrow().selected_row() else {
+            return;
+        };
+        let Some(revision) = imp.revisions.borrow().get(row.index() as usize).cloned() else {
+            return;
+        };
+
+        let data = SongObject::from(revision.song).slide_manager_data(false);
+        self.emit_restore(&data);
+        self.close();
+    }
+
+    fn emit_restore(&self, data: &SlideManagerData) {
+        self.emit_by_name::<()>(signals::RESTORE, &[data]);
+    }
+
+    pub fn connect_restore<F: Fn(&Self, &SlideManagerData) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_closure(
+            signals::RESTORE,
+            false,
+            glib::closure_local!(move |obj: &Self, data: &SlideManagerData| f(obj, data)),
+        )
+    }
+}
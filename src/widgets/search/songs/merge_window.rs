@@ -0,0 +1,407 @@
+use std::collections::HashMap;
+
+use crate::db::query::Query;
+use crate::dto::{SongData, SongVerse};
+use crate::services::song_duplicates::find_duplicates;
+use crate::utils::WidgetExtrasExt;
+use crate::widgets::canvas::serialise::{CanvasItemType, SlideData};
+use gtk::glib;
+use gtk::glib::subclass::types::ObjectSubclassIsExt;
+use gtk::prelude::*;
+
+mod signals {
+    pub const MERGED: &str = "merged";
+}
+
+/// A verse offered for the merged song
+#[derive(Debug, Clone)]
+struct VerseRow {
+    verse: SongVerse,
+    keep: gtk::CheckButton,
+    tag: gtk::Entry,
+}
+
+mod imp {
+    use std::{cell::RefCell, sync::OnceLock};
+
+    use super::*;
+    use crate::utils::WidgetExtrasExt;
+    use gtk::{
+        glib::{
+            self,
+            subclass::{
+                Signal,
+                object::{ObjectImpl, ObjectImplExt},
+                types::{ObjectSubclass, ObjectSubclassExt},
+            },
+        },
+        subclass::{widget::WidgetImpl, window::WindowImpl},
+    };
+
+    #[derive(Default)]
+    pub struct SongMergeWindow {
+        pub(super) groups: RefCell<Vec<Vec<SongData>>>,
+        pub(super) current_group: RefCell<Vec<SongData>>,
+        pub(super) verse_rows: RefCell<Vec<VerseRow>>,
+
+        pub(super) group_list: RefCell<gtk::ListBox>,
+        pub(super) verse_list: RefCell<gtk::ListBox>,
+        pub(super) title_dropdown: RefCell<gtk::DropDown>,
+        pub(super) style_dropdown: RefCell<gtk::DropDown>,
+        pub(super) merge_btn: RefCell<gtk::Button>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SongMergeWindow {
+        const NAME: &'static str = "SongMergeWindow";
+        type Type = super::SongMergeWindow;
+        type ParentType = gtk::Window;
+    }
+
+    impl ObjectImpl for SongMergeWindow {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            obj.set_title(Some("Duplicate songs"));
+            obj.set_default_width(900);
+            obj.set_default_height(560);
+            obj.set_modal(true);
+            obj.add_css_class("dialog");
+
+            let box_ui = gtk::Box::new(gtk::Orientation::Vertical, 0);
+            obj.set_child(Some(&box_ui));
+
+            let pane = gtk::Paned::builder()
+                .position(260)
+                .shrink_start_child(false)
+                .vexpand(true)
+                .build();
+            box_ui.append(&pane);
+
+            let group_list = gtk::ListBox::builder()
+                .selection_mode(gtk::SelectionMode::Single)
+                .build();
+            group_list.set_placeholder(Some(
+                &gtk::Label::builder()
+                    .label("No duplicate songs found")
+                    .margin_top(12)
+                    .build(),
+            ));
+            group_list.connect_row_selected(glib::clone!(
+                #[weak]
+                obj,
+                move |_, row| obj.load_group(row.map(|r| r.index()))
+            ));
+            pane.set_start_child(Some(
+                &gtk::ScrolledWindow::builder()
+                    .child(&group_list)
+                    .width_request(200)
+                    .build(),
+            ));
+
+            let merge_box = gtk::Box::new(gtk::Orientation::Vertical, 6);
+            merge_box.set_margin_all(6);
+            pane.set_end_child(Some(&merge_box));
+
+            let title_dropdown = gtk::DropDown::builder().build();
+            let style_dropdown = gtk::DropDown::builder().build();
+            for (label, dropdown) in [
+                ("Keep title", &title_dropdown),
+                ("Slide styling", &style_dropdown),
+            ] {
+                let row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+                let label = gtk::Label::builder()
+                    .label(label)
+                    .xalign(0.0)
+                    .width_request(100)
+                    .build();
+                dropdown.set_hexpand(true);
+                row.append(&label);
+                row.append(dropdown);
+                merge_box.append(&row);
+            }
+
+            merge_box.append(
+                &gtk::Label::builder()
+                    .label("Verses to keep")
+                    .xalign(0.0)
+                    .margin_top(6)
+                    .build(),
+            );
+            let verse_list = gtk::ListBox::builder()
+                .selection_mode(gtk::SelectionMode::None)
+                .show_separators(true)
+                .build();
+            merge_box.append(
+                &gtk::ScrolledWindow::builder()
+                    .child(&verse_list)
+                    .vexpand(true)
+                    .build(),
+            );
+
+            box_ui.append(&gtk::Separator::new(gtk::Orientation::Horizontal));
+
+            let footer_box = gtk::Box::builder()
+                .margin_top(3)
+                .margin_bottom(3)
+                .margin_start(3)
+                .margin_end(3)
+                .spacing(3)
+                .build();
+            footer_box.append(&gtk::Box::builder().hexpand(true).build());
+
+            let merge_btn = gtk::Button::builder()
+                .label("Merge")
+                .sensitive(false)
+                .build();
+            merge_btn.connect_clicked(glib::clone!(
+                #[weak]
+                obj,
+                move |_| obj.merge_current_group()
+            ));
+
+            let close_btn = gtk::Button::with_label("Close");
+            close_btn.connect_clicked(glib::clone!(
+                #[weak]
+                obj,
+                move |_| obj.close()
+            ));
+
+            footer_box.append(&merge_btn);
+            footer_box.append(&close_btn);
+            box_ui.append(&footer_box);
+
+            self.group_list.replace(group_list);
+            self.verse_list.replace(verse_list);
+            self.title_dropdown.replace(title_dropdown);
+            self.style_dropdown.replace(style_dropdown);
+            self.merge_btn.replace(merge_btn);
+        }
+
+        fn signals() -> &'static [glib::subclass::Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+
+            SIGNALS.get_or_init(|| vec![Signal::builder(signals::MERGED).build()])
+        }
+    }
+    impl WidgetImpl for SongMergeWindow {}
+    impl WindowImpl for SongMergeWindow {}
+}
+
+glib::wrapper! {
+pub struct SongMergeWindow(ObjectSubclass<imp::SongMergeWindow>)
+    @extends gtk::Window, gtk::Widget,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget,gtk::Native,gtk::Root, gtk::ShortcutManager;
+}
+
+impl Default for SongMergeWindow {
+    fn default() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl SongMergeWindow {
+    pub fn new() -> Self {
+        let obj: Self = glib::Object::new();
+        obj.find_duplicates();
+        obj
+    }
+
+    fn find_duplicates(&self) {
+        let songs = match Query::search_songs("", true) {
+            Ok(songs) => songs,
+            Err(e) => {
+                eprintln!("SQL ERROR: {:?}", e);
+                return;
+            }
+        };
+
+        let groups = find_duplicates(&songs);
+        let mut songs = songs
+            .into_iter()
+            .map(|s| (s.song_id, s))
+            .collect::<HashMap<_, _>>();
+        let groups = groups
+            .into_iter()
+            .map(|ids| {
+                ids.iter()
+                    .filter_map(|id| songs.remove(id))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let group_list = self.imp().group_list.borrow().clone();
+        for group in &groups {
+            let titles = group
+                .iter()
+                .map(|s| s.title.as_str())
+                .collect::<Vec<_>>()
+                .join("\n");
+            let label = gtk::Label::builder()
+                .label(titles)
+                .xalign(0.0)
+                .ellipsize(gtk::pango::EllipsizeMode::End)
+                .margin_top(6)
+                .margin_bottom(6)
+                .margin_start(6)
+                .margin_end(6)
+                .build();
+            group_list.append(&label);
+        }
+        self.imp().groups.replace(groups);
+
+        if let Some(row) = group_list.row_at_index(0) {
+            group_list.select_row(Some(&row));
+        }
+    }
+
+    fn load_group(&self, index: Option<i32>) {
+        let imp = self.imp();
+        let group = index
+            .and_then(|i| imp.groups.borrow().get(i as usize).cloned())
+            .unwrap_or_default();
+
+        let titles = group.iter().map(|s| s.title.as_str()).collect::<Vec<_>>();
+        imp.title_dropdown
+            .borrow()
+            .set_model(Some(&gtk::StringList::new(&titles)));
+
+        let mut styles = vec![String::from("Keep each verse's styling")];
+        styles.extend(
+            group
+                .iter()
+                .map(|s| format!("Use styling of \"{}\"", s.title)),
+        );
+        let styles = styles.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+        imp.style_dropdown
+            .borrow()
+            .set_model(Some(&gtk::StringList::new(&styles)));
+
+        let verse_list = imp.verse_list.borrow().clone();
+        verse_list.remove_all();
+
+        let mut rows = Vec::new();
+        for (i, song) in group.iter().enumerate() {
+            for verse in &song.verses {
+                let first_line = gtk::pango::parse_markup(&verse.text, '\0')
+                    .map(|(_, text, _)| text.to_string())
+                    .unwrap_or_else(|_| verse.text.clone());
+                let first_line = first_line.lines().next().unwrap_or_default().to_string();
+
+                let keep = gtk::CheckButton::builder()
+                    .label(format!("{first_line}  ({})", song.title))
+                    .tooltip_text(&verse.text)
+                    .active(i == 0)
+                    .hexpand(true)
+                    .build();
+                let tag = gtk::Entry::builder()
+                    .placeholder_text("Tag")
+                    .text(verse.tag.clone().unwrap_or_default())
+                    .width_chars(8)
+                    .build();
+
+                let row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+                row.set_margin_all(3);
+                row.append(&keep);
+                row.append(&tag);
+                verse_list.append(&row);
+
+                rows.push(VerseRow {
+                    verse: verse.clone(),
+                    keep,
+                    tag,
+                });
+            }
+        }
+
+        imp.verse_rows.replace(rows);
+        imp.merge_btn.borrow().set_sensitive(group.len() > 1);
+        imp.current_group.replace(group);
+    }
+
+    fn merge_current_group(&self) {
+        let imp = self.imp();
+        let group = imp.current_group.borrow().clone();
+
+        let Some(keep) = group.get(imp.title_dropdown.borrow().selected() as usize) else {
+            return;
+        };
+
+        let style = match imp.style_dropdown.borrow().selected() {
+            0 | gtk::INVALID_LIST_POSITION => None,
+            i => group
+                .get(i as usize - 1)
+                .and_then(|s| s.verses.first())
+                .and_then(|v| v.slide.clone()),
+        };
+
+        let verses = imp
+            .verse_rows
+            .borrow()
+            .iter()
+            .filter(|r| r.keep.is_active())
+            .map(|r| {
+                let tag = r.tag.text().to_string();
+                let tag = (!tag.is_empty()).then_some(tag);
+                let slide = match &style {
+                    Some(style) => restyle_slide(style, &r.verse.text),
+                    None => r.verse.slide.clone(),
+                };
+                SongVerse {
+                    tag,
+                    slide,
+                    ..r.verse.clone()
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if verses.is_empty() {
+            return;
+        }
+
+        let merged = SongData::new(keep.song_id, keep.title.clone(), verses);
+        let ids = group.iter().map(|s| s.song_id).collect::<Vec<_>>();
+        if let Err(e) = Query::merge_songs(&merged, &ids) {
+            eprintln!("SQL ERROR: {:?}", e);
+            return;
+        }
+
+        let group_list = imp.group_list.borrow().clone();
+        if let Some(row) = group_list.selected_row() {
+            imp.groups.borrow_mut().remove(row.index() as usize);
+            group_list.remove(&row);
+        }
+        match group_list.row_at_index(0) {
+            Some(row) => group_list.select_row(Some(&row)),
+            None => self.load_group(None),
+        }
+
+        self.emit_merged();
+    }
+
+    fn emit_merged(&self) {
+        self.emit_by_name::<()>(signals::MERGED, &[]);
+    }
+
+    pub fn connect_merged<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            signals::MERGED,
+            false,
+            glib::closure_local!(move |obj: &Self| f(obj)),
+        )
+    }
+}
+
+/// Applies the slide styling in `style` to a verse with `text`
+fn restyle_slide(style: &str, text: &str) -> Option<String> {
+    let mut slide = serde_json::from_str::<SlideData>(style).ok()?;
+
+    for item in &mut slide.items {
+        if let CanvasItemType::Text(text_item) = &mut item.item_type {
+            text_item.text_data = glib::base64_encode(text.as_bytes()).into();
+        };
+    }
+
+    serde_json::to_string(&slide).ok()
+}
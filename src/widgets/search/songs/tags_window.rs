@@ -0,0 +1,245 @@
+use crate::db::query::Query;
+use crate::dto::SongTag;
+use gtk::glib;
+use gtk::glib::subclass::types::ObjectSubclassIsExt;
+use gtk::prelude::*;
+
+mod signals {
+    pub const CHANGED: &str = "changed";
+}
+
+mod imp {
+    use std::{
+        cell::{Cell, RefCell},
+        sync::OnceLock,
+    };
+
+    use super::*;
+    use crate::utils::WidgetExtrasExt;
+    use gtk::{
+        glib::{
+            self,
+            subclass::{
+                Signal,
+                object::{ObjectImpl, ObjectImplExt},
+                types::{ObjectSubclass, ObjectSubclassExt},
+            },
+        },
+        subclass::{widget::WidgetImpl, window::WindowImpl},
+    };
+
+    #[derive(Default)]
+    pub struct SongTagsWindow {
+        pub(super) song_id: Cell<u32>,
+        pub(super) tag_list: RefCell<gtk::ListBox>,
+        pub(super) tag_entry: RefCell<gtk::Entry>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SongTagsWindow {
+        const NAME: &'static str = "SongTagsWindow";
+        type Type = super::SongTagsWindow;
+        type ParentType = gtk::Window;
+    }
+
+    impl ObjectImpl for SongTagsWindow {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            obj.set_default_width(320);
+            obj.set_default_height(400);
+            obj.set_modal(true);
+            obj.add_css_class("dialog");
+
+            let box_ui = gtk::Box::new(gtk::Orientation::Vertical, 0);
+            obj.set_child(Some(&box_ui));
+
+            let tag_list = gtk::ListBox::builder()
+                .selection_mode(gtk::SelectionMode::None)
+                .show_separators(true)
+                .build();
+            tag_list.set_placeholder(Some(
+                &gtk::Label::builder()
+                    .label("No collections yet")
+                    .margin_top(12)
+                    .build(),
+            ));
+            box_ui.append(
+                &gtk::ScrolledWindow::builder()
+                    .child(&tag_list)
+                    .vexpand(true)
+                    .build(),
+            );
+
+            let add_box = gtk::Box::new(gtk::Orientation::Horizontal, 3);
+            add_box.set_margin_all(3);
+            let tag_entry = gtk::Entry::builder()
+                .placeholder_text("New collection")
+                .hexpand(true)
+                .build();
+            let add_btn = gtk::Button::from_icon_name("list-add-symbolic");
+            add_btn.set_tooltip_text(Some("Add collection"));
+            tag_entry.connect_activate(glib::clone!(
+                #[weak]
+                obj,
+                move |_| obj.add_tag()
+            ));
+            add_btn.connect_clicked(glib::clone!(
+                #[weak]
+                obj,
+                move |_| obj.add_tag()
+            ));
+            add_box.append(&tag_entry);
+            add_box.append(&add_btn);
+            box_ui.append(&add_box);
+
+            box_ui.append(&gtk::Separator::new(gtk::Orientation::Horizontal));
+
+            let footer_box = gtk::Box::builder()
+                .margin_top(3)
+                .margin_bottom(3)
+                .margin_start(3)
+                .margin_end(3)
+                .spacing(3)
+                .build();
+            footer_box.append(&gtk::Box::builder().hexpand(true).build());
+            let close_btn = gtk::Button::with_label("Close");
+            close_btn.connect_clicked(glib::clone!(
+                #[weak]
+                obj,
+                move |_| obj.close()
+            ));
+            footer_box.append(&close_btn);
+            box_ui.append(&footer_box);
+
+            self.tag_list.replace(tag_list);
+            self.tag_entry.replace(tag_entry);
+        }
+
+        fn signals() -> &'static [glib::subclass::Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+
+            SIGNALS.get_or_init(|| vec![Signal::builder(signals::CHANGED).build()])
+        }
+    }
+    impl WidgetImpl for SongTagsWindow {}
+    impl WindowImpl for SongTagsWindow {}
+}
+
+glib::wrapper! {
+pub struct SongTagsWindow(ObjectSubclass<imp::SongTagsWindow>)
+    @extends gtk::Window, gtk::Widget,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget,gtk::Native,gtk::Root, gtk::ShortcutManager;
+}
+
+impl Default for SongTagsWindow {
+    fn default() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl SongTagsWindow {
+    /// Edits the collections the song `song_id` belongs to
+    pub fn new(song_id: u32, song_title: &str) -> Self {
+        let obj: Self = glib::Object::new();
+        obj.set_title(Some(&format!("Collections of \"{song_title}\"")));
+        obj.imp().song_id.set(song_id);
+        obj.load_tags();
+        obj
+    }
+
+    fn load_tags(&self) {
+        let imp = self.imp();
+        let song_id = imp.song_id.get();
+
+        let tags = Query::get_song_tags().unwrap_or_else(|e| {
+            eprintln!("SQL ERROR: {:?}", e);
+            Vec::new()
+        });
+        let song_tags = Query::get_tags_of_song(song_id).unwrap_or_else(|e| {
+            eprintln!("SQL ERROR: {:?}", e);
+            Vec::new()
+        });
+
+        let tag_list = imp.tag_list.borrow().clone();
+        tag_list.remove_all();
+
+        for SongTag { tag_id, name } in tags {
+            let check = gtk::CheckButton::builder()
+                .label(&name)
+                .active(song_tags.contains(&tag_id))
+                .hexpand(true)
+                .build();
+            check.connect_toggled(glib::clone!(
+                #[weak(rename_to=obj)]
+                self,
+                move |c| {
+                    match Query::set_song_tagged(song_id, tag_id, c.is_active()) {
+                        Ok(_) => obj.emit_changed(),
+                        Err(e) => eprintln!("SQL ERROR: {:?}", e),
+                    }
+                }
+            ));
+
+            let delete_btn = gtk::Button::from_icon_name("user-trash-symbolic");
+            delete_btn.set_tooltip_text(Some("Delete collection"));
+            delete_btn.add_css_class("flat");
+            delete_btn.connect_clicked(glib::clone!(
+                #[weak(rename_to=obj)]
+                self,
+                move |_| {
+                    match Query::delete_song_tag(tag_id) {
+                        Ok(_) => {
+                            obj.load_tags();
+                            obj.emit_changed();
+                        }
+                        Err(e) => eprintln!("SQL ERROR: {:?}", e),
+                    }
+                }
+            ));
+
+            let row = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+            row.set_margin_top(3);
+            row.set_margin_bottom(3);
+            row.set_margin_start(6);
+            row.set_margin_end(3);
+            row.append(&check);
+            row.append(&delete_btn);
+            tag_list.append(&row);
+        }
+    }
+
+    /// Creates the collection typed in the entry and adds the song to it
+    fn add_tag(&self) {
+        let imp = self.imp();
+        let entry = imp.tag_entry.borrow().clone();
+        let name = entry.text().trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+
+        let res = Query::insert_song_tag(&name)
+            .and_then(|tag_id| Query::set_song_tagged(imp.song_id.get(), tag_id, true));
+        if let Err(e) = res {
+            eprintln!("SQL ERROR: {:?}", e);
+            return;
+        }
+
+        entry.set_text("");
+        self.load_tags();
+        self.emit_changed();
+    }
+
+    fn emit_changed(&self) {
+        self.emit_by_name::<()>(signals::CHANGED, &[]);
+    }
+
+    pub fn connect_changed<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            signals::CHANGED,
+            false,
+            glib::closure_local!(move |obj: &Self| f(obj)),
+        )
+    }
+}
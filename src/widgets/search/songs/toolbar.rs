@@ -1,3 +1,7 @@
 mod canvas_toolbar;
+mod item_toolbar;
+mod shape_toolbar;
 pub mod song_editor_toolbar;
 mod text_toolbar;
+mod timer_toolbar;
+mod video_toolbar;
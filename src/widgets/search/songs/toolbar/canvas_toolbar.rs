@@ -3,27 +3,37 @@ use gtk::glib::{self, subclass::types::ObjectSubclassIsExt};
 use crate::services::slide_manager::SlideManager;
 
 mod imp {
-    use std::cell::RefCell;
+    use std::cell::{Cell, RefCell};
 
     use adw::subclass::prelude::ObjectImplExt;
     use gtk::{
         gdk,
         glib::{
             self,
-            object::CastNone,
+            object::{Cast, CastNone},
             subclass::{
                 object::ObjectImpl,
                 types::{ObjectSubclass, ObjectSubclassExt},
             },
         },
-        prelude::{BoxExt, ButtonExt, WidgetExt},
+        prelude::{BoxExt, ButtonExt, CheckButtonExt, GridExt, PopoverExt, WidgetExt},
         subclass::{box_::BoxImpl, widget::WidgetImpl},
     };
 
     use crate::{
         app_config::AppConfigDir,
-        services::{file_manager::FileManager, slide_manager::SlideManager},
+        services::{
+            file_manager::FileManager,
+            slide_manager::SlideManager,
+            transition::{Easing, SlideTransition, TransitionEffect, WipeDirection},
+        },
         utils::{self, RGBExtra, WidgetExtrasExt},
+        widgets::{
+            audio_cue_editor::AudioCueEditor,
+            canvas::serialise::{
+                BackgroundGradient, GradientKind, GradientStop, ImageFit, ImageOptions,
+            },
+        },
     };
 
     #[derive(Debug, Default)]
@@ -32,6 +42,23 @@ mod imp {
         //
         pub color: RefCell<gtk::ColorDialogButton>,
         pub transition: RefCell<gtk::DropDown>,
+        pub audio: RefCell<AudioCueEditor>,
+        pub gradient: RefCell<gtk::CheckButton>,
+        pub gradient_kind: RefCell<gtk::DropDown>,
+        pub gradient_angle: RefCell<gtk::SpinButton>,
+        pub gradient_start: RefCell<gtk::ColorDialogButton>,
+        pub gradient_end: RefCell<gtk::ColorDialogButton>,
+        pub image_fit: RefCell<gtk::DropDown>,
+        pub position_x: RefCell<gtk::SpinButton>,
+        pub position_y: RefCell<gtk::SpinButton>,
+        pub dim: RefCell<gtk::SpinButton>,
+        pub blur: RefCell<gtk::SpinButton>,
+        pub effect: RefCell<gtk::DropDown>,
+        pub duration: RefCell<gtk::SpinButton>,
+        pub easing: RefCell<gtk::DropDown>,
+        pub wipe_direction: RefCell<gtk::DropDown>,
+
+        pub(super) updating_props: Cell<bool>,
     }
 
     #[glib::object_subclass]
@@ -131,12 +158,264 @@ mod imp {
                         return;
                     };
                     slide.set_transition(transition);
+                    sm.emit_style_changed();
                 }
             });
 
             transition_btn
         }
 
+        fn build_transition_popover(&self) -> gtk::Popover {
+            let grid = gtk::Grid::builder()
+                .row_spacing(6)
+                .column_spacing(12)
+                .build();
+            grid.set_margin_all(6);
+
+            let update = glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move || imp.update_transition()
+            );
+            let drop_down = |names: &[&str]| {
+                let drop_down = gtk::DropDown::from_strings(names);
+                drop_down.connect_selected_notify({
+                    let update = update.clone();
+                    move |_| update()
+                });
+                drop_down
+            };
+
+            let effect = drop_down(&[
+                "Slide transition",
+                "Fade through black",
+                "Zoom",
+                "Wipe",
+                "Dissolve",
+                "Blur in",
+            ]);
+            let easing = drop_down(&["Linear", "Ease in", "Ease out", "Ease in and out"]);
+            let wipe_direction = drop_down(&["Left", "Right", "Up", "Down"]);
+            let duration = gtk::SpinButton::with_range(0.0, 10.0, 0.1);
+            duration.set_digits(1);
+            duration.set_tooltip("Seconds, 0 uses the default duration");
+            duration.connect_value_changed(move |_| update());
+
+            let rows: [(&str, gtk::Widget); 4] = [
+                ("Effect", effect.clone().upcast()),
+                ("Duration", duration.clone().upcast()),
+                ("Easing", easing.clone().upcast()),
+                ("Wipe direction", wipe_direction.clone().upcast()),
+            ];
+            for (row, (label, widget)) in rows.iter().enumerate() {
+                let label = gtk::Label::builder().label(*label).xalign(0.0).build();
+                grid.attach(&label, 0, row as i32, 1, 1);
+                grid.attach(widget, 1, row as i32, 1, 1);
+            }
+
+            self.effect.replace(effect);
+            self.duration.replace(duration);
+            self.easing.replace(easing);
+            self.wipe_direction.replace(wipe_direction);
+
+            gtk::Popover::builder().child(&grid).build()
+        }
+
+        fn update_transition(&self) {
+            if self.updating_props.get() {
+                return;
+            }
+            let Some(sm) = self.slide_manager.upgrade() else {
+                return;
+            };
+            let Some(slide) = sm.current_slide() else {
+                return;
+            };
+
+            let effect = match self.effect.borrow().selected() {
+                1 => Some(TransitionEffect::FadeThroughBlack),
+                2 => Some(TransitionEffect::Zoom),
+                3 => Some(TransitionEffect::Wipe),
+                4 => Some(TransitionEffect::Dissolve),
+                5 => Some(TransitionEffect::BlurIn),
+                _ => None,
+            };
+            let duration = self.duration.borrow().value();
+            self.wipe_direction
+                .borrow()
+                .set_sensitive(effect == Some(TransitionEffect::Wipe));
+
+            slide.set_transition_options(SlideTransition {
+                effect,
+                duration: (duration > 0.0).then_some(duration),
+                easing: match self.easing.borrow().selected() {
+                    0 => Easing::Linear,
+                    1 => Easing::EaseIn,
+                    2 => Easing::EaseOut,
+                    _ => Easing::EaseInOut,
+                },
+                direction: match self.wipe_direction.borrow().selected() {
+                    1 => WipeDirection::Right,
+                    2 => WipeDirection::Up,
+                    3 => WipeDirection::Down,
+                    _ => WipeDirection::Left,
+                },
+            });
+            sm.emit_style_changed();
+        }
+
+        fn spin_button(&self, min: f64, max: f64, step: f64) -> gtk::SpinButton {
+            let spin = gtk::SpinButton::with_range(min, max, step);
+            spin.connect_value_changed(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |_| imp.update_background()
+            ));
+            spin
+        }
+
+        fn color_button(&self) -> gtk::ColorDialogButton {
+            let btn = gtk::ColorDialogButton::new(Some(gtk::ColorDialog::new()));
+            btn.connect_rgba_notify(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |_| imp.update_background()
+            ));
+            btn
+        }
+
+        fn drop_down(&self, names: &[&str]) -> gtk::DropDown {
+            let drop_down = gtk::DropDown::from_strings(names);
+            drop_down.connect_selected_notify(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |_| imp.update_background()
+            ));
+            drop_down
+        }
+
+        fn build_background_popover(&self) -> gtk::Popover {
+            let grid = gtk::Grid::builder()
+                .row_spacing(6)
+                .column_spacing(12)
+                .build();
+            grid.set_margin_all(6);
+
+            let gradient = gtk::CheckButton::new();
+            gradient.connect_toggled(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |_| imp.update_background()
+            ));
+            let gradient_kind = self.drop_down(&["Linear", "Radial"]);
+            let gradient_angle = self.spin_button(0.0, 360.0, 15.0);
+            let gradient_start = self.color_button();
+            let gradient_end = self.color_button();
+            let image_fit = self.drop_down(&["Cover", "Contain", "Stretch", "Tile"]);
+            let position_x = self.spin_button(0.0, 100.0, 5.0);
+            let position_y = self.spin_button(0.0, 100.0, 5.0);
+            let dim = self.spin_button(0.0, 100.0, 5.0);
+            let blur = self.spin_button(0.0, 100.0, 1.0);
+
+            let rows: [(&str, gtk::Widget); 10] = [
+                ("Gradient", gradient.clone().upcast()),
+                ("Gradient type", gradient_kind.clone().upcast()),
+                ("Gradient angle", gradient_angle.clone().upcast()),
+                ("Start color", gradient_start.clone().upcast()),
+                ("End color", gradient_end.clone().upcast()),
+                ("Image fit", image_fit.clone().upcast()),
+                ("Position x %", position_x.clone().upcast()),
+                ("Position y %", position_y.clone().upcast()),
+                ("Dim %", dim.clone().upcast()),
+                ("Blur", blur.clone().upcast()),
+            ];
+            for (row, (label, widget)) in rows.iter().enumerate() {
+                let label = gtk::Label::builder().label(*label).xalign(0.0).build();
+                grid.attach(&label, 0, row as i32, 1, 1);
+                grid.attach(widget, 1, row as i32, 1, 1);
+            }
+
+            self.gradient.replace(gradient);
+            self.gradient_kind.replace(gradient_kind);
+            self.gradient_angle.replace(gradient_angle);
+            self.gradient_start.replace(gradient_start);
+            self.gradient_end.replace(gradient_end);
+            self.image_fit.replace(image_fit);
+            self.position_x.replace(position_x);
+            self.position_y.replace(position_y);
+            self.dim.replace(dim);
+            self.blur.replace(blur);
+
+            gtk::Popover::builder().child(&grid).build()
+        }
+
+        fn update_background(&self) {
+            if self.updating_props.get() {
+                return;
+            }
+            let Some(sm) = self.slide_manager.upgrade() else {
+                return;
+            };
+            let Some(canvas) = sm.current_slide().and_then(|v| v.canvas()) else {
+                return;
+            };
+
+            let gradient = match self.gradient.borrow().is_active() {
+                true => {
+                    let mut gradient = canvas.background_gradient().unwrap_or_default();
+                    gradient.kind = match self.gradient_kind.borrow().selected() {
+                        1 => GradientKind::Radial,
+                        _ => GradientKind::Linear,
+                    };
+                    gradient.angle = self.gradient_angle.borrow().value();
+                    // only the outer stops are editable, any in between are kept
+                    let start = self.gradient_start.borrow().rgba().to_hex();
+                    let end = self.gradient_end.borrow().rgba().to_hex();
+                    if gradient.stops.len() < 2 {
+                        gradient.stops = BackgroundGradient::default().stops;
+                    }
+                    if let Some(stop) = gradient.stops.first_mut() {
+                        *stop = GradientStop {
+                            color: start,
+                            offset: stop.offset,
+                        };
+                    }
+                    if let Some(stop) = gradient.stops.last_mut() {
+                        *stop = GradientStop {
+                            color: end,
+                            offset: stop.offset,
+                        };
+                    }
+                    Some(gradient)
+                }
+                false => None,
+            };
+            self.set_gradient_sensitive(gradient.is_some());
+
+            canvas.set_background_gradient(gradient);
+            canvas.set_background_image(ImageOptions {
+                fit: match self.image_fit.borrow().selected() {
+                    1 => ImageFit::Contain,
+                    2 => ImageFit::Stretch,
+                    3 => ImageFit::Tile,
+                    _ => ImageFit::Cover,
+                },
+                position_x: self.position_x.borrow().value() / 100.0,
+                position_y: self.position_y.borrow().value() / 100.0,
+                dim: self.dim.borrow().value() / 100.0,
+                blur: self.blur.borrow().value(),
+            });
+            canvas.style();
+            sm.emit_style_changed();
+        }
+
+        fn set_gradient_sensitive(&self, sensitive: bool) {
+            self.gradient_kind.borrow().set_sensitive(sensitive);
+            self.gradient_angle.borrow().set_sensitive(sensitive);
+            self.gradient_start.borrow().set_sensitive(sensitive);
+            self.gradient_end.borrow().set_sensitive(sensitive);
+        }
+
         pub(super) fn build_canvas_toolbar(&self) {
             let toolbar = self.obj();
             toolbar.set_height_request(35);
@@ -150,6 +429,14 @@ mod imp {
 
             toolbar.append(&self.build_transition_btn());
 
+            let transition_btn = gtk::MenuButton::builder()
+                .icon_name("preferences-desktop-display-symbolic")
+                .popover(&self.build_transition_popover())
+                .css_classes(["flat"])
+                .build();
+            transition_btn.set_tooltip("Transition effect");
+            toolbar.append(&transition_btn);
+
             let color_btn = gtk::ColorDialogButton::new(Some(gtk::ColorDialog::new()));
             color_btn.set_tooltip("Background color");
             {
@@ -166,12 +453,13 @@ mod imp {
 
                         canvas.set_background_color(c.rgba().to_hex());
                         canvas.style();
+                        sm.emit_style_changed();
                     }
                 });
             }
 
             let image_btn = gtk::Button::builder().icon_name("picture").build();
-            image_btn.set_tooltip("Background image");
+            image_btn.set_tooltip("Background image or video");
             {
                 toolbar.append(&image_btn);
                 image_btn.add_css_class("flat");
@@ -179,7 +467,7 @@ mod imp {
                     let sm = sm.clone();
                     move |btn| {
                         let win = btn.toplevel_window();
-                        let Some(image_file) = FileManager::open_image(win.as_ref()) else {
+                        let Some(image_file) = FileManager::open_media(win.as_ref()) else {
                             return;
                         };
 
@@ -195,12 +483,13 @@ mod imp {
 
                         canvas.set_background_pattern(path);
                         canvas.style();
+                        sm.emit_style_changed();
                     }
                 });
             }
 
             let remove_image_btn = gtk::Button::builder().icon_name("remove-picture").build();
-            remove_image_btn.set_tooltip("Remove background image");
+            remove_image_btn.set_tooltip("Remove background image or video");
             {
                 toolbar.append(&remove_image_btn);
                 remove_image_btn.add_css_class("flat");
@@ -216,6 +505,42 @@ mod imp {
 
                         canvas.set_background_pattern("");
                         canvas.style();
+                        sm.emit_style_changed();
+                    }
+                });
+            }
+
+            let background_btn = gtk::MenuButton::builder()
+                .icon_name("applications-graphics-symbolic")
+                .popover(&self.build_background_popover())
+                .css_classes(["flat"])
+                .build();
+            background_btn.set_tooltip("Background style");
+            toolbar.append(&background_btn);
+
+            let audio_editor = self.audio.borrow().clone();
+            let audio_popover = gtk::Popover::new();
+            audio_popover.set_child(Some(&audio_editor));
+            let audio_btn = gtk::MenuButton::builder()
+                .icon_name("audio-x-generic-symbolic")
+                .popover(&audio_popover)
+                .css_classes(["flat"])
+                .build();
+            audio_btn.set_tooltip("Slide audio");
+            {
+                toolbar.append(&audio_btn);
+                audio_editor.connect_changed({
+                    let sm = self.slide_manager.clone();
+                    move |editor| {
+                        let Some(sm) = sm.upgrade() else {
+                            return;
+                        };
+                        let Some(slide) = sm.current_slide() else {
+                            return;
+                        };
+
+                        slide.set_audio(editor.cue());
+                        sm.emit_style_changed();
                     }
                 });
             }
@@ -240,6 +565,74 @@ mod imp {
             self.transition
                 .borrow()
                 .set_selected(utils::transition_to_int(slide.transition()));
+            self.audio.borrow().set_cue(slide.audio());
+
+            let gradient = c.background_gradient();
+            let stops = gradient.clone().unwrap_or_default().stops;
+            let image = c.background_image();
+
+            self.updating_props.set(true);
+            self.gradient.borrow().set_active(gradient.is_some());
+            if let Some(gradient) = &gradient {
+                self.gradient_kind
+                    .borrow()
+                    .set_selected(match gradient.kind {
+                        GradientKind::Linear => 0,
+                        GradientKind::Radial => 1,
+                    });
+                self.gradient_angle.borrow().set_value(gradient.angle);
+            }
+            let colors = [
+                (&self.gradient_start, stops.first()),
+                (&self.gradient_end, stops.last()),
+            ];
+            for (btn, stop) in colors {
+                if let Some(color) = stop.and_then(|s| gdk::RGBA::parse(&s.color).ok()) {
+                    btn.borrow().set_rgba(&color);
+                }
+            }
+            self.set_gradient_sensitive(gradient.is_some());
+            self.image_fit.borrow().set_selected(match image.fit {
+                ImageFit::Cover => 0,
+                ImageFit::Contain => 1,
+                ImageFit::Stretch => 2,
+                ImageFit::Tile => 3,
+            });
+            self.position_x.borrow().set_value(image.position_x * 100.0);
+            self.position_y.borrow().set_value(image.position_y * 100.0);
+            self.dim.borrow().set_value(image.dim * 100.0);
+            self.blur.borrow().set_value(image.blur);
+
+            let transition = slide.transition_options();
+            self.effect.borrow().set_selected(match transition.effect {
+                None => 0,
+                Some(TransitionEffect::FadeThroughBlack) => 1,
+                Some(TransitionEffect::Zoom) => 2,
+                Some(TransitionEffect::Wipe) => 3,
+                Some(TransitionEffect::Dissolve) => 4,
+                Some(TransitionEffect::BlurIn) => 5,
+            });
+            self.duration
+                .borrow()
+                .set_value(transition.duration.unwrap_or_default());
+            self.easing.borrow().set_selected(match transition.easing {
+                Easing::Linear => 0,
+                Easing::EaseIn => 1,
+                Easing::EaseOut => 2,
+                Easing::EaseInOut => 3,
+            });
+            self.wipe_direction
+                .borrow()
+                .set_selected(match transition.direction {
+                    WipeDirection::Left => 0,
+                    WipeDirection::Right => 1,
+                    WipeDirection::Up => 2,
+                    WipeDirection::Down => 3,
+                });
+            self.wipe_direction
+                .borrow()
+                .set_sensitive(transition.effect == Some(TransitionEffect::Wipe));
+            self.updating_props.set(false);
         }
     }
 }
@@ -0,0 +1,264 @@
+use gtk::glib::{self, subclass::types::ObjectSubclassIsExt};
+
+use crate::services::slide_manager::SlideManager;
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use adw::subclass::prelude::ObjectImplExt;
+    use gtk::{
+        gdk,
+        glib::{
+            self,
+            object::Cast,
+            subclass::{
+                object::ObjectImpl,
+                types::{ObjectSubclass, ObjectSubclassExt},
+            },
+        },
+        prelude::{BoxExt, CheckButtonExt, GridExt, WidgetExt},
+        subclass::{box_::BoxImpl, widget::WidgetImpl},
+    };
+
+    use super::*;
+    use crate::{
+        utils::{RGBExtra, WidgetExtrasExt},
+        widgets::canvas::{
+            canvas_item::{CanvasItem, CanvasItemExt},
+            serialise::{DropShadow, ItemStyle},
+        },
+    };
+
+    #[derive(Debug, Default)]
+    pub struct ItemToolbar {
+        pub slide_manager: glib::WeakRef<SlideManager>,
+        //
+        pub style_btn: RefCell<gtk::MenuButton>,
+        pub rotation: RefCell<gtk::SpinButton>,
+        pub opacity: RefCell<gtk::SpinButton>,
+        pub border_color: RefCell<gtk::ColorDialogButton>,
+        pub border_width: RefCell<gtk::SpinButton>,
+        pub border_radius: RefCell<gtk::SpinButton>,
+        pub shadow: RefCell<gtk::CheckButton>,
+        pub shadow_color: RefCell<gtk::ColorDialogButton>,
+        pub shadow_blur: RefCell<gtk::SpinButton>,
+        pub shadow_x: RefCell<gtk::SpinButton>,
+        pub shadow_y: RefCell<gtk::SpinButton>,
+
+        pub(super) updating_props: Cell<bool>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ItemToolbar {
+        const NAME: &'static str = "ItemToolbar";
+        type Type = super::ItemToolbar;
+        type ParentType = gtk::Box;
+    }
+
+    impl ObjectImpl for ItemToolbar {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().set_css_classes(&["toolbar"]);
+        }
+    }
+    impl WidgetImpl for ItemToolbar {}
+    impl BoxImpl for ItemToolbar {}
+
+    impl ItemToolbar {
+        pub(super) fn build_ui(&self) {
+            let obj = self.obj();
+
+            obj.set_height_request(35);
+            obj.set_spacing(8);
+            obj.set_widget_name("item-toolbar-box");
+            obj.set_margin_all(6);
+
+            let style_btn = gtk::MenuButton::builder()
+                .icon_name("object-rotate-right-symbolic")
+                .sensitive(false)
+                .build();
+            style_btn.set_tooltip("Item style");
+            style_btn.set_popover(Some(&self.build_style_popover()));
+            obj.append(&style_btn);
+            self.style_btn.replace(style_btn);
+        }
+
+        fn spin_button(&self, min: f64, max: f64, step: f64) -> gtk::SpinButton {
+            let spin = gtk::SpinButton::with_range(min, max, step);
+            spin.connect_value_changed(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |_| imp.update_item()
+            ));
+            spin
+        }
+
+        fn color_button(&self) -> gtk::ColorDialogButton {
+            let btn = gtk::ColorDialogButton::new(Some(gtk::ColorDialog::new()));
+            btn.connect_rgba_notify(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |_| imp.update_item()
+            ));
+            btn
+        }
+
+        fn build_style_popover(&self) -> gtk::Popover {
+            let grid = gtk::Grid::builder()
+                .row_spacing(6)
+                .column_spacing(12)
+                .build();
+            grid.set_margin_all(6);
+
+            let rotation = self.spin_button(-360.0, 360.0, 1.0);
+            let opacity = self.spin_button(0.0, 100.0, 5.0);
+            let border_color = self.color_button();
+            let border_width = self.spin_button(0.0, 50.0, 1.0);
+            let border_radius = self.spin_button(0.0, 200.0, 1.0);
+
+            let shadow = gtk::CheckButton::new();
+            shadow.connect_toggled(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |_| imp.update_item()
+            ));
+            let shadow_color = self.color_button();
+            let shadow_blur = self.spin_button(0.0, 100.0, 1.0);
+            let shadow_x = self.spin_button(-200.0, 200.0, 1.0);
+            let shadow_y = self.spin_button(-200.0, 200.0, 1.0);
+
+            let rows: [(&str, gtk::Widget); 10] = [
+                ("Rotation", rotation.clone().upcast()),
+                ("Opacity %", opacity.clone().upcast()),
+                ("Border color", border_color.clone().upcast()),
+                ("Border width", border_width.clone().upcast()),
+                ("Corner radius", border_radius.clone().upcast()),
+                ("Shadow", shadow.clone().upcast()),
+                ("Shadow color", shadow_color.clone().upcast()),
+                ("Shadow blur", shadow_blur.clone().upcast()),
+                ("Shadow offset x", shadow_x.clone().upcast()),
+                ("Shadow offset y", shadow_y.clone().upcast()),
+            ];
+            for (row, (label, widget)) in rows.iter().enumerate() {
+                let label = gtk::Label::builder().label(*label).xalign(0.0).build();
+                grid.attach(&label, 0, row as i32, 1, 1);
+                grid.attach(widget, 1, row as i32, 1, 1);
+            }
+
+            self.rotation.replace(rotation);
+            self.opacity.replace(opacity);
+            self.border_color.replace(border_color);
+            self.border_width.replace(border_width);
+            self.border_radius.replace(border_radius);
+            self.shadow.replace(shadow);
+            self.shadow_color.replace(shadow_color);
+            self.shadow_blur.replace(shadow_blur);
+            self.shadow_x.replace(shadow_x);
+            self.shadow_y.replace(shadow_y);
+
+            gtk::Popover::builder().child(&grid).build()
+        }
+
+        fn current_item(&self) -> Option<CanvasItem> {
+            self.slide_manager
+                .upgrade()
+                .and_then(|sm| sm.current_item())
+        }
+
+        fn update_item(&self) {
+            if self.updating_props.get() {
+                return;
+            }
+            let Some(item) = self.current_item() else {
+                return;
+            };
+
+            let shadow = match self.shadow.borrow().is_active() {
+                true => Some(DropShadow {
+                    color: self.shadow_color.borrow().rgba().to_hex(),
+                    blur: self.shadow_blur.borrow().value(),
+                    offset_x: self.shadow_x.borrow().value(),
+                    offset_y: self.shadow_y.borrow().value(),
+                }),
+                false => None,
+            };
+            self.set_shadow_sensitive(shadow.is_some());
+
+            item.set_item_style(ItemStyle {
+                rotation: self.rotation.borrow().value(),
+                opacity: self.opacity.borrow().value() / 100.0,
+                border_color: self.border_color.borrow().rgba().to_hex(),
+                border_width: self.border_width.borrow().value(),
+                border_radius: self.border_radius.borrow().value(),
+                shadow,
+            });
+            if let Some(sm) = self.slide_manager.upgrade() {
+                sm.emit_style_changed();
+            }
+        }
+
+        fn set_shadow_sensitive(&self, sensitive: bool) {
+            self.shadow_color.borrow().set_sensitive(sensitive);
+            self.shadow_blur.borrow().set_sensitive(sensitive);
+            self.shadow_x.borrow().set_sensitive(sensitive);
+            self.shadow_y.borrow().set_sensitive(sensitive);
+        }
+
+        pub(super) fn update_props(&self) {
+            let item = self.current_item();
+            self.style_btn.borrow().set_sensitive(item.is_some());
+            let Some(item) = item else {
+                return;
+            };
+
+            let style = item.item_style();
+            let shadow = style.shadow.clone().unwrap_or_default();
+
+            self.updating_props.set(true);
+            self.rotation.borrow().set_value(style.rotation);
+            self.opacity.borrow().set_value(style.opacity * 100.0);
+            if let Ok(color) = gdk::RGBA::parse(&style.border_color) {
+                self.border_color.borrow().set_rgba(&color);
+            }
+            self.border_width.borrow().set_value(style.border_width);
+            self.border_radius.borrow().set_value(style.border_radius);
+            self.shadow.borrow().set_active(style.shadow.is_some());
+            if let Ok(color) = gdk::RGBA::parse(&shadow.color) {
+                self.shadow_color.borrow().set_rgba(&color);
+            }
+            self.shadow_blur.borrow().set_value(shadow.blur);
+            self.shadow_x.borrow().set_value(shadow.offset_x);
+            self.shadow_y.borrow().set_value(shadow.offset_y);
+            self.set_shadow_sensitive(style.shadow.is_some());
+            self.updating_props.set(false);
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct ItemToolbar(ObjectSubclass<imp::ItemToolbar>)
+        @extends  gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Orientable, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Default for ItemToolbar {
+    fn default() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl ItemToolbar {
+    pub fn new(slide_manager: &SlideManager) -> Self {
+        let obj: Self = glib::Object::new();
+        obj.imp().slide_manager.set(Some(slide_manager));
+        obj.imp().build_ui();
+
+        slide_manager.connect_item_clicked(glib::clone!(
+            #[weak]
+            obj,
+            move |_, _| obj.imp().update_props()
+        ));
+
+        obj
+    }
+}
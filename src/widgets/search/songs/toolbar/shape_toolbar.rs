@@ -0,0 +1,272 @@
+use gtk::glib::{self, subclass::types::ObjectSubclassIsExt};
+
+use crate::{services::slide_manager::SlideManager, widgets::canvas::serialise::ShapeKind};
+
+const SHAPES: [(ShapeKind, &str); 4] = [
+    (ShapeKind::Rectangle, "Rectangle"),
+    (ShapeKind::RoundedRectangle, "Rounded rectangle"),
+    (ShapeKind::Ellipse, "Ellipse"),
+    (ShapeKind::Line, "Line"),
+];
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use adw::subclass::prelude::ObjectImplExt;
+    use gtk::{
+        gdk,
+        glib::{
+            self,
+            object::{Cast, CastNone},
+            subclass::{
+                object::ObjectImpl,
+                types::{ObjectSubclass, ObjectSubclassExt},
+            },
+        },
+        prelude::{BoxExt, ButtonExt, GridExt, PopoverExt, WidgetExt},
+        subclass::{box_::BoxImpl, widget::WidgetImpl},
+    };
+
+    use super::*;
+    use crate::{
+        utils::{RGBExtra, WidgetExtrasExt},
+        widgets::canvas::shape_item::ShapeItem,
+    };
+
+    #[derive(Debug, Default)]
+    pub struct ShapeToolbar {
+        pub slide_manager: glib::WeakRef<SlideManager>,
+        //
+        pub style_btn: RefCell<gtk::MenuButton>,
+        pub shape: RefCell<gtk::DropDown>,
+        pub fill: RefCell<gtk::ColorDialogButton>,
+        pub stroke: RefCell<gtk::ColorDialogButton>,
+        pub stroke_width: RefCell<gtk::SpinButton>,
+        pub corner_radius: RefCell<gtk::SpinButton>,
+        pub opacity: RefCell<gtk::SpinButton>,
+
+        pub(super) updating_props: Cell<bool>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ShapeToolbar {
+        const NAME: &'static str = "ShapeToolbar";
+        type Type = super::ShapeToolbar;
+        type ParentType = gtk::Box;
+    }
+
+    impl ObjectImpl for ShapeToolbar {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().set_css_classes(&["toolbar"]);
+        }
+    }
+    impl WidgetImpl for ShapeToolbar {}
+    impl BoxImpl for ShapeToolbar {}
+
+    impl ShapeToolbar {
+        pub(super) fn build_ui(&self) {
+            let obj = self.obj();
+
+            obj.set_height_request(35);
+            obj.set_spacing(8);
+            obj.set_widget_name("shape-toolbar-box");
+            obj.set_margin_all(6);
+
+            obj.append(&self.build_add_btn());
+
+            let style_btn = gtk::MenuButton::builder()
+                .icon_name("applications-graphics-symbolic")
+                .sensitive(false)
+                .build();
+            style_btn.set_tooltip("Shape style");
+            style_btn.set_popover(Some(&self.build_style_popover()));
+            obj.append(&style_btn);
+            self.style_btn.replace(style_btn);
+        }
+
+        fn build_add_btn(&self) -> gtk::MenuButton {
+            let box_ui = gtk::Box::new(gtk::Orientation::Vertical, 0);
+            let popover = gtk::Popover::builder().child(&box_ui).build();
+
+            for (shape, label) in SHAPES {
+                let btn = gtk::Button::with_label(label);
+                btn.add_css_class("flat");
+                btn.connect_clicked(glib::clone!(
+                    #[weak(rename_to=imp)]
+                    self,
+                    #[weak]
+                    popover,
+                    move |_| {
+                        popover.popdown();
+                        let Some(sm) = imp.slide_manager.upgrade() else {
+                            return;
+                        };
+                        if let Some(item) = sm.request_new_shape(shape) {
+                            sm.set_current_item(Some(item));
+                        }
+                    }
+                ));
+                box_ui.append(&btn);
+            }
+
+            let add_btn = gtk::MenuButton::builder()
+                .icon_name("list-add-symbolic")
+                .popover(&popover)
+                .build();
+            add_btn.set_tooltip("Add shape");
+            add_btn
+        }
+
+        fn build_style_popover(&self) -> gtk::Popover {
+            let grid = gtk::Grid::builder()
+                .row_spacing(6)
+                .column_spacing(12)
+                .build();
+            grid.set_margin_all(6);
+
+            let shape = gtk::DropDown::from_strings(&SHAPES.map(|(_, label)| label));
+            shape.connect_selected_notify(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |d| {
+                    let Some((kind, _)) = SHAPES.get(d.selected() as usize) else {
+                        return;
+                    };
+                    imp.update_item(|item| item.set_shape(*kind));
+                }
+            ));
+
+            let fill = gtk::ColorDialogButton::new(Some(gtk::ColorDialog::new()));
+            fill.connect_rgba_notify(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |c| imp.update_item(|item| item.set_fill(c.rgba().to_hex()))
+            ));
+
+            let stroke = gtk::ColorDialogButton::new(Some(gtk::ColorDialog::new()));
+            stroke.connect_rgba_notify(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |c| imp.update_item(|item| item.set_stroke(c.rgba().to_hex()))
+            ));
+
+            let stroke_width = gtk::SpinButton::with_range(0.0, 50.0, 1.0);
+            stroke_width.connect_value_changed(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |s| imp.update_item(|item| item.set_stroke_width(s.value()))
+            ));
+
+            let corner_radius = gtk::SpinButton::with_range(0.0, 200.0, 1.0);
+            corner_radius.connect_value_changed(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |s| imp.update_item(|item| item.set_corner_radius(s.value()))
+            ));
+
+            let opacity = gtk::SpinButton::with_range(0.0, 100.0, 5.0);
+            opacity.connect_value_changed(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |s| imp.update_item(|item| item.set_opacity(s.value() / 100.0))
+            ));
+
+            let rows: [(&str, gtk::Widget); 6] = [
+                ("Shape", shape.clone().upcast()),
+                ("Fill", fill.clone().upcast()),
+                ("Stroke", stroke.clone().upcast()),
+                ("Stroke width", stroke_width.clone().upcast()),
+                ("Corner radius", corner_radius.clone().upcast()),
+                ("Opacity %", opacity.clone().upcast()),
+            ];
+            for (row, (label, widget)) in rows.iter().enumerate() {
+                let label = gtk::Label::builder().label(*label).xalign(0.0).build();
+                grid.attach(&label, 0, row as i32, 1, 1);
+                grid.attach(widget, 1, row as i32, 1, 1);
+            }
+
+            self.shape.replace(shape);
+            self.fill.replace(fill);
+            self.stroke.replace(stroke);
+            self.stroke_width.replace(stroke_width);
+            self.corner_radius.replace(corner_radius);
+            self.opacity.replace(opacity);
+
+            gtk::Popover::builder().child(&grid).build()
+        }
+
+        fn current_item(&self) -> Option<ShapeItem> {
+            self.slide_manager
+                .upgrade()
+                .and_then(|sm| sm.current_item())
+                .and_downcast::<ShapeItem>()
+        }
+
+        fn update_item<F: Fn(&ShapeItem)>(&self, f: F) {
+            if self.updating_props.get() {
+                return;
+            }
+            let Some(item) = self.current_item() else {
+                return;
+            };
+
+            f(&item);
+            item.queue_draw();
+            if let Some(sm) = self.slide_manager.upgrade() {
+                sm.emit_style_changed();
+            }
+        }
+
+        pub(super) fn update_props(&self) {
+            let item = self.current_item();
+            self.style_btn.borrow().set_sensitive(item.is_some());
+            let Some(item) = item else {
+                return;
+            };
+
+            self.updating_props.set(true);
+            if let Some(pos) = SHAPES.iter().position(|(kind, _)| *kind == item.shape()) {
+                self.shape.borrow().set_selected(pos as u32);
+            }
+            if let Ok(fill) = gdk::RGBA::parse(item.fill()) {
+                self.fill.borrow().set_rgba(&fill);
+            }
+            if let Ok(stroke) = gdk::RGBA::parse(item.stroke()) {
+                self.stroke.borrow().set_rgba(&stroke);
+            }
+            self.stroke_width.borrow().set_value(item.stroke_width());
+            self.corner_radius.borrow().set_value(item.corner_radius());
+            self.opacity.borrow().set_value(item.opacity() * 100.0);
+            self.updating_props.set(false);
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct ShapeToolbar(ObjectSubclass<imp::ShapeToolbar>)
+        @extends  gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Orientable, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Default for ShapeToolbar {
+    fn default() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl ShapeToolbar {
+    pub fn new(slide_manager: &SlideManager) -> Self {
+        let obj: Self = glib::Object::new();
+        obj.imp().slide_manager.set(Some(slide_manager));
+        obj.imp().build_ui();
+
+        slide_manager.connect_item_clicked(glib::clone!(
+            #[weak]
+            obj,
+            move |_, _| obj.imp().update_props()
+        ));
+
+        obj
+    }
+}
@@ -5,7 +5,10 @@ use gtk::{
 
 use crate::{
     services::slide_manager::SlideManager,
-    widgets::search::songs::toolbar::{canvas_toolbar::CanvasToolbar, text_toolbar::TextToolbar},
+    widgets::search::songs::toolbar::{
+        canvas_toolbar::CanvasToolbar, item_toolbar::ItemToolbar, shape_toolbar::ShapeToolbar,
+        text_toolbar::TextToolbar, timer_toolbar::TimerToolbar, video_toolbar::VideoToolbar,
+    },
 };
 
 mod imp {
@@ -56,11 +59,20 @@ impl SongEditorToolbar {
         obj.imp().slide_manager.replace(slide_manager.clone());
 
         let text_toolbar = TextToolbar::new(slide_manager);
+        let item_toolbar = ItemToolbar::new(slide_manager);
+        let shape_toolbar = ShapeToolbar::new(slide_manager);
+        let video_toolbar = VideoToolbar::new(slide_manager);
+        let timer_toolbar = TimerToolbar::new(slide_manager);
         let canvas_toolbar = CanvasToolbar::new(slide_manager);
         let spacer = gtk::Box::builder().hexpand(true).build();
 
         let base = gtk::Box::new(gtk::Orientation::Horizontal, 2);
         base.append(&text_toolbar);
+        base.append(&gtk::Separator::new(gtk::Orientation::Vertical));
+        base.append(&item_toolbar);
+        base.append(&shape_toolbar);
+        base.append(&video_toolbar);
+        base.append(&timer_toolbar);
         base.append(&spacer);
         base.append(&canvas_toolbar);
 
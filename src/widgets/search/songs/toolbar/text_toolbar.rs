@@ -20,24 +20,34 @@ mod imp {
 
     use adw::subclass::prelude::ObjectImplExt;
     use gtk::{
+        gdk,
         glib::{
             self,
-            object::CastNone,
+            object::{Cast, CastNone},
             subclass::{
                 object::ObjectImpl,
                 types::{ObjectSubclass, ObjectSubclassExt},
             },
         },
-        prelude::{BoxExt, ButtonExt, TextBufferExt, TextTagExt, ToggleButtonExt, WidgetExt},
+        prelude::{
+            BoxExt, ButtonExt, CheckButtonExt, GridExt, TextBufferExt, TextTagExt, ToggleButtonExt,
+            WidgetExt,
+        },
         subclass::{box_::BoxImpl, widget::WidgetImpl},
     };
 
     use crate::{
-        services::slide_manager::SlideManager,
-        utils::{self, WidgetChildrenExt, WidgetExtrasExt, buffer_markup::TextBufferExtra},
+        services::{slide_manager::SlideManager, text_fit::TextFit},
+        utils::{
+            self, RGBExtra, WidgetChildrenExt, WidgetExtrasExt, buffer_markup::TextBufferExtra,
+        },
         widgets::{
             canvas::{
                 canvas_item::{CanvasItem, CanvasItemExt},
+                serialise::{
+                    DropShadow, IndexedEnum, TextAlign, TextDirection, TextJustification,
+                    TextOutline,
+                },
                 text_item::TextItem,
             },
             group_toggle_button::GroupToggleButton,
@@ -56,6 +66,18 @@ mod imp {
         pub underline: RefCell<gtk::ToggleButton>,
         pub shadow: RefCell<gtk::ToggleButton>,
         pub outline: RefCell<gtk::ToggleButton>,
+        pub outline_color: RefCell<gtk::ColorDialogButton>,
+        pub outline_width: RefCell<gtk::SpinButton>,
+        pub shadow_color: RefCell<gtk::ColorDialogButton>,
+        pub shadow_blur: RefCell<gtk::SpinButton>,
+        pub shadow_x: RefCell<gtk::SpinButton>,
+        pub shadow_y: RefCell<gtk::SpinButton>,
+        pub line_spacing: RefCell<gtk::SpinButton>,
+        pub letter_spacing: RefCell<gtk::SpinButton>,
+        pub padding: RefCell<gtk::SpinButton>,
+        pub rtl: RefCell<gtk::CheckButton>,
+        pub default_color: RefCell<gtk::ColorDialogButton>,
+        pub fit: RefCell<gtk::DropDown>,
         pub justification: RefCell<adw::ToggleGroup>,
         pub alignment: RefCell<adw::ToggleGroup>,
 
@@ -63,6 +85,7 @@ mod imp {
         pub(super) cursor_handler_id: RefCell<Option<(gtk::TextBuffer, glib::SignalHandlerId)>>,
 
         pub(super) checking_cursor_position: Cell<bool>,
+        pub(super) updating_props: Cell<bool>,
     }
 
     #[glib::object_subclass]
@@ -121,6 +144,7 @@ mod imp {
                             ti.set_font(family);
                         }
                         ti.style();
+                        sm.emit_style_changed();
                     }
                 });
             }
@@ -160,6 +184,7 @@ mod imp {
 
                         // ti.set_font_color(c.hex());
                         ti.style();
+                        imp.style_changed();
                     }
                 ));
             }
@@ -208,6 +233,7 @@ mod imp {
 
                         // ti.set_font_weight(if t.is_active() { "bold" } else { "regular" });
                         ti.style();
+                        imp.style_changed();
                     }
                 ));
             };
@@ -249,6 +275,7 @@ mod imp {
 
                         // ti.set_font_style(if t.is_active() { "italic" } else { "normal" });
                         ti.style();
+                        imp.style_changed();
                     }
                 ));
             };
@@ -290,6 +317,7 @@ mod imp {
 
                         // ti.set_text_underline(t.is_active());
                         ti.style();
+                        imp.style_changed();
                     }
                 ));
             };
@@ -310,6 +338,7 @@ mod imp {
 
                         ti.set_text_shadow(t.is_active());
                         ti.style();
+                        sm.emit_style_changed();
                     }
                 });
             };
@@ -330,10 +359,18 @@ mod imp {
 
                         ti.set_text_outline(t.is_active());
                         ti.style();
+                        sm.emit_style_changed();
                     }
                 });
             };
 
+            let effects_btn = gtk::MenuButton::builder()
+                .icon_name("preferences-other-symbolic")
+                .popover(&self.build_effects_popover())
+                .build();
+            effects_btn.set_tooltip("Shadow and outline style");
+            obj.append(&effects_btn);
+
             obj.append(&gtk::Separator::new(gtk::Orientation::Vertical));
 
             let justifcation_btn = adw::ToggleGroup::new();
@@ -349,19 +386,22 @@ mod imp {
                             return;
                         };
 
-                        match t.active() {
-                            0 | 1 | 2 => {
-                                ti.set_justification(t.active());
-                                ti.style();
-                            }
-                            _ => (),
+                        let Some(justification) = t
+                            .active_name()
+                            .and_then(|name| TextJustification::from_name(&name))
+                        else {
+                            return;
                         };
+                        ti.set_justification(justification);
+                        ti.style();
+                        sm.emit_style_changed();
                     }
                 });
             };
 
             let justify_left_btn = adw::Toggle::new();
             {
+                justify_left_btn.set_name(Some(TextJustification::Left.name()));
                 justify_left_btn.set_tooltip("Justify left");
                 justify_left_btn.set_icon_name(Some("text-justify-left"));
                 justifcation_btn.add(justify_left_btn);
@@ -369,6 +409,7 @@ mod imp {
 
             let justify_center_btn = adw::Toggle::new();
             {
+                justify_center_btn.set_name(Some(TextJustification::Center.name()));
                 justify_center_btn.set_tooltip("Justify center");
                 justify_center_btn.set_icon_name(Some("text-justify-center"));
                 justifcation_btn.add(justify_center_btn);
@@ -376,11 +417,20 @@ mod imp {
 
             let justify_right_btn = adw::Toggle::new();
             {
+                justify_right_btn.set_name(Some(TextJustification::Right.name()));
                 justify_right_btn.set_tooltip("Justify right");
                 justify_right_btn.set_icon_name(Some("text-justify-right"));
                 justifcation_btn.add(justify_right_btn);
             };
 
+            let justify_fill_btn = adw::Toggle::new();
+            {
+                justify_fill_btn.set_name(Some(TextJustification::Fill.name()));
+                justify_fill_btn.set_tooltip("Justify fill");
+                justify_fill_btn.set_icon_name(Some("text-justify-fill"));
+                justifcation_btn.add(justify_fill_btn);
+            };
+
             obj.append(&gtk::Separator::new(gtk::Orientation::Vertical));
 
             let alignment_btn = adw::ToggleGroup::new();
@@ -396,19 +446,21 @@ mod imp {
                             return;
                         };
 
-                        match t.active() {
-                            0 | 1 | 2 => {
-                                ti.set_align(t.active());
-                                ti.style();
-                            }
-                            _ => (),
+                        let Some(align) =
+                            t.active_name().and_then(|name| TextAlign::from_name(&name))
+                        else {
+                            return;
                         };
+                        ti.set_align(align);
+                        ti.style();
+                        sm.emit_style_changed();
                     }
                 });
             }
 
             let align_top_btn = adw::Toggle::new();
             {
+                align_top_btn.set_name(Some(TextAlign::Top.name()));
                 align_top_btn.set_tooltip("Align top");
                 align_top_btn.set_icon_name(Some("align-top"));
                 alignment_btn.add(align_top_btn);
@@ -416,6 +468,7 @@ mod imp {
 
             let align_middle_btn = adw::Toggle::new();
             {
+                align_middle_btn.set_name(Some(TextAlign::Middle.name()));
                 align_middle_btn.set_tooltip("Align middle");
                 align_middle_btn.set_icon_name(Some("align-middle"));
                 alignment_btn.add(align_middle_btn);
@@ -423,10 +476,200 @@ mod imp {
 
             let align_bottom_btn = adw::Toggle::new();
             {
+                align_bottom_btn.set_name(Some(TextAlign::Bottom.name()));
                 align_bottom_btn.set_tooltip("Align bottom");
                 align_bottom_btn.set_icon_name(Some("align-bottom"));
                 alignment_btn.add(align_bottom_btn);
             };
+
+            let layout_btn = gtk::MenuButton::builder()
+                .icon_name("format-text-direction-rtl-symbolic")
+                .popover(&self.build_layout_popover())
+                .build();
+            layout_btn.set_tooltip("Spacing, padding, direction and fit");
+            obj.append(&layout_btn);
+        }
+
+        fn build_layout_popover(&self) -> gtk::Popover {
+            let grid = gtk::Grid::builder()
+                .row_spacing(6)
+                .column_spacing(12)
+                .build();
+            grid.set_margin_all(6);
+
+            let spin_button = |min: f64, max: f64, step: f64, digits: u32| {
+                let spin = gtk::SpinButton::with_range(min, max, step);
+                spin.set_digits(digits);
+                spin.connect_value_changed(glib::clone!(
+                    #[weak(rename_to=imp)]
+                    self,
+                    move |_| imp.update_layout()
+                ));
+                spin
+            };
+
+            let line_spacing = spin_button(0.5, 4.0, 0.1, 1);
+            let letter_spacing = spin_button(-20.0, 50.0, 1.0, 0);
+            let padding = spin_button(0.0, 200.0, 1.0, 0);
+            let rtl = gtk::CheckButton::new();
+            rtl.connect_toggled(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |_| imp.update_layout()
+            ));
+            let default_color = gtk::ColorDialogButton::new(Some(gtk::ColorDialog::new()));
+            default_color.connect_rgba_notify(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |_| imp.update_layout()
+            ));
+
+            let fit = gtk::DropDown::from_strings(&[
+                "Shrink to fit",
+                "Fixed size",
+                "Grow box",
+                "Shrink, then split slide",
+            ]);
+            fit.set_tooltip("When the text is too big for its box");
+            fit.connect_selected_notify(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |_| imp.update_layout()
+            ));
+
+            let rows: [(&str, gtk::Widget); 6] = [
+                ("Line spacing", line_spacing.clone().upcast()),
+                ("Letter spacing", letter_spacing.clone().upcast()),
+                ("Padding", padding.clone().upcast()),
+                ("Right to left", rtl.clone().upcast()),
+                ("Default color", default_color.clone().upcast()),
+                ("Fit", fit.clone().upcast()),
+            ];
+            for (row, (label, widget)) in rows.iter().enumerate() {
+                let label = gtk::Label::builder().label(*label).xalign(0.0).build();
+                grid.attach(&label, 0, row as i32, 1, 1);
+                grid.attach(widget, 1, row as i32, 1, 1);
+            }
+
+            self.line_spacing.replace(line_spacing);
+            self.letter_spacing.replace(letter_spacing);
+            self.padding.replace(padding);
+            self.rtl.replace(rtl);
+            self.default_color.replace(default_color);
+            self.fit.replace(fit);
+
+            gtk::Popover::builder().child(&grid).build()
+        }
+
+        fn update_layout(&self) {
+            if self.updating_props.get() {
+                return;
+            }
+            let Some(sm) = self.slide_manager.upgrade() else {
+                return;
+            };
+            let Some(ti) = sm.current_item().and_downcast::<TextItem>() else {
+                return;
+            };
+
+            ti.set_line_spacing(self.line_spacing.borrow().value());
+            ti.set_letter_spacing(self.letter_spacing.borrow().value());
+            ti.set_padding(self.padding.borrow().value());
+            ti.set_text_direction(match self.rtl.borrow().is_active() {
+                true => TextDirection::Rtl,
+                false => TextDirection::Ltr,
+            });
+            ti.set_text_color(self.default_color.borrow().rgba().to_hex());
+            ti.set_fit(match self.fit.borrow().selected() {
+                1 => TextFit::Fixed,
+                2 => TextFit::Grow,
+                3 => TextFit::Split,
+                _ => TextFit::Shrink,
+            });
+            ti.style();
+            sm.emit_style_changed();
+        }
+
+        fn build_effects_popover(&self) -> gtk::Popover {
+            let grid = gtk::Grid::builder()
+                .row_spacing(6)
+                .column_spacing(12)
+                .build();
+            grid.set_margin_all(6);
+
+            let color_button = || {
+                let btn = gtk::ColorDialogButton::new(Some(gtk::ColorDialog::new()));
+                btn.connect_rgba_notify(glib::clone!(
+                    #[weak(rename_to=imp)]
+                    self,
+                    move |_| imp.update_effects()
+                ));
+                btn
+            };
+            let spin_button = |min: f64, max: f64| {
+                let spin = gtk::SpinButton::with_range(min, max, 1.0);
+                spin.connect_value_changed(glib::clone!(
+                    #[weak(rename_to=imp)]
+                    self,
+                    move |_| imp.update_effects()
+                ));
+                spin
+            };
+
+            let outline_color = color_button();
+            let outline_width = spin_button(0.0, 50.0);
+            let shadow_color = color_button();
+            let shadow_blur = spin_button(0.0, 100.0);
+            let shadow_x = spin_button(-200.0, 200.0);
+            let shadow_y = spin_button(-200.0, 200.0);
+
+            let rows: [(&str, gtk::Widget); 6] = [
+                ("Outline color", outline_color.clone().upcast()),
+                ("Outline width", outline_width.clone().upcast()),
+                ("Shadow color", shadow_color.clone().upcast()),
+                ("Shadow blur", shadow_blur.clone().upcast()),
+                ("Shadow offset x", shadow_x.clone().upcast()),
+                ("Shadow offset y", shadow_y.clone().upcast()),
+            ];
+            for (row, (label, widget)) in rows.iter().enumerate() {
+                let label = gtk::Label::builder().label(*label).xalign(0.0).build();
+                grid.attach(&label, 0, row as i32, 1, 1);
+                grid.attach(widget, 1, row as i32, 1, 1);
+            }
+
+            self.outline_color.replace(outline_color);
+            self.outline_width.replace(outline_width);
+            self.shadow_color.replace(shadow_color);
+            self.shadow_blur.replace(shadow_blur);
+            self.shadow_x.replace(shadow_x);
+            self.shadow_y.replace(shadow_y);
+
+            gtk::Popover::builder().child(&grid).build()
+        }
+
+        fn update_effects(&self) {
+            if self.updating_props.get() {
+                return;
+            }
+            let Some(sm) = self.slide_manager.upgrade() else {
+                return;
+            };
+            let Some(ti) = sm.current_item().and_downcast::<TextItem>() else {
+                return;
+            };
+
+            ti.set_outline_style(TextOutline {
+                color: self.outline_color.borrow().rgba().to_hex(),
+                width: self.outline_width.borrow().value(),
+            });
+            ti.set_shadow_style(DropShadow {
+                color: self.shadow_color.borrow().rgba().to_hex(),
+                blur: self.shadow_blur.borrow().value(),
+                offset_x: self.shadow_x.borrow().value(),
+                offset_y: self.shadow_y.borrow().value(),
+            });
+            ti.style();
+            sm.emit_style_changed();
         }
 
         fn build_font_size_btn(&self) -> gtk::SpinButton {
@@ -449,6 +692,7 @@ mod imp {
 
                     ti.set_font_size(size as f32);
                     ti.style();
+                    sm.emit_style_changed();
                 }
             });
 
@@ -479,8 +723,48 @@ mod imp {
 
             self.shadow.borrow().set_active(ti.text_shadow());
             self.outline.borrow().set_active(ti.text_outline());
-            self.justification.borrow().set_active(ti.justification());
-            self.alignment.borrow().set_active(ti.align());
+
+            let (outline, shadow) = (ti.outline_style(), ti.shadow_style());
+            self.updating_props.set(true);
+            if let Ok(color) = gdk::RGBA::parse(&outline.color) {
+                self.outline_color.borrow().set_rgba(&color);
+            }
+            self.outline_width.borrow().set_value(outline.width);
+            if let Ok(color) = gdk::RGBA::parse(&shadow.color) {
+                self.shadow_color.borrow().set_rgba(&color);
+            }
+            self.shadow_blur.borrow().set_value(shadow.blur);
+            self.shadow_x.borrow().set_value(shadow.offset_x);
+            self.shadow_y.borrow().set_value(shadow.offset_y);
+            self.line_spacing.borrow().set_value(ti.line_spacing());
+            self.letter_spacing.borrow().set_value(ti.letter_spacing());
+            self.padding.borrow().set_value(ti.padding());
+            self.rtl
+                .borrow()
+                .set_active(ti.text_direction() == TextDirection::Rtl);
+            if let Ok(color) = gdk::RGBA::parse(ti.text_color()) {
+                self.default_color.borrow().set_rgba(&color);
+            }
+            self.fit.borrow().set_selected(match ti.fit() {
+                TextFit::Shrink => 0,
+                TextFit::Fixed => 1,
+                TextFit::Grow => 2,
+                TextFit::Split => 3,
+            });
+            self.updating_props.set(false);
+
+            self.justification
+                .borrow()
+                .set_active_name(Some(ti.justification().name()));
+            self.alignment
+                .borrow()
+                .set_active_name(Some(ti.align().name()));
+        }
+
+        fn style_changed(&self) {
+            if let Some(sm) = self.slide_manager.upgrade() {
+                sm.emit_style_changed();
+            }
         }
 
         pub(super) fn get_current_item(&self) -> Option<TextItem> {
@@ -0,0 +1,361 @@
+use gtk::glib::{self, subclass::types::ObjectSubclassIsExt};
+
+use crate::{
+    services::slide_manager::SlideManager,
+    widgets::canvas::serialise::{TimerFinish, TimerMode},
+};
+
+const MODES: [(TimerMode, &str); 4] = [
+    (TimerMode::Countdown, "Countdown"),
+    (TimerMode::CountdownTo, "Countdown to time"),
+    (TimerMode::CountUp, "Count up"),
+    (TimerMode::Clock, "Clock"),
+];
+
+const FINISH_ACTIONS: [(TimerFinish, &str); 3] = [
+    (TimerFinish::None, "Nothing"),
+    (TimerFinish::NextSlide, "Next slide"),
+    (TimerFinish::ShowLogo, "Show logo"),
+];
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use adw::subclass::prelude::ObjectImplExt;
+    use gtk::{
+        gdk,
+        glib::{
+            self,
+            object::{Cast, CastNone},
+            subclass::{
+                object::ObjectImpl,
+                types::{ObjectSubclass, ObjectSubclassExt},
+            },
+        },
+        prelude::{BoxExt, ButtonExt, EditableExt, EntryExt, GridExt, WidgetExt},
+        subclass::{box_::BoxImpl, widget::WidgetImpl},
+    };
+
+    use super::*;
+    use crate::{
+        services::timer,
+        utils::{RGBExtra, WidgetExtrasExt},
+        widgets::canvas::{serialise::TimerItemData, timer_item::TimerItem},
+    };
+
+    #[derive(Debug, Default)]
+    pub struct TimerToolbar {
+        pub slide_manager: glib::WeakRef<SlideManager>,
+        //
+        pub options_btn: RefCell<gtk::MenuButton>,
+        pub mode: RefCell<gtk::DropDown>,
+        pub minutes: RefCell<gtk::SpinButton>,
+        pub seconds: RefCell<gtk::SpinButton>,
+        pub target: RefCell<gtk::Entry>,
+        pub format: RefCell<gtk::Entry>,
+        pub font: RefCell<gtk::FontDialogButton>,
+        pub font_size: RefCell<gtk::SpinButton>,
+        pub color: RefCell<gtk::ColorDialogButton>,
+        pub on_finish: RefCell<gtk::DropDown>,
+
+        pub(super) updating_props: Cell<bool>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for TimerToolbar {
+        const NAME: &'static str = "TimerToolbar";
+        type Type = super::TimerToolbar;
+        type ParentType = gtk::Box;
+    }
+
+    impl ObjectImpl for TimerToolbar {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().set_css_classes(&["toolbar"]);
+        }
+    }
+    impl WidgetImpl for TimerToolbar {}
+    impl BoxImpl for TimerToolbar {}
+
+    impl TimerToolbar {
+        pub(super) fn build_ui(&self) {
+            let obj = self.obj();
+
+            obj.set_height_request(35);
+            obj.set_spacing(8);
+            obj.set_widget_name("timer-toolbar-box");
+            obj.set_margin_all(6);
+
+            let add_btn = gtk::Button::builder()
+                .icon_name("alarm-symbolic")
+                .css_classes(["flat"])
+                .build();
+            add_btn.set_tooltip("Add timer");
+            add_btn.connect_clicked(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |_| {
+                    let Some(sm) = imp.slide_manager.upgrade() else {
+                        return;
+                    };
+                    if let Some(item) = sm.request_new_timer(TimerItemData::default()) {
+                        sm.set_current_item(Some(item));
+                    }
+                }
+            ));
+            obj.append(&add_btn);
+
+            let options_btn = gtk::MenuButton::builder()
+                .icon_name("preferences-system-time-symbolic")
+                .sensitive(false)
+                .build();
+            options_btn.set_tooltip("Timer options");
+            options_btn.set_popover(Some(&self.build_options_popover()));
+            obj.append(&options_btn);
+            self.options_btn.replace(options_btn);
+        }
+
+        fn build_options_popover(&self) -> gtk::Popover {
+            let grid = gtk::Grid::builder()
+                .row_spacing(6)
+                .column_spacing(12)
+                .build();
+            grid.set_margin_all(6);
+
+            let mode = gtk::DropDown::from_strings(&MODES.map(|(_, label)| label));
+            mode.connect_selected_notify(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |d| {
+                    let Some((mode, _)) = MODES.get(d.selected() as usize) else {
+                        return;
+                    };
+                    imp.update_item(|data| data.mode = *mode);
+                    imp.update_sensitivity();
+                }
+            ));
+
+            let minutes = gtk::SpinButton::with_range(0.0, 1440.0, 1.0);
+            let seconds = gtk::SpinButton::with_range(0.0, 59.0, 1.0);
+            for spin in [&minutes, &seconds] {
+                spin.connect_value_changed(glib::clone!(
+                    #[weak(rename_to=imp)]
+                    self,
+                    move |_| {
+                        let duration = imp.minutes.borrow().value_as_int() * 60
+                            + imp.seconds.borrow().value_as_int();
+                        imp.update_item(|data| data.duration = duration.max(0) as u32);
+                    }
+                ));
+            }
+            let duration = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+            duration.append(&minutes);
+            duration.append(&gtk::Label::new(Some("min")));
+            duration.append(&seconds);
+            duration.append(&gtk::Label::new(Some("s")));
+
+            let target = gtk::Entry::builder().placeholder_text("HH:MM").build();
+            target.connect_changed(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |e| {
+                    let text = e.text().to_string();
+                    // keep the last valid time while it is typed
+                    match timer::parse_time_of_day(&text) {
+                        Some(_) => {
+                            e.remove_css_class("error");
+                            imp.update_item(|data| data.target = text.trim().to_string());
+                        }
+                        None => e.add_css_class("error"),
+                    }
+                }
+            ));
+
+            let format = gtk::Entry::new();
+            format.set_tooltip(
+                "{hh} {mm} {ss} are padded, {h} {m} {s} are not, {h12} {ampm} for a 12 hour clock",
+            );
+            format.connect_changed(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |e| {
+                    let text = e.text().to_string();
+                    imp.update_item(|data| data.format = text.clone());
+                }
+            ));
+
+            let font = gtk::FontDialogButton::new(Some(gtk::FontDialog::new()));
+            font.set_use_size(false);
+            font.connect_font_desc_notify(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |f| {
+                    let Some(desc) = f.font_desc() else {
+                        return;
+                    };
+                    imp.update_item(|data| data.font = desc.to_str().to_string());
+                }
+            ));
+
+            let font_size = gtk::SpinButton::with_range(1.0, 300.0, 1.0);
+            font_size.connect_value_changed(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |s| imp.update_item(|data| data.font_size = s.value() as f32)
+            ));
+
+            let color = gtk::ColorDialogButton::new(Some(gtk::ColorDialog::new()));
+            color.connect_rgba_notify(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |c| imp.update_item(|data| data.color = c.rgba().to_hex())
+            ));
+
+            let on_finish = gtk::DropDown::from_strings(&FINISH_ACTIONS.map(|(_, label)| label));
+            on_finish.connect_selected_notify(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |d| {
+                    let Some((action, _)) = FINISH_ACTIONS.get(d.selected() as usize) else {
+                        return;
+                    };
+                    imp.update_item(|data| data.on_finish = *action);
+                }
+            ));
+
+            let rows: [(&str, gtk::Widget); 8] = [
+                ("Mode", mode.clone().upcast()),
+                ("Duration", duration.upcast()),
+                ("Count down to", target.clone().upcast()),
+                ("Format", format.clone().upcast()),
+                ("Font", font.clone().upcast()),
+                ("Font size", font_size.clone().upcast()),
+                ("Colour", color.clone().upcast()),
+                ("When finished", on_finish.clone().upcast()),
+            ];
+            for (row, (label, widget)) in rows.iter().enumerate() {
+                let label = gtk::Label::builder().label(*label).xalign(0.0).build();
+                grid.attach(&label, 0, row as i32, 1, 1);
+                grid.attach(widget, 1, row as i32, 1, 1);
+            }
+
+            self.mode.replace(mode);
+            self.minutes.replace(minutes);
+            self.seconds.replace(seconds);
+            self.target.replace(target);
+            self.format.replace(format);
+            self.font.replace(font);
+            self.font_size.replace(font_size);
+            self.color.replace(color);
+            self.on_finish.replace(on_finish);
+
+            gtk::Popover::builder().child(&grid).build()
+        }
+
+        fn current_item(&self) -> Option<TimerItem> {
+            self.slide_manager
+                .upgrade()
+                .and_then(|sm| sm.current_item())
+                .and_downcast::<TimerItem>()
+        }
+
+        fn update_item<F: Fn(&mut TimerItemData)>(&self, f: F) {
+            if self.updating_props.get() {
+                return;
+            }
+            let Some(item) = self.current_item() else {
+                return;
+            };
+
+            let mut data = item.timer();
+            f(&mut data);
+            item.set_timer(data);
+            if let Some(sm) = self.slide_manager.upgrade() {
+                sm.emit_style_changed();
+            }
+        }
+
+        /// Only the options of the selected mode can be edited
+        fn update_sensitivity(&self) {
+            let mode = MODES
+                .get(self.mode.borrow().selected() as usize)
+                .map(|(mode, _)| *mode)
+                .unwrap_or_default();
+            let countdown = matches!(mode, TimerMode::Countdown | TimerMode::CountdownTo);
+
+            self.minutes
+                .borrow()
+                .set_sensitive(mode == TimerMode::Countdown);
+            self.seconds
+                .borrow()
+                .set_sensitive(mode == TimerMode::Countdown);
+            self.target
+                .borrow()
+                .set_sensitive(mode == TimerMode::CountdownTo);
+            self.on_finish.borrow().set_sensitive(countdown);
+            let format = self.format.borrow().clone();
+            format.set_placeholder_text(Some(timer::default_format(mode)));
+        }
+
+        pub(super) fn update_props(&self) {
+            let item = self.current_item();
+            self.options_btn.borrow().set_sensitive(item.is_some());
+            let Some(item) = item else {
+                return;
+            };
+            let data = item.timer();
+
+            self.updating_props.set(true);
+            if let Some(pos) = MODES.iter().position(|(mode, _)| *mode == data.mode) {
+                self.mode.borrow().set_selected(pos as u32);
+            }
+            self.minutes.borrow().set_value((data.duration / 60) as f64);
+            self.seconds.borrow().set_value((data.duration % 60) as f64);
+            self.target.borrow().set_text(&data.target);
+            self.format.borrow().set_text(&data.format);
+            self.font
+                .borrow()
+                .set_font_desc(&gtk::pango::FontDescription::from_string(&data.font));
+            self.font_size.borrow().set_value(data.font_size as f64);
+            if let Ok(color) = gdk::RGBA::parse(&data.color) {
+                self.color.borrow().set_rgba(&color);
+            }
+            if let Some(pos) = FINISH_ACTIONS
+                .iter()
+                .position(|(action, _)| *action == data.on_finish)
+            {
+                self.on_finish.borrow().set_selected(pos as u32);
+            }
+            self.update_sensitivity();
+            self.updating_props.set(false);
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct TimerToolbar(ObjectSubclass<imp::TimerToolbar>)
+        @extends  gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Orientable, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Default for TimerToolbar {
+    fn default() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl TimerToolbar {
+    pub fn new(slide_manager: &SlideManager) -> Self {
+        let obj: Self = glib::Object::new();
+        obj.imp().slide_manager.set(Some(slide_manager));
+        obj.imp().build_ui();
+
+        slide_manager.connect_item_clicked(glib::clone!(
+            #[weak]
+            obj,
+            move |_, _| obj.imp().update_props()
+        ));
+
+        obj
+    }
+}
@@ -0,0 +1,252 @@
+use gtk::glib::{self, subclass::types::ObjectSubclassIsExt};
+
+use crate::services::slide_manager::SlideManager;
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use adw::subclass::prelude::ObjectImplExt;
+    use gtk::{
+        glib::{
+            self,
+            object::CastNone,
+            subclass::{
+                object::ObjectImpl,
+                types::{ObjectSubclass, ObjectSubclassExt},
+            },
+        },
+        prelude::{BoxExt, ButtonExt, CheckButtonExt, GridExt, WidgetExt},
+        subclass::{box_::BoxImpl, widget::WidgetImpl},
+    };
+
+    use super::*;
+    use crate::{
+        services::file_manager::FileManager,
+        utils::WidgetExtrasExt,
+        widgets::canvas::{serialise::VideoOptions, video_item::VideoItem},
+    };
+
+    #[derive(Debug, Default)]
+    pub struct VideoToolbar {
+        pub slide_manager: glib::WeakRef<SlideManager>,
+        //
+        pub options_btn: RefCell<gtk::MenuButton>,
+        pub loop_video: RefCell<gtk::CheckButton>,
+        pub muted: RefCell<gtk::CheckButton>,
+        pub start_offset: RefCell<gtk::SpinButton>,
+
+        pub(super) updating_props: Cell<bool>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for VideoToolbar {
+        const NAME: &'static str = "VideoToolbar";
+        type Type = super::VideoToolbar;
+        type ParentType = gtk::Box;
+    }
+
+    impl ObjectImpl for VideoToolbar {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().set_css_classes(&["toolbar"]);
+        }
+    }
+    impl WidgetImpl for VideoToolbar {}
+    impl BoxImpl for VideoToolbar {}
+
+    /// What the video options apply to
+    enum Target {
+        Item(VideoItem),
+        Background(crate::widgets::canvas::canvas::Canvas),
+    }
+
+    impl VideoToolbar {
+        pub(super) fn build_ui(&self) {
+            let obj = self.obj();
+
+            obj.set_height_request(35);
+            obj.set_spacing(8);
+            obj.set_widget_name("video-toolbar-box");
+            obj.set_margin_all(6);
+
+            let add_btn = gtk::Button::builder()
+                .icon_name("video-x-generic-symbolic")
+                .css_classes(["flat"])
+                .build();
+            add_btn.set_tooltip("Add video");
+            add_btn.connect_clicked(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |btn| {
+                    let Some(sm) = imp.slide_manager.upgrade() else {
+                        return;
+                    };
+                    let Some(file) = FileManager::open_video(btn.toplevel_window().as_ref()) else {
+                        return;
+                    };
+                    let Some(path) = FileManager::file_to_link(
+                        &file,
+                        crate::app_config::AppConfigDir::SlideMedia,
+                    ) else {
+                        return;
+                    };
+
+                    let file = gtk::gio::File::for_path(path);
+                    if let Some(item) = sm.request_new_video(&file) {
+                        sm.set_current_item(Some(item));
+                    }
+                }
+            ));
+            obj.append(&add_btn);
+
+            let options_btn = gtk::MenuButton::builder()
+                .icon_name("emblem-system-symbolic")
+                .sensitive(false)
+                .build();
+            options_btn.set_tooltip("Video options");
+            options_btn.set_popover(Some(&self.build_options_popover()));
+            obj.append(&options_btn);
+            self.options_btn.replace(options_btn);
+        }
+
+        fn build_options_popover(&self) -> gtk::Popover {
+            let grid = gtk::Grid::builder()
+                .row_spacing(6)
+                .column_spacing(12)
+                .build();
+            grid.set_margin_all(6);
+
+            let loop_video = gtk::CheckButton::with_label("Loop");
+            let muted = gtk::CheckButton::with_label("Mute");
+            let start_offset = gtk::SpinButton::with_range(0.0, 3600.0, 0.5);
+            start_offset.set_digits(1);
+
+            for check in [&loop_video, &muted] {
+                check.connect_toggled(glib::clone!(
+                    #[weak(rename_to=imp)]
+                    self,
+                    move |_| imp.update_options()
+                ));
+            }
+            start_offset.connect_value_changed(glib::clone!(
+                #[weak(rename_to=imp)]
+                self,
+                move |_| imp.update_options()
+            ));
+
+            grid.attach(&loop_video, 0, 0, 2, 1);
+            grid.attach(&muted, 0, 1, 2, 1);
+            let label = gtk::Label::builder()
+                .label("Start at (seconds)")
+                .xalign(0.0)
+                .build();
+            grid.attach(&label, 0, 2, 1, 1);
+            grid.attach(&start_offset, 1, 2, 1, 1);
+
+            self.loop_video.replace(loop_video);
+            self.muted.replace(muted);
+            self.start_offset.replace(start_offset);
+
+            gtk::Popover::builder().child(&grid).build()
+        }
+
+        /// The selected video item, or else the slide background when it is a video
+        fn target(&self) -> Option<Target> {
+            let sm = self.slide_manager.upgrade()?;
+
+            if let Some(item) = sm.current_item().and_downcast::<VideoItem>() {
+                return Some(Target::Item(item));
+            }
+
+            let canvas = sm.current_slide().and_then(|s| s.canvas())?;
+            let pattern = canvas.background_pattern();
+            (!pattern.is_empty() && FileManager::is_video(std::path::Path::new(&pattern)))
+                .then_some(Target::Background(canvas))
+        }
+
+        fn update_options(&self) {
+            if self.updating_props.get() {
+                return;
+            }
+            let Some(target) = self.target() else {
+                return;
+            };
+
+            let options = VideoOptions {
+                loop_video: self.loop_video.borrow().is_active(),
+                muted: self.muted.borrow().is_active(),
+                start_offset: (self.start_offset.borrow().value() * 1000.0) as u32,
+            };
+
+            match target {
+                Target::Item(item) => item.set_options(options),
+                Target::Background(canvas) => {
+                    canvas.set_background_video(options);
+                    canvas.style();
+                }
+            }
+
+            if let Some(sm) = self.slide_manager.upgrade() {
+                sm.emit_style_changed();
+            }
+        }
+
+        pub(super) fn update_props(&self) {
+            let target = self.target();
+            self.options_btn.borrow().set_sensitive(target.is_some());
+
+            let options = match target {
+                Some(Target::Item(item)) => item.options(),
+                Some(Target::Background(canvas)) => canvas.background_video(),
+                None => return,
+            };
+
+            self.updating_props.set(true);
+            self.loop_video.borrow().set_active(options.loop_video);
+            self.muted.borrow().set_active(options.muted);
+            self.start_offset
+                .borrow()
+                .set_value(options.start_offset as f64 / 1000.0);
+            self.updating_props.set(false);
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct VideoToolbar(ObjectSubclass<imp::VideoToolbar>)
+        @extends  gtk::Box, gtk::Widget,
+        @implements gtk::Accessible, gtk::Orientable, gtk::Buildable, gtk::ConstraintTarget;
+}
+
+impl Default for VideoToolbar {
+    fn default() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl VideoToolbar {
+    pub fn new(slide_manager: &SlideManager) -> Self {
+        let obj: Self = glib::Object::new();
+        obj.imp().slide_manager.set(Some(slide_manager));
+        obj.imp().build_ui();
+
+        slide_manager.connect_item_clicked(glib::clone!(
+            #[weak]
+            obj,
+            move |_, _| obj.imp().update_props()
+        ));
+        slide_manager.connect_current_slide_changed(glib::clone!(
+            #[weak]
+            obj,
+            move |_, _| obj.imp().update_props()
+        ));
+        // the background may have just been set to a video
+        slide_manager.connect_changed(glib::clone!(
+            #[weak]
+            obj,
+            move |_, _, _, _| obj.imp().update_props()
+        ));
+
+        obj
+    }
+}
@@ -1,4 +1,9 @@
-use gtk::{gio, glib};
+use gtk::{
+    gdk, gio,
+    glib::{self, value::ToValue, variant::ToVariant},
+};
+
+use crate::utils::RGBExtra;
 
 mod imp {
     use std::{cell::RefCell, collections::HashMap, usize};
@@ -8,7 +13,7 @@ mod imp {
             self,
             prelude::{DisplayExt, MonitorExt},
         },
-        gio::prelude::ListModelExtManual,
+        gio::prelude::{FileExt, ListModelExt},
         glib::{
             self,
             object::{Cast, CastNone},
@@ -21,7 +26,10 @@ mod imp {
             variant::ToVariant,
         },
         pango::{self, prelude::FontFamilyExt},
-        prelude::{GtkWindowExt, ListItemExt, RangeExt, ScaleExt, WidgetExt},
+        prelude::{
+            BoxExt, ButtonExt, EditableExt, EntryExt, EventControllerExt, ListItemExt, RangeExt,
+            ScaleExt, WidgetExt,
+        },
         subclass::{
             widget::{
                 CompositeTemplateClass, CompositeTemplateInitializingExt, WidgetClassExt,
@@ -33,10 +41,15 @@ mod imp {
 
     use super::*;
     use crate::{
-        application::OwApplication,
-        services::settings::ApplicationSettings,
+        services::{
+            file_manager::FileManager,
+            output_manager::OutputManager,
+            outputs::{self, OutputRole},
+            settings::ApplicationSettings,
+            shortcuts::{self, Shortcut, ShortcutAction},
+        },
         structs::integer_object::IntegerObject,
-        utils::{self, RGBExtra, WidgetChildrenExt},
+        utils::{self, WidgetChildrenExt, WidgetExtrasExt},
         widgets::canvas::{canvas::Canvas, serialise::CanvasData},
     };
 
@@ -50,21 +63,38 @@ mod imp {
         #[template_child]
         output_view: gtk::TemplateChild<gtk::Box>,
         #[template_child]
-        monitor_dropdowon: gtk::TemplateChild<gtk::DropDown>,
-
-        #[template_child]
-        monitor_width: gtk::TemplateChild<gtk::Label>,
-        #[template_child]
-        monitor_height: gtk::TemplateChild<gtk::Label>,
+        monitor_list: gtk::TemplateChild<gtk::ListBox>,
 
         #[template_child]
         demo_screen: gtk::TemplateChild<Canvas>,
         #[template_child]
         screen_aspect_frame: gtk::TemplateChild<gtk::AspectFrame>,
+        #[template_child]
+        logo_image_label: gtk::TemplateChild<gtk::Label>,
+        #[template_child]
+        logo_image_btn: gtk::TemplateChild<gtk::Button>,
+        #[template_child]
+        logo_clear_btn: gtk::TemplateChild<gtk::Button>,
+        #[template_child]
+        logo_background_btn: gtk::TemplateChild<gtk::ColorDialogButton>,
 
         // song
         #[template_child]
         song_font_dropdown: gtk::TemplateChild<gtk::DropDown>,
+        #[template_child]
+        song_secondary_language_entry: gtk::TemplateChild<gtk::Entry>,
+        #[template_child]
+        song_secondary_font_dropdown: gtk::TemplateChild<gtk::DropDown>,
+        #[template_child]
+        song_secondary_size_btn: gtk::TemplateChild<gtk::SpinButton>,
+        #[template_child]
+        song_secondary_italic: gtk::TemplateChild<gtk::CheckButton>,
+        #[template_child]
+        song_revision_limit_btn: gtk::TemplateChild<gtk::SpinButton>,
+        #[template_child]
+        song_revision_max_age_btn: gtk::TemplateChild<gtk::SpinButton>,
+        #[template_child]
+        min_font_size_btn: gtk::TemplateChild<gtk::SpinButton>,
 
         // scripture
         #[template_child]
@@ -87,6 +117,28 @@ mod imp {
         #[template_child]
         transition_scale: gtk::TemplateChild<gtk::Scale>,
 
+        // stage display
+        #[template_child]
+        stage_layout_dropdown: gtk::TemplateChild<gtk::DropDown>,
+        #[template_child]
+        stage_font_scale_btn: gtk::TemplateChild<gtk::SpinButton>,
+
+        // lower third
+        #[template_child]
+        lower_third_height_btn: gtk::TemplateChild<gtk::SpinButton>,
+        #[template_child]
+        lower_third_margin_btn: gtk::TemplateChild<gtk::SpinButton>,
+        #[template_child]
+        lower_third_lines_btn: gtk::TemplateChild<gtk::SpinButton>,
+        #[template_child]
+        lower_third_font_dropdown: gtk::TemplateChild<gtk::DropDown>,
+        #[template_child]
+        lower_third_font_size_btn: gtk::TemplateChild<gtk::SpinButton>,
+        #[template_child]
+        lower_third_color_btn: gtk::TemplateChild<gtk::ColorDialogButton>,
+        #[template_child]
+        lower_third_background_btn: gtk::TemplateChild<gtk::ColorDialogButton>,
+
         // alert
         #[template_child]
         alert_dropdown: gtk::TemplateChild<gtk::DropDown>,
@@ -97,7 +149,17 @@ mod imp {
         #[template_child]
         alert_color_btn: gtk::TemplateChild<gtk::ColorDialogButton>,
 
-        monitor_map: RefCell<HashMap<String, gdk::Monitor>>,
+        // shortcuts
+        #[template_child]
+        shortcut_list: gtk::TemplateChild<gtk::ListBox>,
+        #[template_child]
+        shortcut_tag_entry: gtk::TemplateChild<gtk::Entry>,
+        #[template_child]
+        shortcut_add_btn: gtk::TemplateChild<gtk::Button>,
+        #[template_child]
+        shortcut_reset_btn: gtk::TemplateChild<gtk::Button>,
+        /// action that gets the next key pressed, with its button
+        recording: RefCell<Option<(ShortcutAction, gtk::Button)>>,
 
         fonts_map: RefCell<HashMap<String, pango::FontFamily>>,
     }
@@ -125,6 +187,8 @@ mod imp {
             self.register_song_fonts();
             self.register_scripture_fonts();
             self.register_alert();
+            self.register_logo();
+            self.register_shortcuts();
 
             self.sidebar.set_stack(&self.stack);
 
@@ -135,70 +199,11 @@ mod imp {
             self.demo_screen.imp().load_data();
             self.demo_screen.style();
 
-            if let Some(display) = gtk::gdk::Display::default()
-                && let Some(dropdown_model) = self
-                    .monitor_dropdowon
-                    .model()
-                    .and_downcast::<gtk::StringList>()
-            {
-                let mut mon_hash = self.monitor_map.borrow_mut();
-                let mon_list = display.monitors();
-
-                for monitor in mon_list.iter::<gdk::Monitor>() {
-                    let Ok(monitor) = monitor else {
-                        return;
-                    };
-
-                    if let Some(name) = monitor.model() {
-                        let name = name.to_string();
-                        dropdown_model.append(&name);
-                        mon_hash.insert(name.clone(), monitor);
-                    }
-                }
-            }
-
-            let fn_connect = glib::clone!(
-                #[weak(rename_to = imp)]
-                self,
-                move |dropdown: &gtk::DropDown| {
-                    let Some(item) = dropdown.selected_item().and_downcast::<gtk::StringObject>()
-                    else {
-                        return;
-                    };
-                    let item: String = item.into();
-
-                    let monitor_map = imp.monitor_map.borrow();
-                    let Some(monitor) = monitor_map.get(&item) else {
-                        imp.monitor_width.set_label("0");
-                        imp.monitor_height.set_label("0");
-                        imp.screen_aspect_frame.set_ratio(1.0);
-                        return;
-                    };
-
-                    let geo = monitor.geometry();
-                    imp.monitor_width.set_label(&geo.width().to_string());
-                    imp.monitor_height.set_label(&geo.height().to_string());
-
-                    let ratio = geo.width() as f32 / geo.height() as f32;
-                    imp.screen_aspect_frame.set_ratio(ratio);
-
-                    let Some(app) = imp.obj().application().and_downcast::<OwApplication>() else {
-                        return;
-                    };
-
-                    let ext_screen = app.main_window().extended_screen();
-                    ext_screen.unfullscreen();
-                    ext_screen.fullscreen_on_monitor(monitor);
-                }
-            );
+            self.register_monitors();
 
             self.transition_scale
                 .set_format_value_func(|_, v| format!("{:.1}s", v));
 
-            fn_connect(&self.monitor_dropdowon.clone());
-            self.monitor_dropdowon
-                .connect_selected_item_notify(fn_connect);
-
             // NOTE: this happens here to ensure that all models are set
             // before binding settings to values
             // this way default values from settings are not overwritten
@@ -322,6 +327,8 @@ mod imp {
             });
             // font_dropdown.set_factory(Some(&factory));
             font_dropdown.set_model(Some(&model));
+            self.song_secondary_font_dropdown.set_model(Some(&model));
+            self.lower_third_font_dropdown.set_model(Some(&model));
         }
         fn register_transtions(&self) {
             let vector: Vec<IntegerObject> = (0..=22).map(IntegerObject::new).collect();
@@ -453,6 +460,57 @@ mod imp {
                 ))
                 .build();
 
+            settings
+                .bind_song_secondary_language(&self.song_secondary_language_entry.clone(), "text")
+                .build();
+            let song_secondary_font_dropdown = self.song_secondary_font_dropdown.clone();
+            settings
+                .bind_song_secondary_font(&song_secondary_font_dropdown, "selected")
+                .mapping(glib::clone!(
+                    #[strong]
+                    font_names,
+                    move |font, _| {
+                        let font: String = font
+                            .get()
+                            .expect("The variant needs to be of type `String`.");
+
+                        let found = font_names
+                            .iter()
+                            .position(|v| *v == font)
+                            .unwrap_or_default() as u32;
+
+                        Some(found.to_value())
+                    }
+                ))
+                .set_mapping(glib::clone!(
+                    #[strong]
+                    font_names,
+                    move |font, _| {
+                        let selected: u32 =
+                            font.get().expect("The variant needs to be of type `u32`.");
+
+                        let font = font_names.get(selected as usize)?;
+                        Some(font.to_variant())
+                    }
+                ))
+                .build();
+            settings
+                .bind_song_secondary_size(&self.song_secondary_size_btn.clone(), "value")
+                .build();
+            settings
+                .bind_song_secondary_italic(&self.song_secondary_italic.clone(), "active")
+                .build();
+
+            settings
+                .bind_song_revision_limit(&self.song_revision_limit_btn.clone(), "value")
+                .build();
+            settings
+                .bind_song_revision_max_age(&self.song_revision_max_age_btn.clone(), "value")
+                .build();
+            settings
+                .bind_min_font_size(&self.min_font_size_btn.clone(), "value")
+                .build();
+
             let scripture_font_dropdown = self.scripture_font_dropdown.clone();
             settings
                 .bind_scripture_font(&scripture_font_dropdown, "selected")
@@ -487,6 +545,63 @@ mod imp {
                 ))
                 .build();
 
+            settings
+                .bind_stage_layout(&self.stage_layout_dropdown.clone(), "selected")
+                .build();
+            settings
+                .bind_stage_font_scale(&self.stage_font_scale_btn.clone(), "value")
+                .build();
+
+            settings
+                .bind_lower_third_height(&self.lower_third_height_btn.clone(), "value")
+                .build();
+            settings
+                .bind_lower_third_margin(&self.lower_third_margin_btn.clone(), "value")
+                .build();
+            settings
+                .bind_lower_third_lines(&self.lower_third_lines_btn.clone(), "value")
+                .build();
+            settings
+                .bind_lower_third_font(&self.lower_third_font_dropdown.clone(), "selected")
+                .mapping(glib::clone!(
+                    #[strong]
+                    font_names,
+                    move |font, _| {
+                        let font: String = font
+                            .get()
+                            .expect("The variant needs to be of type `String`.");
+
+                        let found = font_names
+                            .iter()
+                            .position(|v| *v == font)
+                            .unwrap_or_default() as u32;
+
+                        Some(found.to_value())
+                    }
+                ))
+                .set_mapping(glib::clone!(
+                    #[strong]
+                    font_names,
+                    move |font, _| {
+                        let selected: u32 =
+                            font.get().expect("The variant needs to be of type `u32`.");
+
+                        let font = font_names.get(selected as usize)?;
+                        Some(font.to_variant())
+                    }
+                ))
+                .build();
+            settings
+                .bind_lower_third_font_size(&self.lower_third_font_size_btn.clone(), "value")
+                .build();
+            bind_rgba_color(
+                settings.bind_lower_third_color(&self.lower_third_color_btn.clone(), "rgba"),
+            );
+            bind_rgba_color(
+                settings
+                    .bind_lower_third_background(&self.lower_third_background_btn.clone(), "rgba"),
+            );
+
             settings
                 .bind_alert_position(&self.alert_dropdown.clone(), "selected")
                 .build();
@@ -497,28 +612,303 @@ mod imp {
             settings
                 .bind_alert_speed(&self.alert_speed_btn.clone(), "value")
                 .build();
+            bind_hex_color(settings.bind_alert_color(&self.alert_color_btn.clone(), "rgba"));
+
             settings
-                .bind_alert_color(&self.alert_color_btn.clone(), "rgba")
-                .mapping(|rgba, _| {
-                    let rgba: String = rgba
+                .bind_logo_image(&self.logo_image_label.clone(), "label")
+                .get_only()
+                .mapping(|path, _| {
+                    let path: String = path
                         .get()
                         .expect("The variant needs to be of type `String`.");
+                    let name = std::path::Path::new(&path)
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or(String::from("No image"));
+                    Some(name.to_value())
+                })
+                .build();
+            bind_hex_color(
+                settings.bind_logo_background(&self.logo_background_btn.clone(), "rgba"),
+            );
+        }
+
+        fn register_monitors(&self) {
+            self.fill_monitor_list();
 
-                    let (r, g, b) = hex_to_rgba(&rgba[0..7]).unwrap_or((0, 0, 0));
+            let settings = ApplicationSettings::get_instance();
+            settings.connect_outputs_changed(glib::clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| {
+                    // the changed dropdown is still in the list
+                    glib::idle_add_local_once(move || imp.fill_monitor_list());
+                }
+            ));
+            if let Some(display) = gdk::Display::default() {
+                display.monitors().connect_items_changed(glib::clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    move |_, _, _, _| {
+                        glib::idle_add_local_once(move || imp.fill_monitor_list());
+                    }
+                ));
+            }
+        }
 
-                    let color =
-                        gdk::RGBA::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0);
+        /// One row per connected monitor with its size and role
+        fn fill_monitor_list(&self) {
+            self.monitor_list.remove_all();
 
-                    Some(color.to_value())
-                })
-                .set_mapping(move |rgba, _| {
-                    let rgba: gdk::RGBA =
-                        rgba.get().expect("The variant needs to be of type `RGBA`.");
+            let roles = OutputManager::roles();
+            if roles.is_empty() {
+                self.monitor_list
+                    .append(&gtk::Label::new(Some("No monitors found")));
+            }
 
-                    let hex = rgba.to_hex();
-                    Some(hex.to_variant())
-                })
-                .build();
+            let labels = OutputRole::ALL.map(|r| r.label());
+            for (monitor, info, role) in roles {
+                let geometry = monitor.geometry();
+                if role == OutputRole::Live {
+                    let ratio = geometry.width() as f32 / geometry.height() as f32;
+                    self.screen_aspect_frame.set_ratio(ratio);
+                }
+
+                let name = gtk::Label::builder()
+                    .label(info.label())
+                    .hexpand(true)
+                    .xalign(0.0)
+                    .build();
+                let size =
+                    gtk::Label::new(Some(&format!("{}×{}", geometry.width(), geometry.height())));
+                size.add_css_class("dim-label");
+
+                let dropdown = gtk::DropDown::from_strings(&labels);
+                let selected = OutputRole::ALL.iter().position(|r| *r == role);
+                dropdown.set_selected(selected.unwrap_or_default() as u32);
+                dropdown.connect_selected_notify(move |dropdown| {
+                    let Some(role) = OutputRole::ALL.get(dropdown.selected() as usize) else {
+                        return;
+                    };
+
+                    let settings = ApplicationSettings::get_instance();
+                    let mut configs = outputs::parse_outputs(&settings.outputs());
+                    outputs::set_role(&mut configs, &info, *role);
+                    let entries = outputs::format_outputs(&configs);
+                    let entries = entries.iter().map(String::as_str).collect::<Vec<_>>();
+                    settings.set_outputs(&entries);
+                });
+
+                let row = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+                row.set_margin_all(6);
+                row.append(&name);
+                row.append(&size);
+                row.append(&dropdown);
+                self.monitor_list.append(&row);
+            }
+        }
+
+        fn register_shortcuts(&self) {
+            self.fill_shortcut_list();
+
+            ApplicationSettings::get_instance().connect_shortcuts_changed(glib::clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| {
+                    // the clicked button is still in the list
+                    glib::idle_add_local_once(move || imp.fill_shortcut_list());
+                }
+            ));
+
+            let add_verse = glib::clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move || {
+                    let tag = shortcuts::normalise_tag(&imp.shortcut_tag_entry.text());
+                    if tag.is_empty() {
+                        return;
+                    }
+                    imp.shortcut_tag_entry.set_text("");
+
+                    let mut list = Self::shortcuts();
+                    let action = ShortcutAction::Verse(tag);
+                    if !list.iter().any(|s| s.action == action) {
+                        shortcuts::set_keys(&mut list, &action, &[]);
+                        Self::save_shortcuts(&list);
+                    }
+                }
+            );
+            let add = add_verse.clone();
+            self.shortcut_add_btn.connect_clicked(move |_| add());
+            self.shortcut_tag_entry
+                .connect_activate(move |_| add_verse());
+
+            self.shortcut_reset_btn.connect_clicked(|_| {
+                ApplicationSettings::get_instance().set_shortcuts(&[]);
+            });
+
+            // keys for the action being recorded, before the window uses them
+            let controller = gtk::EventControllerKey::new();
+            controller.set_propagation_phase(gtk::PropagationPhase::Capture);
+            controller.connect_key_pressed(glib::clone!(
+                #[weak(rename_to = imp)]
+                self,
+                #[upgrade_or]
+                glib::Propagation::Proceed,
+                move |controller, key, _, state| imp.record_key(controller, key, state)
+            ));
+            self.obj().add_controller(controller);
+        }
+
+        fn shortcuts() -> Vec<Shortcut> {
+            shortcuts::parse_shortcuts(&ApplicationSettings::get_instance().shortcuts())
+        }
+
+        fn save_shortcuts(list: &[Shortcut]) {
+            let entries = shortcuts::format_shortcuts(list);
+            let entries = entries.iter().map(String::as_str).collect::<Vec<_>>();
+            ApplicationSettings::get_instance().set_shortcuts(&entries);
+        }
+
+        /// One row per action with its keys, a button to add a key and one
+        /// to take them off. Verse actions can also be removed
+        fn fill_shortcut_list(&self) {
+            self.shortcut_list.remove_all();
+            self.recording.replace(None);
+
+            for shortcut in Self::shortcuts() {
+                let name = gtk::Label::builder()
+                    .label(shortcut.action.label())
+                    .hexpand(true)
+                    .xalign(0.0)
+                    .build();
+
+                let keys = gtk::ShortcutLabel::builder()
+                    .accelerator(shortcut.keys.join(" "))
+                    .disabled_text("Disabled")
+                    .build();
+
+                let add_btn = gtk::Button::from_icon_name("list-add-symbolic");
+                add_btn.set_tooltip_text(Some("Add a key"));
+                add_btn.connect_clicked(glib::clone!(
+                    #[weak(rename_to = imp)]
+                    self,
+                    #[strong(rename_to = action)]
+                    shortcut.action,
+                    move |btn| {
+                        btn.set_icon_name("media-record-symbolic");
+                        btn.set_tooltip_text(Some("Press a key, Escape to cancel"));
+                        let previous = imp.recording.replace(Some((action.clone(), btn.clone())));
+                        if let Some((_, previous)) = previous.filter(|(_, b)| b != btn) {
+                            previous.set_icon_name("list-add-symbolic");
+                            previous.set_tooltip_text(Some("Add a key"));
+                        }
+                    }
+                ));
+
+                let clear_btn = gtk::Button::from_icon_name("edit-clear-symbolic");
+                clear_btn.set_tooltip_text(Some("Remove the keys"));
+                clear_btn.set_sensitive(!shortcut.keys.is_empty());
+                clear_btn.connect_clicked(glib::clone!(
+                    #[strong(rename_to = action)]
+                    shortcut.action,
+                    move |_| {
+                        let mut list = Self::shortcuts();
+                        shortcuts::set_keys(&mut list, &action, &[]);
+                        Self::save_shortcuts(&list);
+                    }
+                ));
+
+                let row = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+                row.set_margin_all(6);
+                row.append(&name);
+                row.append(&keys);
+                row.append(&add_btn);
+
+                // a verse jump without keys is removed, so it only gets the trash
+                if let ShortcutAction::Verse(_) = shortcut.action {
+                    let remove_btn = gtk::Button::from_icon_name("user-trash-symbolic");
+                    remove_btn.set_tooltip_text(Some("Remove the verse jump"));
+                    remove_btn.connect_clicked(glib::clone!(
+                        #[strong(rename_to = action)]
+                        shortcut.action,
+                        move |_| {
+                            let mut list = Self::shortcuts();
+                            list.retain(|s| s.action != action);
+                            Self::save_shortcuts(&list);
+                        }
+                    ));
+                    row.append(&remove_btn);
+                } else {
+                    row.append(&clear_btn);
+                }
+
+                self.shortcut_list.append(&row);
+            }
+        }
+
+        /// Adds the key pressed to the action being recorded
+        fn record_key(
+            &self,
+            controller: &gtk::EventControllerKey,
+            key: gdk::Key,
+            state: gdk::ModifierType,
+        ) -> glib::Propagation {
+            if self.recording.borrow().is_none() {
+                return glib::Propagation::Proceed;
+            }
+            // wait for the key a modifier goes with
+            let modifier = controller
+                .current_event()
+                .and_then(|e| e.downcast::<gdk::KeyEvent>().ok())
+                .is_some_and(|e| e.is_modifier());
+            if modifier {
+                return glib::Propagation::Stop;
+            }
+
+            let Some((action, _)) = self.recording.take() else {
+                return glib::Propagation::Proceed;
+            };
+            if key == gdk::Key::Escape {
+                self.fill_shortcut_list();
+                return glib::Propagation::Stop;
+            }
+
+            let state = state & gtk::accelerator_get_default_mod_mask();
+            let accel = gtk::accelerator_name(key.to_lower(), state).to_string();
+            let mut list = Self::shortcuts();
+            let mut keys = list
+                .iter()
+                .find(|s| s.action == action)
+                .map(|s| s.keys.clone())
+                .unwrap_or_default();
+            if !keys.contains(&accel) {
+                keys.push(accel);
+            }
+            shortcuts::set_keys(&mut list, &action, &keys);
+            Self::save_shortcuts(&list);
+            glib::Propagation::Stop
+        }
+
+        fn register_logo(&self) {
+            self.logo_image_btn.connect_clicked(glib::clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| {
+                    let window = imp.obj().clone().upcast::<gtk::Window>();
+                    let Some(path) = FileManager::open_image(Some(&window)).and_then(|f| f.path())
+                    else {
+                        return;
+                    };
+
+                    let path = path.display().to_string();
+                    ApplicationSettings::get_instance().set_logo_image(&path);
+                }
+            ));
+
+            self.logo_clear_btn.connect_clicked(|_| {
+                ApplicationSettings::get_instance().set_logo_image("");
+            });
         }
     }
 }
@@ -541,6 +931,47 @@ impl SettingsWindow {
     }
 }
 
+/// Binds a `#rrggbb` setting to an `rgba` property
+fn bind_hex_color(builder: gio::BindingBuilder<'_>) {
+    builder
+        .mapping(|rgba, _| {
+            let rgba: String = rgba
+                .get()
+                .expect("The variant needs to be of type `String`.");
+
+            let (r, g, b) = hex_to_rgba(&rgba[0..7]).unwrap_or((0, 0, 0));
+
+            let color = gdk::RGBA::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0);
+
+            Some(color.to_value())
+        })
+        .set_mapping(move |rgba, _| {
+            let rgba: gdk::RGBA = rgba.get().expect("The variant needs to be of type `RGBA`.");
+
+            let hex = rgba.to_hex();
+            Some(hex.to_variant())
+        })
+        .build();
+}
+
+/// Like [bind_hex_color], keeping the alpha of the colour
+fn bind_rgba_color(builder: gio::BindingBuilder<'_>) {
+    builder
+        .mapping(|rgba, _| {
+            let rgba: String = rgba
+                .get()
+                .expect("The variant needs to be of type `String`.");
+
+            let color = gdk::RGBA::parse(rgba.as_str()).unwrap_or(gdk::RGBA::BLACK);
+            Some(color.to_value())
+        })
+        .set_mapping(move |rgba, _| {
+            let rgba: gdk::RGBA = rgba.get().expect("The variant needs to be of type `RGBA`.");
+            Some(rgba.to_hex().to_variant())
+        })
+        .build();
+}
+
 fn hex_to_rgba(val: &str) -> Option<(u8, u8, u8)> {
     let val = val.strip_prefix('#')?;
 
@@ -0,0 +1,253 @@
+use gtk::glib::{self, subclass::types::ObjectSubclassIsExt};
+use gtk::prelude::*;
+
+use crate::{
+    db::query::Query,
+    dto::SongUsageReport,
+    services::{file_manager::FileManager, song_usage::usage_report_csv},
+};
+
+const COLUMNS: [&str; 5] = ["Title", "CCLI #", "Times used", "Slides shown", "Last used"];
+
+mod imp {
+    use std::cell::RefCell;
+
+    use super::*;
+    use crate::utils::WidgetExtrasExt;
+    use gtk::{
+        glib::{
+            self,
+            subclass::{
+                object::{ObjectImpl, ObjectImplExt},
+                types::{ObjectSubclass, ObjectSubclassExt},
+            },
+        },
+        subclass::{widget::WidgetImpl, window::WindowImpl},
+    };
+
+    #[derive(Default)]
+    pub struct SongUsageWindow {
+        pub(super) from_calendar: RefCell<gtk::Calendar>,
+        pub(super) to_calendar: RefCell<gtk::Calendar>,
+        pub(super) report_grid: RefCell<gtk::Grid>,
+        pub(super) report: RefCell<Vec<SongUsageReport>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SongUsageWindow {
+        const NAME: &'static str = "SongUsageWindow";
+        type Type = super::SongUsageWindow;
+        type ParentType = gtk::Window;
+    }
+
+    impl ObjectImpl for SongUsageWindow {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            obj.set_title(Some("Song usage"));
+            obj.set_default_width(700);
+            obj.set_default_height(500);
+            obj.add_css_class("dialog");
+
+            let box_ui = gtk::Box::new(gtk::Orientation::Vertical, 0);
+            obj.set_child(Some(&box_ui));
+
+            let range_box = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+            range_box.set_margin_all(6);
+            box_ui.append(&range_box);
+
+            let from_calendar = gtk::Calendar::new();
+            let to_calendar = gtk::Calendar::new();
+            if let Ok(now) = glib::DateTime::now_local()
+                && let Ok(start) = now.add_days(1 - now.day_of_month())
+            {
+                from_calendar.select_day(&start);
+            }
+
+            for (label, calendar) in [("From", &from_calendar), ("To", &to_calendar)] {
+                let btn = gtk::MenuButton::builder()
+                    .popover(&gtk::Popover::builder().child(calendar).build())
+                    .build();
+                let set_label = glib::clone!(
+                    #[weak]
+                    btn,
+                    move |c: &gtk::Calendar| {
+                        let date = c.date().format("%Y-%m-%d").unwrap_or_default();
+                        btn.set_label(&date);
+                    }
+                );
+                set_label(calendar);
+                calendar.connect_day_selected(glib::clone!(
+                    #[weak]
+                    obj,
+                    move |c| {
+                        set_label(c);
+                        obj.load_report();
+                    }
+                ));
+
+                range_box.append(&gtk::Label::new(Some(label)));
+                range_box.append(&btn);
+            }
+
+            let report_grid = gtk::Grid::builder()
+                .column_spacing(18)
+                .row_spacing(6)
+                .build();
+            report_grid.set_margin_all(12);
+            box_ui.append(
+                &gtk::ScrolledWindow::builder()
+                    .child(&report_grid)
+                    .vexpand(true)
+                    .build(),
+            );
+
+            box_ui.append(&gtk::Separator::new(gtk::Orientation::Horizontal));
+
+            let footer_box = gtk::Box::builder()
+                .margin_top(3)
+                .margin_bottom(3)
+                .margin_start(3)
+                .margin_end(3)
+                .spacing(3)
+                .build();
+            footer_box.append(&gtk::Box::builder().hexpand(true).build());
+
+            let export_btn = gtk::Button::with_label("Export CSV");
+            export_btn.connect_clicked(glib::clone!(
+                #[weak]
+                obj,
+                move |_| obj.export_csv()
+            ));
+            let close_btn = gtk::Button::with_label("Close");
+            close_btn.connect_clicked(glib::clone!(
+                #[weak]
+                obj,
+                move |_| obj.close()
+            ));
+            footer_box.append(&export_btn);
+            footer_box.append(&close_btn);
+            box_ui.append(&footer_box);
+
+            self.from_calendar.replace(from_calendar);
+            self.to_calendar.replace(to_calendar);
+            self.report_grid.replace(report_grid);
+
+            obj.load_report();
+        }
+    }
+    impl WidgetImpl for SongUsageWindow {}
+    impl WindowImpl for SongUsageWindow {}
+}
+
+glib::wrapper! {
+pub struct SongUsageWindow(ObjectSubclass<imp::SongUsageWindow>)
+    @extends gtk::Window, gtk::Widget,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget,gtk::Native,gtk::Root, gtk::ShortcutManager;
+}
+
+impl Default for SongUsageWindow {
+    fn default() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl SongUsageWindow {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    /// Selected range as unix timestamps, the end day is included
+    fn range(&self) -> (i64, i64) {
+        let imp = self.imp();
+        let from = imp.from_calendar.borrow().date();
+        let to = imp.to_calendar.borrow().date();
+
+        let from = start_of_day(&from);
+        let to = start_of_day(&to);
+        let end = to.add_days(1).unwrap_or(to);
+        (from.to_unix(), end.to_unix())
+    }
+
+    fn load_report(&self) {
+        let imp = self.imp();
+        let Some(grid) = imp.report_grid.try_borrow().ok().map(|g| g.clone()) else {
+            return;
+        };
+
+        while let Some(child) = grid.first_child() {
+            grid.remove(&child);
+        }
+
+        let (from, to) = self.range();
+        let report = match Query::song_usage_report(from, to) {
+            Ok(r) => r,
+            Err(e) => {
+                eprintln!("SQL ERROR: {:?}", e);
+                Vec::new()
+            }
+        };
+
+        for (col, title) in COLUMNS.iter().enumerate() {
+            let label = gtk::Label::builder().label(*title).xalign(0.0).build();
+            label.add_css_class("heading");
+            grid.attach(&label, col as i32, 0, 1, 1);
+        }
+
+        for (row, entry) in report.iter().enumerate() {
+            let last_used = glib::DateTime::from_unix_local(entry.last_used)
+                .and_then(|d| d.format("%Y-%m-%d %H:%M"))
+                .map(|d| d.to_string())
+                .unwrap_or_default();
+            let values = [
+                entry.title.clone(),
+                entry.details.ccli_number.clone().unwrap_or_default(),
+                entry.times_used.to_string(),
+                entry.slides_shown.to_string(),
+                last_used,
+            ];
+
+            for (col, value) in values.iter().enumerate() {
+                let label = gtk::Label::builder()
+                    .label(value)
+                    .xalign(0.0)
+                    .ellipsize(gtk::pango::EllipsizeMode::End)
+                    .build();
+                grid.attach(&label, col as i32, row as i32 + 1, 1, 1);
+            }
+        }
+
+        if report.is_empty() {
+            let label = gtk::Label::new(Some("No songs were shown in this period"));
+            grid.attach(&label, 0, 1, COLUMNS.len() as i32, 1);
+        }
+
+        imp.report.replace(report);
+    }
+
+    fn export_csv(&self) {
+        let csv = usage_report_csv(&self.imp().report.borrow());
+
+        let mut filters = glib::List::new();
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some("CSV"));
+        filter.add_pattern("*.csv");
+        filters.push_back(filter);
+
+        FileManager::save_file(
+            "Export song usage",
+            "Export",
+            &mut filters,
+            csv.as_bytes(),
+            Some(self.upcast_ref()),
+        );
+    }
+}
+
+/// Local midnight of the day of `date`, calendar dates keep the time of day
+/// they were selected at
+fn start_of_day(date: &glib::DateTime) -> glib::DateTime {
+    glib::DateTime::from_local(date.year(), date.month(), date.day_of_month(), 0, 0, 0.0)
+        .unwrap_or_else(|_| date.clone())
+}
@@ -0,0 +1,294 @@
+use gtk::{
+    glib::{self, object::ObjectExt, subclass::types::ObjectSubclassIsExt},
+    pango,
+    prelude::{OrientableExt, WidgetExt},
+};
+
+use crate::{
+    services::{
+        settings::ApplicationSettings,
+        stage::{self, StageLayout},
+    },
+    utils::TextBufferExtraExt,
+    widgets::extended_screen::ExtendedScreen,
+};
+
+/// Size of the next slide and notes text, from the current slide text size
+const SECONDARY_SCALE: f64 = 0.6;
+/// Size of the clock, timer and title, from the current slide text size
+const HEADER_SCALE: f64 = 0.5;
+
+mod imp {
+    use std::cell::{Cell, RefCell};
+
+    use gtk::{
+        glib::{
+            self, Properties,
+            subclass::{
+                object::{ObjectImpl, ObjectImplExt},
+                types::{ObjectSubclass, ObjectSubclassExt},
+            },
+        },
+        prelude::{BoxExt, GtkWindowExt, ObjectExt, OrientableExt, WidgetExt},
+        subclass::{
+            prelude::DerivedObjectProperties,
+            widget::{WidgetImpl, WidgetImplExt},
+            window::WindowImpl,
+        },
+    };
+
+    use crate::services::{settings::ApplicationSettings, slide_manager::SlideManager};
+    use crate::utils::WidgetExtrasExt;
+
+    /// Shows the preacher and singers the live slide, the next slide,
+    /// the item notes, a clock and the service timer
+    #[derive(Default, Properties)]
+    #[properties(wrapper_type = super::StageScreen)]
+    pub struct StageScreen {
+        #[property(get, construct_only)]
+        pub(super) slide_manager: glib::WeakRef<SlideManager>,
+        #[property(get, set = Self::set_note)]
+        pub(super) note: RefCell<String>,
+        /// unix time the service timer counts from, `0` when not started
+        #[property(get, set)]
+        pub(super) service_start: Cell<i64>,
+
+        pub(super) clock: gtk::Label,
+        pub(super) timer: gtk::Label,
+        pub(super) title: gtk::Label,
+        pub(super) slides: gtk::Box,
+        pub(super) current: gtk::Label,
+        pub(super) next: gtk::Label,
+        pub(super) next_box: gtk::Box,
+        pub(super) notes: gtk::Label,
+        /// height the text was last sized for
+        pub(super) sized_height: Cell<i32>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for StageScreen {
+        const NAME: &'static str = "StageScreen";
+        type Type = super::StageScreen;
+        type ParentType = gtk::Window;
+    }
+
+    #[glib::derived_properties]
+    impl ObjectImpl for StageScreen {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+            obj.set_title(Some("Stage display"));
+            obj.set_default_size(800, 450);
+
+            #[cfg(not(debug_assertions))]
+            {
+                obj.set_decorated(false);
+                let c = gtk::gdk::Cursor::from_name("none", None);
+                obj.set_cursor(c.as_ref());
+            }
+
+            let header = gtk::CenterBox::new();
+            header.set_start_widget(Some(&self.clock));
+            header.set_center_widget(Some(&self.title));
+            header.set_end_widget(Some(&self.timer));
+            self.title.set_ellipsize(gtk::pango::EllipsizeMode::End);
+
+            for label in [&self.current, &self.next, &self.notes] {
+                label.set_wrap(true);
+                label.set_xalign(0.0);
+                label.set_yalign(0.0);
+            }
+            self.current.set_vexpand(true);
+            self.current.set_hexpand(true);
+            self.next.add_css_class("dim-label");
+            self.notes.add_css_class("dim-label");
+
+            let next_caption = gtk::Label::new(Some("Next"));
+            next_caption.set_xalign(0.0);
+            next_caption.add_css_class("caption-heading");
+            self.next_box.set_orientation(gtk::Orientation::Vertical);
+            self.next_box.set_hexpand(true);
+            self.next_box.set_vexpand(true);
+            self.next_box.append(&next_caption);
+            self.next_box.append(&self.next);
+
+            self.slides.set_spacing(24);
+            self.slides.set_vexpand(true);
+            self.slides.set_homogeneous(true);
+            self.slides.append(&self.current);
+            self.slides.append(&self.next_box);
+
+            let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+            content.add_css_class("black_bg_box");
+            content.set_margin_all(24);
+            content.append(&header);
+            content.append(&gtk::Separator::new(gtk::Orientation::Horizontal));
+            content.append(&self.slides);
+            content.append(&self.notes);
+            obj.set_child(Some(&content));
+
+            if let Some(sm) = self.slide_manager.upgrade() {
+                sm.connect_current_slide_changed(glib::clone!(
+                    #[weak]
+                    obj,
+                    move |_, _| obj.update_slides()
+                ));
+                sm.connect_title_notify(glib::clone!(
+                    #[weak]
+                    obj,
+                    move |_| obj.update_slides()
+                ));
+            }
+
+            let settings = ApplicationSettings::get_instance();
+            settings.connect_stage_layout_changed(glib::clone!(
+                #[weak]
+                obj,
+                move |_| obj.update_layout()
+            ));
+            settings.connect_stage_font_scale_changed(glib::clone!(
+                #[weak(rename_to = imp)]
+                self,
+                move |_| {
+                    imp.sized_height.set(0);
+                    imp.obj().queue_resize();
+                }
+            ));
+
+            glib::timeout_add_seconds_local(
+                1,
+                glib::clone!(
+                    #[weak]
+                    obj,
+                    #[upgrade_or]
+                    glib::ControlFlow::Break,
+                    move || {
+                        obj.update_clock();
+                        glib::ControlFlow::Continue
+                    }
+                ),
+            );
+            obj.connect_service_start_notify(|obj| obj.update_clock());
+
+            obj.update_layout();
+            obj.update_slides();
+            obj.update_clock();
+        }
+    }
+
+    impl WidgetImpl for StageScreen {
+        fn size_allocate(&self, width: i32, height: i32, baseline: i32) {
+            self.parent_size_allocate(width, height, baseline);
+            if self.sized_height.replace(height) != height {
+                // resizing from the allocation needs another layout pass
+                let obj = self.obj().clone();
+                glib::idle_add_local_once(move || obj.update_text_size());
+            }
+        }
+    }
+    impl WindowImpl for StageScreen {}
+
+    impl StageScreen {
+        fn set_note(&self, note: String) {
+            self.notes.set_label(&note);
+            self.notes.set_visible(!note.is_empty());
+            self.note.replace(note);
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct StageScreen(ObjectSubclass<imp::StageScreen>)
+        @extends gtk::Window, gtk::Widget,
+        @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget, gtk::Native, gtk::Root, gtk::ShortcutManager;
+}
+
+impl StageScreen {
+    /// A stage display following the live slides of `screen`
+    pub fn new(screen: &ExtendedScreen) -> Self {
+        let obj: Self = glib::Object::builder()
+            .property("slide_manager", screen.slide_manager())
+            .build();
+
+        screen
+            .bind_property("note", &obj, "note")
+            .sync_create()
+            .build();
+        screen
+            .bind_property("service_start", &obj, "service_start")
+            .sync_create()
+            .build();
+        obj
+    }
+
+    fn update_slides(&self) {
+        let imp = self.imp();
+        let Some(sm) = self.slide_manager() else {
+            return;
+        };
+
+        let slides = sm.slides();
+        let current = sm
+            .current_slide()
+            .and_then(|c| slides.iter().position(|s| *s == c));
+        let text = |index: Option<usize>| {
+            index
+                .and_then(|i| slides.get(i))
+                .and_then(|s| s.entry_buffer())
+                .map(|b| b.full_text().to_string())
+                .unwrap_or_default()
+        };
+
+        imp.title.set_label(&sm.title());
+        imp.current.set_label(&text(current));
+        imp.next
+            .set_label(&text(stage::next_index(current, slides.len())));
+    }
+
+    fn update_layout(&self) {
+        let imp = self.imp();
+        let layout = StageLayout::from_index(ApplicationSettings::get_instance().stage_layout());
+
+        imp.slides.set_orientation(match layout {
+            StageLayout::SideBySide => gtk::Orientation::Horizontal,
+            _ => gtk::Orientation::Vertical,
+        });
+        imp.next_box.set_visible(layout.shows_next());
+    }
+
+    fn update_text_size(&self) {
+        let imp = self.imp();
+        let scale = ApplicationSettings::get_instance().stage_font_scale();
+        let size = stage::text_size(self.height(), scale);
+
+        let sized = |label: &gtk::Label, size: f64| {
+            let attrs = pango::AttrList::new();
+            attrs.insert(pango::AttrSize::new_size_absolute(
+                (size * pango::SCALE as f64) as i32,
+            ));
+            label.set_attributes(Some(&attrs));
+        };
+        sized(&imp.current, size);
+        sized(&imp.next, size * SECONDARY_SCALE);
+        sized(&imp.notes, size * SECONDARY_SCALE);
+        for label in [&imp.clock, &imp.timer, &imp.title] {
+            sized(label, size * HEADER_SCALE);
+        }
+    }
+
+    fn update_clock(&self) {
+        let imp = self.imp();
+        let Ok(now) = glib::DateTime::now_local() else {
+            return;
+        };
+
+        if let Ok(clock) = now.format("%H:%M") {
+            imp.clock.set_label(&clock);
+        }
+        let timer = match self.service_start() {
+            0 => stage::format_elapsed(0),
+            start => stage::format_elapsed(now.to_unix() - start),
+        };
+        imp.timer.set_label(&timer);
+    }
+}
@@ -0,0 +1,400 @@
+use crate::app_config::AppConfig;
+use crate::db::query::Query;
+use crate::services::theme::{SlideTheme, ThemeContent};
+use crate::widgets::canvas::serialise::{CanvasItemType, SlideData, SlideManagerData};
+use gtk::glib;
+use gtk::glib::subclass::types::ObjectSubclassIsExt;
+use gtk::prelude::*;
+
+const SAMPLE_TEXT: &str = "Sample slide text";
+const SAMPLE_FOOTER: &str = "Footer";
+
+mod imp {
+    use std::cell::RefCell;
+
+    use super::*;
+    use crate::{
+        services::slide_manager::SlideManager, utils::WidgetExtrasExt,
+        widgets::search::songs::toolbar::song_editor_toolbar::SongEditorToolbar,
+    };
+    use gtk::{
+        glib::{
+            self,
+            subclass::{
+                object::{ObjectImpl, ObjectImplExt},
+                types::{ObjectSubclass, ObjectSubclassExt},
+            },
+        },
+        subclass::{widget::WidgetImpl, window::WindowImpl},
+    };
+
+    #[derive(Default)]
+    pub struct ThemeWindow {
+        pub(super) slide_manager: RefCell<SlideManager>,
+        pub(super) themes: RefCell<Vec<SlideTheme>>,
+        pub(super) theme_list: RefCell<gtk::ListBox>,
+        pub(super) new_entry: RefCell<gtk::Entry>,
+        pub(super) name_entry: RefCell<gtk::Entry>,
+        pub(super) show_footer: RefCell<gtk::CheckButton>,
+        pub(super) editor: RefCell<gtk::Box>,
+        /// one drop down per [ThemeContent::ALL]
+        pub(super) defaults: RefCell<Vec<gtk::DropDown>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ThemeWindow {
+        const NAME: &'static str = "ThemeWindow";
+        type Type = super::ThemeWindow;
+        type ParentType = gtk::Window;
+    }
+
+    impl ObjectImpl for ThemeWindow {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+
+            obj.set_title(Some("Themes"));
+            obj.set_default_width(1000);
+            obj.set_default_height(600);
+            obj.set_modal(true);
+            obj.add_css_class("dialog");
+
+            let box_ui = gtk::Box::new(gtk::Orientation::Vertical, 0);
+            obj.set_child(Some(&box_ui));
+
+            let defaults_box = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+            defaults_box.set_margin_all(6);
+            let mut defaults = Vec::new();
+            for content in ThemeContent::ALL {
+                let label = gtk::Label::builder()
+                    .label(format!("{} theme", content.label()))
+                    .margin_start(6)
+                    .build();
+                let dropdown = gtk::DropDown::from_strings(&["None"]);
+                dropdown.connect_selected_notify(glib::clone!(
+                    #[weak]
+                    obj,
+                    move |d| obj.set_default_theme(content, d.selected())
+                ));
+                defaults_box.append(&label);
+                defaults_box.append(&dropdown);
+                defaults.push(dropdown);
+            }
+            self.defaults.replace(defaults);
+            box_ui.append(&defaults_box);
+            box_ui.append(&gtk::Separator::new(gtk::Orientation::Horizontal));
+
+            let pane = gtk::Paned::builder()
+                .position(240)
+                .shrink_start_child(false)
+                .vexpand(true)
+                .build();
+            box_ui.append(&pane);
+
+            let list_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+            let theme_list = gtk::ListBox::builder()
+                .selection_mode(gtk::SelectionMode::Single)
+                .show_separators(true)
+                .build();
+            theme_list.set_placeholder(Some(
+                &gtk::Label::builder()
+                    .label("No themes yet")
+                    .margin_top(12)
+                    .build(),
+            ));
+            theme_list.connect_row_selected(glib::clone!(
+                #[weak]
+                obj,
+                move |_, _| obj.load_selected_theme()
+            ));
+            list_box.append(
+                &gtk::ScrolledWindow::builder()
+                    .child(&theme_list)
+                    .vexpand(true)
+                    .build(),
+            );
+
+            let add_box = gtk::Box::new(gtk::Orientation::Horizontal, 3);
+            add_box.set_margin_all(3);
+            let new_entry = gtk::Entry::builder()
+                .placeholder_text("New theme")
+                .hexpand(true)
+                .build();
+            new_entry.connect_activate(glib::clone!(
+                #[weak]
+                obj,
+                move |_| obj.add_theme()
+            ));
+            let add_btn = gtk::Button::from_icon_name("list-add-symbolic");
+            add_btn.set_tooltip_text(Some("Add theme"));
+            add_btn.connect_clicked(glib::clone!(
+                #[weak]
+                obj,
+                move |_| obj.add_theme()
+            ));
+            let delete_btn = gtk::Button::from_icon_name("user-trash-symbolic");
+            delete_btn.set_tooltip_text(Some("Delete theme"));
+            delete_btn.connect_clicked(glib::clone!(
+                #[weak]
+                obj,
+                move |_| obj.delete_theme()
+            ));
+            add_box.append(&new_entry);
+            add_box.append(&add_btn);
+            add_box.append(&delete_btn);
+            list_box.append(&add_box);
+            pane.set_start_child(Some(&list_box));
+
+            let editor = gtk::Box::builder()
+                .orientation(gtk::Orientation::Vertical)
+                .sensitive(false)
+                .build();
+            let sm = self.slide_manager.borrow().clone();
+            editor.append(&SongEditorToolbar::new(&sm));
+            editor.append(&gtk::Separator::new(gtk::Orientation::Horizontal));
+
+            let screen = sm.slideshow();
+            screen.set_margin_all(4);
+            editor.append(
+                &gtk::AspectFrame::builder()
+                    .ratio(AppConfig::aspect_ratio())
+                    .obey_child(false)
+                    .child(&screen)
+                    .vexpand(true)
+                    .build(),
+            );
+
+            let theme_box = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+            theme_box.set_margin_all(6);
+            let name_entry = gtk::Entry::builder()
+                .placeholder_text("Theme name")
+                .hexpand(true)
+                .build();
+            let show_footer = gtk::CheckButton::with_label("Show footer");
+            show_footer.set_tooltip_text(Some("Song details or the bible translation"));
+            let save_btn = gtk::Button::with_label("Save theme");
+            save_btn.connect_clicked(glib::clone!(
+                #[weak]
+                obj,
+                move |_| obj.save_theme()
+            ));
+            theme_box.append(&gtk::Label::new(Some("Name")));
+            theme_box.append(&name_entry);
+            theme_box.append(&show_footer);
+            theme_box.append(&save_btn);
+            editor.append(&theme_box);
+            pane.set_end_child(Some(&editor));
+
+            box_ui.append(&gtk::Separator::new(gtk::Orientation::Horizontal));
+            let footer_box = gtk::Box::builder()
+                .margin_top(3)
+                .margin_bottom(3)
+                .margin_start(3)
+                .margin_end(3)
+                .spacing(3)
+                .build();
+            footer_box.append(&gtk::Box::builder().hexpand(true).build());
+            let close_btn = gtk::Button::with_label("Close");
+            close_btn.connect_clicked(glib::clone!(
+                #[weak]
+                obj,
+                move |_| obj.close()
+            ));
+            footer_box.append(&close_btn);
+            box_ui.append(&footer_box);
+
+            self.theme_list.replace(theme_list);
+            self.new_entry.replace(new_entry);
+            self.name_entry.replace(name_entry);
+            self.show_footer.replace(show_footer);
+            self.editor.replace(editor);
+        }
+    }
+    impl WidgetImpl for ThemeWindow {}
+    impl WindowImpl for ThemeWindow {}
+}
+
+glib::wrapper! {
+pub struct ThemeWindow(ObjectSubclass<imp::ThemeWindow>)
+    @extends gtk::Window, gtk::Widget,
+    @implements gtk::Accessible, gtk::Buildable, gtk::ConstraintTarget,gtk::Native,gtk::Root, gtk::ShortcutManager;
+}
+
+impl Default for ThemeWindow {
+    fn default() -> Self {
+        glib::Object::new()
+    }
+}
+
+impl ThemeWindow {
+    /// Lists the saved themes and edits them on a slide canvas
+    pub fn new() -> Self {
+        let obj: Self = glib::Object::new();
+        obj.load_themes(None);
+        obj
+    }
+
+    /// Reloads the theme list, selecting `select` when given
+    fn load_themes(&self, select: Option<u32>) {
+        let imp = self.imp();
+        let themes = Query::get_themes().unwrap_or_else(|e| {
+            eprintln!("SQL ERROR: {:?}", e);
+            Vec::new()
+        });
+        imp.themes.replace(themes.clone());
+
+        let theme_list = imp.theme_list.borrow().clone();
+        theme_list.remove_all();
+        for theme in &themes {
+            let label = gtk::Label::builder()
+                .label(&theme.name)
+                .xalign(0.0)
+                .margin_top(6)
+                .margin_bottom(6)
+                .margin_start(6)
+                .build();
+            theme_list.append(&label);
+        }
+
+        let names = std::iter::once("None")
+            .chain(themes.iter().map(|t| t.name.as_str()))
+            .collect::<Vec<_>>();
+        for (content, dropdown) in ThemeContent::ALL.iter().zip(imp.defaults.borrow().iter()) {
+            let default_id = SlideTheme::default_for(*content).map(|t| t.id);
+            let position = default_id
+                .and_then(|id| themes.iter().position(|t| t.id == id))
+                .map_or(0, |i| i + 1);
+
+            // the model is swapped with the default already in place
+            // so reloading does not write the defaults back
+            let model = gtk::StringList::new(&names);
+            dropdown.set_model(None::<&gtk::gio::ListModel>);
+            dropdown.set_selected(gtk::INVALID_LIST_POSITION);
+            dropdown.set_model(Some(&model));
+            dropdown.set_selected(position as u32);
+        }
+
+        let row = select
+            .and_then(|id| themes.iter().position(|t| t.id == id))
+            .and_then(|i| theme_list.row_at_index(i as i32));
+        theme_list.select_row(row.as_ref());
+        self.load_selected_theme();
+    }
+
+    fn selected_theme(&self) -> Option<SlideTheme> {
+        let imp = self.imp();
+        let row = imp.theme_list.borrow().selected_row()?;
+        imp.themes.borrow().get(row.index() as usize).cloned()
+    }
+
+    fn load_selected_theme(&self) {
+        let imp = self.imp();
+        let theme = self.selected_theme();
+        imp.editor.borrow().set_sensitive(theme.is_some());
+
+        let sm = imp.slide_manager.borrow().clone();
+        sm.reset();
+        let Some(theme) = theme else {
+            imp.name_entry.borrow().set_text("");
+            return;
+        };
+
+        imp.name_entry.borrow().set_text(&theme.name);
+        imp.show_footer.borrow().set_active(theme.show_footer);
+        sm.load_data(SlideManagerData::new(0, 0, [sample_slide(&theme)]));
+    }
+
+    fn add_theme(&self) {
+        let entry = self.imp().new_entry.borrow().clone();
+        let name = entry.text().trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+
+        let theme = SlideTheme {
+            name,
+            ..SlideTheme::default()
+        };
+        match Query::insert_theme(&theme) {
+            Ok(id) => {
+                entry.set_text("");
+                self.load_themes(Some(id));
+            }
+            Err(e) => eprintln!("SQL ERROR: {:?}", e),
+        }
+    }
+
+    fn delete_theme(&self) {
+        let Some(theme) = self.selected_theme() else {
+            return;
+        };
+
+        match Query::delete_theme(theme.id) {
+            Ok(_) => self.load_themes(None),
+            Err(e) => eprintln!("SQL ERROR: {:?}", e),
+        }
+    }
+
+    /// Saves the slide on the canvas as the template of the selected theme
+    fn save_theme(&self) {
+        let imp = self.imp();
+        let Some(mut theme) = self.selected_theme() else {
+            return;
+        };
+        let Some(slide) = imp.slide_manager.borrow().slides().first().cloned() else {
+            return;
+        };
+
+        let name = imp.name_entry.borrow().text().trim().to_string();
+        if !name.is_empty() {
+            theme.name = name;
+        }
+        theme.show_footer = imp.show_footer.borrow().is_active();
+        theme.template = slide.serialise();
+        theme.template.preview = Vec::new();
+        for item in &mut theme.template.items {
+            if let CanvasItemType::Text(text_item) = &mut item.item_type {
+                text_item.text_data = String::new();
+            }
+        }
+
+        match Query::update_theme(&theme) {
+            Ok(_) => self.load_themes(Some(theme.id)),
+            Err(e) => eprintln!("SQL ERROR: {:?}", e),
+        }
+    }
+
+    /// `position` 0 is "None", the rest follow the theme list
+    fn set_default_theme(&self, content: ThemeContent, position: u32) {
+        if position == gtk::INVALID_LIST_POSITION {
+            return;
+        }
+
+        let theme_id = (position as usize)
+            .checked_sub(1)
+            .and_then(|i| self.imp().themes.borrow().get(i).map(|t| t.id));
+        if let Err(e) = Query::set_default_theme(content, theme_id) {
+            eprintln!("SQL ERROR: {:?}", e);
+        }
+    }
+}
+
+/// The template of `theme` with placeholder text, the footer is
+/// always shown so its layout can be edited
+fn sample_slide(theme: &SlideTheme) -> SlideData {
+    let mut slide = theme.template.clone();
+    let samples = [SAMPLE_TEXT, SAMPLE_FOOTER];
+
+    let text_items = slide
+        .items
+        .iter_mut()
+        .filter_map(|item| match &mut item.item_type {
+            CanvasItemType::Text(text_item) => Some(text_item),
+            _ => None,
+        });
+    for (text_item, sample) in text_items.zip(samples) {
+        text_item.text_data = glib::base64_encode(sample.as_bytes()).into();
+    }
+
+    slide
+}